@@ -57,6 +57,53 @@ def gast(
         float | npt.ArrayLike[np.float]: Greenwich apparant sidereal time, radians, at input time(s)
     """
 
+def last(
+    longitude: float,
+    tm: (
+        time
+        | npt.ArrayLike[time]
+        | datetime.datetime
+        | npt.ArrayLike[datetime.datetime]
+    ),
+) -> float | npt.ArrayLike[np.float]:
+    """Local Apparent Sidereal Time
+
+    Args:
+        longitude (float): Site longitude, radians, positive east of Greenwich
+        tm (satkit.time | npt.ArrayLike[satkit.time] | datetime.datetime | npt.ArrayLike[datetime.datetime]): scalar, list, or numpy array of astro.time or datetime.datetime representing time at which to calculate output
+
+    Returns:
+        float | npt.ArrayLike[np.float]: Local apparent sidereal time, radians, in range [0, 2π), at input time(s)
+
+    Example:
+        >>> lst = satkit.frametransform.last(math.radians(-71.1), satkit.time(2024, 3, 20))
+    """
+
+def local_hour_angle(
+    ra: float,
+    longitude: float,
+    tm: (
+        time
+        | npt.ArrayLike[time]
+        | datetime.datetime
+        | npt.ArrayLike[datetime.datetime]
+    ),
+) -> float | npt.ArrayLike[np.float]:
+    """Local hour angle of an object
+
+    Notes:
+        * Angle measured westward along the equator from the local meridian to the object
+        * Zero at transit, negative east of the meridian, positive west
+
+    Args:
+        ra (float): Apparent right ascension of object, radians
+        longitude (float): Site longitude, radians, positive east of Greenwich
+        tm (satkit.time | npt.ArrayLike[satkit.time] | datetime.datetime | npt.ArrayLike[datetime.datetime]): scalar, list, or numpy array of astro.time or datetime.datetime representing time at which to calculate output
+
+    Returns:
+        float | npt.ArrayLike[np.float]: Local hour angle, radians, in range [-π, π), at input time(s)
+    """
+
 def earth_rotation_angle(
     tm: (
         time
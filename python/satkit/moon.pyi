@@ -49,4 +49,45 @@ def pos_gcrf(
 
     Accurate to 0.3 degree in ecliptic longitude, 0.2 degree in ecliptic latitude,
     and 1275 km in range
-    """
\ No newline at end of file
+    """
+
+@typing.overload
+def pos_itrf(time: satkit.time) -> npt.ArrayLike[np.float64]:
+    """
+    Approximate Moon position in the ITRF Frame
+
+    Moon position in GCRF from pos_gcrf, rotated into the
+    Earth-fixed ITRF frame
+
+    Input:
+
+    time:  satkit.time object
+
+    Output:
+
+    3-element numpy array representing moon position in ITRF frame
+    at given time.  Units are meters
+    """
+
+@typing.overload
+def pos_itrf(
+    time: npt.ArrayLike[satkit.time]|list[satkit.time]
+             ) -> npt.ArrayLike[np.float64]:
+    """
+    Approximate Moon position in the ITRF Frame
+
+    Moon position in GCRF from pos_gcrf, rotated into the
+    Earth-fixed ITRF frame
+
+    Input:
+
+    time:  satkit.time list, or numpy array
+            for which to compute position
+
+    Output:
+
+    Nx3 numpy array representing moon position in ITRF frame
+    at given times.  Units are meters
+
+    Computation is done with the Python global interpreter lock released
+    """
@@ -61,6 +61,28 @@ class TLE:
             only 1 are passed in
         """
 
+    @staticmethod
+    def from_spacetrack_json(text: str) -> list[TLE] | TLE:
+        """Return TLE(s) loaded from Space-Track or CelesTrak General Perturbations (GP) JSON
+
+            Raw TLE lines ("TLE_LINE1" & "TLE_LINE2") are used if present; otherwise
+            the TLE is reconstructed from the decomposed element fields ("EPOCH",
+            "MEAN_MOTION", "ECCENTRICITY", ...).  Numeric fields may be JSON numbers
+            or strings
+
+        Args:
+            text (str): JSON text of a single GP record or a list of records
+
+        Returns:
+            list[TLE] | TLE: a single TLE for a single record, or a list of TLE
+            objects for a list of records
+
+        Example:
+            >>> import requests
+            >>> text = requests.get("https://celestrak.org/NORAD/elements/gp.php?CATNR=25544&FORMAT=json").text
+            >>> tle = satkit.TLE.from_spacetrack_json(text)[0]
+        """
+
     @property
     def satnum(self) -> int:
         """Satellite number, or equivalently the NORAD ID"""
@@ -173,6 +195,89 @@ def sgp4(
         ITRFCoord(lat:  -0.0363 deg, lon:  -2.2438 deg, hae: 35799.51 km)
     """
 
+def catalog_states(
+    catalog: list[TLE],
+    time: time,
+    **kwargs,
+) -> tuple[npt.ArrayLike[np.int32], npt.ArrayLike[np.float64], list[tuple[int, sgp4error]]]:
+    """Position & velocity of each satellite in a TLE catalog at a common time
+
+    Note:
+        SGP4 is run on each TLE, and the results are gathered into a single
+        array, for e.g. analysis pipelines.  Satellites for which SGP4 fails
+        have NaN rows in the output, and are listed in the failure output
+
+    Args:
+        catalog (list[TLE]): TLE catalog, e.g. as loaded with TLE.from_file
+        time (satkit.time): time at which to compute position and velocity
+
+    Keyword Args:
+        gravconst (satkit.sgp4_gravconst): gravity constant to use.  Default is gravconst.wgs72
+        opsmode (satkit.sgp4_opsmode): opsmode.afspc (Air Force Space Command) or opsmode.improved.  Default is opsmode.afspc
+
+    Returns:
+        tuple[npt.ArrayLike[np.int32], npt.ArrayLike[np.float64], list[tuple[int, sgp4error]]]:
+        satellite numbers (N), Nx6 array of TEME position (meters) and
+        velocity (meters/second), and list of (satellite number, error)
+        for satellites on which SGP4 failed
+
+    Example:
+        >>> tles = satkit.TLE.from_file("catalog.txt")
+        >>> ids, states, failed = satkit.catalog_states(tles, satkit.time.now())
+    """
+
+def bstar_to_ballistic(bstar: float) -> float:
+    """Convert SGP4 starred ballistic coefficient (B*) to the ballistic coefficient used by the numerical drag model
+
+    Note:
+        B* = rho0 * Cd * A / (2 * m), where rho0 is the SGP4 reference density.
+        Negative B* values (sometimes found in TLE fits) are passed through, giving
+        a non-physical negative ballistic coefficient, which the numerical propagator
+        treats as no drag
+
+    Args:
+        bstar (float): Starred ballistic coefficient, in units of inverse Earth radii
+
+    Returns:
+        float: Coefficient of drag times area over mass, Cd * A / m, in m^2/kg
+
+    Example:
+        >>> satprops = satkit.satproperties_static(cdaoverm=satkit.bstar_to_ballistic(tle.bstar))
+    """
+
+def ballistic_to_bstar(bc: float) -> float:
+    """Convert the ballistic coefficient used by the numerical drag model to SGP4 starred ballistic coefficient (B*)
+
+    Note:
+        Inverse of bstar_to_ballistic; negative values are passed through
+
+    Args:
+        bc (float): Coefficient of drag times area over mass, Cd * A / m, in m^2/kg
+
+    Returns:
+        float: Starred ballistic coefficient, in units of inverse Earth radii
+    """
+
+def select_tle(tles: list[TLE], time: time) -> TLE | None:
+    """Select the TLE with epoch nearest a given time
+
+    When several element sets for an object span a period of time, the
+    one with epoch nearest the time of interest generally gives the most
+    accurate SGP4 prediction
+
+    Args:
+        tles (list[TLE]): Element sets, in any order
+        time (time): Time of interest
+
+    Returns:
+        TLE | None: Element set with minimum absolute difference between epoch and time,
+        the earlier of two equally distant, or None if the list is empty
+
+    Example:
+        >>> tle = satkit.select_tle(tles, satkit.time(2024, 1, 11, 18, 0, 0))
+        >>> pos, vel = satkit.sgp4(tle, satkit.time(2024, 1, 11, 18, 0, 0))
+    """
+
 class sgp4_gravconst:
     """Gravity constant to use for SGP4 propagation"""
 
@@ -243,6 +348,7 @@ def gravity(
     Keyword Args:
         model (gravmodel): The gravity model to use.  Default is gravmodel.jgm3
         order (int): The order of the gravity model to use.  Default is 6, maximum is 16
+        degree (int): The degree of the gravity model to use.  Default is equal to order
 
     Returns:
         npt.ArrayLike[np.float]: acceleration in m/s^2 in the International Terrestrial Reference Frame (ITRF)
@@ -250,6 +356,7 @@ def gravity(
 
     Notes:
         *  For details of calculation, see Chapter 3.2 of: "Satellite Orbits: Models, Methods, Applications", O. Montenbruck and B. Gill, Springer, 2012.
+        *  The expansion is evaluated, with no error, at points inside the Earth's reference sphere, but the result there becomes unreliable as degree increases
 
     """
 
@@ -797,6 +904,73 @@ class time:
         Includes fractional comopnent of seconds
         """
 
+    def tai_minus_utc(self) -> int:
+        """
+        Leap-second offset, TAI - UTC, at this time
+
+        Returns:
+            int: Number of seconds by which TAI is ahead of UTC
+
+        Notes:
+            * During a leap second (23:59:60 UTC) the offset prior to the leap second is returned
+            * Returns 0 for times before 1972
+        """
+
+    def round_to(self, step: duration, origin: time | None = None) -> time:
+        """
+        Round to the nearest multiple of a step from an origin
+
+        Args:
+            step (satkit.duration): Cadence to round to, e.g. 10 seconds
+            origin (satkit.time, optional): Origin of the cadence.  Default is the start of the UTC day
+
+        Returns:
+            satkit.time: Time on the cadence nearest to this time
+
+        Notes:
+            * Rounding is in elapsed time from the origin, so on a day with a leap second
+              the tick 86400 seconds after midnight is 23:59:60
+
+        Example:
+            >>> t = satkit.time(2024, 1, 1, 12, 0, 7)
+            >>> t.round_to(satkit.duration.from_seconds(10))
+            # 2024-01-01 12:00:10 UTC
+        """
+
+    def truncate_to(self, step: duration, origin: time | None = None) -> time:
+        """
+        Truncate (round down) to a multiple of a step from an origin
+
+        Args:
+            step (satkit.duration): Cadence to truncate to, e.g. 10 seconds
+            origin (satkit.time, optional): Origin of the cadence.  Default is the start of the UTC day
+
+        Returns:
+            satkit.time: Time on the cadence at or before this time
+        """
+
+    def bin_key(self, step: duration, origin: time) -> int:
+        """
+        Integer index of the bin of a cadence containing this time, e.g. as a stable key for time-indexed caches
+
+        Args:
+            step (satkit.duration): Width of the bins, e.g. 60 seconds
+            origin (satkit.time): Start of bin 0
+
+        Returns:
+            int: Bin index, i.e. the number of whole steps from the origin to this time truncated to the cadence
+
+        Notes:
+            * Times exactly on a bin boundary are in the bin starting at that boundary
+            * Times before the origin have negative indices, e.g. -1 for the bin just before the origin
+            * If step is not positive, bins are one microsecond wide
+
+        Example:
+            >>> origin = satkit.time(2024, 1, 1)
+            >>> satkit.time(2024, 1, 1, 0, 2, 30).bin_key(satkit.duration.from_seconds(60), origin)
+            2
+        """
+
     def as_iso8601(self) -> str:
         """
         Represent time as ISO 8601 string
@@ -1276,6 +1450,52 @@ class kepler:
             tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]: Tuple with two elements representing the position and velocity vectors
         """
 
+    def state_at_true_anomaly(
+        self, nu: float, mu: float = 3.986004418e14
+    ) -> typing.Tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]:
+        """Position and velocity at a given true anomaly on the orbit, treating the motion as purely Keplerian
+
+        Args:
+            nu (float): True anomaly, radians
+            mu (float, optional): Gravitational parameter of the central body, m^3/s^2.  Default is that of the Earth
+
+        Returns:
+            tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]: Position (meters) and velocity (meters/second) vectors
+
+        Raises:
+            RuntimeError: If the semiparameter is not positive, or the true anomaly is beyond the asymptotes of a hyperbolic orbit
+        """
+
+    def perigee_state(
+        self, mu: float = 3.986004418e14
+    ) -> typing.Tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]:
+        """Position and velocity at perigee (true anomaly of zero), treating the motion as purely Keplerian
+
+        Args:
+            mu (float, optional): Gravitational parameter of the central body, m^3/s^2.  Default is that of the Earth
+
+        Returns:
+            tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]: Position (meters) and velocity (meters/second) vectors
+
+        Raises:
+            RuntimeError: If the semiparameter is not positive
+        """
+
+    def apogee_state(
+        self, mu: float = 3.986004418e14
+    ) -> typing.Tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]:
+        """Position and velocity at apogee (true anomaly of pi), treating the motion as purely Keplerian
+
+        Args:
+            mu (float, optional): Gravitational parameter of the central body, m^3/s^2.  Default is that of the Earth
+
+        Returns:
+            tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]: Position (meters) and velocity (meters/second) vectors
+
+        Raises:
+            RuntimeError: If the orbit is not closed (eccentricity >= 1), so has no apogee
+        """
+
     def propagate(self, dt: duration | float) -> kepler:
         """Propagate Keplerian element set by input duration
 
@@ -1451,6 +1671,31 @@ class itrfcoord:
             satkit.quaternion: Quaternion representiong rotation from East-North-Up (ENU) to ITRF at this location
         """
 
+    def az_el_range(
+        self,
+        other: itrfcoord,
+        refraction: bool = False,
+        pressure: float = 1010.0,
+        temperature: float = 10.0,
+    ) -> typing.Tuple[float, float, float]:
+        """Look angles from this coordinate to another
+
+        Args:
+            other (itrfcoord): ITRF coordinate of target
+            refraction (bool, optional): Correct elevation for atmospheric refraction, so that it is the apparent (observed) elevation.  Default is False
+            pressure (float, optional): Atmospheric pressure at observer, hPa, used for refraction.  Default is 1010
+            temperature (float, optional): Air temperature at observer, degrees Celsius, used for refraction.  Default is 10
+
+        Notes:
+            Refraction uses the formula of Saemundsson (1986).  Below -1 degree of
+            geometric elevation, outside the valid range of the formula, refraction
+            tapers linearly to zero at -2 degrees; below that, the geometric
+            elevation is returned unchanged
+
+        Returns:
+            tuple[float, float, float]: (azimuth in radians clockwise from north, elevation in radians, range in meters)
+        """
+
     def geodesic_distance(self, other: itrfcoord) -> typing.Tuple[float, float, float]:
         """Use Vincenty formula to compute geodesic distance:
         https://en.wikipedia.org/wiki/Vincenty%27s_formulae
@@ -1478,6 +1723,23 @@ class itrfcoord:
 
         """
 
+    def offset_enu(self, east: float, north: float, up: float) -> itrfcoord:
+        """Offset this coordinate by a vector in the local East-North-Up (ENU) frame
+
+        Args:
+            east (float): Offset to the east, meters
+            north (float): Offset to the north, meters
+            up (float): Offset along the local vertical, meters
+
+        Notes:
+            The offset is in the local tangent plane, so is intended for small offsets (up to a few kilometers).
+            A horizontal offset of d meters also raises the height above the ellipsoid by about d^2 / 2R.
+            Use ``move_with_heading`` to travel along the Earth surface
+
+        Returns:
+            itrfcoord: New ITRF coordinate at the offset location
+        """
+
 class consts:
     """Some constants that are useful for saetllite dynamics"""
 
@@ -1609,6 +1871,36 @@ class satstate:
             npt.ArrayLike[np.float64] | None: 6x6 numpy array representing state covariance in GCRF frame or None if not set
         """
 
+    @property
+    def consider_cov(self) -> npt.ArrayLike[np.float64] | None:
+        """Contribution of "consider" parameters to the 6x6 state covariance in GCRF frame
+
+        This is kept separate from the state covariance, "cov"
+
+        Returns:
+            npt.ArrayLike[np.float64] | None: 6x6 numpy array representing covariance contribution of consider parameters, or None if not set
+        """
+
+    @property
+    def total_cov(self) -> npt.ArrayLike[np.float64] | None:
+        """Total 6x6 state covariance in GCRF frame: state covariance plus contribution of consider parameters
+
+        Returns:
+            npt.ArrayLike[np.float64] | None: 6x6 numpy array representing total covariance, or None if neither is set
+        """
+
+    def set_consider_cov(self, cov: npt.ArrayLike[np.float64]):
+        """Set covariance of "consider" parameters
+
+        Consider parameters are not estimated, but their uncertainty
+        contributes to the state uncertainty as the state is propagated.
+        The consider parameters are the coefficient of drag times area over mass,
+        and the coefficient of radiation pressure times area over mass
+
+        Args:
+            cov (npt.ArrayLike[np.float64]): 2x2 covariance of consider parameters, (m^2/kg)^2
+        """
+
     @property
     def time(self) -> time:
         """Return time of this satellite state
@@ -1629,6 +1921,89 @@ class satstate:
             satstate: New satellite state object representing the state at the new time
         """
 
+    def propagate_with_stm(
+        self, time: time | duration, propsettings=None
+    ) -> tuple[satstate, npt.NDArray[np.float64]]:
+        """Propagate this state to a new time, and also return the state transition matrix
+
+        The state transition matrix is computed whether or not the covariance is set,
+        e.g. to map a covariance that is only known later
+
+        Args:
+            time (satkit.time|satkit.duration): Time or duration from current time to which to propagate the state
+            propsettings (satkit.propsettings, optional): object describing settings to use in the propagation.
+                If omitted, default is used
+
+        Returns:
+            tuple[satstate, npt.NDArray[np.float64]]: New satellite state object at the new time,
+            as returned by "propagate", and 6x6 state transition matrix of position & velocity,
+            in the GCRF frame, from the current time to the new time
+
+        Example:
+            >>> state2, phi = state.propagate_with_stm(satkit.duration.from_hours(3))
+            >>> cov2 = phi @ cov @ phi.T
+        """
+
+    def moon_relative(self) -> npt.NDArray[np.float64]:
+        """Position & velocity of the satellite relative to the Moon, e.g. for cislunar analysis
+
+        The frame is Moon-centered & inertial, with axes parallel to the GCRF
+
+        Returns:
+            npt.NDArray[np.float64]: 6-element Moon-relative position (meters) & velocity (meters / second)
+
+        Notes:
+            * Moon position & velocity are from the JPL ephemeris
+            * The relative velocity is the satellite GCRF velocity minus the Moon's, i.e. as seen from the Moon
+        """
+
+def cov_gcrf2ric(
+    states: satstate | list[satstate] | npt.ArrayLike[np.float64],
+    covs: npt.ArrayLike[np.float64],
+) -> npt.NDArray[np.float64]:
+    """Rotate position & velocity covariances from the GCRF to the RIC (radial, in-track, cross-track) frame of the corresponding states
+
+    Args:
+        states (satstate | list[satstate] | npt.ArrayLike[np.float64]): States defining the RIC frames, as a list of satstate objects,
+            or an Nx6 array of GCRF position (meters) & velocity (meters / second).  A single satstate or 6-element array is also accepted
+        covs (npt.ArrayLike[np.float64]): Nx6x6 array of GCRF position & velocity covariances, or a single 6x6 covariance.
+            Covariances of satstate objects are ignored
+
+    Returns:
+        npt.NDArray[np.float64]: Nx6x6 array of covariances in the RIC frame, or 6x6 if a single covariance is input
+
+    Notes:
+        * The same 3x3 rotation is applied to position & velocity
+        * Number of states must match number of covariances
+
+    Example:
+        >>> ric = satkit.cov_gcrf2ric(pv, covs)  # pv is Nx6, covs is Nx6x6
+    """
+
+def relative_state(chief: satstate, deputy: satstate) -> npt.NDArray[np.float64]:
+    """Position & velocity of a deputy satellite relative to a chief, in the chief's RIC (radial, in-track, cross-track) frame
+
+    Args:
+        chief (satstate): State of the chief satellite, defining the RIC frame
+        deputy (satstate): State of the deputy satellite, at the same time
+
+    Returns:
+        npt.NDArray[np.float64]: 6-element RIC relative position (meters) & velocity (meters / second)
+
+    Raises:
+        ValueError: If the states are at different times
+
+    Notes:
+        * Relative velocity is as seen in the rotating RIC frame, i.e. it excludes the frame rotation
+          at the chief's orbital rate; this is the relative velocity of the Clohessy-Wiltshire equations,
+          and is zero for two satellites in the same circular orbit
+        * To compare states at different times, first propagate one to the time of the other
+
+    Example:
+        >>> rel = satkit.relative_state(chief, deputy)
+        >>> print(f"In-track separation: {rel[1]:.1f} m")
+    """
+
 class propstats:
     """Statistics of a satellite propagation"""
 
@@ -1691,6 +2066,31 @@ class propresult:
             npt.ArrayLike[float]: 6-element numpy array representing state of satellite in meters & meters/second
         """
 
+    @property
+    def drag_work() -> float:
+        """Work done by atmospheric drag over the propagation, per unit mass of the satellite, J/kg
+
+        Notes:
+        * This is the integral of drag acceleration dotted with velocity, taken forward in time over the propagated arc, so is negative (drag removes orbital energy) for both forward and backward propagation
+        * Only computed if ``compute_drag_work`` is set in the propagation settings; otherwise zero
+        * Zero if drag is not modeled (no satellite properties, or satellite above 700 km)
+
+        Returns:
+            float: Work done by drag, J/kg
+        """
+
+    @property
+    def steps() -> list[tuple[time, npt.ArrayLike[float]]] | None:
+        """Time and 6-element state (pos + vel, meters & meters/second) at the end of each accepted integrator step
+
+        Notes:
+        * Only recorded if ``output_steps`` is set in the propagation settings; otherwise None
+        * Times are in the direction of propagation, and the last is the stop time
+
+        Returns:
+            list[tuple[satkit.time, npt.ArrayLike[float]]] | None: Time and state at each accepted step
+        """
+
     @property
     def state_start() -> npt.ArrayLike[float]:
         """6-element state (pos + vel) of satellite in meters & meters/second at start of propagation
@@ -1759,6 +2159,54 @@ class propresult:
             npt.ArrayLike[np.float64] | typing.Tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]: 6-element vector representing state at given time. if output_phi, also output 6x6 state transition matrix at given time
         """
 
+    def stm_between(t1: time, t2: time) -> npt.ArrayLike[np.float64]:
+        """State transition matrix between two times within the propagation
+
+        Computed from the interpolated state transition matrices relative
+        to the start time:  Phi(t2, t1) = Phi(t2, t0) * Phi(t1, t0)^-1
+
+        Requires the state transition matrix to have been propagated,
+        with interpolation enabled
+
+        Args:
+            t1 (satkit.time): Time from which to map the state
+            t2 (satkit.time): Time to which the state is mapped
+
+        Returns:
+            npt.ArrayLike[np.float64]: 6x6 state transition matrix from t1 to t2
+        """
+
+    def iter(self, step: duration) -> typing.Iterator[typing.Tuple[time, npt.ArrayLike[np.float64]]]:
+        """Iterate over interpolated states at a fixed step
+
+        Requires the propagation to have been run with interpolation enabled
+
+        Args:
+            step (satkit.duration): Step between samples.  Must be positive; samples are taken in the direction of propagation
+
+        Returns:
+            Iterator[tuple[satkit.time, npt.ArrayLike[np.float64]]]: Iterator yielding time and 6-element GCRF state (position in meters, velocity in m/s), from the start time to at or before the end time
+
+        Example:
+            >>> for (t, state) in res.iter(satkit.duration.from_minutes(10)):
+            >>>     print(t, state[0:3])
+        """
+
+    def format(self, units: str | None = None, precision: int = 3) -> str:
+        """String representation with selectable units and precision
+
+        The default string representation (str(result)) shows position
+        in km and velocity in m/s, with 3 decimal places, as does this
+        with default arguments
+
+        Args:
+            units (str | None): "m" for meters and meters/second, or "km" for kilometers and kilometers/second.  Default is None, for kilometers and meters/second
+            precision (int): Number of decimal places.  Default is 3
+
+        Returns:
+            str: String representation of the result.  Values of magnitude 1e10 or larger are in scientific notation
+        """
+
 class satproperties_static:
     """Satellite properties relevant for drag and radiation pressure
 
@@ -1811,7 +2259,15 @@ class propsettings:
         * rel_error: 1e-8
         * gravity_order: 4
         * use_spaceweather: True
+        * zonal_wind: 0.0
         * use_jplephem: True
+        * ocean_tides: False
+        * pole_tide: False
+        * relativity: False
+        * freeze_third_bodies: False
+        * compute_drag_work: False
+        * max_steps: None
+        * output_steps: False
         * enable_interp: True
 
     * enable_interp enables high-preciion interpolation of state between start and stop times via the returned function,
@@ -1823,11 +2279,20 @@ class propsettings:
         """Create propagation settings object used to configure high-precision orbit propagator
 
         Args:
-            abs_error (float, optional keyword): Maximum absolute value of error for any element in propagated state following ODE integration. Default is 1e-8
+            abs_error (float | list[float], optional keyword): Maximum absolute value of error for any element in propagated state following ODE integration. May also be a per-component list of length 6 (position & velocity), or matching the integrated state size, a multiple of 6 (e.g. 42 for the 6x7 state with state transition matrix, 54 with consider parameters; column-major). Default is 1e-8
             rel_error (float, optional keyword): Maximum relative error of any element in propagated state following ODE integration. Default is 1e-8
             gravity_order (int, optional keyword): Earth gravity order to use in ODE integration. Default is 4
             use_spaceweather (bool, optional keyword): Use space weather data when computing atmospheric density for drag forces. Default is True
+            zonal_wind (float, optional keyword): Constant zonal thermospheric wind, m/s, positive eastward, relative to the co-rotating atmosphere used for drag. Default is 0.0
             use_jplephem (bool, optional keyword): Use JPL ephemeris for solar system bodies. Default is True
+            ocean_tides (bool, optional keyword): Include ocean tide perturbations to the geopotential. Coefficient file "fes2004_Cnm-Snm.dat" is downloaded to the data directory if not present. Default is False
+            pole_tide (bool, optional keyword): Include solid Earth and ocean pole tide perturbations to the geopotential. Default is False
+            relativity (bool, optional keyword): Include relativistic (Schwarzschild, de Sitter, Lense-Thirring) accelerations. Default is False
+            empirical (list[float] | None, optional keyword): Empirical accelerations, m/s^2, as 9 parameters: radial, along-track, cross-track, each as (constant, cos(u), sin(u)) where u is argument of latitude. Default is None
+            compute_drag_work (bool, optional keyword): Compute the work done by drag, in the ``drag_work`` property of the result, when satellite properties are given. Default is False
+            freeze_third_bodies (bool, optional keyword): Evaluate sun & moon positions once, at the midpoint of each propagation arc, to save ephemeris evaluations on short arcs. Default is False
+            max_steps (int | None, optional keyword): Maximum number of accepted integrator steps; propagation raises an error if exceeded. Default is None (unbounded)
+            output_steps (bool, optional keyword): Record time and state at the end of each accepted integrator step, in the ``steps`` property of the result. Default is False
             enable_interp (bool, optional keyword): Store intermediate data that allows for fast high-precision interpolation of state between start and stop times. Default is True
 
 
@@ -1836,11 +2301,16 @@ class propsettings:
         """
 
     @property
-    def abs_error() -> float:
+    def abs_error() -> float | list[float]:
         """Maxmum absolute value of error for any element in propagated state following ODE integration
 
+        Notes:
+
+        * May be set to a per-component list of length 6 (position & velocity), or matching the integrated state size, a multiple of 6 (e.g. 42 for the 6x7 state with state transition matrix, 54 with consider parameters; column-major)
+        * With a length-6 list, the scalar absolute error is used for the state transition matrix
+
         Returns:
-            float: Maximum absolute value of error for any element in propagated state following ODE integration, default is 1e-8
+            float | list[float]: Maximum absolute value of error for any element in propagated state following ODE integration, default is 1e-8
         """
 
     @property
@@ -1877,6 +2347,129 @@ class propsettings:
 
         """
 
+    @property
+    def zonal_wind() -> float:
+        """Constant horizontal zonal (east-west) thermospheric wind used in drag computation, m/s
+
+        Notes:
+
+        * Positive is eastward (prograde)
+        * Wind is relative to the atmosphere co-rotating with the Earth
+        * Default value is 0.0, i.e. pure co-rotation
+
+        Returns:
+            float: Zonal wind speed, m/s
+        """
+
+    @property
+    def ocean_tides() -> bool:
+        """Include ocean tide perturbations to the geopotential
+
+        Notes:
+
+        * Requires ocean tide coefficient file "fes2004_Cnm-Snm.dat" in the data directory; it is downloaded if absent, and propagation raises an error if the download fails
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether ocean tides are included
+        """
+
+    @property
+    def pole_tide() -> bool:
+        """Include solid Earth and ocean pole tide perturbations to the geopotential
+
+        Notes:
+
+        * Uses polar motion from the Earth orientation parameters
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether pole tides are included
+        """
+
+    @property
+    def relativity() -> bool:
+        """Include relativistic accelerations: Schwarzschild, de Sitter (geodesic precession), and Lense-Thirring (frame dragging)
+
+        Notes:
+
+        * See IERS 2010 Conventions, Equation 10.12
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether relativistic accelerations are included
+        """
+
+    @property
+    def empirical() -> list[float] | None:
+        """Empirical accelerations in the radial, along-track, cross-track frame, in m/s^2
+
+        Notes:
+
+        * 9 parameters: radial, along-track, cross-track, each as (constant, cos(u), sin(u))
+        * u is the argument of latitude; for equatorial orbits it is measured from the GCRF x axis
+        * Default value is None
+
+        Returns:
+            list[float] | None: Empirical acceleration parameters, or None if not used
+        """
+
+    @property
+    def max_steps() -> int | None:
+        """Maximum number of accepted integrator steps, guarding against runaway propagations
+
+        Notes:
+
+        * Propagation raises an error reporting the steps taken and time reached if this is exceeded
+        * Default value is None, i.e. unbounded
+
+        Returns:
+            int | None: Maximum number of accepted steps, or None if unbounded
+        """
+
+    @property
+    def compute_drag_work() -> bool:
+        """Compute the work done by atmospheric drag over the propagation, when satellite properties are given
+
+        Notes:
+
+        * Work is returned in the ``drag_work`` property of the propagation result
+        * The drag power is integrated over each step, which costs extra atmospheric density evaluations, so this is opt-in
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether drag work is computed
+        """
+
+    @property
+    def freeze_third_bodies() -> bool:
+        """Evaluate the sun & moon positions once, at the midpoint of each propagation arc, rather than sampling them over the arc
+
+        Notes:
+
+        * Saves ephemeris evaluations, which is worthwhile for many short arcs
+        * The moon moves about 0.55 degrees per hour, so the third-body error grows with arc length; use only for short arcs
+        * Precomputed terms are not used when set
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether sun & moon positions are frozen
+        """
+
+    @property
+    def output_steps() -> bool:
+        """Record time and state at the end of each accepted integrator step, e.g. to inspect the integrator's behavior
+
+        Notes:
+
+        * Steps are returned in the ``steps`` property of the propagation result
+        * Storage grows with the number of steps, so this is opt-in
+        * Default value is False
+
+        Returns:
+            bool: Indicate whether integrator steps are recorded
+        """
+
     @property
     def enable_interp() -> bool:
         """Store intermediate data that allows for fast high-precision interpolation of state between start and stop times
@@ -1932,3 +2525,31 @@ def propagate(
     * Solid Earth tides are not (yet) included in the model
 
     """
+
+def propagate_cov(
+    state0: npt.ArrayLike[np.float64],
+    cov0: npt.ArrayLike[np.float64],
+    start: time,
+    stop: time,
+    propsettings: propsettings | None = None,
+    satproperties: satproperties_static | None = None,
+) -> tuple[npt.ArrayLike[np.float64], npt.ArrayLike[np.float64]]:
+    """Propagate satellite state and covariance
+
+    The covariance is propagated with the state transition matrix:
+    P(stop) = Phi * P(start) * Phi^T
+
+    Args:
+        state0 (npt.ArrayLike[float]): 6-element numpy array representing satellite GCRF position & velocity in meters and meters/second
+        cov0 (npt.ArrayLike[float]): 6x6 symmetric covariance of state0, in meters and meters/second
+        start (satkit.time): Time of "state0"
+        stop (satkit.time): Time to which state and covariance are propagated
+        propsettings (satkit.propsettings, optional): Settings for the propagation.  If left out, default will be used
+        satproperties (satkit.satproperties_static, optional): Drag and radiation pressure succeptibility of satellite.  If left out, drag and radiation pressure are neglected
+
+    Returns:
+        tuple[npt.ArrayLike[float], npt.ArrayLike[float]]: 6-element state and 6x6 covariance at "stop"
+
+    Raises:
+        ValueError: If covariance is not 6x6 and symmetric
+    """
@@ -79,6 +79,48 @@ def pos_gcrf(time: satkit.time) -> npt.ArrayLike[np.float64]:
 
     """
 
+@typing.overload
+def pos_itrf(time: satkit.time) -> npt.ArrayLike[np.float64]:
+    """
+    Sun position in the International Terrestrial Reference Frame (ITRF)
+
+    Sun position in GCRF from pos_gcrf, rotated into the
+    Earth-fixed ITRF frame
+
+    Input:
+
+    time:  satkit.time object representing time
+            at which to compute position
+
+    Output:
+
+    3-element numpy array representing sun position in ITRF frame
+    at given time.  Units are meters
+    """
+
+@typing.overload
+def pos_itrf(
+    time: npt.ArrayLike[satkit.time] | list[satkit.time],
+) -> npt.ArrayLike[np.float64]:
+    """
+    Sun position in the International Terrestrial Reference Frame (ITRF)
+
+    Sun position in GCRF from pos_gcrf, rotated into the
+    Earth-fixed ITRF frame
+
+    Input:
+
+    time:  list or numpy array of satkit.time objects representing times
+            at which to compute position
+
+    Output:
+
+    Nx3 numpy array representing sun position in ITRF frame
+    at the "N" given times.  Units are meters
+
+    Computation is done with the Python global interpreter lock released
+    """
+
 @typing.overload
 def pos_mod(time: satkit.time) -> npt.ArrayLike[np.float64]:
     """
@@ -3,6 +3,7 @@ import pytest
 import numpy as np
 import math as m
 import os
+import pickle
 from sp3file import read_sp3file
 from datetime import datetime, timezone
 import satkit as sk
@@ -112,6 +113,40 @@ class TestTime:
         t2 = t1 - d
         assert t2 == sk.time(2020, 12, 31, 0, 0, 0)
 
+    def test_time_round(self):
+        """
+        Test rounding & truncating to a cadence
+        """
+        t = sk.time(2024, 1, 1, 12, 0, 7)
+        step = sk.duration.from_seconds(10)
+        assert t.round_to(step) == sk.time(2024, 1, 1, 12, 0, 10)
+        assert t.truncate_to(step) == sk.time(2024, 1, 1, 12, 0, 0)
+        origin = sk.time(2024, 1, 1, 12, 0, 5)
+        assert t.round_to(step, origin) == origin
+        assert t.truncate_to(step, origin) == origin
+
+    def test_time_bin_key(self):
+        """
+        Test integer bin index of a cadence
+        """
+        origin = sk.time(2024, 1, 1)
+        step = sk.duration.from_seconds(10)
+        t = origin + sk.duration.from_seconds(42)
+        assert t.bin_key(step, origin) == 4
+        assert (origin + sk.duration.from_seconds(40)).bin_key(step, origin) == 4
+        assert (origin + sk.duration.from_seconds(50)).bin_key(step, origin) == 5
+        assert (origin - sk.duration.from_seconds(0.5)).bin_key(step, origin) == -1
+        assert (origin - step).bin_key(step, origin) == -1
+
+    def test_time_tai_minus_utc(self):
+        """
+        Test leap-second offset
+        """
+        assert sk.time(2024, 6, 1).tai_minus_utc() == 37
+        t = sk.time(2017, 1, 1)
+        assert (t - sk.duration.from_seconds(2)).tai_minus_utc() == 36
+        assert t.tai_minus_utc() == 37
+
     def test_time_gregorian(self):
         """
         Test conversion to Gregorian calendar
@@ -166,6 +201,32 @@ class TestKepler:
             np.array([4.902279, 5.533140, -1.975710]) * 1.0e3, 1.0e-3
         )
 
+    def test_kepler_apsis_states(self):
+        """
+        Test position and velocity at perigee and apogee
+        """
+        a = 2.4e7
+        eccen = 0.7
+        kep = sk.kepler(a, eccen, 0.5, 1.0, 2.0, 1.3)
+        rp, vp = kep.perigee_state()
+        ra, va = kep.apogee_state()
+        mu = sk.consts.mu_earth
+        assert np.linalg.norm(rp) == pytest.approx(a * (1 - eccen), 1.0e-12)
+        assert np.linalg.norm(ra) == pytest.approx(a * (1 + eccen), 1.0e-12)
+        for r, v in [(rp, vp), (ra, va)]:
+            rn = np.linalg.norm(r)
+            assert np.linalg.norm(v) == pytest.approx(
+                m.sqrt(mu * (2 / rn - 1 / a)), 1.0e-12
+            )
+        pos, vel = kep.state_at_true_anomaly(kep.nu)
+        assert pos == pytest.approx(kep.to_pv()[0], 1.0e-12)
+
+        # Hyperbolic orbit has no apogee
+        hyp = sk.kepler(-1.0e7, 1.5, 0.5, 1.0, 2.0, 0.3)
+        hyp.perigee_state()
+        with pytest.raises(RuntimeError):
+            hyp.apogee_state()
+
 
 class TestJPLEphem:
     def test_jplephem_testvecs(self):
@@ -377,6 +438,24 @@ class TestFrameTransform:
         truth = -207.4212121875 * m.pi / 180
         assert gmst == pytest.approx(truth)
 
+    def test_last(self):
+        """
+        Test local apparent sidereal time & hour angle
+        """
+        tm = sk.time(2024, 3, 20, 22, 5, 0)
+        gast = sk.frametransform.gast(tm)
+        last = sk.frametransform.last(0.0, tm)
+        assert last >= 0 and last < 2 * m.pi
+        assert m.remainder(last - gast, 2 * m.pi) == pytest.approx(0, abs=1e-12)
+
+        lon = m.radians(-71.0)
+        ra = sk.frametransform.last(lon, tm)
+        assert sk.frametransform.local_hour_angle(ra, lon, tm) == pytest.approx(
+            0, abs=1e-12
+        )
+        ha = sk.frametransform.local_hour_angle(ra + m.radians(15), lon, tm)
+        assert ha == pytest.approx(-m.radians(15), abs=1e-12)
+
 
 class TestITRFCoord:
     def test_geodetic(self):
@@ -432,6 +511,30 @@ class TestSun:
         pref = np.array([146259922.0e3, 28585947.0e3, 12397430.0e3])
         assert p == pytest.approx(pref, 5e-4)
 
+    def test_sunpos_array(self):
+        """
+        Sun position over a year of daily times
+        """
+        t0 = sk.time(2024, 1, 1)
+        times = [t0 + sk.duration.from_days(d) for d in range(366)]
+        p = sk.sun.pos_gcrf(times)
+        assert p.shape == (366, 3)
+        dist = np.linalg.norm(p, axis=1) / sk.consts.au
+        assert np.all(dist > 0.98)
+        assert np.all(dist < 1.02)
+
+        # ITRF position is a rotation of the GCRF position
+        pitrf = sk.sun.pos_itrf(times)
+        assert pitrf.shape == (366, 3)
+        assert np.linalg.norm(pitrf, axis=1) == pytest.approx(
+            np.linalg.norm(p, axis=1)
+        )
+
+        # Single time gives a 3-element array
+        assert sk.sun.pos_gcrf(t0).shape == (3,)
+        assert sk.moon.pos_itrf(t0).shape == (3,)
+        assert sk.moon.pos_gcrf(times).shape == (366, 3)
+
     def test_sun_rise_set(self):
         """
         Vallado example 5-2
@@ -572,6 +675,20 @@ class TestGeodesicDistance:
         dist_ref = 3459 * 5280 * 12 * 2.54 / 100
         assert dist == pytest.approx(dist_ref, 1.0e-2)
 
+    def test_az_el_range(self):
+        """
+        Look angles, with and without refraction
+        """
+        enu = np.array([-1.0e5, 1.0e5, 0.0])
+        target = sk.itrfcoord(self.newyork.vector + self.newyork.qenu2itrf * enu)
+        [az, el, rng] = self.newyork.az_el_range(target)
+        assert az == pytest.approx(7 * m.pi / 4, abs=1e-9)
+        assert el == pytest.approx(0, abs=1e-9)
+        assert rng == pytest.approx(np.linalg.norm(enu))
+        [az2, el2, rng2] = self.newyork.az_el_range(target, refraction=True)
+        assert az2 == az
+        assert m.degrees(el2 - el) == pytest.approx(0.5, abs=0.05)
+
     def test_heading_dist(self):
         """
         test that moving a distance at a given heading along surface of
@@ -582,6 +699,17 @@ class TestGeodesicDistance:
         diff = self.london - loc2
         assert np.linalg.norm(diff) < 1e-8
 
+    def test_offset_enu(self):
+        """
+        Offset by a local east-north-up vector
+        """
+        loc = self.newyork.offset_enu(0.0, 0.0, 100.0)
+        assert loc.altitude == pytest.approx(self.newyork.altitude + 100.0, abs=1e-6)
+        loc = self.newyork.offset_enu(-300.0, 200.0, 50.0)
+        enu = np.array([-300.0, 200.0, 50.0])
+        diff = loc.vector - self.newyork.vector - self.newyork.qenu2itrf * enu
+        assert np.linalg.norm(diff) < 1e-6
+
 
 class TestHighPrecisionPropagation:
 
@@ -611,6 +739,208 @@ class TestHighPrecisionPropagation:
 
         assert istate1 == pytest.approx(istate2, rel=1e-7)
 
+    def test_drag_work(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_hours(3.0)
+
+        r = sk.consts.earth_radius + 350.0e3
+        pos = np.array([r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / r), 0])
+
+        settings = sk.propsettings()
+        satprops = sk.satproperties_static()
+        satprops.cdaoverm = 0.022
+
+        res = sk.propagate(
+            np.concatenate((pos, vel)),
+            starttime,
+            stop=stoptime,
+            propsettings=settings,
+            satproperties=satprops,
+        )
+        assert res.drag_work == 0.0
+
+        settings.compute_drag_work = True
+        res = sk.propagate(
+            np.concatenate((pos, vel)), starttime, stop=stoptime, propsettings=settings
+        )
+        assert res.drag_work == 0.0
+        res = sk.propagate(
+            np.concatenate((pos, vel)),
+            starttime,
+            stop=stoptime,
+            propsettings=settings,
+            satproperties=satprops,
+        )
+        assert res.drag_work < 0.0
+
+    def test_abs_error_vec(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_days(1.0)
+
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+
+        # Loose position tolerance, tight velocity tolerance
+        settings = sk.propsettings(abs_error=[1.0, 1.0, 1.0, 1e-6, 1e-6, 1e-6])
+        assert len(settings.abs_error) == 6
+        res = sk.propagate(
+            np.concatenate((pos, vel)), starttime, stop=stoptime, propsettings=settings
+        )
+        assert res.state.shape == (6,)
+
+        # Vector must match state size
+        with pytest.raises(ValueError):
+            settings.abs_error = [1.0, 1.0, 1.0]
+
+        # Any multiple of 6 is accepted, e.g. state with consider parameters
+        settings.abs_error = [1.0e-8] * 54
+        assert len(settings.abs_error) == 54
+
+        settings.abs_error = 1e-9
+        assert settings.abs_error == 1e-9
+
+    def test_zonal_wind(self):
+        settings = sk.propsettings()
+        assert settings.zonal_wind == 0.0
+        settings = sk.propsettings(zonal_wind=100.0)
+        assert settings.zonal_wind == 100.0
+        settings.zonal_wind = -50.0
+        assert settings.zonal_wind == -50.0
+
+    def test_max_steps(self):
+        settings = sk.propsettings()
+        assert settings.max_steps is None
+        settings = sk.propsettings(max_steps=1000)
+        assert settings.max_steps == 1000
+        settings.max_steps = None
+        assert settings.max_steps is None
+
+    def test_output_steps(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+        state0 = np.concatenate((pos, vel))
+
+        settings = sk.propsettings()
+        assert settings.output_steps is False
+        res = sk.propagate(
+            state0, starttime, stop=starttime + sk.duration.from_hours(6.0), propsettings=settings
+        )
+        assert res.steps is None
+
+        settings.output_steps = True
+        for hours in [6.0, -6.0]:
+            stoptime = starttime + sk.duration.from_hours(hours)
+            res = sk.propagate(state0, starttime, stop=stoptime, propsettings=settings)
+            assert len(res.steps) == res.stats.num_accept
+            dt = [(t - starttime).seconds() * m.copysign(1, hours) for t, _ in res.steps]
+            assert all(b > a for a, b in zip(dt, dt[1:]))
+            t, state = res.steps[-1]
+            assert t == stoptime
+            assert state == pytest.approx(res.state, abs=1e-9)
+
+    def test_freeze_third_bodies(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_hours(1.0)
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+        state0 = np.concatenate((pos, vel))
+
+        settings = sk.propsettings()
+        assert settings.freeze_third_bodies is False
+        res = sk.propagate(state0, starttime, stop=stoptime, propsettings=settings)
+        settings = sk.propsettings(freeze_third_bodies=True)
+        assert settings.freeze_third_bodies is True
+        res2 = sk.propagate(state0, starttime, stop=stoptime, propsettings=settings)
+        assert np.linalg.norm(res.pos - res2.pos) < 1.0
+
+    def test_stm_between(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_days(1.0)
+
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+
+        res = sk.propagate(
+            np.concatenate((pos, vel)), starttime, stop=stoptime, output_phi=True
+        )
+        t1 = starttime + sk.duration.from_hours(4.0)
+        t2 = starttime + sk.duration.from_hours(10.0)
+        phi = res.stm_between(t1, t2)
+        assert phi.shape == (6, 6)
+
+        # Mapping from a time to itself is identity
+        assert res.stm_between(t1, t1) == pytest.approx(np.eye(6), abs=1e-8)
+
+    def test_propagate_cov(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_hours(6.0)
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+        state0 = np.concatenate((pos, vel))
+        cov0 = np.diag([100.0, 200.0, 300.0, 0.01, 0.02, 0.03])
+        cov0[0, 3] = cov0[3, 0] = 0.5
+
+        state1, cov1 = sk.propagate_cov(state0, cov0, starttime, stoptime)
+        assert state1.shape == (6,)
+        assert cov1.shape == (6, 6)
+        assert cov1 == pytest.approx(cov1.T)
+
+        # Propagating backward recovers the original state & covariance
+        state2, cov2 = sk.propagate_cov(state1, cov1, stoptime, starttime)
+        assert state2 == pytest.approx(state0, abs=1e-3)
+        assert cov2 == pytest.approx(cov0, abs=1e-4)
+
+        # Covariance must be 6x6 and symmetric
+        with pytest.raises(ValueError):
+            sk.propagate_cov(state0, np.eye(3), starttime, stoptime)
+        cov_bad = np.copy(cov0)
+        cov_bad[0, 1] = 1.0
+        with pytest.raises(ValueError):
+            sk.propagate_cov(state0, cov_bad, starttime, stoptime)
+
+    def test_iter(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_hours(1.0)
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+        res = sk.propagate(np.concatenate((pos, vel)), starttime, stop=stoptime)
+
+        # Step that does not divide the span stops before the end
+        samples = list(res.iter(sk.duration.from_minutes(7.0)))
+        assert len(samples) == 9
+        assert samples[0][0] == starttime
+        assert samples[-1][0] == starttime + sk.duration.from_minutes(56.0)
+        assert samples[0][1] == pytest.approx(np.concatenate((pos, vel)))
+        assert samples[3][1] == pytest.approx(res.interp(samples[3][0]))
+
+        # Step that divides the span includes the end
+        samples = list(res.iter(sk.duration.from_minutes(10.0)))
+        assert len(samples) == 7
+        assert samples[-1][0] == stoptime
+
+    def test_format(self):
+        starttime = sk.time(2015, 3, 20, 0, 0, 0)
+        stoptime = starttime + sk.duration.from_hours(1.0)
+        pos = np.array([sk.consts.geo_r, 0, 0])
+        vel = np.array([0, m.sqrt(sk.consts.mu_earth / sk.consts.geo_r), 0])
+        res = sk.propagate(np.concatenate((pos, vel)), starttime, stop=stoptime)
+
+        s = res.format("m", 6)
+        posline = [l for l in s.splitlines() if "Pos:" in l][0]
+        assert posline.strip().endswith("] m")
+        for v in posline.split("[")[1].split("]")[0].split(","):
+            assert len(v.strip().split(".")[1]) == 6
+        assert "m/s" in s
+
+        # Default units are km and m/s, matching str()
+        assert res.format() == str(res)
+        assert "km/s" not in res.format()
+        assert "km/s" in res.format("km")
+        with pytest.raises(ValueError):
+            res.format("au", 3)
+
     def test_gps(self):
 
         # File contains test calculation vectors provided by NASA
@@ -682,6 +1012,144 @@ class TestSatState:
         assert np.array([0.0, 1.0, 0.0]) == pytest.approx(ry, abs=1e-10)
         assert np.array([0.0, 0.0, 1.0]) == pytest.approx(rz, abs=1e-10)
 
+    def test_consider_cov(self):
+        """
+        Uncertainty in drag coefficient inflates along-track uncertainty
+        """
+        time = sk.time(2015, 3, 20, 0, 0, 0)
+        r = sk.consts.earth_radius + 400.0e3
+        satstate = sk.satstate(
+            time,
+            np.array([r, 0, 0]),
+            np.array([0, m.sqrt(sk.consts.mu_earth / r), 0]),
+        )
+        satstate.set_consider_cov(np.array([[1.0e-6, 0.0], [0.0, 0.0]]))
+        assert satstate.cov is None
+        assert satstate.consider_cov == pytest.approx(np.zeros((6, 6)))
+
+        state2 = satstate.propagate(time + sk.duration.from_hours(12.0))
+        assert state2.cov is None
+        ccov = state2.consider_cov
+        assert ccov.shape == (6, 6)
+        assert ccov[0:3, 0:3].trace() > 0
+        assert state2.total_cov == pytest.approx(ccov)
+
+        # Pickle round trip keeps covariance & consider parameters
+        state2.cov = np.diag([1.0e4, 1.0e4, 1.0e4, 1.0, 1.0, 1.0])
+        state3 = pickle.loads(pickle.dumps(state2))
+        assert state3.time == state2.time
+        assert state3.pos == pytest.approx(state2.pos)
+        assert state3.cov == pytest.approx(state2.cov)
+        assert state3.consider_cov == pytest.approx(ccov)
+        assert state3.total_cov == pytest.approx(state2.total_cov)
+
+    def test_propagate_with_stm(self):
+        """
+        State transition matrix maps covariance as built-in propagation does
+        """
+        time = sk.time(2015, 3, 20, 0, 0, 0)
+        r = sk.consts.earth_radius + 600.0e3
+        satstate = sk.satstate(
+            time,
+            np.array([r, 0, 0]),
+            np.array([0, 5.0e3, 5.0e3]),
+        )
+        stop = time + sk.duration.from_hours(3.0)
+        state2, phi = satstate.propagate_with_stm(stop)
+        assert state2.cov is None
+        assert phi.shape == (6, 6)
+
+        cov = np.diag([100.0, 400.0, 900.0, 1.0e-4, 4.0e-4, 9.0e-4])
+        satstate.cov = cov
+        state2_cov = satstate.propagate(stop)
+        assert state2_cov.cov == pytest.approx(phi @ cov @ phi.T, rel=1e-9)
+
+    def test_relative_state(self):
+        """
+        Along-track offset appears in the in-track RIC component
+        """
+        time = sk.time(2024, 1, 1, 0, 0, 0)
+        r = sk.consts.earth_radius + 500.0e3
+        v = m.sqrt(sk.consts.mu_earth / r)
+        pos = np.array([r, 0, 0])
+        vel = np.array([0, v * m.cos(0.9), v * m.sin(0.9)])
+        chief = sk.satstate(time, pos, vel)
+
+        # Deputy 100 m ahead along the velocity
+        deputy = sk.satstate(time, pos + 100.0 * vel / v, vel)
+        rel = sk.relative_state(chief, deputy)
+        assert rel.shape == (6,)
+        assert rel[0:3] == pytest.approx([0.0, 100.0, 0.0], abs=1e-6)
+
+        # Frame rotation at the orbital rate appears as radial velocity
+        assert rel[3] == pytest.approx(100.0 * v / r, rel=1e-9)
+
+        later = sk.satstate(time + sk.duration.from_seconds(1), pos, vel)
+        with pytest.raises(ValueError):
+            sk.relative_state(chief, later)
+
+    def test_moon_relative(self):
+        """
+        LEO satellite is about an Earth-Moon distance from the Moon
+        """
+        time = sk.time(2024, 3, 1)
+        r = sk.consts.earth_radius + 500.0e3
+        v = m.sqrt(sk.consts.mu_earth / r)
+        pos = np.array([r, 0, 0])
+        vel = np.array([0, v, 0])
+        rel = sk.satstate(time, pos, vel).moon_relative()
+        assert rel.shape == (6,)
+        pmoon, vmoon = sk.jplephem.geocentric_state(sk.solarsystem.Moon, time)
+        assert rel[0:3] == pytest.approx(pos - pmoon, abs=1e-6)
+        assert rel[3:6] == pytest.approx(vel - vmoon, abs=1e-9)
+        assert abs(np.linalg.norm(rel[0:3]) - np.linalg.norm(pmoon)) <= r
+
+    def test_cov_gcrf2ric(self):
+        """
+        Batched covariance rotation matches single conversions
+        """
+        rng = np.random.default_rng(1)
+        n = 10
+        r = sk.consts.earth_radius + 500.0e3
+        pv = np.zeros((n, 6))
+        pv[:, 0:3] = rng.normal(size=(n, 3))
+        pv[:, 0:3] *= r / np.linalg.norm(pv[:, 0:3], axis=1)[:, None]
+        pv[:, 3:6] = np.cross(pv[:, 0:3], rng.normal(size=(n, 3)))
+        pv[:, 3:6] *= 7.6e3 / np.linalg.norm(pv[:, 3:6], axis=1)[:, None]
+        a = rng.normal(size=(n, 6, 6))
+        covs = a @ a.transpose(0, 2, 1)
+
+        ric = sk.cov_gcrf2ric(pv, covs)
+        assert ric.shape == (n, 6, 6)
+        for i in range(n):
+            single = sk.cov_gcrf2ric(pv[i, :], covs[i, :, :])
+            assert single.shape == (6, 6)
+            assert ric[i, :, :] == pytest.approx(single, rel=1e-12)
+
+            # Explicit rotation
+            rhat = pv[i, 0:3] / np.linalg.norm(pv[i, 0:3])
+            chat = np.cross(pv[i, 0:3], pv[i, 3:6])
+            chat /= np.linalg.norm(chat)
+            dcm = np.array([rhat, np.cross(chat, rhat), chat])
+            rot = np.zeros((6, 6))
+            rot[0:3, 0:3] = dcm
+            rot[3:6, 3:6] = dcm
+            expected = rot @ covs[i, :, :] @ rot.T
+            assert ric[i, :, :] == pytest.approx(expected, rel=1e-9, abs=1e-9)
+
+        # List of satstate gives the same result
+        time = sk.time(2024, 1, 1)
+        states = [sk.satstate(time, pv[i, 0:3], pv[i, 3:6]) for i in range(n)]
+        assert sk.cov_gcrf2ric(states, covs) == pytest.approx(ric, rel=1e-12)
+
+        # Mismatched number of states, and bad shapes
+        with pytest.raises(ValueError):
+            sk.cov_gcrf2ric(pv[0:3, :], covs)
+        with pytest.raises(ValueError):
+            sk.cov_gcrf2ric(pv[:, 0:5], covs)
+        with pytest.raises(ValueError):
+            sk.cov_gcrf2ric(pv, covs[:, 0:5, :])
+
 
 class TestSGP4:
     def test_sgp4_multiple(self):
@@ -714,6 +1182,129 @@ class TestSGP4:
         assert p2 == pytest.approx(np.squeeze(p[2, :, :]))
         assert v2 == pytest.approx(np.squeeze(v[2, :, :]))
 
+    def test_sgp4_gravconst(self):
+        """
+        Check that the gravity constant set is applied, including
+        when propagating multiple TLEs at once
+        """
+        lines = [
+            "0 STARLINK-3118",
+            "1 49140U 21082L   24030.39663557  .00000076  00000-0  14180-4 0  9995",
+            "2 49140  70.0008  34.1139 0002663 260.3521  99.7337 14.98327656131736",
+            "0 STARLINK-3093",
+            "1 49141U 21082M   24030.50141584 -.00000431  00000-0 -28322-4 0  9990",
+            "2 49141  70.0000  73.8654 0002647 256.8611 103.2253 14.98324813131968",
+        ]
+        tles = sk.TLE.from_lines(lines)
+        tm = sk.time(2024, 1, 31)
+        [p72, _v] = sk.sgp4(tles, tm)
+        [p84, _v] = sk.sgp4(tles, tm, gravconst=sk.sgp4_gravconst.wgs84)
+        [p1, _v] = sk.sgp4(tles[1], tm, gravconst=sk.sgp4_gravconst.wgs84)
+        assert np.squeeze(p84[1]) == pytest.approx(p1)
+        diff = np.linalg.norm(np.squeeze(p84[1]) - np.squeeze(p72[1]))
+        assert diff > 1.0 and diff < 1.0e3
+
+    def test_bstar_ballistic(self):
+        """
+        Check conversion between B* and ballistic coefficient
+        """
+        lines = [
+            "0 STARLINK-3118",
+            "1 49140U 21082L   24030.39663557  .00000076  00000-0  14180-4 0  9995",
+            "2 49140  70.0008  34.1139 0002663 260.3521  99.7337 14.98327656131736",
+        ]
+        tle = sk.TLE.from_lines(lines)
+        bc = sk.bstar_to_ballistic(tle.bstar)
+        assert sk.ballistic_to_bstar(bc) == pytest.approx(tle.bstar)
+        assert bc == pytest.approx(12.741621 * tle.bstar, rel=1e-5)
+        assert bc > 1.0e-4 and bc < 0.1
+        # Negative B* is passed through
+        assert sk.bstar_to_ballistic(-2.8322e-5) < 0
+
+    def test_select_tle(self):
+        """
+        Nearest epoch, ties to the earlier, None for an empty list
+        """
+        line2 = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537"
+        tles = [
+            sk.TLE.from_lines(
+                [
+                    f"1 25544U 98067A   {epoch}  .00016717  00000-0  10270-3 0  9005",
+                    line2,
+                ]
+            )
+            for epoch in ["24010.50000000", "24012.00000000", "24011.00000000"]
+        ]
+        assert sk.select_tle(tles, sk.time(2024, 1, 11, 18, 0, 0)) is tles[1]
+        assert sk.select_tle(tles, sk.time(2024, 1, 11, 3, 0, 0)) is tles[2]
+        assert sk.select_tle(tles, sk.time(2024, 1, 11, 12, 0, 0)) is tles[2]
+        assert sk.select_tle(tles, sk.time(2024, 1, 10, 18, 0, 0)) is tles[0]
+        assert sk.select_tle([], sk.time(2024, 1, 11)) is None
+
+    def test_spacetrack_json(self):
+        """
+        Check TLEs loaded from GP JSON match the card format
+        """
+        lines = [
+            "0 ISS (ZARYA)",
+            "1 25544U 98067A   24001.50000000  .00016717  00000-0  30270-3 0  9993",
+            "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.49815308432345",
+        ]
+        card = sk.TLE.from_lines(lines)
+        text = """[{
+            "OBJECT_NAME": "ISS (ZARYA)", "OBJECT_ID": "1998-067A",
+            "EPOCH": "2024-01-01T12:00:00.000000", "MEAN_MOTION": 15.49815308,
+            "ECCENTRICITY": 0.0006703, "INCLINATION": 51.6416,
+            "RA_OF_ASC_NODE": 247.4627, "ARG_OF_PERICENTER": 130.536,
+            "MEAN_ANOMALY": 325.0288, "EPHEMERIS_TYPE": 0,
+            "CLASSIFICATION_TYPE": "U", "NORAD_CAT_ID": 25544,
+            "ELEMENT_SET_NO": 999, "REV_AT_EPOCH": 43234, "BSTAR": 0.0003027,
+            "MEAN_MOTION_DOT": 0.00016717, "MEAN_MOTION_DDOT": 0
+        }]"""
+        tles = sk.TLE.from_spacetrack_json(text)
+        assert len(tles) == 1
+        assert tles[0].satnum == 25544
+        assert tles[0].name() == "ISS (ZARYA)"
+        tm = [card.epoch + sk.duration.from_hours(x) for x in range(24)]
+        [p, v] = sk.sgp4(tles[0], tm)
+        [pcard, vcard] = sk.sgp4(card, tm)
+        assert p == pytest.approx(pcard, abs=1.0e-3)
+        assert v == pytest.approx(vcard, abs=1.0e-6)
+
+        # Single record gives a single TLE
+        tle = sk.TLE.from_spacetrack_json(text.strip()[1:-1])
+        assert tle.satnum == 25544
+        with pytest.raises(ValueError):
+            sk.TLE.from_spacetrack_json('{"OBJECT_NAME": "ISS (ZARYA)"}')
+
+    def test_catalog_states(self):
+        """
+        Check batch propagation of a TLE catalog to a common time
+        """
+        lines = [
+            "0 STARLINK-3118",
+            "1 49140U 21082L   24030.39663557  .00000076  00000-0  14180-4 0  9995",
+            "2 49140  70.0008  34.1139 0002663 260.3521  99.7337 14.98327656131736",
+            "0 STARLINK-3093",
+            "1 49141U 21082M   24030.50141584 -.00000431  00000-0 -28322-4 0  9990",
+            "2 49141  70.0000  73.8654 0002647 256.8611 103.2253 14.98324813131968",
+            "0 STARLINK-3042",
+            "1 49142U 21082N   24030.19218442  .00000448  00000-0  45331-4 0  9999",
+            "2 49142  70.0005  34.6319 0002749 265.6056  94.4790 14.98327526131704",
+        ]
+        tles = sk.TLE.from_lines(lines)
+        tm = sk.time(2024, 1, 31)
+        ids, states, failed = sk.catalog_states(tles, tm)
+        assert ids.shape == (3,)
+        assert states.shape == (3, 6)
+        assert list(ids) == [49140, 49141, 49142]
+        assert len(failed) == 0
+
+        # Row matches propagation of a single TLE
+        p, v = sk.sgp4(tles[1], tm)
+        assert states[1, 0:3] == pytest.approx(p)
+        assert states[1, 3:6] == pytest.approx(v)
+
     def test_sgp4_vallado(self):
         """
         SGP4 Test Vectors from vallado
@@ -0,0 +1,192 @@
+//! Wrapping, normalization, and unwrapping of angles
+//!
+//! Wrapped results lie in half-open ranges, so each angle has exactly
+//! one representation: [0, 2π), [-π, π), and [0, 360).  Inputs that are
+//! within roundoff of a boundary (e.g. `3.0 * PI`, which is not exactly
+//! 3π) may land on either side of it
+//!
+//! Very large inputs are reduced exactly by `rem_euclid`, but an `f64`
+//! angle of magnitude `x` is itself only known to about `x * 1e-16`
+//! radians, so little of the original angle remains for `x` beyond
+//! about 1e15
+
+use std::f64::consts::PI;
+
+const TWO_PI: f64 = 2.0 * PI;
+
+/// Reduce to [0, period)
+///
+/// `rem_euclid` rounds up to `period` for tiny negative inputs,
+/// which is mapped back to zero
+fn wrap_to_period(angle: f64, period: f64) -> f64 {
+    let r = angle.rem_euclid(period);
+    match r >= period {
+        true => 0.0,
+        false => r,
+    }
+}
+
+/// Wrap an angle to [0, 2π)
+///
+/// # Arguments
+///
+/// * `angle` - Angle, radians
+///
+/// # Returns
+///
+/// * Equivalent angle in [0, 2π), radians.  NaN & infinite inputs give NaN
+pub fn wrap_to_2pi(angle: f64) -> f64 {
+    wrap_to_period(angle, TWO_PI)
+}
+
+/// Wrap an angle to [-π, π)
+///
+/// # Arguments
+///
+/// * `angle` - Angle, radians
+///
+/// # Returns
+///
+/// * Equivalent angle in [-π, π), radians, so π maps to -π.
+///   NaN & infinite inputs give NaN
+pub fn wrap_to_pi(angle: f64) -> f64 {
+    let r = wrap_to_2pi(angle);
+    match r >= PI {
+        true => r - TWO_PI,
+        false => r,
+    }
+}
+
+/// Normalize an angle in degrees to [0, 360)
+///
+/// # Arguments
+///
+/// * `angle` - Angle, degrees
+///
+/// # Returns
+///
+/// * Equivalent angle in [0, 360), degrees.  NaN & infinite inputs give NaN
+pub fn normalize_degrees(angle: f64) -> f64 {
+    wrap_to_period(angle, 360.0)
+}
+
+/// Unwrap a sequence of phase angles
+///
+/// Adds multiples of 2π to each angle so that successive angles differ
+/// by at most π, removing the jumps introduced by wrapping
+///
+/// # Arguments
+///
+/// * `angles` - Sequence of angles, radians
+///
+/// # Returns
+///
+/// * Continuous sequence of angles, radians, starting at the first input
+///
+/// # Notes
+///
+/// * A true change of exactly ±π between samples is ambiguous; it is
+///   kept as is
+/// * NaN values are passed through, and the following angle is
+///   unwrapped relative to the last finite angle
+///
+/// # Example
+///
+/// ```
+/// use satkit::angles::unwrap;
+/// let wrapped = [3.0, -3.0, -2.5];
+/// let unwrapped = unwrap(&wrapped);
+/// assert!((unwrapped[1] - (-3.0 + 2.0 * std::f64::consts::PI)).abs() < 1.0e-12);
+/// ```
+pub fn unwrap(angles: &[f64]) -> Vec<f64> {
+    let mut offset = 0.0;
+    let mut prev: Option<f64> = None;
+    angles
+        .iter()
+        .map(|&a| {
+            if a.is_nan() {
+                return a;
+            }
+            if let Some(p) = prev {
+                let d = a - p;
+                if d.abs() > PI {
+                    offset -= TWO_PI * (d / TWO_PI).round();
+                }
+            }
+            prev = Some(a);
+            a + offset
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        // Boundaries: ±3π are both odd multiples of π, so wrap to π,
+        // represented as -π, to within roundoff
+        for a in [3.0 * PI, -3.0 * PI, PI, -PI] {
+            assert!((wrap_to_pi(a).abs() - PI).abs() < 1.0e-12);
+            assert!((wrap_to_2pi(a) - PI).abs() < 1.0e-12);
+        }
+        assert_eq!(wrap_to_pi(PI), -PI);
+        assert_eq!(wrap_to_pi(-PI), -PI);
+        assert_eq!(wrap_to_2pi(TWO_PI), 0.0);
+        assert_eq!(wrap_to_2pi(0.0), 0.0);
+
+        // Tiny negative values wrap to just under 2π, which may round
+        // up to 2π; the result is always in range
+        for a in [-1.0e-20, -1.0e-300, -f64::EPSILON] {
+            let w = wrap_to_2pi(a);
+            assert!((0.0..TWO_PI).contains(&w));
+            assert!((-PI..PI).contains(&wrap_to_pi(a)));
+        }
+
+        // Large inputs stay in range
+        for a in [1.0e6, -1.0e6, 1.0e20, -1.0e20, f64::MAX, f64::MIN] {
+            assert!((0.0..TWO_PI).contains(&wrap_to_2pi(a)));
+            assert!((-PI..PI).contains(&wrap_to_pi(a)));
+            assert!((0.0..360.0).contains(&normalize_degrees(a)));
+        }
+        assert!((wrap_to_pi(1.0e6) - (1.0e6 - 159155.0 * TWO_PI)).abs() < 1.0e-9);
+
+        assert_eq!(normalize_degrees(360.0), 0.0);
+        assert_eq!(normalize_degrees(-90.0), 270.0);
+        assert_eq!(normalize_degrees(725.0), 5.0);
+        assert!((0.0..360.0).contains(&normalize_degrees(-1.0e-20)));
+        assert!(wrap_to_pi(f64::NAN).is_nan());
+        assert!(wrap_to_2pi(f64::INFINITY).is_nan());
+    }
+
+    #[test]
+    fn test_unwrap() {
+        // Linearly increasing phase, wrapped, is restored
+        let truth: Vec<f64> = (0..50).map(|ix| -1.0 + 0.9 * ix as f64).collect();
+        let wrapped: Vec<f64> = truth.iter().map(|a| wrap_to_pi(*a)).collect();
+        let unwrapped = unwrap(&wrapped);
+        for (u, t) in unwrapped.iter().zip(truth.iter()) {
+            assert!((u - t).abs() < 1.0e-12);
+        }
+
+        // Decreasing phase, wrapped to [0, 2π), with a jump sequence
+        let unwrapped = unwrap(&[0.5, 0.1, 6.0, 5.5, 0.2, 6.1]);
+        let expected = [0.5, 0.1, 6.0 - TWO_PI, 5.5 - TWO_PI, 0.2, 6.1 - TWO_PI];
+        for (u, e) in unwrapped.iter().zip(expected.iter()) {
+            assert!((u - e).abs() < 1.0e-12);
+        }
+
+        // Jumps of several turns are removed
+        let unwrapped = unwrap(&[0.0, 0.1 + 3.0 * TWO_PI, 0.2 - 2.0 * TWO_PI]);
+        assert!((unwrapped[1] - 0.1).abs() < 1.0e-12);
+        assert!((unwrapped[2] - 0.2).abs() < 1.0e-12);
+
+        // NaN passed through
+        let unwrapped = unwrap(&[3.0, f64::NAN, -3.0]);
+        assert!(unwrapped[1].is_nan());
+        assert!((unwrapped[2] - (TWO_PI - 3.0)).abs() < 1.0e-12);
+
+        assert!(unwrap(&[]).is_empty());
+    }
+}
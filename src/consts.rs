@@ -34,3 +34,62 @@ pub const JGM3_A: f64 = 0.6378136300E+07;
 ///  JGM3 J2 term
 pub const JGM3_J2: f64 = -0.0010826360229829945;
 //jgm3_J2:f64 = -sqrt(5.) * -0.484169548456e-03;
+
+/// Consistent set of gravitational constants, for use in orbit propagation
+///
+/// Different reference frameworks adopt slightly different values for the
+/// Earth gravitational parameter & radius, and for the sun and moon
+/// gravitational parameters.  Mixing them introduces errors of order
+/// meters, so a propagation should use a single set
+///
+/// * `Egm96` - EGM96 Earth GM & radius (identical to JGM3), with the
+///   nominal sun & moon parameters [`MU_SUN`] and [`MU_MOON`]
+/// * `Egm2008` - EGM2008 Earth GM & radius.  These were adopted unchanged
+///   from EGM96, so this set is identical to `Egm96`
+/// * `De440` - JPL DE440 planetary ephemeris values.  These are
+///   TDB-compatible, so the Earth GM differs from the TT-compatible
+///   geopotential value by about 1.5e-8 (the TDB/TT rate difference)
+///
+/// Frame transforms do not depend on any of these constants.  Relativistic
+/// accelerations use the nominal [`MU_EARTH`] and [`MU_SUN`] regardless of set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ConstantSet {
+    #[default]
+    Egm96,
+    Egm2008,
+    De440,
+}
+
+impl ConstantSet {
+    /// Gravitational parameter of Earth, m^3/s^2
+    pub const fn mu_earth(&self) -> f64 {
+        match self {
+            Self::Egm96 | Self::Egm2008 => 3.986004415E14,
+            Self::De440 => 3.98600435507E14,
+        }
+    }
+
+    /// Reference radius of Earth, meters
+    pub const fn earth_radius(&self) -> f64 {
+        match self {
+            Self::Egm96 | Self::Egm2008 => 6378136.3,
+            Self::De440 => 6378136.6,
+        }
+    }
+
+    /// Gravitational parameter of Sun, m^3/s^2
+    pub const fn mu_sun(&self) -> f64 {
+        match self {
+            Self::Egm96 | Self::Egm2008 => MU_SUN,
+            Self::De440 => 1.327_124_400_412_794E20,
+        }
+    }
+
+    /// Gravitational parameter of Moon, m^3/s^2
+    pub const fn mu_moon(&self) -> f64 {
+        match self {
+            Self::Egm96 | Self::Egm2008 => MU_MOON,
+            Self::De440 => 4.902800118E12,
+        }
+    }
+}
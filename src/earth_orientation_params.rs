@@ -34,6 +34,11 @@ fn load_eop_file_csv(filename: Option<PathBuf>) -> SKResult<Vec<EOPEntry>> {
     // Download EOP data from celetrak.org
     download_if_not_exist(&path, Some("http://celestrak.org/SpaceData/"))?;
 
+    log::debug!(
+        target: "satkit::data",
+        "Loading earth orientation parameters from {}",
+        path.display()
+    );
     let file: File = File::open(&path)?;
 
     io::BufReader::new(file)
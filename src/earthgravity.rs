@@ -1,3 +1,4 @@
+use crate::consts::ConstantSet;
 use crate::utils::{datadir, download_if_not_exist};
 use crate::{skerror, SKResult};
 use std::collections::HashMap;
@@ -5,7 +6,9 @@ use std::io::{self, BufRead};
 use std::path::PathBuf;
 
 use nalgebra as na;
-type CoeffTable = na::DMatrix<f64>;
+/// Table of unnormalized gravity coefficients;
+/// C_nm is at (n, m), S_nm is at (m-1, n)
+pub type CoeffTable = na::DMatrix<f64>;
 
 type DivisorTable = na::SMatrix<f64, 44, 44>;
 
@@ -30,7 +33,14 @@ pub enum GravityModel {
 ///
 pub fn jgm3() -> &'static Gravity {
     static INSTANCE: OnceCell<Gravity> = OnceCell::new();
-    INSTANCE.get_or_init(|| Gravity::from_file("JGM3.gfc").unwrap())
+    INSTANCE.get_or_init(|| {
+        let mut g = Gravity::from_file("JGM3.gfc").unwrap();
+        // JGM3 is tide-free (Tapley et al., 1996), but the file may not say
+        if g.tide_system == TideSystem::Unspecified {
+            g.tide_system = TideSystem::TideFree;
+        }
+        g
+    })
 }
 
 ///
@@ -57,6 +67,24 @@ pub fn itu_grace16() -> &'static Gravity {
     INSTANCE.get_or_init(|| Gravity::from_file("ITU_GRACE16.gfc").unwrap())
 }
 
+///
+/// Singleton for JGM3 gravity model, with the Earth gravitational
+/// parameter & radius of the given constant set
+///
+/// See [`Gravity::with_constants`]
+///
+pub fn jgm3_with_constants(set: &ConstantSet) -> &'static Gravity {
+    static EGM96: OnceCell<Gravity> = OnceCell::new();
+    static EGM2008: OnceCell<Gravity> = OnceCell::new();
+    static DE440: OnceCell<Gravity> = OnceCell::new();
+    let cell = match set {
+        ConstantSet::Egm96 => &EGM96,
+        ConstantSet::Egm2008 => &EGM2008,
+        ConstantSet::De440 => &DE440,
+    };
+    cell.get_or_init(|| jgm3().with_constants(set.mu_earth(), set.earth_radius()))
+}
+
 ///
 /// Gravity model hash
 ///
@@ -131,17 +159,127 @@ pub fn accel_and_partials(pos_itrf: &Vec3, order: usize, model: GravityModel) ->
         .accel_and_partials(pos_itrf, order)
 }
 
+///
+/// Return acceleration due to Earth gravity at the input position,
+/// truncated at the given degree and order.  The acceleration does not
+/// include the centrifugal force, and is output in m/s^2 in the
+/// International Terrestrial Reference Frame (ITRF)
+///
+/// # Arguments
+///
+/// * `pos_itrf` - nalgebra 3-vector representing ITRF position in meters
+/// * `degree` - Maximum degree of terms to include.  Maximum is 40
+/// * `order` - Maximum order of terms to include.  Limited to `degree`
+/// * `model` - The gravity model to use, of type "GravityModel"
+///
+/// # Notes
+///
+/// * See [`Gravity::accel_degree_order`] for behavior inside the
+///   reference sphere
+///
+pub fn gravity_acceleration(
+    pos_itrf: &Vec3,
+    degree: usize,
+    order: usize,
+    model: GravityModel,
+) -> Vec3 {
+    gravhash()
+        .get(&model)
+        .unwrap()
+        .accel_degree_order(pos_itrf, degree, order)
+}
+
 pub fn accel_jgm3(pos_itrf: &Vec3, order: usize) -> Vec3 {
     jgm3().accel(pos_itrf, order)
 }
 
+///
+/// Treatment of the permanent tide in gravity model coefficients
+///
+/// The sun & moon produce a time-averaged (permanent) tidal
+/// potential, and a corresponding permanent deformation of the Earth.
+/// Gravity models differ in whether these are included in C20:
+///
+/// * `TideFree` - neither the direct potential nor the deformation is included
+/// * `ZeroTide` - the permanent deformation is included, the direct potential is not
+/// * `MeanTide` - both the permanent deformation and direct potential are included
+/// * `Unspecified` - the model file does not say
+///
+/// Solid Earth tide corrections computed with frequency-independent
+/// Love numbers (IERS 2010 Conventions, Section 6.2) include the permanent
+/// tide, so should be applied to a tide-free model, or else have
+/// [`Gravity::permanent_tide_c20`] removed
+///
+/// # References
+///
+/// * IERS Conventions (2010), IERS Technical Note 36, Section 6.2.2
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TideSystem {
+    TideFree,
+    ZeroTide,
+    MeanTide,
+    Unspecified,
+}
+
+impl TideSystem {
+    /// Permanent tide amplitude times normalization, A0 * H0 (IERS 2010 eq. 6.13)
+    const A0H0: f64 = 4.4228e-8 * -0.31460;
+    /// Nominal degree-2 zonal Love number
+    const K20: f64 = 0.30190;
+
+    /// Normalized C20 of this tide system relative to tide-free,
+    /// or None if unspecified
+    pub fn c20_offset(&self) -> Option<f64> {
+        match self {
+            Self::TideFree => Some(0.0),
+            Self::ZeroTide => Some(Self::K20 * Self::A0H0),
+            Self::MeanTide => Some((1.0 + Self::K20) * Self::A0H0),
+            Self::Unspecified => None,
+        }
+    }
+}
+
+impl std::str::FromStr for TideSystem {
+    type Err = std::convert::Infallible;
+
+    /// Parse the "tide_system" keyword of an ICGEM .gfc file header
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "tide_free" => Self::TideFree,
+            "zero_tide" => Self::ZeroTide,
+            "mean_tide" => Self::MeanTide,
+            _ => Self::Unspecified,
+        })
+    }
+}
+
+/// Factor converting fully-normalized gravity coefficient of
+/// degree `n` and order `m` to unnormalized coefficient
+pub fn normalization(n: usize, m: usize) -> f64 {
+    let mut scale: f64 = 1.0;
+    for k in (n - m + 1)..(n + m + 1) {
+        scale *= k as f64;
+    }
+    scale /= 2.0f64.mul_add(n as f64, 1.0);
+    if m > 0 {
+        scale /= 2.0;
+    }
+    1.0 / f64::sqrt(scale)
+}
+
 #[derive(Debug, Clone)]
 pub struct Gravity {
     pub name: String,
     pub gravity_constant: f64,
     pub radius: f64,
     pub max_degree: usize,
+    pub tide_system: TideSystem,
     pub coeffs: CoeffTable,
+    /// Fully-normalized coefficients, in same layout as `coeffs`.
+    /// Unnormalized coefficients of high degree & order
+    /// are not representable as f64, so these are kept as well
+    pub coeffs_normalized: CoeffTable,
     pub divisor_table: DivisorTable,
     pub divisor_table2: DivisorTable,
 }
@@ -169,89 +307,207 @@ type Mat3 = na::Matrix3<f64>;
 ///
 
 impl Gravity {
+    /// Unnormalized J2 of the model (J2 = -C20)
+    pub fn j2(&self) -> f64 {
+        -self.coeffs[(2, 0)]
+    }
+
+    /// Normalized C20 contribution of the permanent tide in this model,
+    /// relative to a tide-free model
+    ///
+    /// This should be removed from a solid-tide C20 correction that
+    /// includes the permanent tide, to avoid double-counting it
+    ///
+    /// Returns an error if the tide system is unspecified
+    pub fn permanent_tide_c20(&self) -> SKResult<f64> {
+        match self.tide_system.c20_offset() {
+            Some(v) => Ok(v),
+            None => skerror!(
+                "Gravity model {} has unspecified tide system; set `tide_system` explicitly",
+                self.name
+            ),
+        }
+    }
+
+    /// Change in normalized C20 converting the model to a different
+    /// tide system
+    ///
+    /// Returns an error if the source or target tide system is unspecified
+    pub fn tide_system_c20_delta(&self, tide_system: TideSystem) -> SKResult<f64> {
+        let Some(to) = tide_system.c20_offset() else {
+            return skerror!("Cannot convert gravity model to unspecified tide system");
+        };
+        Ok(to - self.permanent_tide_c20()?)
+    }
+
+    /// Return copy of the model converted to a different tide system,
+    /// by adjusting C20 for the permanent tide
+    ///
+    /// Returns an error if the source or target tide system is unspecified
+    pub fn with_tide_system(&self, tide_system: TideSystem) -> SKResult<Self> {
+        let dc20 = self.tide_system_c20_delta(tide_system)?;
+        let mut g = self.clone();
+        // Coefficients are stored unnormalized; normalization factor for C20 is sqrt(5)
+        g.coeffs[(2, 0)] += dc20 * 5.0_f64.sqrt();
+        g.coeffs_normalized[(2, 0)] += dc20;
+        g.tide_system = tide_system;
+        Ok(g)
+    }
+
     pub fn accel(&self, pos: &Vec3, order: usize) -> Vec3 {
+        self.accel_coeffs(pos, order, &self.coeffs)
+    }
+
+    /// Return copy of the model with a different gravitational parameter
+    /// and reference radius
+    ///
+    /// The coefficients are rescaled to the new reference radius, so that
+    /// the shape of the potential is unchanged; the potential and
+    /// acceleration scale with the ratio of gravitational parameters
+    ///
+    /// # Arguments
+    ///
+    /// * `gravity_constant` - Gravitational parameter, m^3/s^2
+    /// * `radius` - Reference radius, meters
+    pub fn with_constants(&self, gravity_constant: f64, radius: f64) -> Self {
+        let mut g = self.clone();
+        g.gravity_constant = gravity_constant;
+        g.radius = radius;
+        let ratio = self.radius / radius;
+        for n in 0..=self.max_degree {
+            let scale = ratio.powi(n as i32);
+            for m in 0..=n {
+                g.coeffs[(n, m)] *= scale;
+                g.coeffs_normalized[(n, m)] *= scale;
+                if m > 0 {
+                    g.coeffs[(m - 1, n)] *= scale;
+                    g.coeffs_normalized[(m - 1, n)] *= scale;
+                }
+            }
+        }
+        g
+    }
+
+    /// Acceleration due to gravity, truncated at the given degree and order
+    ///
+    /// The acceleration does not include the centrifugal force, and is output
+    /// in m/s^2 in the International Terrestrial Reference Frame (ITRF)
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - ITRF position in meters
+    /// * `degree` - Maximum degree of terms to include.  Limited to the
+    ///   degree of the model, and to 40
+    /// * `order` - Maximum order of terms to include.  Limited to `degree`
+    ///
+    /// # Notes
+    ///
+    /// * The spherical-harmonic expansion is only guaranteed to converge
+    ///   outside of the sphere enclosing all of the Earth's mass.  At points
+    ///   below this (e.g., on or below the Earth's surface) the series is
+    ///   still evaluated, with no error, but the result is only meaningful
+    ///   for low degrees, and becomes unreliable as degree increases
+    pub fn accel_degree_order(&self, pos: &Vec3, degree: usize, order: usize) -> Vec3 {
+        let degree = degree.min(self.coeffs.nrows() - 1).min(40);
+        if degree == 0 {
+            return -self.gravity_constant * pos / pos.norm().powi(3);
+        }
+        if order >= degree {
+            return self.accel_coeffs(pos, degree, &self.coeffs);
+        }
+        let mut coeffs = self.coeffs.view((0, 0), (degree + 1, degree + 1)).into_owned();
+        for n in 0..=degree {
+            for m in (order + 1)..=n {
+                coeffs[(n, m)] = 0.0;
+                coeffs[(m - 1, n)] = 0.0;
+            }
+        }
+        self.accel_coeffs(pos, degree, &coeffs)
+    }
+
+    fn accel_coeffs(&self, pos: &Vec3, order: usize, coeffs: &CoeffTable) -> Vec3 {
         // This is tedious, but using generics allows for vectors to be
         // allocated on the stack, which is faster
         if order == 1 {
-            self.accel_t::<1, 5>(pos)
+            self.accel_t::<1, 5>(pos, coeffs)
         } else if order == 2 {
-            self.accel_t::<2, 6>(pos)
+            self.accel_t::<2, 6>(pos, coeffs)
         } else if order == 3 {
-            self.accel_t::<3, 7>(pos)
+            self.accel_t::<3, 7>(pos, coeffs)
         } else if order == 4 {
-            self.accel_t::<4, 8>(pos)
+            self.accel_t::<4, 8>(pos, coeffs)
         } else if order == 5 {
-            self.accel_t::<5, 9>(pos)
+            self.accel_t::<5, 9>(pos, coeffs)
         } else if order == 6 {
-            self.accel_t::<6, 10>(pos)
+            self.accel_t::<6, 10>(pos, coeffs)
         } else if order == 7 {
-            self.accel_t::<7, 11>(pos)
+            self.accel_t::<7, 11>(pos, coeffs)
         } else if order == 8 {
-            self.accel_t::<8, 12>(pos)
+            self.accel_t::<8, 12>(pos, coeffs)
         } else if order == 9 {
-            self.accel_t::<9, 13>(pos)
+            self.accel_t::<9, 13>(pos, coeffs)
         } else if order == 10 {
-            self.accel_t::<10, 14>(pos)
+            self.accel_t::<10, 14>(pos, coeffs)
         } else if order == 11 {
-            self.accel_t::<11, 15>(pos)
+            self.accel_t::<11, 15>(pos, coeffs)
         } else if order == 12 {
-            self.accel_t::<12, 16>(pos)
+            self.accel_t::<12, 16>(pos, coeffs)
         } else if order == 13 {
-            self.accel_t::<13, 17>(pos)
+            self.accel_t::<13, 17>(pos, coeffs)
         } else if order == 14 {
-            self.accel_t::<14, 18>(pos)
+            self.accel_t::<14, 18>(pos, coeffs)
         } else if order == 15 {
-            self.accel_t::<15, 19>(pos)
+            self.accel_t::<15, 19>(pos, coeffs)
         } else if order == 16 {
-            self.accel_t::<16, 20>(pos)
+            self.accel_t::<16, 20>(pos, coeffs)
         } else if order == 17 {
-            self.accel_t::<17, 21>(pos)
+            self.accel_t::<17, 21>(pos, coeffs)
         } else if order == 18 {
-            self.accel_t::<18, 22>(pos)
+            self.accel_t::<18, 22>(pos, coeffs)
         } else if order == 19 {
-            self.accel_t::<19, 23>(pos)
+            self.accel_t::<19, 23>(pos, coeffs)
         } else if order == 20 {
-            self.accel_t::<20, 24>(pos)
+            self.accel_t::<20, 24>(pos, coeffs)
         } else if order == 21 {
-            self.accel_t::<21, 25>(pos)
+            self.accel_t::<21, 25>(pos, coeffs)
         } else if order == 22 {
-            self.accel_t::<22, 26>(pos)
+            self.accel_t::<22, 26>(pos, coeffs)
         } else if order == 23 {
-            self.accel_t::<23, 27>(pos)
+            self.accel_t::<23, 27>(pos, coeffs)
         } else if order == 24 {
-            self.accel_t::<24, 28>(pos)
+            self.accel_t::<24, 28>(pos, coeffs)
         } else if order == 25 {
-            self.accel_t::<25, 29>(pos)
+            self.accel_t::<25, 29>(pos, coeffs)
         } else if order == 26 {
-            self.accel_t::<26, 30>(pos)
+            self.accel_t::<26, 30>(pos, coeffs)
         } else if order == 27 {
-            self.accel_t::<27, 31>(pos)
+            self.accel_t::<27, 31>(pos, coeffs)
         } else if order == 28 {
-            self.accel_t::<28, 32>(pos)
+            self.accel_t::<28, 32>(pos, coeffs)
         } else if order == 29 {
-            self.accel_t::<29, 33>(pos)
+            self.accel_t::<29, 33>(pos, coeffs)
         } else if order == 30 {
-            self.accel_t::<30, 34>(pos)
+            self.accel_t::<30, 34>(pos, coeffs)
         } else if order == 31 {
-            self.accel_t::<31, 35>(pos)
+            self.accel_t::<31, 35>(pos, coeffs)
         } else if order == 32 {
-            self.accel_t::<32, 36>(pos)
+            self.accel_t::<32, 36>(pos, coeffs)
         } else if order == 33 {
-            self.accel_t::<33, 37>(pos)
+            self.accel_t::<33, 37>(pos, coeffs)
         } else if order == 34 {
-            self.accel_t::<34, 38>(pos)
+            self.accel_t::<34, 38>(pos, coeffs)
         } else if order == 35 {
-            self.accel_t::<35, 39>(pos)
+            self.accel_t::<35, 39>(pos, coeffs)
         } else if order == 36 {
-            self.accel_t::<36, 40>(pos)
+            self.accel_t::<36, 40>(pos, coeffs)
         } else if order == 37 {
-            self.accel_t::<37, 41>(pos)
+            self.accel_t::<37, 41>(pos, coeffs)
         } else if order == 38 {
-            self.accel_t::<38, 42>(pos)
+            self.accel_t::<38, 42>(pos, coeffs)
         } else if order == 39 {
-            self.accel_t::<39, 43>(pos)
+            self.accel_t::<39, 43>(pos, coeffs)
         } else {
-            self.accel_t::<40, 44>(pos)
+            self.accel_t::<40, 44>(pos, coeffs)
         }
     }
 
@@ -346,15 +602,45 @@ impl Gravity {
         pos: &Vec3,
     ) -> (Vec3, na::Matrix3<f64>) {
         let (v, w) = self.compute_legendre::<NP4>(pos);
-        let accel = self.accel_from_legendre_t::<N, NP4>(&v, &w);
+        let accel = self.accel_from_legendre_t::<N, NP4>(&self.coeffs, &v, &w);
         let partials = self.partials_from_legendre_t::<N, NP4>(&v, &w);
         (accel, partials)
     }
 
-    fn accel_t<const N: usize, const NP4: usize>(&self, pos: &Vec3) -> Vec3 {
+    /// Acceleration due to a (small) set of gravity coefficients other than
+    /// those of the model, e.g. tidal corrections, using the model's
+    /// gravitational constant and radius
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - ITRF position in meters
+    /// * `dcoeffs` - Unnormalized coefficient table, in same layout as `coeffs`,
+    ///   of degree up to 4
+    pub fn accel_delta(&self, pos: &Vec3, dcoeffs: &CoeffTable) -> Vec3 {
+        match dcoeffs.nrows() {
+            0..=3 => self.accel_delta_t::<2, 6>(pos, dcoeffs),
+            4 => self.accel_delta_t::<3, 7>(pos, dcoeffs),
+            _ => self.accel_delta_t::<4, 8>(pos, dcoeffs),
+        }
+    }
+
+    fn accel_delta_t<const N: usize, const NP4: usize>(
+        &self,
+        pos: &Vec3,
+        dcoeffs: &CoeffTable,
+    ) -> Vec3 {
+        let mut c = CoeffTable::zeros(N + 1, N + 1);
+        let n = usize::min(dcoeffs.nrows(), N + 1);
+        c.view_mut((0, 0), (n, n))
+            .copy_from(&dcoeffs.view((0, 0), (n, n)));
+        let (v, w) = self.compute_legendre::<NP4>(pos);
+        self.accel_from_legendre_t::<N, NP4>(&c, &v, &w)
+    }
+
+    fn accel_t<const N: usize, const NP4: usize>(&self, pos: &Vec3, coeffs: &CoeffTable) -> Vec3 {
         let (v, w) = self.compute_legendre::<NP4>(pos);
 
-        self.accel_from_legendre_t::<N, NP4>(&v, &w)
+        self.accel_from_legendre_t::<N, NP4>(coeffs, &v, &w)
     }
 
     // Equations 7.65 to 7.69 in Montenbruck & Gill
@@ -437,6 +723,7 @@ impl Gravity {
     /// See Equation 3.33 in Montenbruck & Gill
     fn accel_from_legendre_t<const N: usize, const NP4: usize>(
         &self,
+        coeffs: &CoeffTable,
         v: &Legendre<NP4>,
         w: &Legendre<NP4>,
     ) -> Vec3 {
@@ -444,10 +731,10 @@ impl Gravity {
 
         for n in 0..(N + 1) {
             for m in 0..(n + 1) {
-                let cnm = self.coeffs[(n, m)];
+                let cnm = coeffs[(n, m)];
                 let mut snm = 0.0;
                 if m > 0 {
-                    snm = self.coeffs[(m - 1, n)];
+                    snm = coeffs[(m - 1, n)];
                 }
                 if m == 0 {
                     accel[0] -= cnm * v[(n + 1, 1)];
@@ -535,21 +822,27 @@ impl Gravity {
         }
         */
 
+        log::debug!(target: "satkit::data", "Loading gravity model from {}", path.display());
         let file = std::fs::File::open(&path)?;
 
+        let lines: Vec<String> = io::BufReader::new(file)
+            .lines()
+            .map(|x| x.unwrap_or(String::from("")))
+            .collect();
+        Self::from_lines(&lines)
+    }
+
+    /// Parse gravity model from lines of an ICGEM .gfc file
+    pub(crate) fn from_lines(lines: &[String]) -> SKResult<Self> {
         let mut name = String::new();
         let mut gravity_constant: f64 = 0.0;
         let mut radius: f64 = 0.0;
         let mut max_degree: usize = 0;
+        let mut tide_system = TideSystem::Unspecified;
         let mut header_cnt = 0;
 
-        let lines: Vec<String> = io::BufReader::new(file)
-            .lines()
-            .map(|x| x.unwrap_or(String::from("")))
-            .collect();
-
         // Read header lines
-        for line in &lines {
+        for line in lines {
             header_cnt += 1;
 
             let s: Vec<&str> = line.split_whitespace().collect();
@@ -562,6 +855,8 @@ impl Gravity {
                 gravity_constant = s[1].parse::<f64>()?;
             } else if s[0] == "radius" {
                 radius = s[1].parse::<f64>()?;
+            } else if s[0] == "tide_system" {
+                tide_system = s[1].parse()?;
             } else if s[0] == "max_degree" {
                 max_degree = s[1].parse::<usize>()?;
                 //cs = Some(na::DMatrix::<f64>::zeros(
@@ -595,18 +890,12 @@ impl Gravity {
             }
         }
 
+        let cs_normalized = cs.clone();
+
         // Convert from normalized coefficients to actual coefficients
         for n in 0..(max_degree + 1) {
             for m in 0..(n + 1) {
-                let mut scale: f64 = 1.0;
-                for k in (n - m + 1)..(n + m + 1) {
-                    scale *= k as f64;
-                }
-                scale /= 2.0f64.mul_add(n as f64, 1.0);
-                if m > 0 {
-                    scale /= 2.0;
-                }
-                scale = 1.0 / f64::sqrt(scale);
+                let scale = normalization(n, m);
                 cs[(n, m)] *= scale;
 
                 if m > 0 {
@@ -634,7 +923,9 @@ impl Gravity {
             gravity_constant,
             radius,
             max_degree,
+            tide_system,
             coeffs: cs,
+            coeffs_normalized: cs_normalized,
             divisor_table: d1,
             divisor_table2: d2,
         })
@@ -751,4 +1042,163 @@ mod tests {
             assert_relative_eq!(accel2, accel3, max_relative = 1.0e-4);
         }
     }
+
+    fn test_model(tide_system: Option<&str>) -> Gravity {
+        let mut lines = vec![
+            "modelname test".to_string(),
+            "earth_gravity_constant 3.986004415E+14".to_string(),
+            "radius 6378136.3".to_string(),
+            "max_degree 2".to_string(),
+        ];
+        if let Some(ts) = tide_system {
+            lines.push(format!("tide_system {}", ts));
+        }
+        lines.push("end_of_head =========".to_string());
+        for l in [
+            "gfc 0 0 1.0 0.0",
+            "gfc 1 0 0.0 0.0",
+            "gfc 1 1 0.0 0.0",
+            "gfc 2 0 -4.841653717360E-04 0.0",
+            "gfc 2 1 0.0 0.0",
+            "gfc 2 2 2.439383573283E-06 -1.400273703859E-06",
+        ] {
+            lines.push(l.to_string());
+        }
+        Gravity::from_lines(&lines).unwrap()
+    }
+
+    #[test]
+    fn test_tide_system() {
+        let zt = test_model(Some("zero_tide"));
+        assert_eq!(zt.tide_system, TideSystem::ZeroTide);
+        assert_relative_eq!(zt.j2(), 4.841653717360E-04 * 5.0_f64.sqrt());
+
+        // Tide-free C20 is less negative than zero-tide by k20 * A0 * H0 = 4.2007e-9
+        let tf = zt.with_tide_system(TideSystem::TideFree).unwrap();
+        let dc20 = (tf.coeffs[(2, 0)] - zt.coeffs[(2, 0)]) / 5.0_f64.sqrt();
+        assert_relative_eq!(dc20, 4.2007e-9, max_relative = 1.0e-4);
+        assert_relative_eq!(
+            zt.tide_system_c20_delta(TideSystem::TideFree).unwrap(),
+            dc20,
+            max_relative = 1.0e-12
+        );
+        assert_relative_eq!(tf.permanent_tide_c20().unwrap(), 0.0);
+
+        // Mean-tide C20 is more negative than zero-tide by A0 * H0 = 1.3914e-8
+        let mt = zt.with_tide_system(TideSystem::MeanTide).unwrap();
+        let dc20 = (mt.coeffs[(2, 0)] - zt.coeffs[(2, 0)]) / 5.0_f64.sqrt();
+        assert_relative_eq!(dc20, -1.3914e-8, max_relative = 1.0e-4);
+
+        // Net J2 is consistent: tide-free < zero-tide < mean-tide,
+        // and conversions round-trip
+        assert!(tf.j2() < zt.j2() && zt.j2() < mt.j2());
+        let zt2 = mt
+            .with_tide_system(TideSystem::TideFree)
+            .unwrap()
+            .with_tide_system(TideSystem::ZeroTide)
+            .unwrap();
+        assert_relative_eq!(zt2.j2(), zt.j2(), max_relative = 1.0e-14);
+
+        // Other coefficients are untouched
+        assert_eq!(tf.coeffs[(2, 2)], zt.coeffs[(2, 2)]);
+        assert_eq!(tf.coeffs[(1, 2)], zt.coeffs[(1, 2)]);
+    }
+
+    #[test]
+    fn test_tide_system_unspecified() {
+        let g = test_model(None);
+        assert_eq!(g.tide_system, TideSystem::Unspecified);
+        assert!(g.permanent_tide_c20().is_err());
+        assert!(g.with_tide_system(TideSystem::TideFree).is_err());
+
+        let g = test_model(Some("tide_free"));
+        assert!(g.with_tide_system(TideSystem::Unspecified).is_err());
+    }
+
+    #[test]
+    fn test_accel_degree_order() {
+        let g = test_model(Some("zero_tide"));
+        let gm = g.gravity_constant;
+
+        // At large radius, reduces to point mass
+        let pos = Vec3::new(3.0e8, -4.0e8, 5.0e8);
+        let a = g.accel_degree_order(&pos, 2, 2);
+        assert_relative_eq!(a.norm(), gm / pos.norm_squared(), max_relative = 1.0e-7);
+        assert_relative_eq!(a.normalize(), -pos.normalize(), epsilon = 1.0e-7);
+
+        // Degree 0 is exactly point mass
+        let pos = Vec3::new(4.0e6, -3.0e6, 5.0e6);
+        let a = g.accel_degree_order(&pos, 0, 0);
+        assert_relative_eq!(a, -pos * gm / pos.norm().powi(3), max_relative = 1.0e-14);
+
+        // J2 produces latitude-dependent deviation in radial acceleration:
+        // a_r = -GM/r^2 * (1 - 3/2 J2 (R/r)^2 (3 sin^2(lat) - 1))
+        let r = 7.0e6;
+        let j2 = g.j2();
+        for latdeg in [0.0_f64, 30.0, 45.0, 60.0, 90.0] {
+            let lat = latdeg.to_radians();
+            let pos = Vec3::new(lat.cos(), 0.0, lat.sin()) * r;
+            let a = g.accel_degree_order(&pos, 2, 0);
+            let ar = a.dot(&pos.normalize());
+            let expected = -gm / (r * r)
+                * (1.0 - 1.5 * j2 * (g.radius / r).powi(2) * (3.0 * lat.sin().powi(2) - 1.0));
+            assert_relative_eq!(ar, expected, max_relative = 1.0e-12);
+
+            // Zonal terms only: symmetric in longitude
+            let rot = na::Rotation3::from_axis_angle(&Vec3::z_axis(), 1.2);
+            let a2 = g.accel_degree_order(&(rot * pos), 2, 0);
+            assert_relative_eq!(a2, rot * a, max_relative = 1.0e-12);
+        }
+
+        // Full degree & order matches accel; truncating order removes C22 & S22
+        assert_eq!(g.accel_degree_order(&pos, 2, 2), g.accel(&pos, 2));
+        assert_eq!(g.accel_degree_order(&pos, 2, 5), g.accel(&pos, 2));
+        let mut gz = g.clone();
+        gz.coeffs[(2, 2)] = 0.0;
+        gz.coeffs[(1, 2)] = 0.0;
+        assert_relative_eq!(
+            g.accel_degree_order(&pos, 2, 1),
+            gz.accel(&pos, 2),
+            max_relative = 1.0e-14
+        );
+    }
+
+    #[test]
+    fn test_with_constants() {
+        let g = test_model(Some("zero_tide"));
+        let g2 = g.with_constants(g.gravity_constant * (1.0 + 1.0e-6), g.radius + 100.0);
+        assert_eq!(g2.radius, g.radius + 100.0);
+
+        // Same potential shape, scaled by ratio of gravitational parameters
+        let pos = Vec3::new(4.0e6, -3.0e6, 5.0e6);
+        assert_relative_eq!(
+            g2.accel(&pos, 2),
+            g.accel(&pos, 2) * (1.0 + 1.0e-6),
+            max_relative = 1.0e-13
+        );
+        assert!(g2.j2() < g.j2());
+
+        // Unchanged constants give an identical model
+        let g3 = g.with_constants(g.gravity_constant, g.radius);
+        assert_eq!(g3.coeffs, g.coeffs);
+    }
+
+    #[test]
+    fn test_accel_delta() {
+        // Acceleration from a coefficient table should match that of a
+        // model with the same coefficients
+        let g = test_model(Some("zero_tide"));
+        let pos = Vec3::new(4.0e6, -3.0e6, 5.0e6);
+        let a1 = g.accel(&pos, 2);
+        let a2 = g.accel_delta(&pos, &g.coeffs);
+        assert_relative_eq!(a1, a2, max_relative = 1.0e-14);
+
+        // Linear in the coefficients
+        let mut dc = CoeffTable::zeros(5, 5);
+        dc[(2, 2)] = 1.0e-9;
+        let mut g2 = g.clone();
+        g2.coeffs[(2, 2)] += 1.0e-9;
+        let da = g2.accel(&pos, 2) - g.accel(&pos, 2);
+        assert_relative_eq!(g.accel_delta(&pos, &dc), da, max_relative = 1.0e-6);
+    }
 }
@@ -1 +1,2 @@
+pub mod rts;
 pub mod ukf;
@@ -0,0 +1,198 @@
+//! Rauch-Tung-Striebel fixed-interval smoother
+//!
+//! Backward pass over the output of a forward (extended) Kalman filter,
+//! giving an estimate at each time that uses all of the measurements,
+//! not only those up to that time
+
+use crate::orbitprop::{CovFrame, SatState, StateCov};
+use crate::skerror;
+use crate::types::Matrix6;
+use crate::SKResult;
+
+/// GCRF covariance of a filter state, or error if it has none
+fn state_cov(state: &SatState, name: &str, ix: usize) -> SKResult<Matrix6> {
+    match state.in_frame(CovFrame::GCRF).cov {
+        StateCov::PVCov(cov) => Ok(cov),
+        StateCov::None => skerror!("{} state {} has no covariance", name, ix),
+    }
+}
+
+/// Rauch-Tung-Striebel smoother
+///
+/// Runs the backward RTS recursion over the stored output of a forward
+/// extended Kalman filter
+///
+/// # Arguments
+///
+/// * `filtered_states` - Filtered (a posteriori) states, with covariance,
+///   in increasing time order
+/// * `predicted_states` - Predicted (a priori) states, with covariance,
+///   before the measurement update.  `predicted_states[k]` is the prediction
+///   of `filtered_states[k]` to the time of `filtered_states[k + 1]`,
+///   including process noise
+/// * `stms` - State transition matrices.  `stms[k]` maps the state at the
+///   time of `filtered_states[k]` to the time of `filtered_states[k + 1]`
+///
+/// # Returns
+///
+/// * Smoothed states, with covariance, at the times of `filtered_states`.
+///   The last smoothed state is the last filtered state
+///
+/// # Notes
+///
+/// * `predicted_states` & `stms` must both have one fewer element than
+///   `filtered_states`.  Predicted state times are checked against the
+///   filtered state times, so that each state transition matrix is applied
+///   between the correct pair of times
+/// * Consider parameter covariance, if any, is copied from the filtered states
+/// * Smoothed covariances are in the GCRF, whatever the covariance frame of
+///   the input states
+pub fn rts_smooth(
+    filtered_states: &[SatState],
+    predicted_states: &[SatState],
+    stms: &[Matrix6],
+) -> SKResult<Vec<SatState>> {
+    let n = filtered_states.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    if predicted_states.len() != n - 1 || stms.len() != n - 1 {
+        return skerror!(
+            "Expected {} predicted states & state transition matrices for {} filtered states; got {} & {}",
+            n - 1,
+            n,
+            predicted_states.len(),
+            stms.len()
+        );
+    }
+    for ix in 0..n - 1 {
+        if filtered_states[ix + 1].time <= filtered_states[ix].time {
+            return skerror!("Filtered states must be in increasing time order");
+        }
+        if predicted_states[ix].time != filtered_states[ix + 1].time {
+            return skerror!(
+                "Predicted state {} time {} does not match filtered state {} time {}",
+                ix,
+                predicted_states[ix].time,
+                ix + 1,
+                filtered_states[ix + 1].time
+            );
+        }
+    }
+
+    let mut smoothed: Vec<SatState> = filtered_states
+        .iter()
+        .map(|s| s.in_frame(CovFrame::GCRF))
+        .collect();
+    let mut pv_s = filtered_states[n - 1].pv;
+    let mut cov_s = state_cov(&filtered_states[n - 1], "Filtered", n - 1)?;
+    for ix in (0..n - 1).rev() {
+        let cov_f = state_cov(&filtered_states[ix], "Filtered", ix)?;
+        let cov_p = state_cov(&predicted_states[ix], "Predicted", ix)?;
+        let cov_p_inv = match cov_p.try_inverse() {
+            Some(v) => v,
+            None => return skerror!("Predicted covariance {} is singular", ix),
+        };
+
+        // Smoother gain
+        let gain = cov_f * stms[ix].transpose() * cov_p_inv;
+        pv_s = filtered_states[ix].pv + gain * (pv_s - predicted_states[ix].pv);
+        cov_s = cov_f + gain * (cov_s - cov_p) * gain.transpose();
+        // Enforce symmetry against roundoff
+        cov_s = 0.5 * (cov_s + cov_s.transpose());
+
+        smoothed[ix].pv = pv_s;
+        smoothed[ix].cov = StateCov::PVCov(cov_s);
+    }
+    Ok(smoothed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Matrix, Vector6};
+    use crate::{Duration, Instant};
+
+    #[test]
+    fn test_rts_smooth() -> SKResult<()> {
+        // Linear Kalman filter on constant-velocity motion, with
+        // position measurements at 10-second intervals
+        let dt = 10.0;
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let mut stm = Matrix6::identity();
+        stm.fixed_view_mut::<3, 3>(0, 3)
+            .copy_from(&(Matrix::<3, 3>::identity() * dt));
+        let q = Matrix6::from_diagonal(&Vector6::new(0.1, 0.1, 0.1, 1.0e-3, 1.0e-3, 1.0e-3));
+        let r = Matrix::<3, 3>::identity() * 100.0;
+        let mut h = Matrix::<3, 6>::zeros();
+        h.fixed_view_mut::<3, 3>(0, 0)
+            .copy_from(&Matrix::<3, 3>::identity());
+
+        let truth = |k: usize| {
+            let t = dt * k as f64;
+            Vector6::new(1.0e3 + 5.0 * t, -2.0e3 + 3.0 * t, 500.0 - t, 5.0, 3.0, -1.0)
+        };
+        // Deterministic pseudo-random measurement noise
+        let noise = |k: usize, j: usize| ((k * 7 + j * 13) as f64 * 1.7).sin() * 10.0;
+
+        let mut state = SatState {
+            time: start,
+            pv: truth(0) + Vector6::new(20.0, -20.0, 10.0, 1.0, -1.0, 0.5),
+            cov: StateCov::PVCov(Matrix6::from_diagonal(&Vector6::new(
+                1.0e4, 1.0e4, 1.0e4, 10.0, 10.0, 10.0,
+            ))),
+            consider: None,
+            cov_frame: CovFrame::GCRF,
+        };
+
+        let nsteps = 30;
+        let mut filtered = vec![state.clone()];
+        let mut predicted = Vec::new();
+        let mut stms = Vec::new();
+        for k in 1..=nsteps {
+            let cov = state_cov(&state, "Filtered", k - 1)?;
+            let pred = SatState {
+                time: start + Duration::from_seconds(dt * k as f64),
+                pv: stm * state.pv,
+                cov: StateCov::PVCov(stm * cov * stm.transpose() + q),
+                consider: None,
+                cov_frame: CovFrame::GCRF,
+            };
+            let cov_p = state_cov(&pred, "Predicted", k - 1)?;
+            let y = truth(k).fixed_rows::<3>(0)
+                + crate::types::Vector3::new(noise(k, 0), noise(k, 1), noise(k, 2));
+            let s = h * cov_p * h.transpose() + r;
+            let gain = cov_p * h.transpose() * s.try_inverse().unwrap();
+            state = SatState {
+                time: pred.time,
+                pv: pred.pv + gain * (y - h * pred.pv),
+                cov: StateCov::PVCov((Matrix6::identity() - gain * h) * cov_p),
+                consider: None,
+                cov_frame: CovFrame::GCRF,
+            };
+            predicted.push(pred);
+            stms.push(stm);
+            filtered.push(state.clone());
+        }
+
+        let smoothed = rts_smooth(&filtered, &predicted, &stms)?;
+        assert_eq!(smoothed.len(), filtered.len());
+
+        // Smoothed covariance is smaller than filtered at interior
+        // times, and identical at the last time
+        for ix in 1..nsteps {
+            let cf = state_cov(&filtered[ix], "Filtered", ix)?;
+            let cs = state_cov(&smoothed[ix], "Smoothed", ix)?;
+            assert!(cs.trace() < cf.trace());
+            assert!((cf - cs).symmetric_eigenvalues().min() > -1.0e-9);
+        }
+        let cf = state_cov(&filtered[nsteps], "Filtered", nsteps)?;
+        let cs = state_cov(&smoothed[nsteps], "Smoothed", nsteps)?;
+        assert!((cf - cs).norm() < 1.0e-12);
+
+        // Mismatched times are rejected
+        assert!(rts_smooth(&filtered[1..], &predicted, &stms[1..]).is_err());
+        assert!(rts_smooth(&filtered[..nsteps], &predicted[1..], &stms[1..]).is_err());
+        Ok(())
+    }
+}
@@ -10,6 +10,8 @@ type Vector<const T: usize> = nalgebra::SVector<f64, T>;
 
 /// Generic float matrix type of fixed size
 type Matrix<const M: usize, const N: usize> = nalgebra::SMatrix<f64, M, N>;
+use crate::orbitprop::{CovFrame, SatState, StateCov};
+use crate::skerror;
 use crate::SKResult;
 
 /// Unscented Kalman Filter
@@ -222,6 +224,93 @@ impl<const N: usize> UKF<N> {
     }
 }
 
+/// Unscented transform parameters
+///
+/// See [`UKF`] for their meaning
+#[derive(Debug, Clone, Copy)]
+pub struct UTParams {
+    /// Spread of sigma points about the mean
+    pub alpha: f64,
+    /// Prior knowledge of the distribution; 2 is optimal for Gaussian
+    pub beta: f64,
+    /// Secondary scaling parameter
+    pub kappa: f64,
+}
+
+impl Default for UTParams {
+    /// Same values as [`UKF::new_default`]
+    fn default() -> Self {
+        Self {
+            alpha: 0.001,
+            beta: 2.0,
+            kappa: 0.0,
+        }
+    }
+}
+
+/// Unscented Kalman filter measurement update of a satellite state
+///
+/// Sigma points are generated from the state & covariance, pushed through
+/// the nonlinear measurement function, and used to compute the
+/// predicted measurement covariance, the state-measurement cross-covariance,
+/// and the Kalman gain
+///
+/// # Arguments
+///
+/// * `state` - Satellite state, with position & velocity covariance
+/// * `measurement` - Measurement vector
+/// * `meas_fn` - Function computing the measurement from the
+///   6-element GCRF position & velocity state
+/// * `noise` - Measurement noise covariance
+/// * `ut_params` - Unscented transform parameters
+///
+/// # Returns
+///
+/// * Updated state & covariance, or error if the state has no covariance
+///   or its covariance is not positive definite
+///
+/// # Notes
+///
+/// * Consider parameter covariance, if any, is copied unchanged
+/// * Covariance in a local frame is converted to the GCRF for the update,
+///   and the updated covariance expressed in the same local frame
+pub fn ukf_update<const M: usize>(
+    state: &SatState,
+    measurement: &Vector<M>,
+    meas_fn: impl Fn(Vector<6>) -> SKResult<Vector<M>>,
+    noise: &Matrix<M, M>,
+    ut_params: &UTParams,
+) -> SKResult<SatState> {
+    if state.cov_frame != CovFrame::GCRF {
+        let updated = ukf_update(
+            &state.in_frame(CovFrame::GCRF),
+            measurement,
+            meas_fn,
+            noise,
+            ut_params,
+        )?;
+        return Ok(updated.in_frame(state.cov_frame));
+    }
+    let cov = match state.cov {
+        StateCov::PVCov(cov) => cov,
+        StateCov::None => return skerror!("State has no covariance"),
+    };
+    if cov.cholesky().is_none() {
+        return skerror!("State covariance is not positive definite");
+    }
+
+    let mut ukf = UKF::<6>::new(ut_params.alpha, ut_params.beta, ut_params.kappa);
+    ukf.x = state.pv;
+    ukf.p = cov;
+    ukf.update(measurement, noise, meas_fn)?;
+
+    let mut updated = state.clone();
+    updated.pv = ukf.x;
+    // Enforce symmetry against roundoff
+    updated.cov = StateCov::PVCov(0.5 * (ukf.p + ukf.p.transpose()));
+    Ok(updated)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,4 +343,82 @@ mod tests {
             ukf.p += q;
         }
     }
+
+    #[test]
+    fn test_ukf_update() {
+        use rand::SeedableRng;
+
+        // Angles-only measurement from a nearby observer, with a prior
+        // cross-range uncertainty that is large relative to the range, so
+        // the angles saturate & are strongly nonlinear over the prior
+        let observer = Vector::<3>::new(6.4e6, 0.0, 0.0);
+        let meas = |x: Vector<6>| -> SKResult<Vector<2>> {
+            let rho = x.fixed_rows::<3>(0) - observer;
+            Ok(Vector::<2>::new(
+                rho[1].atan2(rho[0]),
+                (rho[2] / rho.norm()).asin(),
+            ))
+        };
+        let prior = Vector::<6>::new(6.5e6, 0.0, 0.0, 0.0, 7.5e3, 0.0);
+        let sigma = Vector::<6>::new(1.0e4, 2.0e5, 2.0e5, 10.0, 10.0, 10.0);
+        let cov = Matrix::<6, 6>::from_diagonal(&sigma.component_mul(&sigma));
+        let sigma_meas = 1.0e-3;
+        let noise = Matrix::<2, 2>::identity() * sigma_meas * sigma_meas;
+        // kappa = 3 - N matches the fourth moments of a Gaussian
+        let params = UTParams {
+            alpha: 1.0,
+            beta: 2.0,
+            kappa: -3.0,
+        };
+        let state = SatState {
+            time: crate::Instant::from_datetime(2024, 1, 1, 0, 0, 0.0),
+            pv: prior,
+            cov: StateCov::PVCov(cov),
+            consider: None,
+            cov_frame: CovFrame::GCRF,
+        };
+
+        // EKF gain, with measurement linearized at the prior mean
+        let mut h = Matrix::<2, 6>::zeros();
+        for j in 0..3 {
+            let mut dx = Vector::<6>::zeros();
+            dx[j] = 1.0;
+            let col = (meas(prior + dx).unwrap() - meas(prior - dx).unwrap()) / 2.0;
+            h.set_column(j, &col);
+        }
+        let s = h * cov * h.transpose() + noise;
+        let ekf_gain = cov * h.transpose() * s.try_inverse().unwrap();
+
+        // Monte-Carlo over truth drawn from the prior
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let (mut err_ukf, mut err_ekf) = (0.0, 0.0);
+        let ntrials = 500;
+        for _ix in 0..ntrials {
+            let truth = prior + Vector::<6>::from_fn(|i, _| sigma[i] * normal.sample(&mut rng));
+            let y = meas(truth).unwrap()
+                + Vector::<2>::from_fn(|_, _| sigma_meas * normal.sample(&mut rng));
+
+            let ukf = ukf_update(&state, &y, meas, &noise, &params).unwrap();
+            err_ukf += (ukf.pv - truth).fixed_rows::<3>(0).norm_squared();
+
+            let pv_ekf = prior + ekf_gain * (y - meas(prior).unwrap());
+            err_ekf += (pv_ekf - truth).fixed_rows::<3>(0).norm_squared();
+        }
+        let rms_ukf = (err_ukf / ntrials as f64).sqrt();
+        let rms_ekf = (err_ekf / ntrials as f64).sqrt();
+        assert!(rms_ukf < rms_ekf);
+
+        // Non positive-definite covariance is an error
+        let mut bad = cov;
+        bad[(0, 0)] = -1.0;
+        let state = SatState {
+            time: crate::Instant::from_datetime(2024, 1, 1, 0, 0, 0.0),
+            pv: prior,
+            cov: StateCov::PVCov(bad),
+            consider: None,
+            cov_frame: CovFrame::GCRF,
+        };
+        assert!(ukf_update(&state, &meas(prior).unwrap(), meas, &noise, &params).is_err());
+    }
 }
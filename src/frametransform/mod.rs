@@ -11,6 +11,7 @@ pub type Quat = na::UnitQuaternion<f64>;
 use super::earth_orientation_params;
 pub use qcirs2gcrs::qcirs2gcrs;
 pub use qcirs2gcrs::qcirs2gcrs_dxdy;
+pub(crate) use qcirs2gcrs::lunisolar_arguments;
 
 /// Right-handed rotation of coordinate sytstem about x axis
 /// (left-handed rotation of vector)
@@ -80,6 +81,41 @@ pub fn gast(tm: &Instant) -> f64 {
     gmst(tm) + eqeq(tm)
 }
 
+///
+/// Local Apparent Sidereal Time
+///
+/// # Arguments
+///
+/// * `longitude` - Site longitude, radians, positive east of Greenwich
+/// * `tm` - Instant object representing input time
+///
+/// # Returns
+///
+/// * `last` - in radians, in range [0, 2π)
+///
+pub fn last(longitude: f64, tm: &Instant) -> f64 {
+    crate::angles::wrap_to_2pi(gast(tm) + longitude)
+}
+
+///
+/// Local hour angle of an object, the angle measured westward
+/// along the equator from the local meridian to the object
+///
+/// # Arguments
+///
+/// * `ra` - Apparent right ascension of object, radians
+/// * `longitude` - Site longitude, radians, positive east of Greenwich
+/// * `tm` - Instant object representing input time
+///
+/// # Returns
+///
+/// * Local hour angle, in radians, in range [-π, π).  Zero at transit,
+///   negative for objects rising east of the meridian, positive west
+///
+pub fn local_hour_angle(ra: f64, longitude: f64, tm: &Instant) -> f64 {
+    crate::angles::wrap_to_pi(last(longitude, tm) - ra)
+}
+
 ///
 /// Earth Rotation Angle
 ///
@@ -401,6 +437,29 @@ pub fn qgcrf2itrf(tm: &Instant) -> Quat {
     qitrf2gcrf(tm).conjugate()
 }
 
+/// ITRF position & Earth-fixed velocity from GCRF position & velocity
+///
+/// The velocity is relative to the rotating Earth: the GCRF velocity
+/// rotated to the ITRF, less the Earth rotation rate crossed with the
+/// position.  The much slower precession, nutation & polar motion rates
+/// are neglected
+///
+/// # Arguments
+///
+/// * `q` - Rotation from GCRF to ITRF at the state time, e.g. from
+///   [`qgcrf2itrf`] or [`qgcrf2itrf_approx`]
+/// * `pv` - GCRF position (m) & velocity (m/s)
+///
+/// # Returns
+///
+/// * Tuple with ITRF position (m) & velocity (m/s)
+pub(crate) fn pv_gcrf2itrf(q: &Quat, pv: &crate::types::Vector6) -> (Vec3, Vec3) {
+    let omega = Vec3::new(0.0, 0.0, crate::consts::OMEGA_EARTH);
+    let pos = q * pv.fixed_rows::<3>(0);
+    let vel = q * pv.fixed_rows::<3>(3) - omega.cross(&pos);
+    (pos, vel)
+}
+
 ///
 /// Quaternion representing rotation from the
 /// Terrestrial Intermediate Reference System
@@ -444,6 +503,35 @@ mod tests {
         assert!(((gmval - truth) / truth).abs() < 1.0e-6)
     }
 
+    #[test]
+    fn test_last() {
+        let tm = Instant::from_datetime(2024, 3, 20, 22, 5, 0.0);
+        let g = gast(&tm);
+
+        // Prime meridian: LAST is GAST, wrapped
+        let l0 = last(0.0, &tm);
+        assert!((0.0..2.0 * PI).contains(&l0));
+        assert!(crate::angles::wrap_to_pi(l0 - g).abs() < 1.0e-12);
+
+        // East longitudes are ahead of Greenwich
+        let lon = 120.0_f64.to_radians();
+        assert!(crate::angles::wrap_to_pi(last(lon, &tm) - g - lon).abs() < 1.0e-12);
+        for lon in [-179.0_f64, -90.0, 45.0, 359.0] {
+            assert!((0.0..2.0 * PI).contains(&last(lon.to_radians(), &tm)));
+        }
+
+        // Object at transit has zero hour angle, and an object
+        // 1 hour (15 degrees) east of the meridian has -1 hour
+        let lon = (-71.0_f64).to_radians();
+        let ra = last(lon, &tm);
+        assert!(local_hour_angle(ra, lon, &tm).abs() < 1.0e-12);
+        let ha = local_hour_angle(ra + 15.0_f64.to_radians(), lon, &tm);
+        assert!((ha + 15.0_f64.to_radians()).abs() < 1.0e-12);
+        let later = tm + Duration::from_hours(1.0);
+        let ha = local_hour_angle(ra, lon, &later);
+        assert!((ha - 15.04107_f64.to_radians()).abs() < 1.0e-6);
+    }
+
     #[test]
     fn test_gcrs2itrf() {
         // Example 3-14 from Vallado
@@ -26,17 +26,19 @@ fn table5d_singleton() -> &'static IERSTable {
     INSTANCE.get_or_init(|| IERSTable::from_file("tab5.2d.txt").unwrap())
 }
 
-pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
-    let t_tt = (tm.as_mjd_with_scale(TimeScale::TT) - 51544.5) / 36525.0;
+/// Fundamental lunisolar arguments (Delaunay variables) l, l', F, D, Omega,
+/// in radians
+///
+/// Equation 5.43 in IERS technical note 36
+///
+/// # Arguments
+///
+/// * `t_tt` - Julian centuries since J2000, TT
+pub(crate) fn lunisolar_arguments(t_tt: f64) -> [f64; 5] {
     const ASEC2RAD: f64 = PI / 180.0 / 3600.0;
 
-    let mut delaunay = Delaunay::zeros();
-
-    // Arguments for lunisolar nutation
-    // Equation 5.43 in IERS technical note 36
-
     // Mean anomaly of the Moon
-    delaunay[0] = ASEC2RAD
+    let l = ASEC2RAD
         * 3600.0f64.mul_add(
             134.96340251,
             t_tt * t_tt.mul_add(
@@ -46,7 +48,7 @@ pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
         );
 
     // Mean anomaly of the sun
-    delaunay[1] = ASEC2RAD
+    let lp = ASEC2RAD
         * 3600.0f64.mul_add(
             357.52910918,
             t_tt * t_tt.mul_add(
@@ -56,7 +58,7 @@ pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
         );
 
     // F = L-Omega
-    delaunay[2] = ASEC2RAD
+    let f = ASEC2RAD
         * 3600.0f64.mul_add(
             93.27209062,
             t_tt * t_tt.mul_add(
@@ -66,7 +68,7 @@ pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
         );
 
     // D = Mean elongation of the Moon from the Sun
-    delaunay[3] = ASEC2RAD
+    let d = ASEC2RAD
         * 3600.0f64.mul_add(
             297.85019547,
             t_tt * t_tt.mul_add(
@@ -76,7 +78,7 @@ pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
         );
 
     // Omega = mean longitude of ascending node of the Moon
-    delaunay[4] = ASEC2RAD
+    let om = ASEC2RAD
         * 3600.0f64.mul_add(
             125.04455501,
             t_tt * t_tt.mul_add(
@@ -85,6 +87,19 @@ pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
             ),
         );
 
+    [l, lp, f, d, om]
+}
+
+pub fn qcirs2gcrs_dxdy(tm: &Instant, dxdy: Option<(f64, f64)>) -> Quat {
+    let t_tt = (tm.as_mjd_with_scale(TimeScale::TT) - 51544.5) / 36525.0;
+    const ASEC2RAD: f64 = PI / 180.0 / 3600.0;
+
+    let mut delaunay = Delaunay::zeros();
+
+    // Arguments for lunisolar nutation
+    let args = lunisolar_arguments(t_tt);
+    delaunay.fixed_rows_mut::<5>(0).copy_from_slice(&args);
+
     // Planetary nutation
     // Equations 5.44 in IERS technical note 36
     delaunay[5] = 2608.7903141574f64.mul_add(t_tt, 4.402608842);
@@ -0,0 +1,368 @@
+//! Geoid undulation & gravity anomaly from spherical-harmonic gravity models
+//!
+//! The disturbing potential is the difference between the gravity model
+//! potential and the normal potential of the WGS-84 reference ellipsoid.
+//! Geoid undulation follows from Bruns' formula, and the free-air gravity
+//! anomaly from the fundamental equation of physical geodesy in spherical
+//! approximation.
+//!
+//! # Notes
+//!
+//! * The zero-degree term is included; it accounts for the difference in
+//!   gravitational constant between the model and the reference ellipsoid,
+//!   and for the difference between the conventional geoid potential
+//!   [`W0`] and the normal potential of the ellipsoid
+//! * Degree-1 terms are excluded, as the models are (by definition) in a
+//!   frame centered on the Earth's center of mass
+//! * Values are computed at the surface of the ellipsoid, so the undulation
+//!   is strictly a height anomaly.  The difference from the geoid height
+//!   is a function of the local topography, and is usually below a meter
+//!   except in mountainous areas
+//! * Results are in the tide system of the gravity model
+//!
+//! # References
+//!
+//! * Hofmann-Wellenhof & Moritz, "Physical Geodesy", 2nd edition, Springer, 2006
+//! * NIMA TR8350.2, "Department of Defense World Geodetic System 1984", 3rd edition, 2000
+
+use crate::earthgravity::{gravhash, Gravity, GravityModel};
+use crate::itrfcoord::ITRFCoord;
+
+/// WGS-84 semi-major axis, meters
+const WGS84_A: f64 = crate::consts::WGS84_A;
+/// WGS-84 flattening
+const WGS84_F: f64 = 1.0 / 298.257223563;
+/// WGS-84 gravitational constant, m^3/s^2
+const WGS84_GM: f64 = 3.986004418e14;
+/// WGS-84 angular velocity, rad/s
+const WGS84_OMEGA: f64 = 7.292115e-5;
+
+/// Conventional potential of the geoid, m^2/s^2 (IERS Conventions 2010, Table 1.1)
+pub const W0: f64 = 62636856.0;
+
+/// Normal gravity field of the WGS-84 ellipsoid, from its four defining constants
+#[derive(Debug, Clone)]
+pub struct NormalGravity {
+    /// Semi-major axis, meters
+    pub a: f64,
+    /// Gravitational constant, m^3/s^2
+    pub gm: f64,
+    /// First eccentricity squared
+    pub e2: f64,
+    /// Normal gravity at the equator, m/s^2
+    pub gamma_e: f64,
+    /// Normal gravity at the poles, m/s^2
+    pub gamma_p: f64,
+    /// Normal potential on the ellipsoid surface, m^2/s^2
+    pub u0: f64,
+    /// Fully-normalized even zonal coefficients C(2n, 0), n = 1..5
+    pub zonals: [f64; 5],
+}
+
+impl NormalGravity {
+    /// Normal gravity field of the WGS-84 ellipsoid
+    ///
+    /// # References
+    ///
+    /// * Hofmann-Wellenhof & Moritz, Sections 2.7 - 2.9
+    pub fn wgs84() -> Self {
+        let a = WGS84_A;
+        let b = a * (1.0 - WGS84_F);
+        let ee = a.mul_add(a, -b * b).sqrt();
+        let e2 = (ee / a).powi(2);
+        let ep = ee / b;
+        let m = WGS84_OMEGA.powi(2) * a * a * b / WGS84_GM;
+        let q0 = 0.5 * ((1.0 + 3.0 / (ep * ep)) * ep.atan() - 3.0 / ep);
+        let q0p = 3.0 * (1.0 + 1.0 / (ep * ep)) * (1.0 - ep.atan() / ep) - 1.0;
+
+        let gamma_e = WGS84_GM / (a * b) * (1.0 - m - m * ep * q0p / (6.0 * q0));
+        let gamma_p = WGS84_GM / (a * a) * (1.0 + m * ep * q0p / (3.0 * q0));
+        let u0 = WGS84_GM / ee * ep.atan() + WGS84_OMEGA.powi(2) * a * a / 3.0;
+
+        let j2 = e2 / 3.0 * (1.0 - 2.0 * m * ep / (15.0 * q0));
+        let mut zonals = [0.0; 5];
+        for (ix, z) in zonals.iter_mut().enumerate() {
+            let n = (ix + 1) as f64;
+            let j2n = -(-e2).powi(ix as i32 + 1) * 3.0 / ((2.0 * n + 1.0) * (2.0 * n + 3.0))
+                * (1.0 - n + 5.0 * n * j2 / e2);
+            *z = -j2n / (4.0 * n + 1.0).sqrt();
+        }
+
+        Self {
+            a,
+            gm: WGS84_GM,
+            e2,
+            gamma_e,
+            gamma_p,
+            u0,
+            zonals,
+        }
+    }
+
+    /// Normal gravity on the ellipsoid surface at the given geodetic latitude,
+    /// from the formula of Somigliana
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Geodetic latitude, radians
+    ///
+    /// # Returns
+    ///
+    /// * Normal gravity, m/s^2
+    pub fn gamma(&self, lat: f64) -> f64 {
+        let b = self.a * (1.0 - self.e2).sqrt();
+        let k = b * self.gamma_p / (self.a * self.gamma_e) - 1.0;
+        let s2 = lat.sin().powi(2);
+        self.gamma_e * k.mul_add(s2, 1.0) / self.e2.mul_add(-s2, 1.0).sqrt()
+    }
+}
+
+impl Gravity {
+    /// Disturbing potential (m^2/s^2) and free-air gravity anomaly (m/s^2)
+    /// at the ellipsoid surface
+    fn disturbing_potential(&self, normal: &NormalGravity, lat: f64, lon: f64) -> (f64, f64) {
+        let pos = ITRFCoord::from_geodetic_rad(lat, lon, 0.0).itrf;
+        let r = pos.norm();
+        let t = pos[2] / r;
+        let u = pos[0].hypot(pos[1]) / r;
+        let nmax = self.max_degree;
+        let coeffs = &self.coeffs_normalized;
+
+        // Model coefficients minus normal-field coefficients,
+        // the latter scaled to the model GM & radius
+        let gmratio = normal.gm / self.gravity_constant;
+        let dc = |n: usize, m: usize| -> f64 {
+            let mut c = coeffs[(n, m)];
+            if m == 0 {
+                if n == 0 {
+                    c -= gmratio;
+                } else if n.is_multiple_of(2) && n <= 2 * normal.zonals.len() {
+                    c -= gmratio
+                        * (normal.a / self.radius).powi(n as i32)
+                        * normal.zonals[n / 2 - 1];
+                }
+            }
+            c
+        };
+
+        let ar: Vec<f64> = (0..=nmax)
+            .map(|n| (self.radius / r).powi(n as i32))
+            .collect();
+
+        // Sectoral Legendre functions, divided by u^m
+        let mut qmm = vec![1.0; nmax + 1];
+        for m in 1..=nmax {
+            qmm[m] = match m {
+                1 => 3.0_f64.sqrt(),
+                _ => qmm[m - 1] * ((2 * m + 1) as f64 / (2 * m) as f64).sqrt(),
+            };
+        }
+
+        // Sum over order with Horner's scheme in u, so that high orders,
+        // which are scaled by u^m, do not underflow near the poles
+        let mut tsum = 0.0;
+        let mut gsum = 0.0;
+        for m in (0..=nmax).rev() {
+            let (sinml, cosml) = (m as f64 * lon).sin_cos();
+            let mut tm = 0.0;
+            let mut gm = 0.0;
+            let mut q2 = 0.0;
+            let mut q1 = qmm[m];
+            for n in m..=nmax {
+                let q = match n - m {
+                    0 => qmm[m],
+                    1 => ((2 * m + 3) as f64).sqrt() * t * qmm[m],
+                    _ => {
+                        let nf = n as f64;
+                        let mf = m as f64;
+                        let anm =
+                            ((2.0 * nf - 1.0) * (2.0 * nf + 1.0) / ((nf - mf) * (nf + mf))).sqrt();
+                        let bnm = ((2.0 * nf + 1.0) * (nf + mf - 1.0) * (nf - mf - 1.0)
+                            / ((nf - mf) * (nf + mf) * (2.0 * nf - 3.0)))
+                            .sqrt();
+                        anm * t * q1 - bnm * q2
+                    }
+                };
+                if n > m {
+                    q2 = q1;
+                    q1 = q;
+                }
+                if n == 1 {
+                    continue;
+                }
+                let mut cs = dc(n, m) * cosml;
+                if m > 0 {
+                    cs += coeffs[(m - 1, n)] * sinml;
+                }
+                let v = ar[n] * q * cs;
+                tm += v;
+                gm += (n as f64 - 1.0) * v;
+            }
+            tsum = tsum * u + tm;
+            gsum = gsum * u + gm;
+        }
+
+        let dw = W0 - normal.u0;
+        let tpot = self.gravity_constant / r * tsum - dw;
+        let anomaly = self.gravity_constant / (r * r) * gsum + 2.0 * dw / r;
+        (tpot, anomaly)
+    }
+
+    /// Geoid undulation (geoid height above the WGS-84 ellipsoid)
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Geodetic latitude, radians
+    /// * `lon` - Longitude, radians
+    ///
+    /// # Returns
+    ///
+    /// * Geoid undulation, meters
+    ///
+    /// # Notes
+    ///
+    /// * All terms of the model are used.  See [`crate::geoid`]
+    ///   for treatment of the zero- and first-degree terms
+    pub fn geoid_undulation(&self, lat: f64, lon: f64) -> f64 {
+        let normal = NormalGravity::wgs84();
+        let (tpot, _) = self.disturbing_potential(&normal, lat, lon);
+        tpot / normal.gamma(lat)
+    }
+
+    /// Free-air gravity anomaly at the surface of the WGS-84 ellipsoid
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Geodetic latitude, radians
+    /// * `lon` - Longitude, radians
+    ///
+    /// # Returns
+    ///
+    /// * Gravity anomaly, m/s^2 (1 mGal = 1.0e-5 m/s^2)
+    ///
+    /// # Notes
+    ///
+    /// * All terms of the model are used.  See [`crate::geoid`]
+    ///   for treatment of the zero- and first-degree terms
+    pub fn gravity_anomaly(&self, lat: f64, lon: f64) -> f64 {
+        let normal = NormalGravity::wgs84();
+        self.disturbing_potential(&normal, lat, lon).1
+    }
+}
+
+/// Geoid undulation (geoid height above the WGS-84 ellipsoid)
+///
+/// # Arguments
+///
+/// * `lat` - Geodetic latitude, radians
+/// * `lon` - Longitude, radians
+/// * `model` - The gravity model to use, of type "GravityModel"
+///
+/// # Returns
+///
+/// * Geoid undulation, meters
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::earthgravity::GravityModel;
+/// use satkit::geoid::geoid_undulation;
+///
+/// let n = geoid_undulation(0.0, 0.0, GravityModel::EGM96);
+/// println!("geoid height = {:.2} m", n);
+/// ```
+pub fn geoid_undulation(lat: f64, lon: f64, model: GravityModel) -> f64 {
+    gravhash().get(&model).unwrap().geoid_undulation(lat, lon)
+}
+
+/// Free-air gravity anomaly at the surface of the WGS-84 ellipsoid
+///
+/// # Arguments
+///
+/// * `lat` - Geodetic latitude, radians
+/// * `lon` - Longitude, radians
+/// * `model` - The gravity model to use, of type "GravityModel"
+///
+/// # Returns
+///
+/// * Gravity anomaly, m/s^2 (1 mGal = 1.0e-5 m/s^2)
+pub fn gravity_anomaly(lat: f64, lon: f64, model: GravityModel) -> f64 {
+    gravhash().get(&model).unwrap().gravity_anomaly(lat, lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_normal_gravity() {
+        // Derived constants, from NIMA TR8350.2 Tables 3.3 & 3.4
+        let ng = NormalGravity::wgs84();
+        assert_relative_eq!(ng.gamma_e, 9.7803253359, epsilon = 1.0e-10);
+        assert_relative_eq!(ng.gamma_p, 9.8321849378, epsilon = 1.0e-10);
+        assert_relative_eq!(ng.u0, 62636851.7146, epsilon = 1.0e-4);
+        assert_relative_eq!(ng.zonals[0], -0.484166774985e-3, max_relative = 1.0e-11);
+        assert_relative_eq!(ng.zonals[1], 0.790303733511e-6, max_relative = 1.0e-9);
+        assert_relative_eq!(ng.zonals[2], -0.168724961151e-8, max_relative = 1.0e-8);
+        assert_relative_eq!(ng.gamma(0.0), ng.gamma_e, epsilon = 1.0e-12);
+        assert_relative_eq!(
+            ng.gamma(std::f64::consts::FRAC_PI_2),
+            ng.gamma_p,
+            epsilon = 1.0e-12
+        );
+    }
+
+    #[test]
+    fn test_normal_model() {
+        // A gravity model equal to the normal field has zero
+        // undulation & anomaly, apart from the W0 - U0 offset
+        let ng = NormalGravity::wgs84();
+        let mut lines = vec![
+            "modelname normal".to_string(),
+            format!("earth_gravity_constant {:e}", ng.gm),
+            format!("radius {}", ng.a),
+            "max_degree 10".to_string(),
+            "end_of_head =========".to_string(),
+        ];
+        for n in 0..=10_usize {
+            for m in 0..=n {
+                let c = match (n, m) {
+                    (0, 0) => 1.0,
+                    (n, 0) if n.is_multiple_of(2) => ng.zonals[n / 2 - 1],
+                    _ => 0.0,
+                };
+                lines.push(format!("gfc {} {} {:e} 0.0", n, m, c));
+            }
+        }
+        let g = Gravity::from_lines(&lines).unwrap();
+        let dw = W0 - ng.u0;
+        for (lat, lon) in [
+            (0.0_f64, 0.0_f64),
+            (45.0, 100.0),
+            (-80.0, -30.0),
+            (90.0, 0.0),
+        ] {
+            let (lat, lon) = (lat.to_radians(), lon.to_radians());
+            assert_relative_eq!(
+                g.geoid_undulation(lat, lon),
+                -dw / ng.gamma(lat),
+                epsilon = 1.0e-6
+            );
+            let r = ITRFCoord::from_geodetic_rad(lat, lon, 0.0).itrf.norm();
+            assert_relative_eq!(g.gravity_anomaly(lat, lon), 2.0 * dw / r, epsilon = 1.0e-10);
+        }
+    }
+
+    #[test]
+    fn test_geoid_egm96() {
+        // NGA EGM96 test point (Gulf of Guinea), from the
+        // "intptdac.dat" / "outintpt.dat" reference values
+        let n = geoid_undulation(
+            -0.4667440_f64.to_radians(),
+            0.0023000_f64.to_radians(),
+            GravityModel::EGM96,
+        );
+        assert!((n - 17.329).abs() < 1.0, "n = {}", n);
+    }
+}
@@ -0,0 +1,331 @@
+//! Gauss method of angles-only initial orbit determination
+//!
+//! See Curtis, "Orbital Mechanics for Engineering Students",
+//! Algorithms 5.5 & 5.6, and Vallado, "Fundamentals of Astrodynamics
+//! and Applications", Algorithm 52
+
+use nalgebra as na;
+
+use super::Observation;
+use crate::consts::{EARTH_RADIUS, MU_EARTH};
+use crate::frametransform::qitrf2gcrf;
+use crate::orbitprop::{universal_anomaly, SatState};
+use crate::skerror;
+use crate::{ITRFCoord, SKResult};
+
+type Vec3 = na::Vector3<f64>;
+
+/// Maximum iterations for iterative refinement
+const MAX_ITER: usize = 100;
+
+/// Convergence tolerance of iterative refinement,
+/// relative change in slant range
+const REFINE_TOL: f64 = 1.0e-11;
+
+/// Minimum triple product of the line-of-sight unit vectors.
+/// Below this, lines of sight are nearly coplanar (e.g., very short arcs)
+/// and the slant ranges cannot be determined
+const MIN_D0: f64 = 1.0e-10;
+
+/// Lagrange f and g coefficients for two-body motion,
+/// via the universal-variable formulation
+///
+/// Position after time `dt` is `f * r0 + g * v0`
+///
+/// # Arguments
+///
+/// * `r0` - Initial position, meters
+/// * `v0` - Initial velocity, meters / second
+/// * `dt` - Time of flight, seconds
+fn lagrange_fg(r0: &Vec3, v0: &Vec3, dt: f64) -> SKResult<(f64, f64)> {
+    let r0n = r0.norm();
+    let beta = 2.0 * MU_EARTH / r0n - v0.norm_squared();
+    let (_, g) = universal_anomaly(r0n, r0.dot(v0), beta, MU_EARTH, dt)?;
+    Ok((1.0 - MU_EARTH * g[2] / r0n, dt - MU_EARTH * g[3]))
+}
+
+/// Positive real roots of the Gauss 8th-order polynomial
+/// x^8 + a x^6 + b x^3 + c = 0
+///
+/// Roots are found as eigenvalues of the companion matrix, then
+/// polished with Newton iterations.  The polynomial is scaled by
+/// `scale` to keep the companion matrix well conditioned
+fn gauss_poly_roots(a: f64, b: f64, c: f64, scale: f64) -> Vec<f64> {
+    let c6 = a / scale.powi(2);
+    let c3 = b / scale.powi(5);
+    let c0 = c / scale.powi(8);
+
+    let mut companion = na::SMatrix::<f64, 8, 8>::zeros();
+    for i in 0..7 {
+        companion[(i + 1, i)] = 1.0;
+    }
+    companion[(0, 7)] = -c0;
+    companion[(3, 7)] = -c3;
+    companion[(6, 7)] = -c6;
+
+    let mut roots: Vec<f64> = Vec::new();
+    for root in companion.complex_eigenvalues().iter() {
+        if root.re <= 0.0 || root.im.abs() > 1.0e-6 * root.re {
+            continue;
+        }
+        let mut y = root.re;
+        for _ in 0..10 {
+            let p = y.powi(8) + c6 * y.powi(6) + c3 * y.powi(3) + c0;
+            let dp = 8.0 * y.powi(7) + 6.0 * c6 * y.powi(5) + 3.0 * c3 * y * y;
+            y -= p / dp;
+        }
+        if y > 0.0 && !roots.iter().any(|r| (r - y).abs() < 1.0e-9 * y) {
+            roots.push(y);
+        }
+    }
+    roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    roots.into_iter().map(|y| y * scale).collect()
+}
+
+/// Gauss angles-only initial orbit determination
+///
+/// Estimate satellite position & velocity from three angles-only
+/// observations taken from a single ground site
+///
+/// The Gauss 8th-order polynomial may have more than one physically
+/// meaningful root.  This function returns the solution with the smallest
+/// geocentric distance at the middle observation; use [`gauss_iod_all`]
+/// to get all candidate solutions
+///
+/// # Arguments
+///
+/// * `obs` - Three observations, in increasing time order
+/// * `site` - Location of observer
+///
+/// # Returns
+///
+/// * Satellite state at the time of the middle observation
+///
+/// # Notes
+///
+/// * The initial estimate is refined by iterating on the exact
+///   Lagrange coefficients (Curtis Algorithm 5.6)
+/// * Observations spanning a very short arc have nearly coplanar lines
+///   of sight and are poorly conditioned; an error is returned if the
+///   slant ranges cannot be determined
+pub fn gauss_iod(obs: &[Observation; 3], site: &ITRFCoord) -> SKResult<SatState> {
+    let mut candidates = gauss_iod_all(obs, site)?;
+    Ok(candidates.remove(0))
+}
+
+/// Gauss angles-only initial orbit determination, returning
+/// all physically meaningful candidate solutions
+///
+/// Candidates correspond to the positive real roots of the Gauss
+/// 8th-order polynomial that are above the Earth's surface and produce
+/// positive slant ranges for all observations.  They are ordered by
+/// increasing geocentric distance at the middle observation
+///
+/// # Arguments
+///
+/// * `obs` - Three observations, in increasing time order
+/// * `site` - Location of observer
+///
+/// # Returns
+///
+/// * Candidate satellite states at the time of the middle observation
+pub fn gauss_iod_all(obs: &[Observation; 3], site: &ITRFCoord) -> SKResult<Vec<SatState>> {
+    let sites = [0, 1, 2].map(|ix| qitrf2gcrf(&obs[ix].time) * site.itrf);
+    gauss_iod_gcrf(obs, &sites)
+}
+
+/// Gauss angles-only initial orbit determination, with
+/// observer locations given in the GCRF frame
+fn gauss_iod_gcrf(obs: &[Observation; 3], sites: &[Vec3; 3]) -> SKResult<Vec<SatState>> {
+    let tau1 = (obs[0].time - obs[1].time).as_seconds();
+    let tau3 = (obs[2].time - obs[1].time).as_seconds();
+    let tau = tau3 - tau1;
+    if tau1 >= 0.0 || tau3 <= 0.0 {
+        return skerror!("Observations must be in increasing time order");
+    }
+
+    let rhohat = [0, 1, 2].map(|ix| obs[ix].los.normalize());
+    let p = [
+        rhohat[1].cross(&rhohat[2]),
+        rhohat[0].cross(&rhohat[2]),
+        rhohat[0].cross(&rhohat[1]),
+    ];
+    let d0 = rhohat[0].dot(&p[0]);
+    if d0.abs() < MIN_D0 {
+        return skerror!(
+            "Lines of sight are nearly coplanar (triple product = {:e}); arc may be too short",
+            d0
+        );
+    }
+    let d = na::Matrix3::<f64>::from_fn(|i, j| sites[i].dot(&p[j]));
+
+    // Coefficients of 8th-order polynomial in geocentric distance at middle observation
+    let biga = (-d[(0, 1)] * tau3 / tau + d[(1, 1)] + d[(2, 1)] * tau1 / tau) / d0;
+    let bigb = (d[(0, 1)] * (tau3 * tau3 - tau * tau) * tau3 / tau
+        + d[(2, 1)] * (tau * tau - tau1 * tau1) * tau1 / tau)
+        / (6.0 * d0);
+    let e = sites[1].dot(&rhohat[1]);
+    let a = -(biga * biga + 2.0 * biga * e + sites[1].norm_squared());
+    let b = -2.0 * MU_EARTH * bigb * (biga + e);
+    let c = -(MU_EARTH * bigb).powi(2);
+
+    let mut candidates: Vec<SatState> = Vec::new();
+    for r2 in gauss_poly_roots(a, b, c, sites[1].norm()) {
+        if r2 <= EARTH_RADIUS {
+            continue;
+        }
+        let r23 = r2.powi(3);
+
+        // Slant ranges, Curtis Equations 5.112 - 5.114
+        let rho1 = ((6.0 * (d[(2, 0)] * tau1 / tau3 + d[(1, 0)] * tau / tau3) * r23
+            + MU_EARTH * d[(2, 0)] * (tau * tau - tau1 * tau1) * tau1 / tau3)
+            / (6.0 * r23 + MU_EARTH * (tau * tau - tau3 * tau3))
+            - d[(0, 0)])
+            / d0;
+        let rho2 = biga + MU_EARTH * bigb / r23;
+        let rho3 = ((6.0 * (d[(0, 2)] * tau3 / tau1 - d[(1, 2)] * tau / tau1) * r23
+            + MU_EARTH * d[(0, 2)] * (tau * tau - tau3 * tau3) * tau3 / tau1)
+            / (6.0 * r23 + MU_EARTH * (tau * tau - tau1 * tau1))
+            - d[(2, 2)])
+            / d0;
+        if rho1 <= 0.0 || rho2 <= 0.0 || rho3 <= 0.0 {
+            continue;
+        }
+
+        // Lagrange coefficients from truncated series
+        let mut f1 = 1.0 - 0.5 * MU_EARTH * tau1 * tau1 / r23;
+        let mut g1 = tau1 - MU_EARTH * tau1.powi(3) / (6.0 * r23);
+        let mut f3 = 1.0 - 0.5 * MU_EARTH * tau3 * tau3 / r23;
+        let mut g3 = tau3 - MU_EARTH * tau3.powi(3) / (6.0 * r23);
+
+        let mut rho = [rho1, rho2, rho3];
+        let pos = |rho: &[f64; 3], ix: usize| sites[ix] + rhohat[ix] * rho[ix];
+        let mut r2vec = pos(&rho, 1);
+        let mut v2vec = (pos(&rho, 2) * f1 - pos(&rho, 0) * f3) / (f1 * g3 - f3 * g1);
+
+        // Iterative refinement with exact Lagrange coefficients,
+        // Curtis Algorithm 5.6.  Coefficients are averaged with their
+        // previous values to damp oscillation.  If refinement fails,
+        // the last valid estimate is kept
+        for _ in 0..MAX_ITER {
+            let (Ok((f1new, g1new)), Ok((f3new, g3new))) = (
+                lagrange_fg(&r2vec, &v2vec, tau1),
+                lagrange_fg(&r2vec, &v2vec, tau3),
+            ) else {
+                break;
+            };
+            let (f1n, g1n) = (0.5 * (f1 + f1new), 0.5 * (g1 + g1new));
+            let (f3n, g3n) = (0.5 * (f3 + f3new), 0.5 * (g3 + g3new));
+
+            let den = f1n * g3n - f3n * g1n;
+            let c1 = g3n / den;
+            let c3 = -g1n / den;
+            let rhonew = [
+                (-d[(0, 0)] + d[(1, 0)] / c1 - c3 / c1 * d[(2, 0)]) / d0,
+                (-c1 * d[(0, 1)] + d[(1, 1)] - c3 * d[(2, 1)]) / d0,
+                (-c1 / c3 * d[(0, 2)] + d[(1, 2)] / c3 - d[(2, 2)]) / d0,
+            ];
+            if rhonew.iter().any(|r| !r.is_finite() || *r <= 0.0) {
+                break;
+            }
+            let maxchange = (0..3)
+                .map(|ix| ((rhonew[ix] - rho[ix]) / rhonew[ix]).abs())
+                .fold(0.0, f64::max);
+
+            (f1, g1, f3, g3) = (f1n, g1n, f3n, g3n);
+            rho = rhonew;
+            r2vec = pos(&rho, 1);
+            v2vec = (pos(&rho, 2) * f1 - pos(&rho, 0) * f3) / den;
+
+            if maxchange < REFINE_TOL {
+                break;
+            }
+        }
+
+        candidates.push(SatState::from_pv(&obs[1].time, &r2vec, &v2vec));
+    }
+
+    if candidates.is_empty() {
+        return skerror!("No physically meaningful solution found");
+    }
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kepler::{Anomaly, Kepler};
+    use crate::{Duration, Instant};
+
+    #[test]
+    fn test_lagrange_fg() -> SKResult<()> {
+        // Compare against Keplerian propagation for an eccentric orbit
+        let kep = Kepler::new(9000.0e3, 0.2, 0.7, 1.1, 0.4, Anomaly::True(0.3));
+        let (r0, v0) = kep.to_pv();
+        for dt in [-1500.0, 300.0, 4000.0] {
+            let (r1, _v1) = kep.propagate(&Duration::from_seconds(dt)).to_pv();
+            let (f, g) = lagrange_fg(&r0, &v0, dt)?;
+            assert!((r0 * f + v0 * g - r1).norm() < 1.0e-3);
+        }
+        Ok(())
+    }
+
+    /// Site rotating with the Earth, in an approximate inertial frame
+    fn site_gcrf(t: f64) -> Vec3 {
+        let lat = 40.0_f64.to_radians();
+        let r0 = Vec3::new(lat.cos(), 0.0, lat.sin()) * EARTH_RADIUS;
+        na::Rotation3::from_axis_angle(&Vec3::z_axis(), 7.292115e-5 * t) * r0
+    }
+
+    #[test]
+    fn test_gauss_iod() -> SKResult<()> {
+        let t2 = Instant::from_datetime(2024, 3, 15, 4, 30, 0.0);
+
+        // Satellite in low-Earth orbit, nearly overhead at middle observation
+        let site = site_gcrf(0.0);
+        let r2 = site.normalize() * 7200.0e3 + Vec3::new(3.0e5, -2.0e5, 0.0);
+        let v2 = Vec3::z().cross(&r2).normalize() * 7.0e3 + Vec3::new(0.0, 0.0, 3.0e3);
+
+        let dts = [-120.0, 0.0, 150.0];
+        let mut obs: Vec<Observation> = Vec::new();
+        let mut sites: Vec<Vec3> = Vec::new();
+        for dt in dts {
+            let (f, g) = lagrange_fg(&r2, &v2, dt)?;
+            let sat = r2 * f + v2 * g;
+            let s = site_gcrf(dt);
+            obs.push(Observation {
+                time: t2 + Duration::from_seconds(dt),
+                los: (sat - s).normalize(),
+            });
+            sites.push(s);
+        }
+        let obs: [Observation; 3] = obs.try_into().unwrap();
+        let sites: [Vec3; 3] = sites.try_into().unwrap();
+
+        let candidates = gauss_iod_gcrf(&obs, &sites)?;
+        let state = candidates
+            .iter()
+            .min_by(|a, b| {
+                let da = (a.pos_gcrf() - r2).norm();
+                let db = (b.pos_gcrf() - r2).norm();
+                da.partial_cmp(&db).unwrap()
+            })
+            .unwrap();
+        assert_eq!(state.time, t2);
+        assert!((state.pos_gcrf() - r2).norm() < 1.0);
+        assert!((state.vel_gcrf() - v2).norm() < 1.0e-3);
+
+        // Out-of-order observations are rejected
+        let swapped = [obs[1].clone(), obs[0].clone(), obs[2].clone()];
+        assert!(gauss_iod_gcrf(&swapped, &sites).is_err());
+
+        // Degenerate geometry: identical lines of sight
+        let degenerate = [0, 1, 2].map(|ix| Observation {
+            time: obs[ix].time,
+            los: obs[1].los,
+        });
+        assert!(gauss_iod_gcrf(&degenerate, &sites).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Line-of-sight unit vectors from angles-only observations
+
+use nalgebra as na;
+
+use crate::frametransform::qitrf2gcrf;
+use crate::refraction::{refraction_apparent, STANDARD_PRESSURE, STANDARD_TEMPERATURE};
+use crate::{ITRFCoord, Instant};
+
+type Vec3 = na::Vector3<f64>;
+
+/// Line-of-sight unit vector in the GCRF frame from
+/// an azimuth & elevation observation
+///
+/// # Arguments
+///
+/// * `observer` - Location of observer
+/// * `az` - Azimuth, radians, clockwise from north
+/// * `el` - Elevation above local horizon, radians
+/// * `time` - Time of observation
+/// * `correct_refraction` - If true, elevation is the apparent (refracted)
+///   elevation, and atmospheric refraction for standard conditions is
+///   removed.  If false, elevation is taken to be geometric
+///
+/// # Returns
+///
+/// * Unit vector from observer in direction of the observation, GCRF frame
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::{ITRFCoord, Instant};
+/// use satkit::iod::los_gcrf;
+///
+/// let observer = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+/// let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+/// let los = los_gcrf(&observer, 0.5, 0.8, &time, true);
+/// ```
+pub fn los_gcrf(
+    observer: &ITRFCoord,
+    az: f64,
+    el: f64,
+    time: &Instant,
+    correct_refraction: bool,
+) -> Vec3 {
+    let el = match correct_refraction {
+        true => el - refraction_apparent(el, STANDARD_PRESSURE, STANDARD_TEMPERATURE),
+        false => el,
+    };
+    let (saz, caz) = az.sin_cos();
+    let (sel, cel) = el.sin_cos();
+    let enu = Vec3::new(cel * saz, cel * caz, sel);
+    qitrf2gcrf(time) * (observer.q_enu2itrf() * enu)
+}
+
+/// Line-of-sight unit vector in the GCRF frame from a
+/// topocentric right ascension & declination observation
+///
+/// Right ascension & declination are taken to be relative to the GCRF
+/// axes, as for observations reduced against a star catalog.  Such
+/// observations are not affected by refraction, so no correction
+/// is applied
+///
+/// # Arguments
+///
+/// * `ra` - Right ascension, radians
+/// * `dec` - Declination, radians
+///
+/// # Returns
+///
+/// * Unit vector from observer in direction of the observation, GCRF frame
+pub fn los_gcrf_radec(ra: f64, dec: f64) -> Vec3 {
+    let (sra, cra) = ra.sin_cos();
+    let (sdec, cdec) = dec.sin_cos();
+    Vec3::new(cdec * cra, cdec * sra, sdec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frametransform::qgcrf2itrf;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_los_radec() {
+        let los = los_gcrf_radec(PI / 2.0, 0.0);
+        assert!((los - Vec3::y()).norm() < 1.0e-15);
+        let los = los_gcrf_radec(1.0, PI / 2.0);
+        assert!((los - Vec3::z()).norm() < 1.0e-15);
+    }
+
+    #[test]
+    fn test_los_satellite() {
+        let time = Instant::from_datetime(2024, 3, 15, 4, 30, 0.0);
+        let observer = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+
+        // Satellite in GCRF, placed above the observer and offset
+        let qi2g = qitrf2gcrf(&time);
+        let obs_gcrf = qi2g * observer.itrf;
+        let sat_gcrf = obs_gcrf * 1.3 + Vec3::new(1.0e6, -2.0e6, 5.0e5);
+        let truth = (sat_gcrf - obs_gcrf).normalize();
+
+        // Azimuth & elevation from consistent geometry
+        let enu =
+            observer.q_enu2itrf().conjugate() * (qgcrf2itrf(&time) * sat_gcrf - observer.itrf);
+        let az = f64::atan2(enu[0], enu[1]);
+        let el = f64::asin(enu[2] / enu.norm());
+        assert!(el > 0.0);
+
+        let los = los_gcrf(&observer, az, el, &time, false);
+        assert!((los - truth).norm() < 1.0e-12);
+
+        // Right ascension & declination of the same direction
+        let ra = f64::atan2(truth[1], truth[0]);
+        let dec = f64::asin(truth[2]);
+        assert!((los_gcrf_radec(ra, dec) - truth).norm() < 1.0e-12);
+
+        // With refraction, observed elevation is above the geometric
+        // elevation by the refraction at the apparent elevation
+        let rfr_apparent =
+            |el: f64| refraction_apparent(el, STANDARD_PRESSURE, STANDARD_TEMPERATURE);
+        let mut el_apparent = el;
+        for _ in 0..10 {
+            el_apparent = el + rfr_apparent(el_apparent);
+        }
+        let rfr = rfr_apparent(el_apparent);
+        let los = los_gcrf(&observer, az, el_apparent, &time, true);
+        assert!((los - truth).norm() < 1.0e-6 * rfr);
+        let los = los_gcrf(&observer, az, el_apparent, &time, false);
+        assert!(((los - truth).norm() - rfr).abs() < 1.0e-6 * rfr);
+    }
+}
@@ -0,0 +1,9 @@
+//! Initial orbit determination
+
+mod gauss;
+mod los;
+mod observation;
+
+pub use gauss::{gauss_iod, gauss_iod_all};
+pub use los::{los_gcrf, los_gcrf_radec};
+pub use observation::Observation;
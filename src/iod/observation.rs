@@ -0,0 +1,59 @@
+//! Angles-only observations
+
+use nalgebra as na;
+
+use super::los::{los_gcrf, los_gcrf_radec};
+use crate::{ITRFCoord, Instant};
+
+type Vec3 = na::Vector3<f64>;
+
+/// Angles-only observation of a satellite from a ground site
+///
+/// The observation is stored as a line-of-sight unit vector
+/// in the GCRF frame, from the observer to the satellite
+#[derive(Debug, Clone)]
+pub struct Observation {
+    /// Time of observation
+    pub time: Instant,
+    /// Line-of-sight unit vector from observer to satellite, GCRF frame
+    pub los: Vec3,
+}
+
+impl Observation {
+    /// Create observation from topocentric right ascension & declination
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time of observation
+    /// * `ra` - Right ascension, radians
+    /// * `dec` - Declination, radians
+    pub fn from_radec(time: &Instant, ra: f64, dec: f64) -> Self {
+        Self {
+            time: *time,
+            los: los_gcrf_radec(ra, dec),
+        }
+    }
+
+    /// Create observation from azimuth & elevation
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - Location of observer
+    /// * `time` - Time of observation
+    /// * `az` - Azimuth, radians, clockwise from north
+    /// * `el` - Elevation above local horizon, radians
+    /// * `correct_refraction` - If true, elevation is the apparent (refracted)
+    ///   elevation, and atmospheric refraction is removed
+    pub fn from_azel(
+        site: &ITRFCoord,
+        time: &Instant,
+        az: f64,
+        el: f64,
+        correct_refraction: bool,
+    ) -> Self {
+        Self {
+            time: *time,
+            los: los_gcrf(site, az, el, time, correct_refraction),
+        }
+    }
+}
@@ -10,6 +10,53 @@ use crate::types::Quaternion as Quat;
 use crate::types::Vec3;
 use crate::SKResult;
 
+/// Reference ellipsoid for geodetic conversions
+///
+/// [`ITRFCoord`] geodetic conversions use [`Ellipsoid::WGS84`] by default;
+/// the `_ellipsoid` variants of the conversion functions take a custom
+/// ellipsoid, e.g. for another planet
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Ellipsoid {
+    /// Semi-major (equatorial) axis, meters
+    pub a: f64,
+    /// Flattening
+    pub f: f64,
+}
+
+impl Ellipsoid {
+    /// WGS-84 ellipsoid
+    pub const WGS84: Self = Self {
+        a: WGS84_A,
+        f: WGS84_F,
+    };
+
+    /// Create ellipsoid from semi-major axis (meters) & flattening
+    pub const fn new(a: f64, f: f64) -> Self {
+        Self { a, f }
+    }
+
+    /// Spherical "ellipsoid" of the given radius, in meters
+    pub const fn sphere(radius: f64) -> Self {
+        Self { a: radius, f: 0.0 }
+    }
+
+    /// Semi-minor (polar) axis, meters
+    pub fn b(&self) -> f64 {
+        self.a * (1.0 - self.f)
+    }
+
+    /// Square of the first eccentricity
+    pub fn e2(&self) -> f64 {
+        self.f * (2.0 - self.f)
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Self::WGS84
+    }
+}
+
 ///
 /// Representation of a coordinate in the
 /// International Terrestrial Reference Frame (ITRF)
@@ -27,6 +74,25 @@ pub struct ITRFCoord {
     pub itrf: Vec3,
 }
 
+/// Range-rate of a target relative to an Earth-fixed site, as used
+/// for Doppler tracking
+///
+/// See [`ITRFCoord::range_rate`]
+#[derive(Clone, Debug)]
+pub struct RangeRate {
+    /// Range from site to target, meters
+    pub range: f64,
+    /// Rate of change of range, meters / second.  Positive if receding
+    pub range_rate: f64,
+    /// Velocity of target relative to site, in the site East-North-Up frame,
+    /// meters / second
+    pub vel_enu: Vec3,
+    /// Partial derivative of range-rate with respect to target position
+    pub drange_rate_dpos: Vec3,
+    /// Partial derivative of range-rate with respect to target velocity
+    pub drange_rate_dvel: Vec3,
+}
+
 impl std::fmt::Display for ITRFCoord {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let (lat, lon, hae) = self.to_geodetic_deg();
@@ -226,20 +292,41 @@ impl ITRFCoord {
     /// ```
     ///
     pub fn from_geodetic_rad(lat: f64, lon: f64, hae: f64) -> Self {
+        Self::from_geodetic_rad_ellipsoid(lat, lon, hae, &Ellipsoid::WGS84)
+    }
+
+    /// Returns an ITRF Coordinate given the geodetic inputs
+    ///   with radian units for latitude & longitude, relative to
+    ///   the given ellipsoid
+    ///
+    /// # Arguments:
+    ///
+    /// * `lat` - Geodetic latitude in radians
+    /// * `lon` - Geodetic longitude in radians
+    /// * `hae` - Height above ellipsoid, in meters
+    /// * `ellipsoid` - Reference ellipsoid
+    ///
+    pub fn from_geodetic_rad_ellipsoid(
+        lat: f64,
+        lon: f64,
+        hae: f64,
+        ellipsoid: &Ellipsoid,
+    ) -> Self {
         let sinp: f64 = lat.sin();
         let cosp: f64 = lat.cos();
         let sinl: f64 = lon.sin();
         let cosl: f64 = lon.cos();
 
-        let f2 = (1.0 - WGS84_F).powi(2);
+        let a = ellipsoid.a;
+        let f2 = (1.0 - ellipsoid.f).powi(2);
         let c = 1.0 / cosp.mul_add(cosp, f2 * sinp * sinp).sqrt();
         let s = f2 * c;
 
         Self {
             itrf: Vec3::from([
-                WGS84_A.mul_add(c, hae) * cosp * cosl,
-                WGS84_A.mul_add(c, hae) * cosp * sinl,
-                WGS84_A.mul_add(s, hae) * sinp,
+                a.mul_add(c, hae) * cosp * cosl,
+                a.mul_add(c, hae) * cosp * sinl,
+                a.mul_add(s, hae) * sinp,
             ]),
         }
     }
@@ -253,34 +340,53 @@ impl ITRFCoord {
     /// * `.2` - height above ellipsoid, in meters
     ///
     pub fn to_geodetic_rad(&self) -> (f64, f64, f64) {
-        const B: f64 = WGS84_A * (1.0 - WGS84_F);
-        const E2: f64 = 1.0 - (1.0 - WGS84_F) * (1.0 - WGS84_F);
-        const EP2: f64 = E2 / (1.0 - E2);
+        self.to_geodetic_rad_ellipsoid(&Ellipsoid::WGS84)
+    }
+
+    /// Returns 3-element tuple representing geodetic coordinates
+    /// relative to the given ellipsoid
+    ///
+    /// # Arguments:
+    ///
+    /// * `ellipsoid` - Reference ellipsoid
+    ///
+    /// # Tuple contents:
+    ///
+    /// * `.0` - latitude in radians
+    /// * `.1` - longitude in radians
+    /// * `.2` - height above ellipsoid, in meters
+    ///
+    pub fn to_geodetic_rad_ellipsoid(&self, ellipsoid: &Ellipsoid) -> (f64, f64, f64) {
+        let a = ellipsoid.a;
+        let f = ellipsoid.f;
+        let b = ellipsoid.b();
+        let e2 = ellipsoid.e2();
+        let ep2 = e2 / (1.0 - e2);
 
         let rho = self.itrf[0].hypot(self.itrf[1]);
-        let mut beta: f64 = f64::atan2(self.itrf[2], (1.0 - WGS84_F) * rho);
+        let mut beta: f64 = f64::atan2(self.itrf[2], (1.0 - f) * rho);
         let mut sinbeta: f64 = beta.sin();
         let mut cosbeta: f64 = beta.cos();
         let mut phi: f64 = f64::atan2(
-            (B * EP2).mul_add(sinbeta.powi(3), self.itrf[2]),
-            (WGS84_A * E2).mul_add(-cosbeta.powi(3), rho),
+            (b * ep2).mul_add(sinbeta.powi(3), self.itrf[2]),
+            (a * e2).mul_add(-cosbeta.powi(3), rho),
         );
-        let mut betanew: f64 = f64::atan2((1.0 - WGS84_F) * phi.sin(), phi.cos());
+        let mut betanew: f64 = f64::atan2((1.0 - f) * phi.sin(), phi.cos());
         for _x in 0..5 {
             beta = betanew;
             sinbeta = beta.sin();
             cosbeta = beta.cos();
             phi = f64::atan2(
-                (B * EP2).mul_add(sinbeta.powi(3), self.itrf[2]),
-                (WGS84_A * E2).mul_add(-cosbeta.powi(3), rho),
+                (b * ep2).mul_add(sinbeta.powi(3), self.itrf[2]),
+                (a * e2).mul_add(-cosbeta.powi(3), rho),
             );
-            betanew = f64::atan2((1.0 - WGS84_F) * phi.sin(), phi.cos());
+            betanew = f64::atan2((1.0 - f) * phi.sin(), phi.cos());
         }
         let lat: f64 = phi;
         let lon: f64 = f64::atan2(self.itrf[1], self.itrf[0]);
         let sinphi: f64 = phi.sin();
-        let n: f64 = WGS84_A / (E2 * sinphi).mul_add(-sinphi, 1.0).sqrt();
-        let h = rho.mul_add(phi.cos(), (E2 * n).mul_add(sinphi, self.itrf[2]) * sinphi) - n;
+        let n: f64 = a / (e2 * sinphi).mul_add(-sinphi, 1.0).sqrt();
+        let h = rho.mul_add(phi.cos(), (e2 * n).mul_add(sinphi, self.itrf[2]) * sinphi) - n;
         (lat, lon, h)
     }
 
@@ -324,6 +430,17 @@ impl ITRFCoord {
         hae
     }
 
+    /// Return height above the given ellipsoid in meters
+    ///
+    /// # Arguments:
+    ///
+    /// * `ellipsoid` - Reference ellipsoid
+    #[inline]
+    pub fn hae_ellipsoid(&self, ellipsoid: &Ellipsoid) -> f64 {
+        let (_a, _b, hae) = self.to_geodetic_rad_ellipsoid(ellipsoid);
+        hae
+    }
+
     /// Return geodetic latitude in degrees, [-180, 180]
     #[inline]
     pub fn latitude_deg(&self) -> f64 {
@@ -591,6 +708,144 @@ impl ITRFCoord {
     pub fn to_enu(&self, other: &Self) -> Vec3 {
         self.q_enu2itrf().conjugate() * (self.itrf - other.itrf)
     }
+
+    /// Offset this coordinate by a vector in the local
+    /// East-North-Up (ENU) frame
+    ///
+    /// # Arguments
+    ///
+    /// * `east` - Offset to the east, meters
+    /// * `north` - Offset to the north, meters
+    /// * `up` - Offset along the local vertical, meters
+    ///
+    /// # Return
+    ///
+    /// * `ITRFCoord` at the offset location
+    ///
+    /// # Notes
+    ///
+    /// * The offset is along the straight axes of the ENU frame at this
+    ///   coordinate, i.e. in the local tangent plane, so is intended for
+    ///   small offsets (up to a few kilometers).  For larger horizontal
+    ///   offsets the result departs from the curved Earth surface: a
+    ///   horizontal offset of `d` meters also raises the height above the
+    ///   ellipsoid by about `d^2 / 2R`, e.g. 8 cm at 1 km and 8 m at
+    ///   10 km.  Use [`ITRFCoord::move_with_heading`] to travel along the
+    ///   surface
+    ///
+    /// # Examples:
+    /// ```
+    /// use satkit::itrfcoord::ITRFCoord;
+    /// let itrf1 = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+    /// // 100 meters above
+    /// let itrf2 = itrf1.offset_enu(0.0, 0.0, 100.0);
+    /// assert!((itrf2.hae() - 250.0).abs() < 1.0e-6);
+    /// ```
+    pub fn offset_enu(&self, east: f64, north: f64, up: f64) -> Self {
+        self + self.q_enu2itrf() * Vec3::new(east, north, up)
+    }
+
+    /// Look angles from this coordinate to a target
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Coordinate of target
+    ///
+    /// # Return
+    ///
+    /// * Tuple (azimuth, elevation, range).  Azimuth is in radians,
+    ///   clockwise from north; elevation is geometric elevation above
+    ///   the local horizon, in radians; range is in meters
+    ///
+    /// # Examples:
+    /// ```
+    /// use satkit::itrfcoord::ITRFCoord;
+    /// let observer = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+    /// let target = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 1150.0);
+    /// let (_az, el, range) = observer.az_el_range(&target);
+    /// // Target is directly overhead
+    /// assert!((el - std::f64::consts::PI / 2.0).abs() < 1.0e-6);
+    /// assert!((range - 1000.0).abs() < 1.0e-6);
+    /// ```
+    pub fn az_el_range(&self, target: &Self) -> (f64, f64, f64) {
+        let enu = self.q_enu2itrf().conjugate() * (target.itrf - self.itrf);
+        let range = enu.norm();
+        let az = f64::atan2(enu[0], enu[1]).rem_euclid(2.0 * PI);
+        let el = f64::asin(enu[2] / range);
+        (az, el, range)
+    }
+
+    /// Range-rate and East-North-Up velocity of a target, relative to this
+    /// coordinate
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Position of target, ITRF frame, meters
+    /// * `vel` - Velocity of target in the (rotating) ITRF frame, meters / second.
+    ///   This is not the inertial velocity rotated into the ITRF frame; see
+    ///   [`crate::orbitprop::SatState::range_rate`] for a satellite state
+    ///   in the GCRF frame
+    ///
+    /// # Return
+    ///
+    /// * [`RangeRate`], with partials with respect to ITRF position & velocity
+    ///
+    /// # Examples:
+    /// ```
+    /// use satkit::itrfcoord::ITRFCoord;
+    /// use satkit::types::Vec3;
+    /// let site = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+    /// let target = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 1150.0);
+    /// // Target rising vertically at 10 m/s
+    /// let vel = site.q_enu2itrf() * Vec3::new(0.0, 0.0, 10.0);
+    /// let rr = site.range_rate(&target.itrf, &vel);
+    /// assert!((rr.range_rate - 10.0).abs() < 1.0e-9);
+    /// ```
+    pub fn range_rate(&self, pos: &Vec3, vel: &Vec3) -> RangeRate {
+        let rho = pos - self.itrf;
+        let range = rho.norm();
+        let rhohat = rho / range;
+        let range_rate = rhohat.dot(vel);
+        RangeRate {
+            range,
+            range_rate,
+            vel_enu: self.q_enu2itrf().conjugate() * vel,
+            drange_rate_dpos: (vel - rhohat * range_rate) / range,
+            drange_rate_dvel: rhohat,
+        }
+    }
+
+    /// Look angles from this coordinate to a target, with elevation
+    /// corrected for atmospheric refraction
+    ///
+    /// See [`crate::refraction::refraction`]
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Coordinate of target
+    /// * `pressure` - Atmospheric pressure at the observer, hPa (millibar)
+    /// * `temperature` - Air temperature at the observer, degrees Celsius
+    ///
+    /// # Return
+    ///
+    /// * Tuple (azimuth, elevation, range).  Azimuth is in radians,
+    ///   clockwise from north; elevation is apparent (refracted) elevation
+    ///   above the local horizon, in radians; range is in meters.
+    ///   More than 2 degrees below the horizon, where refraction is not
+    ///   modeled, elevation is geometric
+    pub fn az_el_range_apparent(
+        &self,
+        target: &Self,
+        pressure: f64,
+        temperature: f64,
+    ) -> (f64, f64, f64) {
+        let (az, el, range) = self.az_el_range(target);
+        (
+            az,
+            el + crate::refraction::refraction(el, pressure, temperature),
+            range,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -673,4 +928,166 @@ mod tests {
         let itrf2 = itrf1 + itrf1.q_ned2itrf() * na::vector![0.0, 0.0, 10000.0];
         println!("height diff = {}", itrf2.hae() - itrf1.hae());
     }
+
+    #[test]
+    fn test_offset_enu() {
+        let lat = 42.466_f64.to_radians();
+        let itrf = ITRFCoord::from_geodetic_rad(lat, -71.1516_f64.to_radians(), 150.0);
+
+        // 1 km east changes longitude by the offset over the radius
+        // of the parallel at this latitude, and latitude only to
+        // second order in the offset
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let n = WGS84_A / e2.mul_add(-lat.sin().powi(2), 1.0).sqrt();
+        let moved = itrf.offset_enu(1000.0, 0.0, 0.0);
+        let dlon = moved.longitude_rad() - itrf.longitude_rad();
+        assert_relative_eq!(
+            dlon,
+            1000.0 / ((n + 150.0) * lat.cos()),
+            max_relative = 1.0e-6
+        );
+        assert_abs_diff_eq!(moved.latitude_rad(), lat, epsilon = 1.0e-7);
+        // Tangent plane departs from the surface by about d^2 / 2R
+        assert_abs_diff_eq!(moved.hae() - 150.0, 0.08, epsilon = 0.01);
+
+        // Offset is along the ENU axes of the original coordinate
+        let moved = itrf.offset_enu(-300.0, 200.0, 50.0);
+        let enu = itrf.q_enu2itrf().conjugate() * (moved - itrf);
+        assert_abs_diff_eq!(enu, Vec3::new(-300.0, 200.0, 50.0), epsilon = 1.0e-6);
+    }
+
+    #[test]
+    fn test_az_el_range() {
+        let observer = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+
+        // Target 1 km east and 1 km up
+        let target = observer + observer.q_enu2itrf() * na::vector![1000.0, 0.0, 1000.0];
+        let (az, el, range) = observer.az_el_range(&target);
+        assert!((az - PI / 2.0).abs() < 1.0e-9);
+        assert!((el - PI / 4.0).abs() < 1.0e-9);
+        assert!((range - 1000.0 * 2.0_f64.sqrt()).abs() < 1.0e-6);
+
+        // Target to the northwest, on the horizon: azimuth in [0, 2pi),
+        // and refraction raises the apparent elevation by about half a degree
+        let target = observer + observer.q_enu2itrf() * na::vector![-1.0e5, 1.0e5, 0.0];
+        let (az, el, _) = observer.az_el_range(&target);
+        assert!((az - 7.0 * PI / 4.0).abs() < 1.0e-9);
+        assert!(el.abs() < 1.0e-9);
+        let (az2, el2, _) = observer.az_el_range_apparent(
+            &target,
+            crate::refraction::STANDARD_PRESSURE,
+            crate::refraction::STANDARD_TEMPERATURE,
+        );
+        assert_eq!(az2, az);
+        assert!(((el2 - el).to_degrees() - 0.5).abs() < 0.05);
+
+        // Well below the horizon, elevation is geometric
+        let target = observer + observer.q_enu2itrf() * na::vector![0.0, 1.0e5, -1.0e5];
+        let (_, el, _) = observer.az_el_range(&target);
+        let (_, el2, _) = observer.az_el_range_apparent(
+            &target,
+            crate::refraction::STANDARD_PRESSURE,
+            crate::refraction::STANDARD_TEMPERATURE,
+        );
+        assert!((el + PI / 4.0).abs() < 1.0e-9);
+        assert_eq!(el2, el);
+    }
+
+    #[test]
+    fn test_range_rate() {
+        // Circular polar orbit passing over a site on the equator at t = 0,
+        // with ITRF aligned to the inertial frame at t = 0
+        let site = ITRFCoord::from_geodetic_deg(0.0, 0.0, 0.0);
+        let r = crate::consts::EARTH_RADIUS + 500.0e3;
+        let n = (crate::consts::MU_EARTH / r.powi(3)).sqrt();
+        let omega = na::vector![0.0, 0.0, crate::consts::OMEGA_EARTH];
+        let pv = |t: f64| -> (Vec3, Vec3) {
+            let pos = na::vector![(n * t).cos(), 0.0, (n * t).sin()] * r;
+            let vel = na::vector![-(n * t).sin(), 0.0, (n * t).cos()] * r * n;
+            let q = Quat::from_axis_angle(&Vec3::z_axis(), -omega[2] * t);
+            (q * pos, q * (vel - omega.cross(&pos)))
+        };
+
+        let times: Vec<f64> = (-30..=30).map(|ix| ix as f64 * 10.0).collect();
+        let rr: Vec<RangeRate> = times
+            .iter()
+            .map(|t| {
+                let (pos, vel) = pv(*t);
+                site.range_rate(&pos, &vel)
+            })
+            .collect();
+
+        // Range-rate matches numerical derivative of range, including
+        // the Earth-rotation contribution to the relative velocity
+        for (t, rr) in times.iter().zip(rr.iter()) {
+            let dt = 1.0e-3;
+            let rp = (pv(t + dt).0 - site.itrf).norm();
+            let rm = (pv(t - dt).0 - site.itrf).norm();
+            assert!((rr.range_rate - (rp - rm) / (2.0 * dt)).abs() < 1.0e-3);
+            assert!((rr.vel_enu.norm() - pv(*t).1.norm()).abs() < 1.0e-6);
+        }
+
+        // S-curve: approaching, then receding, with a single zero
+        // crossing at the time of closest approach
+        assert!(rr[0].range_rate < -5000.0);
+        assert!(rr[rr.len() - 1].range_rate > 5000.0);
+        let crossings: Vec<usize> = (1..rr.len())
+            .filter(|ix| rr[ix - 1].range_rate < 0.0 && rr[*ix].range_rate >= 0.0)
+            .collect();
+        assert_eq!(crossings.len(), 1);
+        let tca = (0..rr.len())
+            .min_by(|a, b| rr[*a].range.partial_cmp(&rr[*b].range).unwrap())
+            .unwrap();
+        assert!(tca == crossings[0] || tca + 1 == crossings[0]);
+
+        // Partials match finite differences
+        let (pos, vel) = pv(100.0);
+        let rr0 = site.range_rate(&pos, &vel);
+        for ix in 0..3 {
+            let mut dp = Vec3::zeros();
+            dp[ix] = 1.0;
+            let dpos = site.range_rate(&(pos + dp), &vel).range_rate - rr0.range_rate;
+            assert!((dpos - rr0.drange_rate_dpos[ix]).abs() < 1.0e-8);
+            let dvel = site.range_rate(&pos, &(vel + dp)).range_rate - rr0.range_rate;
+            assert!((dvel - rr0.drange_rate_dvel[ix]).abs() < 1.0e-8);
+        }
+    }
+
+    #[test]
+    fn test_ellipsoid() {
+        assert_eq!(Ellipsoid::default(), Ellipsoid::WGS84);
+        let sphere = Ellipsoid::sphere(WGS84_A);
+
+        // Default conversions are WGS84
+        let lat = 45.0_f64.to_radians();
+        let coord = ITRFCoord::from_geodetic_rad(lat, 0.3, 0.0);
+        let wgs84 = ITRFCoord::from_geodetic_rad_ellipsoid(lat, 0.3, 0.0, &Ellipsoid::WGS84);
+        assert_eq!(coord, wgs84);
+        assert_eq!(coord.hae(), coord.hae_ellipsoid(&Ellipsoid::WGS84));
+
+        // On a sphere of the equatorial radius, the surface point is
+        // below the sphere by a * f * sin^2(lat) to first order in flattening
+        let dh = coord.hae_ellipsoid(&sphere) - coord.hae();
+        assert_abs_diff_eq!(dh, -WGS84_A * WGS84_F * lat.sin().powi(2), epsilon = 100.0);
+
+        // Exact at the pole and equator
+        let pole = ITRFCoord::from_geodetic_deg(90.0, 0.0, 1000.0);
+        assert_abs_diff_eq!(
+            pole.hae_ellipsoid(&sphere),
+            1000.0 - WGS84_A * WGS84_F,
+            epsilon = 1.0e-6
+        );
+        let equator = ITRFCoord::from_geodetic_deg(0.0, 20.0, 1000.0);
+        assert_abs_diff_eq!(equator.hae_ellipsoid(&sphere), 1000.0, epsilon = 1.0e-6);
+
+        // Round trip on the sphere gives geocentric latitude
+        let (slat, slon, shae) = coord.to_geodetic_rad_ellipsoid(&sphere);
+        let back = ITRFCoord::from_geodetic_rad_ellipsoid(slat, slon, shae, &sphere);
+        assert_abs_diff_eq!((back.itrf - coord.itrf).norm(), 0.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(
+            slat,
+            coord.itrf[2].atan2(coord.itrf[0].hypot(coord.itrf[1])),
+            epsilon = 1.0e-12
+        );
+    }
 }
@@ -142,6 +142,7 @@ impl JPLEphem {
         download_if_not_exist(&path, None)?;
 
         // Read in bytes
+        log::debug!(target: "satkit::data", "Loading JPL ephemeris from {}", path.display());
         let raw = std::fs::read(path)?;
         let title: &str = std::str::from_utf8(&raw[0..84])?;
 
@@ -1,6 +1,7 @@
 //! Keplerian orbital elements module
 //!
 
+use crate::skerror;
 use crate::SKResult;
 use thiserror::Error;
 
@@ -211,16 +212,32 @@ impl Kepler {
     /// * `Kepler` - A new Keplerian orbital element object
     ///
     pub fn from_pv(r: Vec3, v: Vec3) -> SKResult<Self> {
+        Self::from_pv_with_mu(r, v, crate::consts::MU_EARTH)
+    }
+
+    /// Convert Cartesian coordinates to Keplerian orbital elements,
+    /// about a central body with the given gravitational parameter
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - Position vector, meters
+    /// * `v` - Velocity vector, meters/second
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    ///
+    /// # Returns
+    ///
+    /// * `Kepler` - A new Keplerian orbital element object
+    ///
+    pub fn from_pv_with_mu(r: Vec3, v: Vec3, mu: f64) -> SKResult<Self> {
         let h = r.cross(&v);
         let n = Vec3::z_axis().cross(&h);
-        let e = ((v.norm_squared() - crate::consts::MU_EARTH / r.norm()) * r - r.dot(&v) * v)
-            / crate::consts::MU_EARTH;
+        let e = ((v.norm_squared() - mu / r.norm()) * r - r.dot(&v) * v) / mu;
         let eccen = e.norm();
         if eccen >= 1.0 {
             return KeplerError::EccenOutOfBound(eccen).into();
         }
-        let xi = v.norm().powi(2) / 2.0 - crate::consts::MU_EARTH / r.norm();
-        let a = -crate::consts::MU_EARTH / (2.0 * xi);
+        let xi = v.norm().powi(2) / 2.0 - mu / r.norm();
+        let a = -mu / (2.0 * xi);
         let incl = (h.z / h.norm()).acos();
         let mut raan = (n.x / n.norm()).acos();
         if n.y < 0.0 {
@@ -244,16 +261,576 @@ impl Kepler {
     /// * `(Vec3, Vec3)` - Position and velocity vectors, meters and meters/second
     ///
     pub fn to_pv(&self) -> (Vec3, Vec3) {
+        self.pv_at(self.nu, crate::consts::MU_EARTH)
+    }
+
+    /// Position & velocity at the given true anomaly, without checking
+    /// that the orbit reaches it
+    fn pv_at(&self, nu: f64, mu: f64) -> (Vec3, Vec3) {
         let p = self.a * self.eccen.mul_add(-self.eccen, 1.0);
-        let r = p / self.eccen.mul_add(self.nu.cos(), 1.0);
-        let r_pqw = Vec3::new(r * self.nu.cos(), r * self.nu.sin(), 0.0);
-        let v_pqw = Vec3::new(-self.nu.sin(), self.eccen + self.nu.cos(), 0.0)
-            * (crate::consts::MU_EARTH / p).sqrt();
+        let r = p / self.eccen.mul_add(nu.cos(), 1.0);
+        let r_pqw = Vec3::new(r * nu.cos(), r * nu.sin(), 0.0);
+        let v_pqw = Vec3::new(-nu.sin(), self.eccen + nu.cos(), 0.0) * (mu / p).sqrt();
         let q = Quat::from_axis_angle(&Vec3::z_axis(), self.raan)
             * Quat::from_axis_angle(&Vec3::x_axis(), self.incl)
             * Quat::from_axis_angle(&Vec3::z_axis(), self.w);
         (q * r_pqw, q * v_pqw)
     }
+
+    /// Position & velocity at a given true anomaly on the orbit described
+    /// by the elements, treating the motion as purely Keplerian
+    ///
+    /// # Arguments
+    ///
+    /// * `nu` - True anomaly, radians
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec3, Vec3)` - Position and velocity vectors, meters and
+    ///   meters/second, in the frame of the elements
+    ///
+    /// # Errors
+    ///
+    /// * The semiparameter is not positive, e.g. for a parabolic orbit
+    /// * The true anomaly is beyond the asymptotes of a hyperbolic orbit
+    pub fn state_at_true_anomaly(&self, nu: f64, mu: f64) -> SKResult<(Vec3, Vec3)> {
+        let p = self.semiparameter();
+        if p.is_nan() || p <= 0.0 {
+            return skerror!("Semiparameter must be positive: {}", p);
+        }
+        if self.eccen.mul_add(nu.cos(), 1.0) <= 0.0 {
+            return skerror!(
+                "True anomaly {} rad is beyond the asymptotes of the hyperbolic orbit",
+                nu
+            );
+        }
+        Ok(self.pv_at(nu, mu))
+    }
+
+    /// Position & velocity at perigee (true anomaly of zero), treating the
+    /// motion as purely Keplerian
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec3, Vec3)` - Position and velocity vectors, meters and
+    ///   meters/second.  For a circular orbit, this is the point defined
+    ///   by the argument of perigee
+    ///
+    /// # Errors
+    ///
+    /// * The semiparameter is not positive
+    pub fn perigee_state(&self, mu: f64) -> SKResult<(Vec3, Vec3)> {
+        self.state_at_true_anomaly(0.0, mu)
+    }
+
+    /// Position & velocity at apogee (true anomaly of π), treating the
+    /// motion as purely Keplerian
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec3, Vec3)` - Position and velocity vectors, meters and
+    ///   meters/second
+    ///
+    /// # Errors
+    ///
+    /// * The orbit is not closed (eccentricity ≥ 1), so has no apogee
+    pub fn apogee_state(&self, mu: f64) -> SKResult<(Vec3, Vec3)> {
+        if self.eccen >= 1.0 {
+            return KeplerError::EccenOutOfBound(self.eccen).into();
+        }
+        self.state_at_true_anomaly(std::f64::consts::PI, mu)
+    }
+}
+
+/// Orbit classification, as returned by [`classify_orbit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitClass {
+    /// Low Earth orbit: apogee altitude below 2,000 km
+    Leo,
+    /// Medium Earth orbit: near-circular, between LEO and GEO altitude
+    Meo,
+    /// Geostationary: geosynchronous, near-circular and near-equatorial
+    Geo,
+    /// Geosynchronous: period of one sidereal day, any inclination
+    Gso,
+    /// Highly elliptical orbit
+    Heo,
+    /// Molniya: half-sidereal-day period, high eccentricity,
+    /// critically inclined
+    Molniya,
+    /// Geosynchronous transfer orbit: LEO perigee, GEO apogee
+    Gto,
+    /// Anything else, e.g., escape trajectories or
+    /// near-circular orbits above GEO
+    Other,
+}
+
+/// Altitude, meters, below which an orbit is LEO
+const LEO_MAX_ALT: f64 = 2.0e6;
+/// Tolerance, meters, on semi-major axis for geosynchronous orbits
+const GSO_A_TOL: f64 = 200.0e3;
+/// Maximum eccentricity for geosynchronous orbits
+const GSO_MAX_ECCEN: f64 = 0.1;
+/// Maximum eccentricity for geostationary orbits
+const GEO_MAX_ECCEN: f64 = 0.01;
+/// Maximum inclination, degrees, for geostationary orbits
+const GEO_MAX_INCL_DEG: f64 = 1.0;
+/// Tolerance, meters, on semi-major axis for Molniya orbits
+const MOLNIYA_A_TOL: f64 = 1000.0e3;
+/// Minimum eccentricity for Molniya orbits
+const MOLNIYA_MIN_ECCEN: f64 = 0.5;
+/// Tolerance, degrees, on inclination about the critical
+/// inclination (63.4 deg) for Molniya orbits
+const MOLNIYA_INCL_TOL_DEG: f64 = 5.0;
+/// Tolerance, meters, of GTO apogee radius about the GEO radius
+const GTO_APOGEE_TOL: f64 = 5.0e6;
+/// Eccentricity at or above which an orbit that is not LEO
+/// or otherwise classified is HEO
+const HEO_MIN_ECCEN: f64 = 0.25;
+
+/// Classify an orbit from its Keplerian elements
+///
+/// # Arguments
+///
+/// * `elements` - Keplerian orbital elements
+///
+/// # Returns
+///
+/// * Orbit class
+///
+/// # Notes
+///
+/// Altitudes are above a spherical Earth of radius
+/// [`crate::consts::EARTH_RADIUS`].  Classes are tested in the order below,
+/// and the first match is returned, so e.g. an orbit that is both
+/// geostationary and geosynchronous is [`OrbitClass::Geo`]:
+///
+/// 1. [`OrbitClass::Other`]: eccentricity ≥ 1 (not a closed orbit)
+/// 2. [`OrbitClass::Leo`]: apogee altitude < 2,000 km
+/// 3. [`OrbitClass::Geo`]: semi-major axis within 200 km of
+///    [`crate::consts::GEO_R`], eccentricity < 0.01, inclination < 1°
+/// 4. [`OrbitClass::Gso`]: semi-major axis within 200 km of
+///    [`crate::consts::GEO_R`], eccentricity < 0.1
+/// 5. [`OrbitClass::Molniya`]: semi-major axis within 1,000 km of the
+///    half-sidereal-day value (~26,562 km), eccentricity ≥ 0.5, inclination
+///    within 5° of the critical inclination (63.4° or 116.6°)
+/// 6. [`OrbitClass::Gto`]: perigee altitude < 2,000 km and apogee radius
+///    within 5,000 km of [`crate::consts::GEO_R`]
+/// 7. [`OrbitClass::Heo`]: eccentricity ≥ 0.25
+/// 8. [`OrbitClass::Meo`]: apogee radius below [`crate::consts::GEO_R`]
+///    less 200 km
+/// 9. [`OrbitClass::Other`]: anything else
+///
+/// An orbit exactly at a strict (<) threshold fails that test and
+/// falls through to the next class
+pub fn classify_orbit(elements: &Kepler) -> OrbitClass {
+    use crate::consts::{EARTH_RADIUS, GEO_R};
+
+    let a = elements.a;
+    let e = elements.eccen;
+    if !(0.0..1.0).contains(&e) || a <= 0.0 {
+        return OrbitClass::Other;
+    }
+    let rp = a * (1.0 - e);
+    let ra = a * (1.0 + e);
+    let incl = elements.incl.to_degrees();
+
+    // Semi-major axis for a period of half a sidereal day
+    let a_molniya = GEO_R / 2.0_f64.powf(2.0 / 3.0);
+    // Critical inclination, at which apsides do not precess under J2
+    let critical_incl = (1.0 / 5.0_f64.sqrt()).acos().to_degrees();
+
+    let is_gso = (a - GEO_R).abs() < GSO_A_TOL && e < GSO_MAX_ECCEN;
+
+    if ra - EARTH_RADIUS < LEO_MAX_ALT {
+        OrbitClass::Leo
+    } else if is_gso && e < GEO_MAX_ECCEN && incl < GEO_MAX_INCL_DEG {
+        OrbitClass::Geo
+    } else if is_gso {
+        OrbitClass::Gso
+    } else if (a - a_molniya).abs() < MOLNIYA_A_TOL
+        && e >= MOLNIYA_MIN_ECCEN
+        && ((incl - critical_incl).abs() < MOLNIYA_INCL_TOL_DEG
+            || (incl - (180.0 - critical_incl)).abs() < MOLNIYA_INCL_TOL_DEG)
+    {
+        OrbitClass::Molniya
+    } else if rp - EARTH_RADIUS < LEO_MAX_ALT && (ra - GEO_R).abs() < GTO_APOGEE_TOL {
+        OrbitClass::Gto
+    } else if e >= HEO_MIN_ECCEN {
+        OrbitClass::Heo
+    } else if ra < GEO_R - GSO_A_TOL {
+        OrbitClass::Meo
+    } else {
+        OrbitClass::Other
+    }
+}
+
+/// Osculating eccentricity (Laplace-Runge-Lenz) vector
+///
+/// # Arguments
+///
+/// * `pv` - 6-element vector of [position (m), velocity (m/s)],
+///   in an inertial frame
+/// * `mu` - Gravitational parameter of the central body, m^3/s^2
+///
+/// # Returns
+///
+/// * Dimensionless vector pointing toward perigee, with magnitude
+///   equal to the eccentricity
+///
+/// # Notes
+///
+/// * For a circular orbit the vector is zero (to within roundoff),
+///   and its direction is meaningless
+pub fn eccentricity_vector(pv: &crate::types::Vector6, mu: f64) -> Vec3 {
+    let r: Vec3 = pv.fixed_rows::<3>(0).into();
+    let v: Vec3 = pv.fixed_rows::<3>(3).into();
+    ((v.norm_squared() - mu / r.norm()) * r - r.dot(&v) * v) / mu
+}
+
+/// Ascending node vector, the z axis crossed with the
+/// angular momentum vector
+///
+/// # Arguments
+///
+/// * `pv` - 6-element vector of [position (m), velocity (m/s)],
+///   in an inertial frame
+///
+/// # Returns
+///
+/// * Vector in the equatorial plane pointing toward the ascending node,
+///   m^2/s, with magnitude equal to that of the angular momentum
+///   times the sine of the inclination
+///
+/// # Notes
+///
+/// * For an equatorial orbit the vector is zero (to within roundoff),
+///   and its direction is meaningless
+pub fn node_vector(pv: &crate::types::Vector6) -> Vec3 {
+    let r: Vec3 = pv.fixed_rows::<3>(0).into();
+    let v: Vec3 = pv.fixed_rows::<3>(3).into();
+    Vec3::z().cross(&r.cross(&v))
+}
+
+/// Unit normal to the orbit plane, along the angular momentum vector
+///
+/// # Arguments
+///
+/// * `pv` - 6-element vector of [position (m), velocity (m/s)],
+///   in an inertial frame
+///
+/// # Returns
+///
+/// * Unit vector along position crossed with velocity.  Components
+///   are NaN for a rectilinear trajectory (zero angular momentum)
+pub fn orbit_normal(pv: &crate::types::Vector6) -> Vec3 {
+    let r: Vec3 = pv.fixed_rows::<3>(0).into();
+    let v: Vec3 = pv.fixed_rows::<3>(3).into();
+    r.cross(&v).normalize()
+}
+
+/// Inclination & right ascension of the ascending node of the
+/// orbit plane
+///
+/// # Arguments
+///
+/// * `pv` - 6-element vector of [position (m), velocity (m/s)],
+///   in an inertial frame
+///
+/// # Returns
+///
+/// * (inclination, RAAN), radians.  Inclination is in [0, π] and
+///   RAAN in [0, 2π)
+///
+/// # Notes
+///
+/// * For an equatorial orbit (sine of inclination below 1e-10) the
+///   ascending node is undefined, and RAAN is returned as zero
+pub fn plane_angles(pv: &crate::types::Vector6) -> (f64, f64) {
+    let n = orbit_normal(pv);
+    let sini = n.x.hypot(n.y);
+    let incl = sini.atan2(n.z);
+    let raan = match sini < 1.0e-10 {
+        true => 0.0,
+        false => n.x.atan2(-n.y).rem_euclid(2.0 * std::f64::consts::PI),
+    };
+    (incl, raan)
+}
+
+/// Mean anomaly, and fraction of the period elapsed, at a given time,
+/// from mean elements at an epoch, e.g. for phasing
+///
+/// The mean anomaly advances at the mean motion, n = sqrt(mu / a^3),
+/// optionally with the first-order secular J2 correction (Vallado,
+/// Eq. 9-41):
+///
+/// n' = n (1 + 3/2 J2 (R/p)^2 sqrt(1 - e^2) (1 - 3/2 sin^2 i))
+///
+/// # Arguments
+///
+/// * `elements` - Mean elements at `epoch`
+/// * `epoch` - Epoch of the elements
+/// * `time` - Time at which to compute the mean anomaly
+/// * `mu` - Gravitational parameter of the central body, m^3/s^2
+/// * `j2` - Optional (J2, reference radius in meters) of the central
+///   body, for the J2 mean-motion correction.  J2 is positive for an
+///   oblate body, e.g. `-consts::JGM3_J2`
+///
+/// # Returns
+///
+/// * (mean anomaly in [0, 2π), fraction of the (anomalistic) period
+///   elapsed since epoch, modulo whole revolutions, in [0, 1))
+pub fn mean_anomaly_at(
+    elements: &Kepler,
+    epoch: &crate::Instant,
+    time: &crate::Instant,
+    mu: f64,
+    j2: Option<(f64, f64)>,
+) -> (f64, f64) {
+    use std::f64::consts::PI;
+    let e2 = elements.eccen.mul_add(-elements.eccen, 1.0);
+    let mut n = (mu / elements.a.powi(3)).sqrt();
+    if let Some((j2, radius)) = j2 {
+        let sini = elements.incl.sin();
+        let k2 = 1.5 * j2 * (radius / elements.semiparameter()).powi(2);
+        n *= k2.mul_add(e2.sqrt() * 1.5f64.mul_add(-sini * sini, 1.0), 1.0);
+    }
+    let dm = (n * (*time - *epoch).as_seconds()).rem_euclid(2.0 * PI);
+    (
+        (elements.mean_anomaly() + dm).rem_euclid(2.0 * PI),
+        dm / (2.0 * PI),
+    )
+}
+
+/// Equinoctial reference frame unit vectors (f, g) for inclination
+/// elements (p, q) and retrograde factor `ri` (+1 or -1)
+fn equinoctial_frame(p: f64, q: f64, ri: f64) -> (Vec3, Vec3) {
+    let s2 = 1.0 + p * p + q * q;
+    let fhat = Vec3::new(1.0 - p * p + q * q, 2.0 * p * q, -2.0 * ri * p) / s2;
+    let ghat = Vec3::new(2.0 * ri * p * q, ri * (1.0 + p * p - q * q), 2.0 * q) / s2;
+    (fhat, ghat)
+}
+
+/// Equinoctial orbital elements of a position & velocity
+///
+/// # Arguments
+///
+/// * `pv` - 6-element vector of [position (m), velocity (m/s)],
+///   in an inertial frame
+/// * `mu` - Gravitational parameter of the central body, m^3/s^2
+/// * `retrograde` - Use the retrograde set of elements (retrograde
+///   factor -1), which is singular for equatorial prograde orbits
+///   rather than equatorial retrograde orbits
+///
+/// # Returns
+///
+/// * Elements [a, h, k, p, q, λ], where a is the semi-major axis (m),
+///   h = e sin(ω + IΩ), k = e cos(ω + IΩ), p = tan^I(i/2) sin(Ω),
+///   q = tan^I(i/2) cos(Ω), λ = M + ω + IΩ is the mean longitude
+///   (radians, in [0, 2π)), and I is the retrograde factor
+/// * Error if the orbit is not elliptical, or is singular for the
+///   chosen retrograde factor
+///
+/// # Notes
+///
+/// * Elements are non-singular for circular & equatorial orbits, except
+///   for an inclination of exactly 180 degrees (0 degrees if
+///   `retrograde` is set)
+///
+/// See Broucke & Cefola, "On the Equinoctial Orbit Elements", 1972,
+/// and Vallado, "Fundamentals of Astrodynamics and Applications",
+/// Section 2.4
+pub fn pv_to_equinoctial(
+    pv: &crate::types::Vector6,
+    mu: f64,
+    retrograde: bool,
+) -> SKResult<crate::types::Vector6> {
+    let ri = if retrograde { -1.0 } else { 1.0 };
+    let r: Vec3 = pv.fixed_rows::<3>(0).into();
+    let v: Vec3 = pv.fixed_rows::<3>(3).into();
+    let rnorm = r.norm();
+    let energy = 0.5 * v.norm_squared() - mu / rnorm;
+    let evec = eccentricity_vector(pv, mu);
+    if energy >= 0.0 || evec.norm() >= 1.0 {
+        return skerror!("Orbit is not elliptical");
+    }
+    let a = -mu / (2.0 * energy);
+    let hhat = r.cross(&v).normalize();
+    if 1.0 + ri * hhat.z < 1.0e-12 {
+        return skerror!("Equinoctial elements are singular for this inclination");
+    }
+    let p = hhat.x / (1.0 + ri * hhat.z);
+    let q = -hhat.y / (1.0 + ri * hhat.z);
+    let (fhat, ghat) = equinoctial_frame(p, q, ri);
+    let k = evec.dot(&fhat);
+    let h = evec.dot(&ghat);
+
+    // Mean longitude, from eccentric longitude
+    let (x1, y1) = (r.dot(&fhat), r.dot(&ghat));
+    let beta = (1.0 - h * h - k * k).sqrt();
+    let b = 1.0 / (1.0 + beta);
+    let sinf = h + ((1.0 - h * h * b) * y1 - h * k * b * x1) / (a * beta);
+    let cosf = k + ((1.0 - k * k * b) * x1 - h * k * b * y1) / (a * beta);
+    let lambda = (sinf.atan2(cosf) + h * cosf - k * sinf).rem_euclid(2.0 * std::f64::consts::PI);
+
+    Ok(crate::types::Vector6::new(a, h, k, p, q, lambda))
+}
+
+/// Position & velocity from equinoctial orbital elements
+///
+/// Inverse of [`pv_to_equinoctial`]
+///
+/// # Arguments
+///
+/// * `eq` - Elements [a, h, k, p, q, λ], as returned by [`pv_to_equinoctial`]
+/// * `mu` - Gravitational parameter of the central body, m^3/s^2
+/// * `retrograde` - Elements are the retrograde set (retrograde factor -1).
+///   Must match the value used to compute the elements
+///
+/// # Returns
+///
+/// * 6-element vector of [position (m), velocity (m/s)], in the
+///   inertial frame of the elements
+/// * Error if the elements do not describe an elliptical orbit
+pub fn equinoctial_to_pv(
+    eq: &crate::types::Vector6,
+    mu: f64,
+    retrograde: bool,
+) -> SKResult<crate::types::Vector6> {
+    let ri = if retrograde { -1.0 } else { 1.0 };
+    let (a, h, k, p, q, lambda) = (eq[0], eq[1], eq[2], eq[3], eq[4], eq[5]);
+    if a <= 0.0 || h * h + k * k >= 1.0 {
+        return skerror!("Equinoctial elements do not describe an elliptical orbit");
+    }
+
+    // Solve Kepler's equation for eccentric longitude,
+    // λ = F + h cos(F) - k sin(F)
+    let mut ecc_lon = lambda;
+    for _ in 0..50 {
+        let (sf, cf) = ecc_lon.sin_cos();
+        let delta = (ecc_lon + h * cf - k * sf - lambda) / (1.0 - k * cf - h * sf);
+        ecc_lon -= delta;
+        if delta.abs() < 1.0e-14 {
+            break;
+        }
+    }
+
+    let (sf, cf) = ecc_lon.sin_cos();
+    let b = 1.0 / (1.0 + (1.0 - h * h - k * k).sqrt());
+    let x1 = a * ((1.0 - h * h * b) * cf + h * k * b * sf - k);
+    let y1 = a * ((1.0 - k * k * b) * sf + h * k * b * cf - h);
+    let rnorm = a * (1.0 - k * cf - h * sf);
+    let s = (mu * a).sqrt() / rnorm;
+    let x1dot = s * (h * k * b * cf - (1.0 - h * h * b) * sf);
+    let y1dot = s * ((1.0 - k * k * b) * cf - h * k * b * sf);
+
+    let (fhat, ghat) = equinoctial_frame(p, q, ri);
+    let r = x1 * fhat + y1 * ghat;
+    let v = x1dot * fhat + y1dot * ghat;
+    Ok(crate::types::Vector6::from_iterator(
+        r.iter().chain(v.iter()).copied(),
+    ))
+}
+
+/// Maximum relative difference in semi-major axis for orbits to be
+/// similar enough for an in-track time offset
+const SIMILAR_A_TOL: f64 = 0.01;
+/// Maximum difference in eccentricity for orbits to be similar enough
+/// for an in-track time offset
+const SIMILAR_ECCEN_TOL: f64 = 0.01;
+/// Maximum angle, degrees, between orbit planes for orbits to be
+/// similar enough for an in-track time offset
+const SIMILAR_PLANE_TOL_DEG: f64 = 1.0;
+
+/// In-track time offset of one satellite relative to another on a
+/// similar orbit, e.g. for formation flying or conjunction analysis
+///
+/// The other satellite's position is projected into the reference orbit
+/// plane, and its true anomaly on the reference orbit converted to mean
+/// anomaly.  The offset is the difference from the reference mean
+/// anomaly, divided by the reference mean motion
+///
+/// # Arguments
+///
+/// * `ref_pv` - 6-element vector of [position (m), velocity (m/s)] of
+///   the reference satellite, in an inertial frame
+/// * `other_pv` - Position & velocity of the other satellite, at the
+///   same time & in the same frame
+/// * `mu` - Gravitational parameter of the central body, m^3/s^2
+///
+/// # Returns
+///
+/// * Time, seconds, by which the other satellite is ahead of the
+///   reference (negative if behind), in [-P/2, P/2), where P is the
+///   reference period
+///
+/// # Errors
+///
+/// * Either orbit is not elliptical
+/// * The orbits are too dissimilar for the offset to be meaningful:
+///   semi-major axes differ by more than 1%, eccentricities by more than
+///   0.01, or orbit planes by more than 1 degree
+///
+/// # Notes
+///
+/// * Angles are measured from the reference eccentricity vector, or
+///   from the reference position for an exactly circular orbit, so the
+///   offset is well defined for near-circular orbits
+pub fn in_track_time_offset(
+    ref_pv: &crate::types::Vector6,
+    other_pv: &crate::types::Vector6,
+    mu: f64,
+) -> SKResult<f64> {
+    let semimajor = |pv: &crate::types::Vector6| {
+        -mu / (pv.fixed_rows::<3>(3).norm_squared() - 2.0 * mu / pv.fixed_rows::<3>(0).norm())
+    };
+    let (a, a_other) = (semimajor(ref_pv), semimajor(other_pv));
+    let evec = eccentricity_vector(ref_pv, mu);
+    let (e, e_other) = (evec.norm(), eccentricity_vector(other_pv, mu).norm());
+    if a <= 0.0 || a_other <= 0.0 || e >= 1.0 || e_other >= 1.0 {
+        return skerror!("Orbits must be elliptical");
+    }
+    if (a_other - a).abs() > SIMILAR_A_TOL * a {
+        return skerror!(
+            "Semi-major axes are too dissimilar: {} m and {} m",
+            a,
+            a_other
+        );
+    }
+    if (e_other - e).abs() > SIMILAR_ECCEN_TOL {
+        return skerror!("Eccentricities are too dissimilar: {} and {}", e, e_other);
+    }
+    let hhat = orbit_normal(ref_pv);
+    let plane_angle = hhat.dot(&orbit_normal(other_pv)).clamp(-1.0, 1.0).acos();
+    if plane_angle > SIMILAR_PLANE_TOL_DEG.to_radians() {
+        return skerror!(
+            "Orbit planes differ by {} degrees",
+            plane_angle.to_degrees()
+        );
+    }
+
+    // In-plane frame, with x toward perigee
+    let r: Vec3 = ref_pv.fixed_rows::<3>(0).into();
+    let xhat = evec.try_normalize(0.0).unwrap_or_else(|| r.normalize());
+    let yhat = hhat.cross(&xhat);
+    let mean_anomaly = |pos: Vec3| {
+        let nu = f64::atan2(pos.dot(&yhat), pos.dot(&xhat));
+        let ea = f64::atan2(nu.sin() * e.mul_add(-e, 1.0).sqrt(), e + nu.cos());
+        e.mul_add(-ea.sin(), ea)
+    };
+    let dm = mean_anomaly(other_pv.fixed_rows::<3>(0).into()) - mean_anomaly(r);
+
+    let dm = crate::angles::wrap_to_pi(dm);
+    Ok(dm / (mu / a.powi(3)).sqrt())
 }
 
 impl std::fmt::Display for Kepler {
@@ -271,6 +848,52 @@ impl std::fmt::Display for Kepler {
     }
 }
 
+/// Below this magnitude of the argument, Stumpff functions are evaluated
+/// by their power series rather than in closed form
+const STUMPFF_SERIES_MAX: f64 = 1.0;
+
+/// Stumpff functions c_0 through c_5 of `z`
+///
+/// c_n(z) = sum_k (-z)^k / (n + 2k)!, evaluated by series near zero,
+/// where the closed forms lose precision, and otherwise in closed form
+/// for c_0 & c_1, with c_(n+2) = (1/n! - c_n) / z
+pub(crate) fn stumpff(z: f64) -> [f64; 6] {
+    let mut c = [0.0; 6];
+    if z.abs() < STUMPFF_SERIES_MAX {
+        let mut nfact = 1.0;
+        for (n, cn) in c.iter_mut().enumerate() {
+            if n > 0 {
+                nfact *= n as f64;
+            }
+            let mut term = 1.0 / nfact;
+            let mut k = 0;
+            while term.abs() > f64::EPSILON * 1.0e-3 / nfact {
+                *cn += term;
+                k += 1;
+                term *= -z / (((n + 2 * k - 1) * (n + 2 * k)) as f64);
+            }
+        }
+        return c;
+    }
+    if z > 0.0 {
+        let sz = z.sqrt();
+        c[0] = sz.cos();
+        c[1] = sz.sin() / sz;
+    } else {
+        let sz = (-z).sqrt();
+        c[0] = sz.cosh();
+        c[1] = sz.sinh() / sz;
+    }
+    let mut nfact = 1.0;
+    for n in 0..4 {
+        if n > 0 {
+            nfact *= n as f64;
+        }
+        c[n + 2] = (1.0 / nfact - c[n]) / z;
+    }
+    c
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,6 +919,94 @@ mod tests {
         assert!((v * 1.0e-3 - Vec3::new(4.902279, 5.533140, -1.975710)).norm() < 1e-3);
     }
 
+    #[test]
+    fn test_apsis_states() -> SKResult<()> {
+        use crate::consts::MU_EARTH;
+        let (a, eccen) = (2.4e7, 0.7);
+        let k = Kepler::new(a, eccen, 0.5, 1.0, 2.0, Anomaly::True(1.3));
+        let (rp, vp) = k.perigee_state(MU_EARTH)?;
+        let (ra, va) = k.apogee_state(MU_EARTH)?;
+
+        // Apsis radii, and speeds from vis-viva
+        let visviva = |r: f64, a: f64| (MU_EARTH * (2.0 / r - 1.0 / a)).sqrt();
+        assert!((rp.norm() - a * (1.0 - eccen)).abs() < 1.0e-6);
+        assert!((ra.norm() - a * (1.0 + eccen)).abs() < 1.0e-6);
+        assert!((vp.norm() - visviva(rp.norm(), a)).abs() < 1.0e-9);
+        assert!((va.norm() - visviva(ra.norm(), a)).abs() < 1.0e-9);
+        // Velocity is perpendicular to position at the apsides
+        assert!(rp.dot(&vp).abs() < 1.0e-6 * rp.norm() * vp.norm());
+        assert!(ra.dot(&va).abs() < 1.0e-6 * ra.norm() * va.norm());
+
+        // Perigee has the minimum radius & maximum speed over the orbit
+        for ix in 0..36 {
+            let (r, v) = k.state_at_true_anomaly(ix as f64 * 10.0_f64.to_radians(), MU_EARTH)?;
+            assert!(r.norm() >= rp.norm() - 1.0e-6 && r.norm() <= ra.norm() + 1.0e-6);
+            assert!(v.norm() <= vp.norm() + 1.0e-9 && v.norm() >= va.norm() - 1.0e-9);
+            assert!((v.norm() - visviva(r.norm(), a)).abs() < 1.0e-9);
+        }
+
+        // Matches the current state at the current true anomaly
+        let (r, v) = k.to_pv();
+        let (r2, v2) = k.state_at_true_anomaly(k.nu, MU_EARTH)?;
+        assert!((r - r2).norm() < 1.0e-6 && (v - v2).norm() < 1.0e-9);
+
+        // Hyperbolic: perigee only, and only between the asymptotes
+        let (a, eccen) = (-1.0e7, 1.5);
+        let k = Kepler::new(a, eccen, 0.5, 1.0, 2.0, Anomaly::True(0.3));
+        let (rp, vp) = k.perigee_state(MU_EARTH)?;
+        assert!((rp.norm() - a * (1.0 - eccen)).abs() < 1.0e-6);
+        assert!((vp.norm() - visviva(rp.norm(), a)).abs() < 1.0e-9);
+        assert!(k.apogee_state(MU_EARTH).is_err());
+        let nu_inf = (-1.0 / eccen).acos();
+        assert!(k.state_at_true_anomaly(0.99 * nu_inf, MU_EARTH).is_ok());
+        assert!(k.state_at_true_anomaly(1.01 * nu_inf, MU_EARTH).is_err());
+        assert!(k.state_at_true_anomaly(-1.01 * nu_inf, MU_EARTH).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_track_time_offset() -> SKResult<()> {
+        use crate::consts::MU_EARTH;
+        use crate::types::Vector6;
+        let pv = |k: &Kepler| -> Vector6 {
+            let (r, v) = k.to_pv();
+            Vector6::from_iterator(r.iter().chain(v.iter()).copied())
+        };
+
+        // Same orbit, other satellite at a known true anomaly ahead
+        // or behind, including across perigee
+        let (a, eccen, incl, raan, w) = (7.5e6, 0.05, 0.9, 1.2, 2.1);
+        let orbit = |nu: f64| Kepler::new(a, eccen, incl, raan, w, Anomaly::True(nu));
+        for (nu_ref, dnu) in [(0.3, 0.2), (0.3, -0.2), (6.1, 0.4), (0.1, -0.4), (2.0, 2.5)] {
+            let (kref, kother) = (orbit(nu_ref), orbit(nu_ref + dnu));
+            let dt = in_track_time_offset(&pv(&kref), &pv(&kother), MU_EARTH)?;
+            let dm = crate::angles::wrap_to_pi(kother.mean_anomaly() - kref.mean_anomaly());
+            assert!((dt - dm / kref.mean_motion()).abs() < 1.0e-6);
+            assert!(dt.signum() == dnu.signum());
+
+            // Propagating the reference by the offset reaches the other
+            let pv2 = crate::orbitprop::propagate_twobody(&pv(&kref), MU_EARTH, dt)?;
+            assert!((pv2 - pv(&kother)).fixed_rows::<3>(0).norm() < 1.0e-3);
+        }
+
+        // Circular, where the eccentricity vector is only roundoff
+        let circ = |u: f64| Kepler::new(a, 0.0, incl, raan, 0.0, Anomaly::True(u));
+        let dt = in_track_time_offset(&pv(&circ(1.0)), &pv(&circ(0.9)), MU_EARTH)?;
+        assert!((dt + 0.1 / circ(1.0).mean_motion()).abs() < 1.0e-6);
+
+        // Dissimilar orbits
+        let kref = pv(&orbit(0.3));
+        let other = |a2: f64, e2: f64, incl2: f64| {
+            pv(&Kepler::new(a2, e2, incl2, raan, w, Anomaly::True(0.4)))
+        };
+        assert!(in_track_time_offset(&kref, &other(a, eccen, incl + 0.01), MU_EARTH).is_ok());
+        assert!(in_track_time_offset(&kref, &other(1.05 * a, eccen, incl), MU_EARTH).is_err());
+        assert!(in_track_time_offset(&kref, &other(a, 0.1, incl), MU_EARTH).is_err());
+        assert!(in_track_time_offset(&kref, &other(a, eccen, incl + 0.1), MU_EARTH).is_err());
+        assert!(in_track_time_offset(&kref, &other(-a, 1.5, incl), MU_EARTH).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_frompv() {
         // Vallado example 2-5
@@ -309,4 +1020,233 @@ mod tests {
         assert!((k.w - 53.38_f64.to_radians()).abs() < 1e-3);
         assert!((k.nu - 92.335_f64.to_radians()).abs() < 1e-3);
     }
+
+    #[test]
+    fn test_classify_orbit() {
+        use crate::consts::{EARTH_RADIUS, GEO_R};
+        let orbit = |a: f64, e: f64, incl_deg: f64| {
+            classify_orbit(&Kepler::new(
+                a,
+                e,
+                incl_deg.to_radians(),
+                0.0,
+                0.0,
+                Anomaly::True(0.0),
+            ))
+        };
+
+        // ISS
+        assert_eq!(orbit(EARTH_RADIUS + 420.0e3, 0.0005, 51.6), OrbitClass::Leo);
+        // GPS
+        assert_eq!(orbit(26_560.0e3, 0.01, 55.0), OrbitClass::Meo);
+        // Geostationary, and inclined geosynchronous
+        assert_eq!(orbit(GEO_R, 0.0002, 0.05), OrbitClass::Geo);
+        assert_eq!(orbit(GEO_R + 50.0e3, 0.001, 7.0), OrbitClass::Gso);
+        // Molniya, with same semi-major axis as GPS
+        assert_eq!(orbit(26_560.0e3, 0.72, 63.4), OrbitClass::Molniya);
+        assert_eq!(orbit(26_560.0e3, 0.6, 40.0), OrbitClass::Heo);
+        // GTO with 250 km perigee & apogee at GEO altitude
+        let (rp, ra) = (EARTH_RADIUS + 250.0e3, GEO_R);
+        assert_eq!(
+            orbit(0.5 * (rp + ra), (ra - rp) / (ra + rp), 27.0),
+            OrbitClass::Gto
+        );
+        // Graveyard orbit above GEO, and escape trajectory
+        assert_eq!(orbit(GEO_R + 300.0e3, 0.0, 0.0), OrbitClass::Other);
+        assert_eq!(orbit(-1.0e7, 1.5, 0.0), OrbitClass::Other);
+    }
+
+    #[test]
+    fn test_eccentricity_node_vectors() {
+        use crate::consts::MU_EARTH;
+        use crate::types::Vector6;
+        let pv = |k: &Kepler| -> Vector6 {
+            let (r, v) = k.to_pv();
+            Vector6::from_iterator(r.iter().chain(v.iter()).copied())
+        };
+
+        let (incl, raan, w) = (0.9, 1.2, 2.1);
+        for nu in [0.0, 1.0, 3.0, 5.5] {
+            let k = Kepler::new(12.0e6, 0.3, incl, raan, w, Anomaly::True(nu));
+            let e = eccentricity_vector(&pv(&k), MU_EARTH);
+            assert!((e.norm() - 0.3).abs() < 1.0e-12);
+
+            // Points toward perigee
+            let (rp, _) = Kepler::new(12.0e6, 0.3, incl, raan, w, Anomaly::True(0.0)).to_pv();
+            assert!((e.normalize() - rp.normalize()).norm() < 1.0e-12);
+
+            // Node vector is in the equatorial plane, toward the
+            // ascending node
+            let n = node_vector(&pv(&k));
+            assert!(n.z.abs() < 1.0e-9 * n.norm());
+            assert!((n.y.atan2(n.x) - raan).abs() < 1.0e-12);
+            let h = k.to_pv().0.cross(&k.to_pv().1).norm();
+            assert!((n.norm() - h * incl.sin()).abs() < 1.0e-9 * h);
+        }
+
+        // Circular orbit has zero eccentricity vector,
+        // and equatorial orbit zero node vector
+        let k = Kepler::new(7.0e6, 0.0, 0.0, 0.0, 0.0, Anomaly::True(0.7));
+        assert!(eccentricity_vector(&pv(&k), MU_EARTH).norm() < 1.0e-12);
+        assert!(node_vector(&pv(&k)).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_equinoctial() -> SKResult<()> {
+        use crate::consts::MU_EARTH;
+        use crate::types::Vector6;
+        use std::f64::consts::PI;
+        let pv = |k: &Kepler| -> Vector6 {
+            let (r, v) = k.to_pv();
+            Vector6::from_iterator(r.iter().chain(v.iter()).copied())
+        };
+
+        // Round trip, including circular, equatorial & retrograde orbits,
+        // with the retrograde factor chosen away from its singularity
+        for (eccen, incl) in [(0.3, 0.9), (0.0, 0.9), (0.01, 0.0), (0.2, 2.5), (0.1, PI)] {
+            let retrograde = incl > PI / 2.0;
+            for nu in [0.0, 1.0, 3.0, 5.5] {
+                let k = Kepler::new(12.0e6, eccen, incl, 1.2, 2.1, Anomaly::True(nu));
+                let eq = pv_to_equinoctial(&pv(&k), MU_EARTH, retrograde)?;
+                assert!((eq[0] - 12.0e6).abs() < 1.0e-6);
+                assert!((eq[1].hypot(eq[2]) - eccen).abs() < 1.0e-12);
+                let back = equinoctial_to_pv(&eq, MU_EARTH, retrograde)?;
+                let diff = back - pv(&k);
+                assert!(diff.fixed_rows::<3>(0).norm() < 1.0e-6);
+                assert!(diff.fixed_rows::<3>(3).norm() < 1.0e-9);
+
+                // Mean longitude is M + ω + IΩ
+                if eccen > 0.0 && incl > 0.0 && incl < PI {
+                    let ri = if retrograde { -1.0 } else { 1.0 };
+                    let lambda = k.mean_anomaly() + k.w + ri * k.raan;
+                    let dl = (eq[5] - lambda).rem_euclid(2.0 * PI);
+                    assert!(dl.min(2.0 * PI - dl) < 1.0e-10);
+                }
+            }
+        }
+
+        // Singular inclination for the chosen retrograde factor
+        let k = Kepler::new(12.0e6, 0.1, PI, 0.0, 0.0, Anomaly::True(1.0));
+        assert!(pv_to_equinoctial(&pv(&k), MU_EARTH, false).is_err());
+        let k = Kepler::new(12.0e6, 0.1, 0.0, 0.0, 0.0, Anomaly::True(1.0));
+        assert!(pv_to_equinoctial(&pv(&k), MU_EARTH, true).is_err());
+
+        // Not elliptical
+        let eq = Vector6::new(12.0e6, 0.8, 0.8, 0.0, 0.0, 0.0);
+        assert!(equinoctial_to_pv(&eq, MU_EARTH, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_plane_angles() {
+        use crate::types::Vector6;
+        use std::f64::consts::PI;
+        let pv = |k: &Kepler| -> Vector6 {
+            let (r, v) = k.to_pv();
+            Vector6::from_iterator(r.iter().chain(v.iter()).copied())
+        };
+
+        for (incl, raan) in [(0.9, 1.2), (1.7, 4.0), (PI / 2.0, 0.0), (3.0, 5.9)] {
+            let k = Kepler::new(9.0e6, 0.1, incl, raan, 0.4, Anomaly::True(2.0));
+            let (i, r) = plane_angles(&pv(&k));
+            assert!((i - incl).abs() < 1.0e-12);
+            assert!((r - raan).abs() < 1.0e-12);
+
+            // Normal is perpendicular to position & velocity, and
+            // along the angular momentum
+            let n = orbit_normal(&pv(&k));
+            let (p, v) = k.to_pv();
+            assert!((n.norm() - 1.0).abs() < 1.0e-12);
+            assert!(n.dot(&p).abs() < 1.0e-6 && n.dot(&v).abs() < 1.0e-9);
+            assert!(n.dot(&p.cross(&v)) > 0.0);
+            assert!((n.z - incl.cos()).abs() < 1.0e-12);
+        }
+
+        // Equatorial prograde & retrograde orbits: RAAN is undefined
+        for incl in [0.0, PI] {
+            let k = Kepler::new(9.0e6, 0.1, incl, 1.0, 0.4, Anomaly::True(2.0));
+            let (i, r) = plane_angles(&pv(&k));
+            assert!((i - incl).abs() < 1.0e-12);
+            assert_eq!(r, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_mean_anomaly_at() {
+        use crate::consts::{EARTH_RADIUS, JGM3_J2, MU_EARTH};
+        use crate::{Duration, Instant};
+        use std::f64::consts::PI;
+
+        let wrap = crate::angles::wrap_to_pi;
+
+        let epoch = Instant::from_datetime(2024, 3, 1, 12, 0, 0.0);
+        let k = Kepler::new(7.0e6, 0.02, 0.9, 0.3, 1.2, Anomaly::Mean(6.0));
+        let m0 = k.mean_anomaly();
+        let (m, frac) = mean_anomaly_at(&k, &epoch, &epoch, MU_EARTH, None);
+        assert!(wrap(m - m0).abs() < 1.0e-12);
+        assert_eq!(frac, 0.0);
+
+        // After one full period, back to the start, crossing zero;
+        // a quarter period later, a quarter of the way around
+        let t = epoch + Duration::from_seconds(k.period());
+        let (m, frac) = mean_anomaly_at(&k, &epoch, &t, MU_EARTH, None);
+        assert!(wrap(m - m0).abs() < 1.0e-9);
+        assert!(wrap(2.0 * PI * frac).abs() < 1.0e-9);
+        let t = epoch + Duration::from_seconds(1000.25 * k.period());
+        let (m, frac) = mean_anomaly_at(&k, &epoch, &t, MU_EARTH, None);
+        assert!(wrap(m - m0 - PI / 2.0).abs() < 1.0e-6);
+        assert!((frac - 0.25).abs() < 1.0e-6);
+        assert!((0.0..2.0 * PI).contains(&m));
+
+        // Backward in time
+        let t = epoch - Duration::from_seconds(0.25 * k.period());
+        let (m, frac) = mean_anomaly_at(&k, &epoch, &t, MU_EARTH, None);
+        assert!(wrap(m - m0 + PI / 2.0).abs() < 1.0e-9);
+        assert!((frac - 0.75).abs() < 1.0e-9);
+
+        // With J2, the anomalistic period is shorter at this inclination,
+        // by the first-order correction
+        let j2 = Some((-JGM3_J2, EARTH_RADIUS));
+        let k2 = -1.5 * JGM3_J2 * (EARTH_RADIUS / k.semiparameter()).powi(2);
+        let corr = 1.0 + k2 * (1.0 - k.eccen.powi(2)).sqrt() * (1.0 - 1.5 * k.incl.sin().powi(2));
+        assert!(corr > 1.0);
+        let t = epoch + Duration::from_seconds(k.period() / corr);
+        let (m, _) = mean_anomaly_at(&k, &epoch, &t, MU_EARTH, j2);
+        assert!(wrap(m - m0).abs() < 1.0e-9);
+        let (m, _) = mean_anomaly_at(&k, &epoch, &t, MU_EARTH, None);
+        assert!(wrap(m - m0) < -1.0e-4);
+    }
+
+    #[test]
+    fn test_stumpff() {
+        // Closed forms of c_2 & c_3, elliptic & hyperbolic
+        for z in [4.0_f64, -4.0] {
+            let c = stumpff(z);
+            let (c2, c3) = match z > 0.0 {
+                true => (
+                    (1.0 - z.sqrt().cos()) / z,
+                    (z.sqrt() - z.sqrt().sin()) / z.powf(1.5),
+                ),
+                false => (
+                    ((-z).sqrt().cosh() - 1.0) / -z,
+                    ((-z).sqrt().sinh() - (-z).sqrt()) / (-z).powf(1.5),
+                ),
+            };
+            assert!((c[2] - c2).abs() < 1.0e-14);
+            assert!((c[3] - c3).abs() < 1.0e-14);
+        }
+
+        // Series & closed forms agree across the switchover,
+        // and c_n(0) = 1 / n!
+        let below = stumpff(STUMPFF_SERIES_MAX * (1.0 - 1.0e-12));
+        let above = stumpff(STUMPFF_SERIES_MAX);
+        let mut nfact = 1.0;
+        for n in 0..6 {
+            assert!((below[n] - above[n]).abs() < 1.0e-10);
+            if n > 0 {
+                nfact *= n as f64;
+            }
+            assert!((stumpff(0.0)[n] - 1.0 / nfact).abs() < 1.0e-15);
+        }
+    }
 }
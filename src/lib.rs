@@ -12,8 +12,10 @@
 //!   * Terrestrial Geodetic frame (latitude, longitude)
 //! * Two-Line Element Set (TLE) processing, and propagation with SGP4
 //! * Keplerian orbit propagation
+//! * Angles-only initial orbit determination (Gauss method)
 //! * JPL planetary ephemerides
 //! * High-order gravity models
+//! * Geoid undulation & free-air gravity anomaly
 //! * High-precision, high-speed numerical satellite orbit propagation with high-order (9/8) efficient Runga-Kutta solvers, ability to solve for state transition matrix for covariance propagation, and inclusion following forces:
 //!   * High-order Earth gravity with multiple models
 //!   * Solar gravity
@@ -57,6 +59,8 @@
 // Type definitions
 pub mod types;
 
+/// Angle wrapping & unwrapping
+pub mod angles;
 /// Universal constants
 pub mod consts;
 /// Earth orientation parameters (polar motion, delta-UT1, lenth of day)
@@ -65,6 +69,8 @@ pub mod earth_orientation_params;
 pub mod earthgravity;
 /// Conversion between coordinate frames
 pub mod frametransform;
+/// Geoid undulation & gravity anomaly
+pub mod geoid;
 /// Internation Terrestrial Reference Frame coordinates &
 /// transformations to Geodetic, East-North-Up, North-East-Down
 pub mod itrfcoord;
@@ -84,6 +90,8 @@ pub mod sgp4;
 mod solarsystem;
 /// Space Weather
 pub mod spaceweather;
+/// Atmospheric refraction
+pub mod refraction;
 /// Two-line Element Set
 pub mod tle;
 /// Utility functions
@@ -92,6 +100,9 @@ pub mod utils;
 // Filters
 pub mod filters;
 
+/// Initial orbit determination
+pub mod iod;
+
 /// Coordinate frames
 mod frames;
 
@@ -100,11 +111,11 @@ mod ode;
 
 // Time and duration
 mod time;
-pub use time::{Duration, Instant, TimeScale, Weekday};
+pub use time::{Duration, Instant, IntervalSet, TimeScale, Weekday};
 
 // Objects available at crate level
 pub use frames::Frame;
-pub use itrfcoord::ITRFCoord;
+pub use itrfcoord::{Ellipsoid, ITRFCoord};
 pub use solarsystem::SolarSystem;
 pub use tle::TLE;
 pub(crate) use types::skerror;
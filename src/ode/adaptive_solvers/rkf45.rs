@@ -62,7 +62,7 @@ impl RKAdaptive<6, 1> for RKF45 {
         let mut berr = [0.0; 6];
         let mut ix: usize = 0;
         while ix < 6 {
-            berr[ix] = BSTAR[ix] - Self::B[ix];
+            berr[ix] = Self::B[ix] - BSTAR[ix];
             ix += 1;
         }
         berr
@@ -72,5 +72,7 @@ impl RKAdaptive<6, 1> for RKF45 {
 
     const ORDER: usize = 4;
 
+    const INTERP_ORDER: usize = 1;
+
     const FSAL: bool = false;
 }
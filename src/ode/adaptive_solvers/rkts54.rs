@@ -99,7 +99,9 @@ impl RKAdaptive<7, 4> for RKTS54 {
 
     const ORDER: usize = 5;
 
-    const FSAL: bool = false;
+    const INTERP_ORDER: usize = 4;
+
+    const FSAL: bool = true;
 
     // From expanding expressions in Tsitorous paper...
     const BI: [[f64; 4]; 7] = [
@@ -11,6 +11,8 @@ pub struct RKV65 {}
 impl RKAdaptive<10, 6> for RKV65 {
     const ORDER: usize = 6;
 
+    const INTERP_ORDER: usize = 5;
+
     const FSAL: bool = false;
 
     const B: [f64; 10] = rkv65_table::B;
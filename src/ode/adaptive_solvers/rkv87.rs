@@ -11,6 +11,8 @@ pub struct RKV87 {}
 impl RKAdaptive<17, 7> for RKV87 {
     const ORDER: usize = 8;
 
+    const INTERP_ORDER: usize = 7;
+
     const FSAL: bool = false;
 
     const B: [f64; 17] = rkv87_table::B;
@@ -13,6 +13,8 @@ pub struct RKV98 {}
 impl RKAdaptive<N, NI> for RKV98 {
     const ORDER: usize = 9;
 
+    const INTERP_ORDER: usize = 9;
+
     const FSAL: bool = false;
 
     const B: [f64; N] = bt::B;
@@ -14,6 +14,9 @@ const N: usize = 16;
 impl RKAdaptive<N, 1> for RKV98NoInterp {
     const ORDER: usize = 9;
 
+    // Interpolation is not implemented
+    const INTERP_ORDER: usize = 0;
+
     const FSAL: bool = false;
 
     const B: [f64; N] = bt::B;
@@ -28,6 +28,11 @@ impl<const R: usize, const C: usize> ODEState for nalgebra::SMatrix<f64, R, C> {
         self.add_scalar(s)
     }
 
+    #[inline(always)]
+    fn ode_slice_add(&self, s: &[f64]) -> Self {
+        self + Self::from_column_slice(s)
+    }
+
     #[inline(always)]
     fn ode_nelem(&self) -> usize {
         self.ncols() * self.nrows()
@@ -6,6 +6,7 @@
 //! The tests are run for all adaptive solvers with and without interpolation.
 //!
 
+use super::ODEError;
 use super::ODEResult;
 use super::RKAdaptive;
 use super::RKAdaptiveSettings;
@@ -68,6 +69,36 @@ where
     });
 }
 
+/// Test harmonic oscillator with interpolation, integrating backward
+fn harmonic_oscillator_interp_backward<const N: usize, const NI: usize, F>(_integrator: F)
+where
+    F: RKAdaptive<N, NI>,
+{
+    use std::f64::consts::PI;
+
+    let y0 = State::new(1.0, 0.0);
+
+    let settings = RKAdaptiveSettings {
+        dense_output: true,
+        abserror: 1e-12,
+        relerror: 1e-12,
+        ..RKAdaptiveSettings::default()
+    };
+
+    let res = F::integrate(0.0, -PI, &y0, ydot, &settings).unwrap();
+
+    // Interior points, plus the endpoints of the reversed interval
+    let testcount = 100;
+    (0..=testcount).for_each(|idx| {
+        let x = -(idx as f64) * PI / testcount as f64;
+        let interp = F::interpolate(x, &res).unwrap();
+        assert!((interp[0] - x.cos()).abs() < 1e-10);
+        assert!((interp[1] + x.sin()).abs() < 1e-10);
+    });
+    assert!(F::interpolate(0.1, &res).is_err());
+    assert!(F::interpolate(-PI - 0.1, &res).is_err());
+}
+
 /// Test harmonic oscillator with all integrators
 #[test]
 fn test_harmonic_oscillator() {
@@ -87,3 +118,459 @@ fn test_harmonic_oscillator_interp() {
     harmonic_oscillator_interp(super::solvers::RKV87 {});
     harmonic_oscillator_interp(super::solvers::RKV98 {});
 }
+
+/// Test harmonic oscillator with all integrators with interpolation,
+/// integrating backward
+#[test]
+fn test_harmonic_oscillator_interp_backward() {
+    harmonic_oscillator_interp_backward(super::solvers::RKTS54 {});
+    harmonic_oscillator_interp_backward(super::solvers::RKV65 {});
+    harmonic_oscillator_interp_backward(super::solvers::RKV87 {});
+    harmonic_oscillator_interp_backward(super::solvers::RKV98 {});
+}
+
+/// Check that the local error of the interpolant, against the exact
+/// solution from the start of each step, scales as h^(INTERP_ORDER + 1),
+/// using fixed steps of `h` & `h / 2` (chosen to stay above roundoff)
+fn interp_order<const N: usize, const NI: usize, F>(_integrator: F, h: f64)
+where
+    F: RKAdaptive<N, NI>,
+{
+    let y0 = State::new(1.0, 0.0);
+    let local_error = |h: f64| {
+        // Fixed steps: all accepted, & step size never changes
+        let settings = RKAdaptiveSettings {
+            dense_output: true,
+            abserror: 1.0e3,
+            relerror: 1.0e3,
+            minfac: 1.0,
+            maxfac: 1.0,
+            gamma: 1.0,
+            first_step: Some(h),
+            ..RKAdaptiveSettings::default()
+        };
+        let res = F::integrate(0.0, 3.2, &y0, ydot, &settings).unwrap();
+        let dense = res.dense.as_ref().unwrap();
+        let mut emax: f64 = 0.0;
+        for ((x0, y0), h) in dense.x.iter().zip(dense.y.iter()).zip(dense.h.iter()) {
+            for ix in 1..20 {
+                let dx = h * ix as f64 / 20.0;
+                let y = F::interpolate(x0 + dx, &res).unwrap();
+                emax = emax.max((y[0] - y0[0] * dx.cos() - y0[1] * dx.sin()).abs());
+            }
+        }
+        emax
+    };
+    let ratio = local_error(h) / local_error(h / 2.0);
+    let expected = 2.0_f64.powi(F::INTERP_ORDER as i32 + 1);
+    assert!(ratio > 0.5 * expected && ratio < 2.0 * expected);
+}
+
+/// Interpolant orders of all integrators
+#[test]
+fn test_interp_order() {
+    interp_order(super::solvers::RKF45 {}, 0.4);
+    interp_order(super::solvers::RKTS54 {}, 0.2);
+    interp_order(super::solvers::RKV65 {}, 0.4);
+    interp_order(super::solvers::RKV87 {}, 0.8);
+    interp_order(super::solvers::RKV98 {}, 1.6);
+}
+
+/// Two-body (Keplerian) orbit state, position (m) and velocity (m/s)
+type OrbitState = nalgebra::Vector6<f64>;
+
+fn twobody_ydot(_t: f64, y: &OrbitState) -> ODEResult<OrbitState> {
+    const MU: f64 = 3.986004418e14;
+    let r = y.fixed_rows::<3>(0);
+    let accel = -MU / r.norm().powi(3) * r;
+    Ok(OrbitState::new(
+        y[3], y[4], y[5], accel[0], accel[1], accel[2],
+    ))
+}
+
+/// Check that the automatically-selected initial step is accepted
+/// (not rejected) on the first try
+fn initial_step_accepted<const N: usize, const NI: usize, F>(_integrator: F)
+where
+    F: RKAdaptive<N, NI>,
+{
+    const MU: f64 = 3.986004418e14;
+
+    // Circular orbits at LEO, MEO, GEO radii, plus a Molniya-like
+    // orbit started at perigee and at apogee
+    let circ = |r: f64| OrbitState::new(r, 0.0, 0.0, 0.0, (MU / r).sqrt(), 0.0);
+    let ellip = |rp: f64, ra: f64, at_perigee: bool| {
+        let a = (rp + ra) / 2.0;
+        let r = if at_perigee { rp } else { ra };
+        let v = (MU * (2.0 / r - 1.0 / a)).sqrt();
+        OrbitState::new(r, 0.0, 0.0, 0.0, v * 0.5, v * 0.866)
+    };
+    let states = [
+        circ(6.778e6),
+        circ(2.6560e7),
+        circ(4.2164e7),
+        ellip(6.878e6, 4.6378e7, true),
+        ellip(6.878e6, 4.6378e7, false),
+    ];
+
+    for y0 in states.iter() {
+        for relerror in [1.0e-6, 1.0e-9, 1.0e-12] {
+            let mut settings = RKAdaptiveSettings {
+                abserror: 1.0e-8,
+                relerror,
+                ..RKAdaptiveSettings::default()
+            };
+            for stop in [86400.0, -86400.0] {
+                let h = F::initial_step(0.0, stop, y0, &twobody_ydot, &settings).unwrap();
+                assert!(h.is_finite());
+                assert!(h.signum() == f64::signum(stop));
+
+                // Take exactly one step of the estimated size
+                settings.first_step = Some(h);
+                let res = F::integrate(0.0, h, y0, twobody_ydot, &settings).unwrap();
+                assert_eq!(res.nreject, 0);
+                assert_eq!(res.naccept, 1);
+            }
+        }
+    }
+
+    // Stationary state with zero derivative should not produce
+    // a divide-by-zero
+    let y0 = State::new(0.0, 0.0);
+    let settings = RKAdaptiveSettings::default();
+    let h = F::initial_step(0.0, 10.0, &y0, &ydot, &settings).unwrap();
+    assert!(h.is_finite() && h > 0.0);
+    let res = F::integrate(0.0, 10.0, &y0, ydot, &settings).unwrap();
+    assert!(res.y.norm() == 0.0);
+}
+
+/// Test automatic initial step-size selection for several integrators
+#[test]
+fn test_initial_step() {
+    initial_step_accepted(super::solvers::RKF45 {});
+    initial_step_accepted(super::solvers::RKV65 {});
+    initial_step_accepted(super::solvers::RKV87 {});
+    initial_step_accepted(super::solvers::RKV98 {});
+}
+
+/// Per-component absolute error: tightening only the velocity
+/// tolerance should tighten velocity accuracy without paying for
+/// an equally-tight position tolerance
+#[test]
+fn test_abserror_vec() {
+    use super::solvers::RKV98;
+    const MU: f64 = 3.986004418e14;
+    let r0 = 6.878e6;
+    let y0 = OrbitState::new(
+        r0,
+        0.0,
+        0.0,
+        0.0,
+        (MU / r0).sqrt() * 0.8,
+        (MU / r0).sqrt() * 0.6,
+    );
+    let tend = 86400.0;
+
+    let run = |abserror_vec: Vec<f64>| {
+        let settings = RKAdaptiveSettings {
+            relerror: 0.0,
+            abserror_vec: Some(abserror_vec),
+            ..RKAdaptiveSettings::default()
+        };
+        RKV98::integrate(0.0, tend, &y0, twobody_ydot, &settings).unwrap()
+    };
+
+    let truth = RKV98::integrate(
+        0.0,
+        tend,
+        &y0,
+        twobody_ydot,
+        &RKAdaptiveSettings {
+            abserror: 1.0e-10,
+            relerror: 1.0e-14,
+            ..RKAdaptiveSettings::default()
+        },
+    )
+    .unwrap();
+    let poserr = |y: &OrbitState| (y - truth.y).fixed_rows::<3>(0).norm();
+    let velerr = |y: &OrbitState| (y - truth.y).fixed_rows::<3>(3).norm();
+
+    let loose = run(vec![1.0; 6]);
+    let veltight = run(vec![1.0, 1.0, 1.0, 1.0e-4, 1.0e-4, 1.0e-4]);
+    let alltight = run(vec![1.0e-4; 6]);
+
+    // Velocity is much more accurate than with the loose tolerance ...
+    assert!(velerr(&veltight.y) < velerr(&loose.y) / 10.0);
+    // ... but position is not held to the tight tolerance, so fewer steps
+    // are needed than when tightening all components
+    assert!(poserr(&veltight.y) > poserr(&alltight.y));
+    assert!(veltight.naccept < alltight.naccept);
+
+    // Vector length must match the state size
+    let settings = RKAdaptiveSettings {
+        abserror_vec: Some(vec![1.0e-8; 3]),
+        ..RKAdaptiveSettings::default()
+    };
+    assert!(matches!(
+        RKV98::integrate(0.0, tend, &y0, twobody_ydot, &settings),
+        Err(ODEError::AbsErrorSizeMismatch { len: 3, nelem: 6 })
+    ));
+}
+
+/// Maximum number of steps: integration stops with an error after
+/// the given number of accepted steps, reporting where it stopped
+#[test]
+fn test_max_steps() {
+    use super::solvers::{RKV98NoInterp, RKV98};
+    const MU: f64 = 3.986004418e14;
+    let r0 = 6.878e6;
+    let y0 = OrbitState::new(r0, 0.0, 0.0, 0.0, (MU / r0).sqrt(), 0.0);
+    let tend = 86400.0;
+
+    let full =
+        RKV98::integrate(0.0, tend, &y0, twobody_ydot, &RKAdaptiveSettings::default()).unwrap();
+    assert!(full.naccept > 10);
+
+    // Generous limit does not change the solution
+    let settings = RKAdaptiveSettings {
+        max_steps: Some(full.naccept),
+        ..RKAdaptiveSettings::default()
+    };
+    let res = RKV98::integrate(0.0, tend, &y0, twobody_ydot, &settings).unwrap();
+    assert_eq!(res.naccept, full.naccept);
+    assert_eq!(res.y, full.y);
+
+    // Tiny limit stops partway, in either direction
+    let settings = RKAdaptiveSettings {
+        max_steps: Some(5),
+        ..RKAdaptiveSettings::default()
+    };
+    for tend in [tend, -tend] {
+        match RKV98NoInterp::integrate(0.0, tend, &y0, twobody_ydot, &settings) {
+            Err(ODEError::MaxStepsExceeded { steps, x }) => {
+                assert_eq!(steps, 5);
+                assert!(x / tend > 0.0 && x / tend < 1.0);
+            }
+            r => panic!("Expected max steps error, got {:?}", r.map(|s| s.x)),
+        }
+    }
+}
+
+thread_local! {
+    /// Messages captured on this thread, while capturing
+    static CAPTURED: std::cell::RefCell<Option<Vec<String>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Logger that records messages from the ODE solvers, only on threads
+/// within [`capture_ode_logs`], so other tests' logging is unaffected
+struct CaptureLogger;
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "satkit::ode" && CAPTURED.with(|c| c.borrow().is_some())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            CAPTURED.with(|c| {
+                if let Some(v) = c.borrow_mut().as_mut() {
+                    v.push(record.args().to_string());
+                }
+            });
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Run `f`, returning its result & the ODE solver log messages it
+/// produced on this thread
+fn capture_ode_logs<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    static LOGGER: CaptureLogger = CaptureLogger;
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+    CAPTURED.with(|c| *c.borrow_mut() = Some(Vec::new()));
+    let result = f();
+    let messages = CAPTURED.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    (result, messages)
+}
+
+/// Accepted steps are reported to an installed logger
+#[test]
+fn test_step_logging() {
+    use super::solvers::RKV98;
+
+    let y0 = State::new(1.0, 0.0);
+    let (res, messages) = capture_ode_logs(|| {
+        RKV98::integrate(0.0, 10.0, &y0, ydot, &RKAdaptiveSettings::default()).unwrap()
+    });
+    assert!(res.naccept > 0);
+    let naccept = messages
+        .iter()
+        .filter(|msg| msg.starts_with("Step accepted"))
+        .count();
+    assert_eq!(naccept, res.naccept);
+}
+
+/// Step output records each accepted step, in the direction of integration
+#[test]
+fn test_step_output() {
+    use super::solvers::RKV98NoInterp;
+    let y0 = OrbitState::new(6.878e6, 0.0, 0.0, 0.0, 6.0e3, 4.5e3);
+    let mut settings = RKAdaptiveSettings {
+        step_output: true,
+        ..RKAdaptiveSettings::default()
+    };
+    for stop in [20000.0, -20000.0] {
+        let res = RKV98NoInterp::integrate(0.0, stop, &y0, twobody_ydot, &settings).unwrap();
+        let steps = res.steps.as_ref().unwrap();
+        assert_eq!(steps.len(), res.naccept);
+        assert!(steps
+            .windows(2)
+            .all(|w| (w[1].0 - w[0].0) * f64::signum(stop) > 0.0));
+        assert_eq!(steps.last().unwrap().0, stop);
+        assert_eq!(steps.last().unwrap().1, res.y);
+    }
+
+    // Not recorded unless requested
+    settings.step_output = false;
+    let res = RKV98NoInterp::integrate(0.0, 100.0, &y0, twobody_ydot, &settings).unwrap();
+    assert!(res.steps.is_none());
+}
+
+/// First-same-as-last methods reuse the last stage of each accepted step
+/// as the first stage of the next, and all methods reuse the first stage
+/// of a rejected step on its retry
+#[test]
+fn test_fsal_evaluations() {
+    use super::solvers::{RKF45, RKTS54};
+    let y0 = OrbitState::new(6.878e6, 0.0, 0.0, 0.0, 6.0e3, 4.5e3);
+    // Overly large first step, so that some steps are rejected
+    let settings = RKAdaptiveSettings {
+        first_step: Some(2000.0),
+        ..RKAdaptiveSettings::default()
+    };
+
+    // First stage evaluated only for the first step
+    let res = RKTS54::integrate(0.0, 20000.0, &y0, twobody_ydot, &settings).unwrap();
+    assert!(res.nreject > 0);
+    assert_eq!(res.nevals, 1 + 6 * (res.naccept + res.nreject));
+
+    // Not FSAL: first stage evaluated for every accepted step, but not
+    // for the retries of rejected steps
+    let res = RKF45::integrate(0.0, 20000.0, &y0, twobody_ydot, &settings).unwrap();
+    assert!(res.nreject > 0);
+    assert_eq!(res.nevals, res.naccept + 5 * (res.naccept + res.nreject));
+
+    // Count matches the actual number of calls
+    let count = std::cell::Cell::new(0);
+    let counting = |x: f64, y: &OrbitState| {
+        count.set(count.get() + 1);
+        twobody_ydot(x, y)
+    };
+    let res = RKTS54::integrate(0.0, 20000.0, &y0, counting, &settings).unwrap();
+    assert_eq!(res.nevals, count.get());
+}
+
+/// Both embedded solutions of a single step, for an RKF45 step of
+/// y' = -y, whose solutions are the stability polynomials of the
+/// 4th- and 5th-order Fehlberg methods
+#[test]
+fn test_step_debug() {
+    use super::solvers::RKF45;
+    let decay = |_x: f64, y: &State| -> ODEResult<State> { Ok(-y) };
+    let y0 = State::new(1.0, -2.0);
+    let h = 0.1;
+    let step = RKF45::step_debug(0.0, &y0, h, decay).unwrap();
+
+    let z: f64 = -h;
+    let taylor4 = 1.0 + z + z.powi(2) / 2.0 + z.powi(3) / 6.0 + z.powi(4) / 24.0;
+    let r4 = taylor4 + z.powi(5) / 104.0;
+    let r5 = taylor4 + z.powi(5) / 120.0 + z.powi(6) / 2080.0;
+    assert!((step.y_high - y0 * r5).abs().max() < 1.0e-15);
+    assert!((step.y_low - y0 * r4).abs().max() < 1.0e-15);
+    assert!((step.error - (step.y_high - step.y_low)).abs().max() < 1.0e-15);
+
+    // Higher-order solution is the one taken by the integrator
+    let settings = RKAdaptiveSettings {
+        first_step: Some(h),
+        ..RKAdaptiveSettings::default()
+    };
+    let res = RKF45::integrate(0.0, h, &y0, decay, &settings).unwrap();
+    assert_eq!(res.naccept, 1);
+    assert_eq!(res.y, step.y_high);
+}
+
+/// Maximum interpolation error, & maximum error at the ends of steps,
+/// in position (meters) over 5000 seconds of an elliptical two-body orbit
+fn twobody_interp_error<const N: usize, const NI: usize, F>(_integrator: F) -> (f64, f64)
+where
+    F: RKAdaptive<N, NI>,
+{
+    const MU: f64 = 3.986004418e14;
+    let r = 7.0e6;
+    let pv = OrbitState::new(
+        r,
+        0.0,
+        0.0,
+        0.0,
+        (MU / r).sqrt() * 0.9,
+        (MU / r).sqrt() * 0.5,
+    );
+    let period = 5000.0;
+    let settings = RKAdaptiveSettings {
+        dense_output: true,
+        abserror: 1.0e-6,
+        relerror: 1.0e-12,
+        ..RKAdaptiveSettings::default()
+    };
+    let res = F::integrate(0.0, period, &pv, twobody_ydot, &settings).unwrap();
+    let error = |x: f64| {
+        let exact = crate::orbitprop::propagate_twobody(&pv, MU, x).unwrap();
+        (F::interpolate(x, &res).unwrap() - exact)
+            .fixed_rows::<3>(0)
+            .norm()
+    };
+    let interp = (0..=1000)
+        .map(|ix| error(ix as f64 * period / 1000.0))
+        .fold(0.0, f64::max);
+    let nodes = res
+        .dense
+        .as_ref()
+        .unwrap()
+        .x
+        .iter()
+        .map(|x| error(*x))
+        .fold(0.0, f64::max);
+    (interp, nodes)
+}
+
+/// Interpolation error relative to the integration error on a two-body
+/// arc: interpolants of at least the embedded order are about as
+/// accurate between steps as at the ends of steps, but the linear
+/// interpolant of RKF45 is orders of magnitude worse, & a warning is
+/// logged when it is requested
+#[test]
+fn test_twobody_interp_error() {
+    use super::solvers::{RKF45, RKTS54, RKV65, RKV87, RKV98};
+    for (interp, nodes) in [
+        twobody_interp_error(RKTS54 {}),
+        twobody_interp_error(RKV65 {}),
+        twobody_interp_error(RKV87 {}),
+        twobody_interp_error(RKV98 {}),
+    ] {
+        assert!(interp < 4.0 * nodes);
+    }
+
+    let ((interp, nodes), messages) = capture_ode_logs(|| twobody_interp_error(RKF45 {}));
+    assert!(interp > 1.0e4 * nodes);
+    let nwarn = messages
+        .iter()
+        .filter(|msg| msg.starts_with("Dense output interpolant"))
+        .count();
+    assert_eq!(nwarn, 1);
+}
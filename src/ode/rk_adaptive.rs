@@ -1,11 +1,82 @@
 use super::types::*;
 use super::RKAdaptiveSettings;
 
+/// Scale against which the local error is normalized:
+/// relative error times state magnitude, plus absolute error
+/// (scalar, or per-component if specified)
+fn error_scale<S: ODEState>(yabs: S, settings: &RKAdaptiveSettings) -> ODEResult<S> {
+    let yrel = yabs * settings.relerror;
+    match &settings.abserror_vec {
+        None => Ok(yrel.ode_scalar_add(settings.abserror)),
+        Some(abserror) => {
+            if abserror.len() != yrel.ode_nelem() {
+                return ODEError::AbsErrorSizeMismatch {
+                    len: abserror.len(),
+                    nelem: yrel.ode_nelem(),
+                }
+                .into();
+            }
+            Ok(yrel.ode_slice_add(abserror))
+        }
+    }
+}
+
+/// Append the stages ("k"s) after the first of a step of size `h`
+/// from (`x`, `y`)
+fn add_stages<const N: usize, S: ODEState>(
+    karr: &mut Vec<S>,
+    x: f64,
+    y: &S,
+    h: f64,
+    a: &[[f64; N]; N],
+    c: &[f64; N],
+    ydot: &impl Fn(f64, &S) -> ODEResult<S>,
+) -> ODEResult<()> {
+    for k in 1..N {
+        karr.push(ydot(
+            h.mul_add(c[k], x),
+            &(karr
+                .iter()
+                .enumerate()
+                .fold(y.clone(), |acc, (idx, ki)| acc + ki.clone() * a[k][idx] * h)),
+        )?);
+    }
+    Ok(())
+}
+
+/// Solution at the end of a step of size `h` from `y`, with the
+/// given weights of the stages
+fn step_solution<S: ODEState>(karr: &[S], y: &S, h: f64, b: &[f64]) -> S {
+    karr.iter()
+        .enumerate()
+        .fold(y.clone() * 1.0 / h, |acc, (idx, k)| {
+            acc + k.clone() * b[idx]
+        })
+        * h
+}
+
+/// Local error estimate of a step of size `h`: the difference of the
+/// propagated & embedded solutions
+fn error_estimate<S: ODEState>(karr: &[S], h: f64, berr: &[f64]) -> S {
+    karr.iter()
+        .enumerate()
+        .fold(S::ode_zero(), |acc, (idx, k)| {
+            if berr[idx].abs() > 1.0e-9 {
+                acc + k.clone() * berr[idx]
+            } else {
+                acc
+            }
+        })
+        * h
+}
+
 pub trait RKAdaptive<const N: usize, const NI: usize> {
     // Butcher Tableau Coefficients
     const A: [[f64; N]; N];
     const C: [f64; N];
     const B: [f64; N];
+    /// Weights of the error estimate: `B` minus the weights of
+    /// the embedded solution
     const BERR: [f64; N];
 
     // Interpolation coefficients
@@ -14,8 +85,20 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
     // order
     const ORDER: usize;
 
+    /// Order of the dense-output interpolant: its local error scales
+    /// as h^(INTERP_ORDER + 1).  Interpolated values are only as accurate
+    /// as the steps if this is at least `ORDER - 1`, the order of the
+    /// error-controlled (embedded) solution; a lower-order interpolant
+    /// (e.g., the linear interpolant of RKF45) can be far less accurate
+    /// than the integration tolerance between steps
+    const INTERP_ORDER: usize;
+
     /// First Same as Last
     /// (first compute of next iteration is same as last compute of last iteration)
+    ///
+    /// If true, the last stage must be evaluated at the end of the step with
+    /// the new state (last row of `A` equal to `B`, and last `C` of 1), and its
+    /// derivative is reused after an accepted step, saving one evaluation
     const FSAL: bool;
 
     fn interpolate<S: ODEState>(xinterp: f64, sol: &ODESolution<S>) -> ODEResult<S> {
@@ -187,6 +270,95 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
         Ok(y)
     }
 
+    /// Estimate the size of the initial step
+    ///
+    /// Uses the algorithm of Hairer, Nørsett & Wanner,
+    /// "Solving Ordinary Differential Equations I", Section II.4,
+    /// which looks at the magnitude of the state, its derivative,
+    /// and an estimate of its second derivative
+    /// (also used by OrdinaryDiffEq.jl and scipy)
+    ///
+    /// Takes two derivative function evaluations
+    ///
+    /// Returns the signed step size (negative if integrating backward)
+    fn initial_step<S: ODEState>(
+        start: f64,
+        stop: f64,
+        y0: &S,
+        ydot: &impl Fn(f64, &S) -> ODEResult<S>,
+        settings: &RKAdaptiveSettings,
+    ) -> ODEResult<f64> {
+        let tdir = match stop > start {
+            true => 1.0,
+            false => -1.0,
+        };
+        let sci = error_scale(y0.ode_abs(), settings)?;
+
+        let d0 = y0.ode_elem_div(&sci).ode_scaled_norm();
+        let ydot0 = ydot(start, y0)?;
+        let d1 = ydot0.ode_elem_div(&sci).ode_scaled_norm();
+
+        // If state or derivative is (nearly) zero, the ratio below
+        // is meaningless ... take a small step and let the
+        // second-derivative estimate sort it out
+        let h0 = match d0 < 1.0e-5 || d1 < 1.0e-5 {
+            true => 1.0e-6,
+            false => 0.01 * d0 / d1,
+        };
+
+        // Explicit Euler step to estimate the second derivative
+        let y1 = y0.clone() + ydot0.clone() * (h0 * tdir);
+        let ydot1 = ydot(h0.mul_add(tdir, start), &y1)?;
+        let d2 = (ydot1 - ydot0).ode_elem_div(&sci).ode_scaled_norm() / h0;
+
+        let dmax = f64::max(d1, d2);
+        let h1: f64 = match dmax <= 1.0e-15 {
+            false => (0.01 / dmax).powf(1.0 / (Self::ORDER as f64 + 1.0)),
+            true => f64::max(1.0e-6, h0 * 1.0e-3),
+        };
+
+        Ok(f64::min(100.0 * h0, h1) * tdir)
+    }
+
+    /// Take a single step, returning both embedded solutions, e.g. for
+    /// diagnosing the local error estimate used for step-size control
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Start of the step
+    /// * `y` - State at the start of the step
+    /// * `h` - Step size; may be negative
+    /// * `ydot` - Derivative function
+    ///
+    /// # Returns
+    ///
+    /// * [`StepDebug`] with the propagated (higher-order) solution, the
+    ///   embedded (lower-order) solution, and the error estimate
+    ///
+    /// # Notes
+    ///
+    /// * All of the adaptive methods here propagate the higher-order
+    ///   solution (local extrapolation), so `y_high` is the state that
+    ///   [`RKAdaptive::integrate`] would take for an accepted step
+    /// * Only built for tests
+    #[cfg(test)]
+    fn step_debug<S: ODEState>(
+        x: f64,
+        y: &S,
+        h: f64,
+        ydot: impl Fn(f64, &S) -> ODEResult<S>,
+    ) -> ODEResult<StepDebug<S>> {
+        let mut karr = Vec::with_capacity(N);
+        karr.push(ydot(x, y)?);
+        add_stages(&mut karr, x, y, h, &Self::A, &Self::C, &ydot)?;
+        let bhat: [f64; N] = std::array::from_fn(|ix| Self::B[ix] - Self::BERR[ix]);
+        Ok(StepDebug {
+            y_high: step_solution(&karr, y, h, &Self::B),
+            y_low: step_solution(&karr, y, h, &bhat),
+            error: error_estimate(&karr, h, &Self::BERR),
+        })
+    }
+
     fn integrate<S: ODEState>(
         start: f64,
         stop: f64,
@@ -206,26 +378,25 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
             false => -1.0,
         };
 
-        // Take guess at initial stepsize
-        let mut h = {
-            // Adapted from OrdinaryDiffEq.jl
-            let sci = (y0.ode_abs() * settings.relerror).ode_scalar_add(settings.abserror);
-
-            let d0 = y0.ode_elem_div(&sci).ode_scaled_norm();
-            let ydot0 = ydot(start, y0)?;
-            let d1 = ydot0.ode_elem_div(&sci).ode_scaled_norm();
-            let h0 = 0.01 * d0 / d1 * tdir;
-            let y1 = y0.clone() + ydot0.clone() * h0;
-            let ydot1 = ydot(start + h0, &y1)?;
-            let d2 = (ydot1 - ydot0).ode_elem_div(&sci).ode_scaled_norm() / h0;
-            let dmax = f64::max(d1, d2);
-            let h1: f64 = match dmax < 1e-15 {
-                false => 10.0_f64.powf(-(2.0 + dmax.log10()) / (Self::ORDER as f64)),
-                true => f64::max(1e-6, h0.abs() * 1e-3),
-            };
-            nevals += 2;
-            f64::min(100.0 * h0.abs(), h1.abs()) * tdir
+        // Take guess at initial stepsize, unless one is specified
+        let mut h = match settings.first_step {
+            Some(h0) => h0.abs() * tdir,
+            None => {
+                nevals += 2;
+                Self::initial_step(start, stop, y0, &ydot, settings)?
+            }
         };
+        // Step size is controlled on the step error only, so warn if the
+        // interpolant cannot match the step accuracy
+        if settings.dense_output && Self::INTERP_ORDER + 1 < Self::ORDER {
+            log::warn!(
+                target: "satkit::ode",
+                "Dense output interpolant is order {}, below the integration order {}; \
+                 interpolated values may exceed the error tolerance",
+                Self::INTERP_ORDER,
+                Self::ORDER
+            );
+        }
         let mut accepted_steps: Option<DenseOutput<S>> = match settings.dense_output {
             false => None,
             true => Some(DenseOutput {
@@ -235,6 +406,15 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
                 y: Vec::new(),
             }),
         };
+        let mut steps: Option<Vec<(f64, S)>> = match settings.step_output {
+            false => None,
+            true => Some(Vec::new()),
+        };
+
+        // Derivative at the current state, if already known: the first
+        // stage of a rejected step, or the last stage of an accepted step
+        // for methods that are "first same as last"
+        let mut kfsal: Option<S> = None;
 
         // OK ... lets integrate!
         loop {
@@ -242,48 +422,30 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
                 h = stop - x;
             }
             let mut karr = Vec::with_capacity(N);
-            karr.push(ydot(x, &y)?);
+            karr.push(match kfsal.take() {
+                Some(k) => k,
+                None => {
+                    nevals += 1;
+                    ydot(x, &y)?
+                }
+            });
 
             // Create the "k"s
-            for k in 1..N {
-                karr.push(ydot(
-                    h.mul_add(Self::C[k], x),
-                    &(karr.iter().enumerate().fold(y.clone(), |acc, (idx, ki)| {
-                        acc + ki.clone() * Self::A[k][idx] * h
-                    })),
-                )?);
-            }
+            add_stages(&mut karr, x, &y, h, &Self::A, &Self::C, &ydot)?;
 
             // Sum the "k"s
-            let ynp1 = karr
-                .iter()
-                .enumerate()
-                .fold(y.clone() * 1.0 / h, |acc, (idx, k)| {
-                    acc + k.clone() * Self::B[idx]
-                })
-                * h;
+            let ynp1 = step_solution(&karr, &y, h, &Self::B);
 
             // Compute the "error" state by differencing the p and p* orders
-            let yerr = karr
-                .iter()
-                .enumerate()
-                .fold(S::ode_zero(), |acc, (idx, k)| {
-                    if Self::BERR[idx].abs() > 1.0e-9 {
-                        acc + k.clone() * Self::BERR[idx]
-                    } else {
-                        acc
-                    }
-                })
-                * h;
+            let yerr = error_estimate(&karr, h, &Self::BERR);
 
             // Compute normalized error
             let enorm = {
-                let mut ymax = y.ode_abs().ode_elem_max(&ynp1.ode_abs()) * settings.relerror;
-                ymax = ymax.ode_scalar_add(settings.abserror);
+                let ymax = error_scale(y.ode_abs().ode_elem_max(&ynp1.ode_abs()), settings)?;
                 let ydiv = yerr.ode_elem_div(&ymax);
                 ydiv.ode_scaled_norm()
             };
-            nevals += N;
+            nevals += N - 1;
 
             if !enorm.is_finite() {
                 return ODEError::StepErrorToSmall.into();
@@ -303,6 +465,12 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
             };
 
             if (enorm < 1.0) || (h.abs() <= settings.dtmin) {
+                // Last stage is the derivative at the new state, so is
+                // the first stage of the next step
+                if Self::FSAL {
+                    kfsal = Some(karr[N - 1].clone());
+                }
+
                 // If dense output requested, record dense output
                 if settings.dense_output {
                     let astep = accepted_steps.as_mut().unwrap();
@@ -312,19 +480,42 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
                     astep.y.push(y.clone());
                 }
 
+                log::trace!(
+                    target: "satkit::ode",
+                    "Step accepted: x = {}, h = {}, error = {:.3e}",
+                    x,
+                    h,
+                    enorm
+                );
+
                 // Adjust step size
                 qold = f64::max(enorm, 1.0e-4);
                 x += h;
                 y = ynp1;
                 h /= q;
+                if let Some(steps) = steps.as_mut() {
+                    steps.push((x, y.clone()));
+                }
 
                 naccept += 1;
                 if (tdir > 0.0 && x >= stop) || (tdir < 0.0 && x <= stop) {
                     break;
                 }
+                if settings.max_steps.is_some_and(|m| naccept >= m) {
+                    return ODEError::MaxStepsExceeded { steps: naccept, x }.into();
+                }
             } else {
+                log::trace!(
+                    target: "satkit::ode",
+                    "Step rejected: x = {}, h = {}, error = {:.3e}",
+                    x,
+                    h,
+                    enorm
+                );
                 nreject += 1;
                 h /= f64::min(1.0 / settings.minfac, q11 / settings.gamma);
+                // Retry from the same state, so the first stage is unchanged
+                kfsal = Some(karr.swap_remove(0));
             }
         }
 
@@ -335,6 +526,7 @@ pub trait RKAdaptive<const N: usize, const NI: usize> {
             x,
             y,
             dense: accepted_steps,
+            steps,
         })
     }
 }
@@ -4,6 +4,10 @@
 pub struct RKAdaptiveSettings {
     /// Absolute error tolerance
     pub abserror: f64,
+    /// Per-component absolute error tolerance.  If set, this overrides
+    /// `abserror`; length must match the number of elements in the state
+    /// (in column-major order for matrix states)
+    pub abserror_vec: Option<Vec<f64>>,
     /// Relative error tolerance
     pub relerror: f64,
     /// Minimum factor for step size
@@ -16,18 +20,30 @@ pub struct RKAdaptiveSettings {
     pub dtmin: f64,
     /// Enable dense output (more storage, but allows interpolation)
     pub dense_output: bool,
+    /// Record "x" and the state at the end of each accepted step
+    pub step_output: bool,
+    /// Size of the first step.  If `None`, it is estimated
+    /// automatically from the derivative at the start
+    pub first_step: Option<f64>,
+    /// Maximum number of accepted steps.  If `None`, the number
+    /// of steps is unbounded
+    pub max_steps: Option<usize>,
 }
 
 impl Default for RKAdaptiveSettings {
     fn default() -> Self {
         Self {
             abserror: 1.0e-8,
+            abserror_vec: None,
             relerror: 1.0e-8,
             minfac: 0.2,
             maxfac: 10.0,
             gamma: 0.9,
             dtmin: 1.0e-6,
             dense_output: false,
+            step_output: false,
+            first_step: None,
+            max_steps: None,
         }
     }
 }
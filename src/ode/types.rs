@@ -17,6 +17,10 @@ pub enum ODEError {
     InterpNotImplemented,
     #[error("Y dot Function Error: {0}")]
     YDotError(String),
+    #[error("Absolute error vector length {len} does not match state size {nelem}")]
+    AbsErrorSizeMismatch { len: usize, nelem: usize },
+    #[error("Maximum number of steps ({steps}) exceeded at x = {x}")]
+    MaxStepsExceeded { steps: usize, x: f64 },
 }
 
 /// Ouptut of ODE integrator
@@ -53,6 +57,10 @@ pub trait ODEState:
     // Add scalar to each element
     fn ode_scalar_add(&self, s: f64) -> Self;
 
+    // Element-wise addition of slice (column-major for matrices)
+    // Slice length must equal number of elements
+    fn ode_slice_add(&self, s: &[f64]) -> Self;
+
     // Number of elements
     fn ode_nelem(&self) -> usize;
 
@@ -83,6 +91,24 @@ where
     pub y: Vec<S>,
 }
 
+/// Both embedded solutions of a single adaptive Runge-Kutta step,
+/// for diagnostics
+///
+/// See [`crate::ode::RKAdaptive::step_debug`]
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct StepDebug<S>
+where
+    S: ODEState,
+{
+    /// Higher-order solution at the end of the step
+    pub y_high: S,
+    /// Lower-order (embedded) solution at the end of the step
+    pub y_low: S,
+    /// Local error estimate, `y_high - y_low`
+    pub error: S,
+}
+
 /// Solution of an ODE
 /// Contains the final state, final x value, and dense output if enabled
 /// Also contains statistics on the number of steps taken
@@ -107,4 +133,7 @@ where
     pub y: S,
     /// The dense output, if enabled
     pub dense: Option<DenseOutput<S>>,
+    /// "x" and state at the end of each accepted step, if enabled
+    #[serde(default = "Option::default")]
+    pub steps: Option<Vec<(f64, S)>>,
 }
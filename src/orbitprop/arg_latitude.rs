@@ -0,0 +1,245 @@
+//! Propagation to a given argument of latitude, e.g. for phasing
+//! and maneuver timing
+
+use super::propagator::propagate;
+use super::search::find_roots;
+use super::{PropSettings, SatState};
+
+use crate::angles::wrap_to_pi;
+use crate::kepler::Kepler;
+use crate::skerror;
+use crate::types::Vector3;
+use crate::{Duration, SKResult};
+
+use std::f64::consts::PI;
+
+/// Number of samples per orbit used to scan for the crossing.  This
+/// keeps the change in argument of latitude between samples well under
+/// π even near perigee of highly eccentric orbits
+const SCAN_PER_ORBIT: usize = 360;
+
+/// Crossings are searched for over this many (osculating) orbits
+const SEARCH_ORBITS: f64 = 1.5;
+
+/// Precision, in seconds, to which the crossing time is found
+const CROSSING_TOL: f64 = 1.0e-6;
+
+/// Crossings closer than this, in seconds, to the start time
+/// are not considered to be the next crossing
+const MIN_CROSSING_TIME: f64 = 1.0e-3;
+
+/// Argument of latitude: angle in the orbit plane from the
+/// ascending node to the position, in the direction of motion
+///
+/// Returns `None` for an equatorial orbit, which has no ascending node
+fn arg_latitude(pos: &Vector3, vel: &Vector3) -> Option<f64> {
+    let h = pos.cross(vel).normalize();
+    let node = Vector3::z().cross(&h);
+    if node.norm() < 1.0e-10 {
+        return None;
+    }
+    let node = node.normalize();
+    Some(
+        node.cross(pos)
+            .dot(&h)
+            .atan2(node.dot(pos))
+            .rem_euclid(2.0 * PI),
+    )
+}
+
+/// Time, in seconds from the start, of the first crossing of the
+/// target argument of latitude after the start
+///
+/// `u_at` gives the argument of latitude at a time from the start; the
+/// search is over `(0, span]`, sampled at `scan_step`
+fn next_crossing<F>(u_at: &F, target_u: f64, span: f64, scan_step: f64) -> SKResult<f64>
+where
+    F: Fn(f64) -> SKResult<f64>,
+{
+    // Signed angle from target to the argument of latitude, from just
+    // after the start time.  This increases through zero at a crossing
+    let offset =
+        |t: f64| -> SKResult<f64> { Ok(wrap_to_pi(u_at(t + MIN_CROSSING_TIME)? - target_u)) };
+
+    // Offset wraps from π to -π half an orbit from the target,
+    // so only a rising sign change is a crossing
+    match find_roots(&offset, span - MIN_CROSSING_TIME, scan_step, CROSSING_TOL)?
+        .into_iter()
+        .find(|root| root.rising)
+    {
+        Some(root) => Ok(root.t + MIN_CROSSING_TIME),
+        None => skerror!("No crossing of argument of latitude {} found", target_u),
+    }
+}
+
+/// Propagate a satellite state to the next time at which it reaches
+/// the given argument of latitude
+///
+/// # Arguments
+///
+/// * `state` - Satellite state.  Covariance, if set, is propagated
+/// * `target_u` - Target argument of latitude, radians.  This is the
+///   angle in the orbit plane from the ascending node to the satellite,
+///   in the direction of motion, so 0 is the ascending node & π the
+///   descending node
+/// * `settings` - Propagation settings.  Default settings are used if `None`
+///
+/// # Returns
+///
+/// * Satellite state at the next crossing of the target argument of
+///   latitude, or error if the orbit is equatorial
+///
+/// # Notes
+///
+/// * The argument of latitude is multivalued in time, so the first
+///   crossing after the state time is returned.  If the state is already
+///   at the target, the crossing one orbit later is returned
+/// * Argument of latitude is computed from the osculating orbit plane
+///   in the GCRF frame
+pub fn propagate_to_arg_latitude(
+    state: &SatState,
+    target_u: f64,
+    settings: Option<&PropSettings>,
+) -> SKResult<SatState> {
+    let (pos, vel) = (state.pos_gcrf(), state.vel_gcrf());
+    if arg_latitude(&pos, &vel).is_none() {
+        return skerror!("Argument of latitude is undefined for an equatorial orbit");
+    }
+    let period = Kepler::from_pv(pos, vel)?.period();
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    let enable_interp = settings.enable_interp;
+    settings.enable_interp = true;
+
+    let span = SEARCH_ORBITS * period;
+    let stop = state.time + Duration::from_seconds(span);
+    let res = propagate(&state.pv, &state.time, &stop, &settings, None)?;
+    let u_at = |t: f64| -> SKResult<f64> {
+        let pv = res.interp(&(state.time + Duration::from_seconds(t)))?;
+        match arg_latitude(&pv.fixed_rows::<3>(0).into(), &pv.fixed_rows::<3>(3).into()) {
+            Some(u) => Ok(u),
+            None => skerror!("Orbit became equatorial during propagation"),
+        }
+    };
+    let t = next_crossing(
+        &u_at,
+        target_u.rem_euclid(2.0 * PI),
+        span,
+        period / SCAN_PER_ORBIT as f64,
+    )?;
+
+    settings.enable_interp = enable_interp;
+    state.propagate(&(state.time + Duration::from_seconds(t)), Some(&settings))
+}
+
+/// Propagate a satellite state to its next ascending or descending node
+///
+/// # Arguments
+///
+/// * `state` - Satellite state.  Covariance, if set, is propagated
+/// * `ascending` - True for the ascending node (argument of latitude 0),
+///   false for the descending node (argument of latitude π)
+/// * `settings` - Propagation settings.  Default settings are used if `None`
+///
+/// # Returns
+///
+/// * Satellite state at the next crossing of the node,
+///   or error if the orbit is equatorial
+///
+/// # Notes
+///
+/// * See [`propagate_to_arg_latitude`]
+pub fn propagate_to_node(
+    state: &SatState,
+    ascending: bool,
+    settings: Option<&PropSettings>,
+) -> SKResult<SatState> {
+    let target_u = match ascending {
+        true => 0.0,
+        false => PI,
+    };
+    propagate_to_arg_latitude(state, target_u, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::Anomaly;
+    use crate::Instant;
+
+    /// Half an arcsecond, radians
+    const HALF_ARCSEC: f64 = 0.5 / 3600.0 * PI / 180.0;
+
+    fn angle_diff(a: f64, b: f64) -> f64 {
+        wrap_to_pi(a - b).abs()
+    }
+
+    #[test]
+    fn test_next_crossing() -> SKResult<()> {
+        // Two-body orbits, including a highly eccentric one, for which
+        // the argument of latitude changes quickly near perigee
+        for (e, w) in [(0.001, 0.3), (0.7, 1.0)] {
+            let a = consts::EARTH_RADIUS + 20000.0e3;
+            let kep = Kepler::new(a, e, 0.9, 0.2, w, Anomaly::True(0.5));
+            let u_at = |t: f64| -> SKResult<f64> {
+                let ma = kep.mean_motion().mul_add(t, kep.mean_anomaly());
+                let k = Kepler::new(a, e, 0.9, 0.2, w, Anomaly::Mean(ma.rem_euclid(2.0 * PI)));
+                let (p, v) = k.to_pv();
+                Ok(arg_latitude(&p, &v).unwrap())
+            };
+            let period = kep.period();
+            let scan = period / SCAN_PER_ORBIT as f64;
+            let u0 = u_at(0.0)?;
+            for target in [0.0, 1.0, PI, u0 - 0.01, u0 + 0.01] {
+                let target = target.rem_euclid(2.0 * PI);
+                let t = next_crossing(&u_at, target, SEARCH_ORBITS * period, scan)?;
+                assert!(angle_diff(u_at(t)?, target) < HALF_ARCSEC);
+                assert!(t > 0.0 && t < period);
+            }
+            // Just passed the target: next crossing is nearly an orbit later
+            let t = next_crossing(&u_at, u0 - 0.01, SEARCH_ORBITS * period, scan)?;
+            assert!(t > 0.9 * period);
+            // Already at the target: next crossing is one orbit later
+            let t = next_crossing(&u_at, u0, SEARCH_ORBITS * period, scan)?;
+            assert!((t - period).abs() < 1.0e-3);
+        }
+
+        // Equatorial orbit has no argument of latitude
+        let kep = Kepler::new(consts::GEO_R, 0.0, 0.0, 0.0, 0.0, Anomaly::True(0.0));
+        let (p, v) = kep.to_pv();
+        assert!(arg_latitude(&p, &v).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagate_to_arg_latitude() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 5, 1, 0, 0, 0.0);
+        let kep = Kepler::new(
+            consts::EARTH_RADIUS + 500.0e3,
+            0.01,
+            97.5_f64.to_radians(),
+            0.3,
+            0.5,
+            Anomaly::True(1.0),
+        );
+        let (pos, vel) = kep.to_pv();
+        let state = SatState::from_pv(&time, &pos, &vel);
+
+        for target in [0.5, 2.0, 4.0] {
+            let s = propagate_to_arg_latitude(&state, target, None)?;
+            let u = arg_latitude(&s.pos_gcrf(), &s.vel_gcrf()).unwrap();
+            assert!(angle_diff(u, target) < HALF_ARCSEC);
+            assert!(s.time > state.time);
+            assert!((s.time - state.time).as_seconds() < kep.period());
+        }
+
+        // Nodes: latitude (z position) is zero, moving north at
+        // the ascending node & south at the descending node
+        let s = propagate_to_node(&state, true, None)?;
+        assert!(s.pos_gcrf()[2].abs() < 1.0 && s.vel_gcrf()[2] > 0.0);
+        let s = propagate_to_node(&state, false, None)?;
+        assert!(s.pos_gcrf()[2].abs() < 1.0 && s.vel_gcrf()[2] < 0.0);
+        Ok(())
+    }
+}
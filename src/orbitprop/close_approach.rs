@@ -0,0 +1,234 @@
+//! Time of closest approach (TCA) between satellite ephemerides
+
+use super::search::find_roots;
+use super::Ephemeris;
+
+use crate::skerror;
+use crate::types::Vector6;
+use crate::{Duration, Instant, SKResult};
+
+/// Precision, in seconds, to which the time of closest approach is found
+const TCA_TOL: f64 = 1.0e-6;
+
+/// Close approach between a primary & a secondary satellite
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloseApproach {
+    /// Index of the secondary satellite in the list that was screened;
+    /// always 0 for [`find_tca`]
+    pub secondary: usize,
+    /// Time of closest approach
+    pub time: Instant,
+    /// Distance between the satellites at closest approach, meters
+    pub miss_distance: f64,
+    /// Relative speed at closest approach, meters / second
+    pub relative_speed: f64,
+}
+
+/// Relative position & velocity dot product, which increases through
+/// zero at a local minimum of the distance
+fn range_rate_sign(rel: &Vector6) -> f64 {
+    rel.fixed_rows::<3>(0).dot(&rel.fixed_rows::<3>(3))
+}
+
+/// Close approaches of a single secondary, in time order
+fn approaches(
+    primary: &Ephemeris,
+    secondary: &Ephemeris,
+    index: usize,
+    step: f64,
+    max_distance: f64,
+) -> SKResult<Vec<CloseApproach>> {
+    let start = match primary.start() > secondary.start() {
+        true => primary.start(),
+        false => secondary.start(),
+    };
+    let stop = match primary.stop() < secondary.stop() {
+        true => primary.stop(),
+        false => secondary.stop(),
+    };
+    if stop <= start {
+        return Ok(Vec::new());
+    }
+
+    let rel = |t: f64| -> SKResult<Vector6> {
+        let time = start + Duration::from_seconds(t);
+        Ok(secondary.interp(&time)? - primary.interp(&time)?)
+    };
+
+    let span = (stop - start).as_seconds();
+    let f = |t: f64| -> SKResult<f64> { Ok(range_rate_sign(&rel(t)?)) };
+    let mut result = Vec::new();
+    for root in find_roots(&f, span, step, TCA_TOL)? {
+        // Falling sign changes are local maxima of the distance
+        if !root.rising {
+            continue;
+        }
+        let r = rel(root.t)?;
+        let miss_distance = r.fixed_rows::<3>(0).norm();
+        if miss_distance <= max_distance {
+            result.push(CloseApproach {
+                secondary: index,
+                time: start + Duration::from_seconds(root.t),
+                miss_distance,
+                relative_speed: r.fixed_rows::<3>(3).norm(),
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Sort close approaches by time, then miss distance, then
+/// secondary index
+fn sort_approaches(v: &mut [CloseApproach]) {
+    v.sort_by(|a, b| {
+        a.time
+            .partial_cmp(&b.time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.miss_distance.total_cmp(&b.miss_distance))
+            .then(a.secondary.cmp(&b.secondary))
+    });
+}
+
+/// Find the times of closest approach between two satellites
+///
+/// # Arguments
+///
+/// * `primary` - Ephemeris of the primary satellite
+/// * `secondary` - Ephemeris of the secondary satellite, in the same frame
+/// * `step` - Step at which the relative motion is sampled to find
+///   candidate approaches.  Must be small compared to the orbit period
+/// * `max_distance` - Only approaches closer than this, in meters,
+///   are returned
+///
+/// # Returns
+///
+/// * Close approaches (local minima of distance) within the common span
+///   of the ephemerides, sorted by time, then by miss distance
+///
+/// # Notes
+///
+/// * A local minimum at either end of the common span is not reported
+pub fn find_tca(
+    primary: &Ephemeris,
+    secondary: &Ephemeris,
+    step: &Duration,
+    max_distance: f64,
+) -> SKResult<Vec<CloseApproach>> {
+    screen_tca(primary, std::slice::from_ref(secondary), step, max_distance)
+}
+
+/// Screen a primary satellite against a list of secondaries
+/// for close approaches
+///
+/// # Arguments
+///
+/// * `primary` - Ephemeris of the primary satellite
+/// * `secondaries` - Ephemerides of the secondary satellites, in the
+///   same frame as the primary
+/// * `step` - Step at which the relative motion is sampled to find
+///   candidate approaches.  Must be small compared to the orbit period
+/// * `max_distance` - Only approaches closer than this, in meters,
+///   are returned
+///
+/// # Returns
+///
+/// * Close approaches to all secondaries, sorted by time, then by miss
+///   distance.  Approaches at identical times & miss distances are
+///   ordered by index of the secondary, so the order depends only on
+///   the inputs
+pub fn screen_tca(
+    primary: &Ephemeris,
+    secondaries: &[Ephemeris],
+    step: &Duration,
+    max_distance: f64,
+) -> SKResult<Vec<CloseApproach>> {
+    if step.as_seconds() <= 0.0 {
+        return skerror!("Step size must be positive");
+    }
+    let mut result = Vec::new();
+    for (index, secondary) in secondaries.iter().enumerate() {
+        result.extend(approaches(
+            primary,
+            secondary,
+            index,
+            step.as_seconds(),
+            max_distance,
+        )?);
+    }
+    sort_approaches(&mut result);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::{Anomaly, Kepler};
+
+    /// Two-body ephemeris at 30-second nodes over a day
+    fn kepler_ephem(kep: &Kepler) -> Ephemeris {
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let nodes = (0..=2880)
+            .map(|ix| {
+                let dt = Duration::from_seconds(30.0 * ix as f64);
+                let (r, v) = kep.propagate(&dt).to_pv();
+                let mut pv = Vector6::zeros();
+                pv.fixed_rows_mut::<3>(0).copy_from(&r);
+                pv.fixed_rows_mut::<3>(3).copy_from(&v);
+                (start + dt, pv)
+            })
+            .collect();
+        Ephemeris::new(nodes).unwrap()
+    }
+
+    #[test]
+    fn test_tca_ordering() -> SKResult<()> {
+        let a = consts::EARTH_RADIUS + 800.0e3;
+        let primary = kepler_ephem(&Kepler::new(a, 0.0, 0.9, 0.0, 0.0, Anomaly::True(0.3)));
+        // Same orbit with different inclinations, so the satellites
+        // meet at the nodes twice per orbit, with a small radial offset
+        // for one of them
+        let sec_a = kepler_ephem(&Kepler::new(a, 0.0, 1.0, 0.0, 0.0, Anomaly::True(0.3)));
+        let sec_b = kepler_ephem(&Kepler::new(
+            a + 100.0,
+            0.0,
+            1.1,
+            0.0,
+            0.0,
+            Anomaly::True(0.3),
+        ));
+        let step = Duration::from_seconds(60.0);
+
+        let tca = find_tca(&primary, &sec_a, &step, 1.0e3)?;
+        let period = 2.0 * std::f64::consts::PI * (a.powi(3) / consts::MU_EARTH).sqrt();
+        assert!(tca.len() >= 2 * (86400.0 / period) as usize - 1);
+        assert!(tca
+            .iter()
+            .all(|c| c.miss_distance < 1.0 && c.secondary == 0));
+
+        // Duplicated secondary gives approaches at identical times &
+        // distances, which are ordered by secondary index
+        let secondaries = [sec_b.clone(), sec_a.clone(), sec_a.clone()];
+        let list = screen_tca(&primary, &secondaries, &step, 1.0e3)?;
+        for w in list.windows(2) {
+            assert!(w[0].time <= w[1].time);
+            if w[0].time == w[1].time {
+                assert!(w[0].miss_distance <= w[1].miss_distance);
+                if w[0].miss_distance == w[1].miss_distance {
+                    assert!(w[0].secondary < w[1].secondary);
+                }
+            }
+        }
+        let ties = list
+            .windows(2)
+            .filter(|w| w[0].time == w[1].time && w[0].secondary == 1)
+            .count();
+        assert_eq!(ties, tca.len());
+
+        // Same inputs always give the same ordered list
+        for _ in 0..3 {
+            assert_eq!(screen_tca(&primary, &secondaries, &step, 1.0e3)?, list);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! High-precision propagation of a constellation of satellites
+//! to a common time
+
+use super::{PropSettings, SatProperties, SatState};
+
+use crate::{Instant, SKResult};
+
+use std::collections::HashMap;
+
+/// Propagate a constellation of satellites to a common time
+///
+/// Precomputed terms (Earth orientation, sun & moon positions) are
+/// computed once, covering all of the satellite epochs and the target
+/// time, and shared between the satellites
+///
+/// # Arguments
+///
+/// * `sats` - Satellites to propagate, as (NORAD ID, state) pairs.
+///   Covariance & consider parameters, if set, are propagated
+/// * `time` - Time to which to propagate all of the satellites
+/// * `settings` - Propagation settings.  Default settings are used if `None`
+/// * `satprops` - Default satellite properties (drag & radiation pressure),
+///   used for satellites not in `overrides`
+/// * `overrides` - Satellite properties for individual satellites,
+///   keyed by NORAD ID.  These may be of different types
+///
+/// # Returns
+///
+/// * Satellite states at `time`, in the order of `sats`
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::orbitprop::{propagate_constellation, SatProperties, SatPropertiesStatic, SatState};
+/// use satkit::{Duration, Instant};
+/// use std::collections::HashMap;
+///
+/// let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+/// let r = satkit::consts::EARTH_RADIUS + 400.0e3;
+/// let v = (satkit::consts::MU_EARTH / r).sqrt();
+/// let sats = [
+///     (1001, SatState::from_pv(&time, &[r, 0.0, 0.0].into(), &[0.0, v, 0.0].into())),
+///     (1002, SatState::from_pv(&time, &[0.0, r, 0.0].into(), &[-v, 0.0, 0.0].into())),
+/// ];
+/// let default = SatPropertiesStatic::new(0.01, 0.01);
+/// let heavy = SatPropertiesStatic::new(0.05, 0.02);
+/// let overrides = HashMap::from([(1002, &heavy as &dyn SatProperties)]);
+/// let states = propagate_constellation(
+///     &sats,
+///     &(time + Duration::from_days(1.0)),
+///     None,
+///     Some(&default),
+///     &overrides,
+/// )
+/// .unwrap();
+/// ```
+pub fn propagate_constellation(
+    sats: &[(i32, SatState)],
+    time: &Instant,
+    settings: Option<&PropSettings>,
+    satprops: Option<&dyn SatProperties>,
+    overrides: &HashMap<i32, &dyn SatProperties>,
+) -> SKResult<Vec<SatState>> {
+    let default = PropSettings::default();
+    let settings = settings.unwrap_or(&default);
+
+    let mut first = *time;
+    let mut last = *time;
+    for (_, state) in sats {
+        if state.time < first {
+            first = state.time;
+        }
+        if state.time > last {
+            last = state.time;
+        }
+    }
+    let precomputed = settings.precomputed_covering(&first, &last)?;
+
+    sats.iter()
+        .map(|(id, state)| {
+            let props = overrides.get(id).copied().or(satprops);
+            state.propagate_with_precomputed(time, settings, precomputed.as_deref(), props)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::{Anomaly, Kepler};
+    use crate::orbitprop::SatPropertiesStatic;
+    use crate::Duration;
+
+    #[test]
+    fn test_constellation() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 5, 1, 0, 0, 0.0);
+        let kep = Kepler::new(
+            consts::EARTH_RADIUS + 350.0e3,
+            0.001,
+            51.6_f64.to_radians(),
+            0.0,
+            0.0,
+            Anomaly::True(0.0),
+        );
+        let (pos, vel) = kep.to_pv();
+        let state = SatState::from_pv(&time, &pos, &vel);
+        let sats = [
+            (1001, state.clone()),
+            (1002, state.clone()),
+            (1003, state.clone()),
+        ];
+        let default = SatPropertiesStatic::new(0.01, 0.0);
+        let heavy = SatPropertiesStatic::new(0.05, 0.0);
+        let unused = SatPropertiesStatic::new(1.0, 0.0);
+        let overrides = HashMap::from([
+            (1002, &heavy as &dyn SatProperties),
+            // Not in the constellation, so unused
+            (9999, &unused as &dyn SatProperties),
+        ]);
+        let stop = time + Duration::from_days(1.0);
+        let states = propagate_constellation(&sats, &stop, None, Some(&default), &overrides)?;
+        assert_eq!(states.len(), sats.len());
+
+        // Satellites missing from the map use the default properties
+        assert_eq!(states[0].pv, states[2].pv);
+
+        // Higher ballistic coefficient decays faster
+        let sma =
+            |s: &SatState| -> SKResult<f64> { Ok(Kepler::from_pv(s.pos_gcrf(), s.vel_gcrf())?.a) };
+        let a0 = sma(&sats[0].1)?;
+        let decay_default = a0 - sma(&states[0])?;
+        let decay_override = a0 - sma(&states[1])?;
+        assert!(decay_default > 0.0);
+        assert!(decay_override > 2.0 * decay_default);
+
+        // Same as propagating each satellite individually
+        let single = sats[1].1.propagate_with_precomputed(
+            &stop,
+            &PropSettings::default(),
+            None,
+            Some(overrides[&1002]),
+        )?;
+        assert!((single.pos_gcrf() - states[1].pos_gcrf()).norm() < 1.0e-3);
+        Ok(())
+    }
+}
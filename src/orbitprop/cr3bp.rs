@@ -0,0 +1,290 @@
+//! Propagation in a rotating (synodic) frame, e.g. for
+//! circular restricted three-body problem (CR3BP) analysis
+
+use crate::consts;
+use crate::ode::{RKAdaptive, RKAdaptiveSettings};
+use crate::types::{Vector3, Vector6};
+use crate::{Duration, SKResult};
+
+use nalgebra as na;
+
+/// Mean Earth-Moon distance, meters
+const EARTH_MOON_DISTANCE: f64 = 384_400.0e3;
+
+/// Reference frame rotating at constant angular velocity relative to
+/// an inertial frame with the same origin
+///
+/// The frames coincide at time zero
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotatingFrame {
+    /// Angular velocity of the rotating frame, in the inertial frame
+    /// (equal in the rotating frame, since it is constant), rad/s
+    pub omega: Vector3,
+}
+
+impl RotatingFrame {
+    /// Rotation from the inertial to the rotating frame at a given
+    /// time, in seconds, after the frames coincide
+    fn rotation(&self, t: f64) -> na::Rotation3<f64> {
+        na::Rotation3::new(-self.omega * t)
+    }
+
+    /// Transform position & velocity from the inertial frame
+    /// into the rotating frame
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - Time, in seconds, after the frames coincide
+    /// * `pv` - Inertial position (m) & velocity (m/s)
+    ///
+    /// # Returns
+    ///
+    /// * Position & velocity relative to the rotating frame
+    pub fn to_rotating(&self, t: f64, pv: &Vector6) -> Vector6 {
+        let r = self.rotation(t);
+        let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+        let vel: Vector3 = pv.fixed_rows::<3>(3).into();
+        let mut out = Vector6::zeros();
+        out.fixed_rows_mut::<3>(0).copy_from(&(r * pos));
+        out.fixed_rows_mut::<3>(3)
+            .copy_from(&(r * (vel - self.omega.cross(&pos))));
+        out
+    }
+
+    /// Transform position & velocity from the rotating frame
+    /// into the inertial frame
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - Time, in seconds, after the frames coincide
+    /// * `pv` - Position (m) & velocity (m/s) relative to the rotating frame
+    ///
+    /// # Returns
+    ///
+    /// * Inertial position & velocity
+    pub fn from_rotating(&self, t: f64, pv: &Vector6) -> Vector6 {
+        let rinv = self.rotation(t).inverse();
+        let pos: Vector3 = rinv * pv.fixed_rows::<3>(0);
+        let vel: Vector3 = rinv * pv.fixed_rows::<3>(3) + self.omega.cross(&pos);
+        let mut out = Vector6::zeros();
+        out.fixed_rows_mut::<3>(0).copy_from(&pos);
+        out.fixed_rows_mut::<3>(3).copy_from(&vel);
+        out
+    }
+
+    /// Fictitious (Coriolis & centrifugal) acceleration in the
+    /// rotating frame
+    ///
+    /// # Arguments
+    ///
+    /// * `pv` - Position (m) & velocity (m/s) relative to the rotating frame
+    ///
+    /// # Returns
+    ///
+    /// * Acceleration, m/s^2: -2 ω × v - ω × (ω × r)
+    pub fn fictitious_accel(&self, pv: &Vector6) -> Vector3 {
+        let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+        let vel: Vector3 = pv.fixed_rows::<3>(3).into();
+        -2.0 * self.omega.cross(&vel) - self.omega.cross(&self.omega.cross(&pos))
+    }
+}
+
+/// Circular restricted three-body problem
+///
+/// Two primaries in circular orbit about their barycenter, and a
+/// massless third body.  States are in the synodic frame: origin at the
+/// barycenter, x axis from the first to the second primary, z axis along
+/// the orbital angular momentum of the primaries, rotating with them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cr3bp {
+    /// Gravitational parameter of the first (larger) primary, m^3/s^2
+    pub mu1: f64,
+    /// Gravitational parameter of the second primary, m^3/s^2
+    pub mu2: f64,
+    /// Distance between the primaries, m
+    pub distance: f64,
+}
+
+impl Cr3bp {
+    /// Earth-Moon system, with the mean Earth-Moon distance
+    pub fn earth_moon() -> Self {
+        Self {
+            mu1: consts::MU_EARTH,
+            mu2: consts::MU_MOON,
+            distance: EARTH_MOON_DISTANCE,
+        }
+    }
+
+    /// Synodic frame, rotating at the mean motion of the primaries
+    pub fn frame(&self) -> RotatingFrame {
+        RotatingFrame {
+            omega: Vector3::z() * ((self.mu1 + self.mu2) / self.distance.powi(3)).sqrt(),
+        }
+    }
+
+    /// Positions of the two primaries in the synodic frame, m
+    pub fn primaries(&self) -> (Vector3, Vector3) {
+        let mutot = self.mu1 + self.mu2;
+        (
+            Vector3::x() * (-self.mu2 / mutot * self.distance),
+            Vector3::x() * (self.mu1 / mutot * self.distance),
+        )
+    }
+
+    /// Acceleration in the synodic frame, including gravity of
+    /// the primaries and the Coriolis & centrifugal terms
+    ///
+    /// # Arguments
+    ///
+    /// * `pv` - Position (m) & velocity (m/s) in the synodic frame
+    ///
+    /// # Returns
+    ///
+    /// * Acceleration, m/s^2
+    pub fn accel(&self, pv: &Vector6) -> Vector3 {
+        let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+        let (p1, p2) = self.primaries();
+        let d1 = pos - p1;
+        let d2 = pos - p2;
+        -self.mu1 * d1 / d1.norm().powi(3) - self.mu2 * d2 / d2.norm().powi(3)
+            + self.frame().fictitious_accel(pv)
+    }
+
+    /// Position of the L1 Lagrange point, between the primaries,
+    /// in the synodic frame, m
+    pub fn l1(&self) -> Vector3 {
+        let (p1, p2) = self.primaries();
+        let w2 = self.frame().omega.norm_squared();
+        // Net acceleration along the x axis, which increases
+        // monotonically between the primaries
+        let f = |x: f64| -> f64 {
+            -self.mu1 * (x - p1[0]) / (x - p1[0]).abs().powi(3)
+                - self.mu2 * (x - p2[0]) / (x - p2[0]).abs().powi(3)
+                + w2 * x
+        };
+        let (mut lo, mut hi) = (p1[0], p2[0]);
+        for _ in 0..200 {
+            let mid = 0.5 * (lo + hi);
+            match f(mid) < 0.0 {
+                true => lo = mid,
+                false => hi = mid,
+            }
+        }
+        Vector3::x() * (0.5 * (lo + hi))
+    }
+
+    /// Propagate a state in the synodic frame
+    ///
+    /// # Arguments
+    ///
+    /// * `pv` - Position (m) & velocity (m/s) in the synodic frame
+    /// * `duration` - Time to propagate; may be negative
+    ///
+    /// # Returns
+    ///
+    /// * Position & velocity in the synodic frame after `duration`
+    ///
+    /// # Notes
+    ///
+    /// * To propagate an inertial state, transform it with
+    ///   [`RotatingFrame::to_rotating`] of [`Cr3bp::frame`] at the time
+    ///   since the frames coincide, and transform the result back with
+    ///   [`RotatingFrame::from_rotating`]
+    pub fn propagate(&self, pv: &Vector6, duration: &Duration) -> SKResult<Vector6> {
+        let settings = RKAdaptiveSettings {
+            abserror: 1.0e-8,
+            relerror: 1.0e-12,
+            ..Default::default()
+        };
+        let ydot = |_x: f64, y: &Vector6| -> crate::ode::ODEResult<Vector6> {
+            let mut dy = Vector6::zeros();
+            dy.fixed_rows_mut::<3>(0).copy_from(&y.fixed_rows::<3>(3));
+            dy.fixed_rows_mut::<3>(3).copy_from(&self.accel(y));
+            Ok(dy)
+        };
+        let res = crate::ode::solvers::RKV98NoInterp::integrate(
+            0.0,
+            duration.as_seconds(),
+            pv,
+            ydot,
+            &settings,
+        )?;
+        Ok(res.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_transform() {
+        let frame = RotatingFrame {
+            omega: Vector3::new(0.1, -0.2, 1.0) * 1.0e-4,
+        };
+        let pv = Vector6::new(7.0e6, -1.0e6, 2.0e5, 100.0, 7.5e3, -20.0);
+        for t in [0.0, 1234.5, -8000.0] {
+            let rot = frame.to_rotating(t, &pv);
+            assert!((frame.from_rotating(t, &rot) - pv).norm() < 1.0e-6);
+        }
+        // Frames coincide at time zero, but velocity relative
+        // to the rotating frame differs by ω × r
+        let rot = frame.to_rotating(0.0, &pv);
+        assert_eq!(rot.fixed_rows::<3>(0), pv.fixed_rows::<3>(0));
+        let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+        assert!(
+            (rot.fixed_rows::<3>(3) - (pv.fixed_rows::<3>(3) - frame.omega.cross(&pos))).norm()
+                < 1.0e-9
+        );
+        // A point fixed in the rotating frame moves in a circle
+        // in the inertial frame
+        let fixed = Vector6::new(1.0e6, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let inertial = frame.from_rotating(5000.0, &fixed);
+        assert!((inertial.fixed_rows::<3>(0).norm() - 1.0e6).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_cr3bp() -> SKResult<()> {
+        let cr3bp = Cr3bp::earth_moon();
+        let frame = cr3bp.frame();
+
+        // L1 is about 326,000 km from Earth, and stationary in the synodic frame
+        let l1 = cr3bp.l1();
+        let (earth, moon) = cr3bp.primaries();
+        assert!(((l1 - earth).norm() - 326.4e6).abs() < 1.0e6);
+        assert!(l1[0] > earth[0] && l1[0] < moon[0]);
+        let mut pv = Vector6::zeros();
+        pv.fixed_rows_mut::<3>(0).copy_from(&l1);
+        assert!(cr3bp.accel(&pv).norm() < 1.0e-15);
+        let pv1 = cr3bp.propagate(&pv, &Duration::from_days(1.0))?;
+        assert!((pv1.fixed_rows::<3>(0) - l1).norm() < 10.0);
+        assert!(pv1.fixed_rows::<3>(3).norm() < 1.0e-3);
+
+        // Propagation in the synodic frame matches propagation in the
+        // inertial frame, with the primaries moving on circles
+        let pv0 = Vector6::new(2.0e8, 5.0e7, 1.0e7, -300.0, 600.0, 50.0);
+        let dt = 2.0 * 86400.0;
+        let pv_rot = cr3bp.propagate(&frame.to_rotating(0.0, &pv0), &Duration::from_seconds(dt))?;
+        let ydot = |t: f64, y: &Vector6| -> crate::ode::ODEResult<Vector6> {
+            let rinv = frame.rotation(t).inverse();
+            let pos: Vector3 = y.fixed_rows::<3>(0).into();
+            let d1 = pos - rinv * earth;
+            let d2 = pos - rinv * moon;
+            let mut dy = Vector6::zeros();
+            dy.fixed_rows_mut::<3>(0).copy_from(&y.fixed_rows::<3>(3));
+            dy.fixed_rows_mut::<3>(3).copy_from(
+                &(-cr3bp.mu1 * d1 / d1.norm().powi(3) - cr3bp.mu2 * d2 / d2.norm().powi(3)),
+            );
+            Ok(dy)
+        };
+        let settings = RKAdaptiveSettings {
+            relerror: 1.0e-12,
+            ..Default::default()
+        };
+        let res = crate::ode::solvers::RKV98NoInterp::integrate(0.0, dt, &pv0, ydot, &settings)?;
+        let pv_in = frame.from_rotating(dt, &pv_rot);
+        assert!((pv_in.fixed_rows::<3>(0) - res.y.fixed_rows::<3>(0)).norm() < 1.0);
+        assert!((pv_in.fixed_rows::<3>(3) - res.y.fixed_rows::<3>(3)).norm() < 1.0e-5);
+        Ok(())
+    }
+}
@@ -17,6 +17,36 @@ const OMEGA_EARTH_MATRIX: na::Matrix3<f64> = na::Matrix3::new(
     0.0,
 );
 
+/// Velocity relative to the atmosphere, in the gcrf frame
+///
+/// The atmosphere co-rotates with the Earth, plus an optional
+/// horizontal zonal wind, in m/s, positive eastward
+fn velocity_rel_atmosphere(
+    pos_gcrf: &na::Vector3<f64>,
+    vel_gcrf: &na::Vector3<f64>,
+    zonal_wind: f64,
+) -> na::Vector3<f64> {
+    let vrel = vel_gcrf - OMEGA_EARTH.cross(pos_gcrf);
+    let rho = pos_gcrf.xy().norm();
+    if zonal_wind == 0.0 || rho == 0.0 {
+        return vrel;
+    }
+    // Local east unit vector; the spin axis is taken as the gcrf z axis,
+    // consistent with the co-rotation term above
+    vrel - zonal_wind * na::vector![-pos_gcrf[1], pos_gcrf[0], 0.0] / rho
+}
+
+/// Partial of the zonal wind velocity with respect to position
+fn zonal_wind_partial(pos_gcrf: &na::Vector3<f64>, zonal_wind: f64) -> na::Matrix3<f64> {
+    let rho = pos_gcrf.xy().norm();
+    if zonal_wind == 0.0 || rho == 0.0 {
+        return na::Matrix3::zeros();
+    }
+    let (x, y) = (pos_gcrf[0], pos_gcrf[1]);
+    zonal_wind / rho.powi(3)
+        * na::Matrix3::new(x * y, -x * x, 0.0, y * y, -x * y, 0.0, 0.0, 0.0, 0.0)
+}
+
 // Compute and return force from drag in the gcrf frame
 pub fn drag_force(
     pos_gcrf: &na::Vector3<f64>,
@@ -25,6 +55,7 @@ pub fn drag_force(
     time: &crate::Instant,
     cd_a_over_m: f64,
     use_spaceweather: bool,
+    zonal_wind: f64,
 ) -> na::Vector3<f64> {
     let itrf = ITRFCoord::from(pos_itrf.as_slice());
     let (density, _temperature) = nrlmsise(
@@ -39,9 +70,10 @@ pub fn drag_force(
     // rotation Earth part in while still staying in the gcrf frame
     // to get velocity relative to wind in gcrf frame
     // This is a little confusing, but if you think about it long enough
-    // it will make sense
-    let vrel = vel_gcrf - OMEGA_EARTH.cross(pos_gcrf);
-    
+    // it will make sense.  Zonal winds, if any, move relative
+    // to the rotating Earth
+    let vrel = velocity_rel_atmosphere(pos_gcrf, vel_gcrf, zonal_wind);
+
     -0.5 * cd_a_over_m * density * vrel * vrel.norm()
 }
 
@@ -112,6 +144,7 @@ pub fn drag_and_partials(
     time: &crate::Instant,
     cd_a_over_m: f64,
     use_spaceweather: bool,
+    zonal_wind: f64,
 ) -> (na::Vector3<f64>, na::Matrix3<f64>, na::Matrix3<f64>) {
     let (density, drhodr) = compute_rho_drhodr(pos_gcrf, qgcrf2itrf, time, use_spaceweather);
 
@@ -119,8 +152,9 @@ pub fn drag_and_partials(
     // rotation Earth part in while still staying in the gcrf frame
     // to get velocity relative to wind in gcrf frame
     // This is a little confusing, but if you think about it long enough
-    // it will make sense
-    let vrel = vel_gcrf - OMEGA_EARTH.cross(pos_gcrf);
+    // it will make sense.  Zonal winds, if any, move relative
+    // to the rotating Earth
+    let vrel = velocity_rel_atmosphere(pos_gcrf, vel_gcrf, zonal_wind);
 
     let drag_accel_gcrf = -0.5 * cd_a_over_m * density * vrel * vrel.norm();
 
@@ -132,7 +166,48 @@ pub fn drag_and_partials(
         * (vrel * vrel.transpose() / vrel.norm() + vrel.norm() * na::Matrix3::<f64>::identity());
 
     let dacceldr = -0.5 * cd_a_over_m * density * vrel * vrel.norm() * drhodr.transpose()
-        - dacceldv * OMEGA_EARTH_MATRIX;
+        - dacceldv * (OMEGA_EARTH_MATRIX + zonal_wind_partial(pos_gcrf, zonal_wind));
 
     (drag_accel_gcrf, dacceldr, dacceldv)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zonal_wind() {
+        let time = Instant::from_datetime(2024, 5, 1, 12, 0, 0.0);
+        let r = crate::consts::EARTH_RADIUS + 400.0e3;
+        let v = (crate::consts::MU_EARTH / r).sqrt();
+        let incl = 51.6_f64.to_radians();
+        let pos = na::vector![r * 0.6, r * 0.8, 0.0];
+        let vel = v * na::vector![-0.8 * incl.cos(), 0.6 * incl.cos(), incl.sin()];
+        let q = na::UnitQuaternion::identity();
+
+        // Zero wind reduces to co-rotation
+        let corot = drag_force(&pos, &pos, &vel, &time, 0.01, false, 0.0);
+        let vrel = vel - OMEGA_EARTH.cross(&pos);
+        assert!((corot.normalize() + vrel.normalize()).norm() < 1.0e-12);
+        let (accel, _, _) = drag_and_partials(&pos, &q, &vel, &time, 0.01, false, 0.0);
+        assert!((accel - corot).norm() < 1.0e-15);
+
+        // Prograde zonal wind slightly reduces along-track drag
+        let wind = drag_force(&pos, &pos, &vel, &time, 0.01, false, 100.0);
+        let along = |a: &na::Vector3<f64>| -a.dot(&vel.normalize());
+        assert!(along(&wind) > 0.0);
+        assert!(along(&wind) < along(&corot));
+        assert!(along(&wind) > 0.95 * along(&corot));
+
+        // Partial of relative velocity with respect to position
+        let dvdr = -(OMEGA_EARTH_MATRIX + zonal_wind_partial(&pos, 100.0));
+        for ix in 0..3 {
+            let mut dp = na::Vector3::zeros();
+            dp[ix] = 1.0;
+            let fd = (velocity_rel_atmosphere(&(pos + dp), &vel, 100.0)
+                - velocity_rel_atmosphere(&(pos - dp), &vel, 100.0))
+                / 2.0;
+            assert!((fd - dvdr.column(ix)).norm() < 1.0e-12);
+        }
+    }
+}
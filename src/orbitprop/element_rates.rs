@@ -0,0 +1,265 @@
+//! Time derivatives of osculating orbital elements, e.g. for
+//! visualizing the effect of perturbations
+
+use super::propagator::propagate;
+use super::{PropSettings, SatState};
+
+use crate::angles::wrap_to_pi;
+use crate::consts::MU_EARTH;
+use crate::kepler::{pv_to_equinoctial, Kepler};
+use crate::types::{Vector3, Vector6};
+use crate::{Duration, SKResult};
+
+/// Half-width, in seconds, of the central difference used to
+/// differentiate numerically propagated elements
+const DIFF_STEP: f64 = 10.0;
+
+/// Time derivatives of the classical osculating orbital elements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementRates {
+    /// Semi-major axis rate, meters / second
+    pub a: f64,
+    /// Eccentricity rate, 1 / second
+    pub eccen: f64,
+    /// Inclination rate, radians / second
+    pub incl: f64,
+    /// Right ascension of the ascending node rate, radians / second
+    pub raan: f64,
+    /// Argument of perigee rate, radians / second
+    pub w: f64,
+    /// Mean anomaly rate, radians / second.  This includes
+    /// the mean motion
+    pub mean_anomaly: f64,
+}
+
+/// Classical element rates from equinoctial elements & their rates
+fn classical_rates(eq: &Vector6, deq: &Vector6) -> ElementRates {
+    let (h, k, p, q) = (eq[1], eq[2], eq[3], eq[4]);
+    let (dh, dk, dp, dq) = (deq[1], deq[2], deq[3], deq[4]);
+
+    let e2 = h * h + k * k;
+    let t2 = p * p + q * q;
+    let t = t2.sqrt();
+    // Longitude of perigee & node
+    let dlonper = (k * dh - h * dk) / e2;
+    let draan = (q * dp - p * dq) / t2;
+    ElementRates {
+        a: deq[0],
+        eccen: (h * dh + k * dk) / e2.sqrt(),
+        incl: 2.0 * (p * dp + q * dq) / (t * (1.0 + t2)),
+        raan: draan,
+        w: dlonper - draan,
+        mean_anomaly: deq[5] - dlonper,
+    }
+}
+
+/// Element rates from states a time `2 * dt` apart, centered on the
+/// time of interest, by central difference of equinoctial elements
+fn rates_from_states(before: &Vector6, after: &Vector6, dt: f64) -> SKResult<ElementRates> {
+    let eq0 = pv_to_equinoctial(before, MU_EARTH, false)?;
+    let eq1 = pv_to_equinoctial(after, MU_EARTH, false)?;
+
+    let mut deq = (eq1 - eq0) / (2.0 * dt);
+    // Mean longitude wraps at 2π
+    deq[5] = wrap_to_pi(eq1[5] - eq0[5]) / (2.0 * dt);
+    let mut eq = (eq0 + eq1) / 2.0;
+    eq[5] = eq0[5] + deq[5] * dt;
+    Ok(classical_rates(&eq, &deq))
+}
+
+/// Osculating element rates from the Gauss variational equations
+///
+/// # Arguments
+///
+/// * `pos` - Position, inertial frame, meters
+/// * `vel` - Velocity, inertial frame, meters / second
+/// * `accel` - Perturbing acceleration, i.e. excluding two-body
+///   gravity, inertial frame, meters / second²
+///
+/// # Returns
+///
+/// * Element rates, or error if the orbit is not elliptical
+///
+/// # Notes
+///
+/// * Rates of the argument of perigee & mean anomaly are singular for
+///   circular orbits, and of the ascending node & argument of perigee for
+///   equatorial orbits
+///
+/// See Vallado, "Fundamentals of Astrodynamics and Applications",
+/// Section 9.3
+pub fn element_rates_gauss(
+    pos: &Vector3,
+    vel: &Vector3,
+    accel: &Vector3,
+) -> SKResult<ElementRates> {
+    let kep = Kepler::from_pv(*pos, *vel)?;
+    let (a, e, incl) = (kep.a, kep.eccen, kep.incl);
+    let r = pos.norm();
+    let p = a * e.mul_add(-e, 1.0);
+    let h = (MU_EARTH * p).sqrt();
+    let b = a * e.mul_add(-e, 1.0).sqrt();
+    let n = kep.mean_motion();
+    let (snu, cnu) = kep.nu.sin_cos();
+    let su = (kep.w + kep.nu).sin();
+    let cu = (kep.w + kep.nu).cos();
+
+    // Acceleration in radial, along-track & cross-track directions
+    let rhat = pos / r;
+    let what = pos.cross(vel).normalize();
+    let shat = what.cross(&rhat);
+    let (ar, as_, aw) = (accel.dot(&rhat), accel.dot(&shat), accel.dot(&what));
+
+    Ok(ElementRates {
+        a: 2.0 * a * a / h * (e * snu * ar + p / r * as_),
+        eccen: (p * snu * ar + ((p + r) * cnu + r * e) * as_) / h,
+        incl: r * cu / h * aw,
+        raan: r * su / (h * incl.sin()) * aw,
+        w: (-p * cnu * ar + (p + r) * snu * as_) / (h * e)
+            - r * su * incl.cos() / (h * incl.sin()) * aw,
+        mean_anomaly: n + b / (a * h * e) * ((p * cnu - 2.0 * e * r) * ar - (p + r) * snu * as_),
+    })
+}
+
+/// Osculating element rates under the full force model,
+/// by numerical differentiation
+///
+/// The state is propagated 10 seconds forward & backward with the
+/// high-precision propagator, and the osculating elements are
+/// differentiated by central difference
+///
+/// # Arguments
+///
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+/// * `settings` - Propagation settings, which determine the forces
+///   that are included.  Default settings are used if `None`
+///
+/// # Returns
+///
+/// * Element rates at the state time
+///
+/// # Notes
+///
+/// * Elements are differentiated in equinoctial form, which is
+///   non-singular for circular & equatorial orbits, then converted
+///   to classical element rates.  Classical rates have the same
+///   singularities as in [`element_rates_gauss`]
+/// * Satellite properties (drag & radiation pressure) are not included
+pub fn element_rates_numerical(
+    state: &SatState,
+    settings: Option<&PropSettings>,
+) -> SKResult<ElementRates> {
+    let default = PropSettings::default();
+    let settings = settings.unwrap_or(&default);
+    let dt = Duration::from_seconds(DIFF_STEP);
+    let before = propagate(&state.pv, &state.time, &(state.time - dt), settings, None)?;
+    let after = propagate(&state.pv, &state.time, &(state.time + dt), settings, None)?;
+    rates_from_states(&before.state_end, &after.state_end, DIFF_STEP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::Anomaly;
+    use crate::Instant;
+
+    /// J2 acceleration, with the Earth pole along the inertial z axis
+    fn j2_accel(pos: &Vector3) -> Vector3 {
+        // JGM3_J2 is the unnormalized C20 coefficient, i.e. -J2
+        let r = pos.norm();
+        let zr2 = (pos.z / r).powi(2);
+        let f = 1.5 * consts::JGM3_J2 * MU_EARTH * consts::EARTH_RADIUS.powi(2) / r.powi(5);
+        Vector3::new(
+            f * pos.x * 5.0f64.mul_add(-zr2, 1.0),
+            f * pos.y * 5.0f64.mul_add(-zr2, 1.0),
+            f * pos.z * 5.0f64.mul_add(-zr2, 3.0),
+        )
+    }
+
+    /// Two-body + J2 propagation with fixed-step 4th-order Runge-Kutta
+    fn propagate_j2(pv: &Vector6, dt: f64) -> Vector6 {
+        let f = |s: &Vector6| -> Vector6 {
+            let pos: Vector3 = s.fixed_rows::<3>(0).into();
+            let acc = -MU_EARTH * pos / pos.norm().powi(3) + j2_accel(&pos);
+            Vector6::new(s[3], s[4], s[5], acc.x, acc.y, acc.z)
+        };
+        let nsteps = 100;
+        let h = dt / nsteps as f64;
+        let mut s = *pv;
+        for _ in 0..nsteps {
+            let k1 = f(&s);
+            let k2 = f(&(s + k1 * (h / 2.0)));
+            let k3 = f(&(s + k2 * (h / 2.0)));
+            let k4 = f(&(s + k3 * h));
+            s += (k1 + 2.0 * k2 + 2.0 * k3 + k4) * (h / 6.0);
+        }
+        s
+    }
+
+    /// Check numerical rates against Gauss rates, relative to the
+    /// size of the perturbation of each element
+    fn check_rates(numerical: &ElementRates, gauss: &ElementRates, n: f64, a: f64, e: f64) {
+        // Scale of J2 perturbation, (J2 (Re / a)²), on each rate
+        let scale = consts::JGM3_J2.abs() * (consts::EARTH_RADIUS / a).powi(2) * n;
+        let tol = 1.0e-3 * scale;
+        assert!((numerical.a - gauss.a).abs() < tol * a);
+        assert!((numerical.eccen - gauss.eccen).abs() < tol);
+        assert!((numerical.incl - gauss.incl).abs() < tol);
+        assert!((numerical.raan - gauss.raan).abs() < tol);
+        assert!((numerical.w - gauss.w).abs() < tol / e);
+        assert!((numerical.mean_anomaly - gauss.mean_anomaly).abs() < tol / e);
+    }
+
+    #[test]
+    fn test_element_rates() -> SKResult<()> {
+        // Eccentric, and nearly circular & nearly equatorial, LEO orbits
+        for (e, incl) in [(0.05, 51.6_f64), (1.0e-4, 0.01)] {
+            let a = consts::EARTH_RADIUS + 700.0e3;
+            let kep = Kepler::new(a, e, incl.to_radians(), 0.4, 1.2, Anomaly::True(0.7));
+            let (pos, vel) = kep.to_pv();
+            let pv = Vector6::new(pos.x, pos.y, pos.z, vel.x, vel.y, vel.z);
+
+            let numerical = rates_from_states(
+                &propagate_j2(&pv, -DIFF_STEP),
+                &propagate_j2(&pv, DIFF_STEP),
+                DIFF_STEP,
+            )?;
+            let gauss = element_rates_gauss(&pos, &vel, &j2_accel(&pos))?;
+            // Node regresses for prograde orbits
+            assert!(gauss.raan < 0.0);
+            check_rates(&numerical, &gauss, kep.mean_motion(), a, e);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_element_rates_numerical() -> SKResult<()> {
+        // Near the J2000 epoch, so the GCRF z axis is close to the
+        // Earth pole.  Gravity to order 2 is dominated by J2; sun & moon
+        // are included but are small at LEO
+        let time = Instant::from_datetime(2000, 1, 2, 0, 0, 0.0);
+        let a = consts::EARTH_RADIUS + 700.0e3;
+        let e = 0.05;
+        let kep = Kepler::new(a, e, 51.6_f64.to_radians(), 0.4, 1.2, Anomaly::True(0.7));
+        let (pos, vel) = kep.to_pv();
+        let state = SatState::from_pv(&time, &pos, &vel);
+
+        let settings = PropSettings {
+            gravity_order: 2,
+            ..Default::default()
+        };
+        let numerical = element_rates_numerical(&state, Some(&settings))?;
+        let gauss = element_rates_gauss(&pos, &vel, &j2_accel(&pos))?;
+        // Looser tolerance for C22, S22, third-body & frame offsets
+        let scale = consts::JGM3_J2.abs() * (consts::EARTH_RADIUS / a).powi(2) * kep.mean_motion();
+        let tol = 1.0e-2 * scale;
+        assert!((numerical.a - gauss.a).abs() < tol * a);
+        assert!((numerical.eccen - gauss.eccen).abs() < tol);
+        assert!((numerical.incl - gauss.incl).abs() < tol);
+        assert!((numerical.raan - gauss.raan).abs() < tol);
+        assert!((numerical.w - gauss.w).abs() < tol / e);
+        assert!((numerical.mean_anomaly - gauss.mean_anomaly).abs() < tol / e);
+        Ok(())
+    }
+}
@@ -0,0 +1,487 @@
+//! Times at which a satellite crosses an elevation threshold, and its
+//! apparent angular & antenna slew rates, as seen from a ground site
+
+use super::propagator::propagate;
+use super::search::{find_roots, golden_max};
+use super::{PropSettings, SatState};
+
+use crate::frametransform::{pv_gcrf2itrf, qgcrf2itrf, qgcrf2itrf_approx};
+use crate::skerror;
+use crate::types::Vector3;
+use crate::{Duration, ITRFCoord, Instant, SKResult};
+
+/// Interval, in seconds, at which elevation is sampled to find
+/// crossings.  Passes that are above the target elevation for less
+/// than this may be missed
+const SCAN_STEP: f64 = 5.0;
+
+/// Precision, in seconds, to which crossing times are found
+const CROSSING_TOL: f64 = 1.0e-4;
+
+/// Interval, in seconds, at which slew rates are sampled over a pass
+/// before the largest sample is refined
+const SLEW_SCAN_STEP: f64 = 1.0;
+
+/// Geometric elevation, radians, of a satellite seen from an observer
+fn elevation(observer: &ITRFCoord, pos_gcrf: &Vector3, time: &Instant) -> f64 {
+    let target = ITRFCoord::from(qgcrf2itrf(time) * pos_gcrf);
+    observer.az_el_range(&target).1
+}
+
+/// Angular rate, radians / second, of the line of sight from an observer
+/// to a target with the given ITRF position & Earth-fixed velocity
+fn los_angular_rate(observer: &ITRFCoord, pos_itrf: &Vector3, vel_itrf: &Vector3) -> f64 {
+    let rho = pos_itrf - observer.itrf;
+    rho.cross(vel_itrf).norm() / rho.norm_squared()
+}
+
+/// Azimuth & elevation rates, radians / second, magnitude only, of an
+/// azimuth-elevation mount pointing from an observer at a target with
+/// the given ITRF position & Earth-fixed velocity
+///
+/// The azimuth rate is infinite for a target at the zenith (to within
+/// rounding), where the elevation rate is that of the horizontal motion
+fn az_el_rates(observer: &ITRFCoord, pos_itrf: &Vector3, vel_itrf: &Vector3) -> (f64, f64) {
+    let q = observer.q_enu2itrf().conjugate();
+    let rho = q * (pos_itrf - observer.itrf);
+    let rhodot = q * vel_itrf;
+    let h2 = rho.x * rho.x + rho.y * rho.y;
+    if h2 <= f64::EPSILON * rho.norm_squared() {
+        return (f64::INFINITY, rhodot.xy().norm() / rho.z.abs());
+    }
+    let az_rate = (rho.y * rhodot.x - rho.x * rhodot.y) / h2;
+    let el_rate = (h2 * rhodot.z - rho.z * (rho.x * rhodot.x + rho.y * rhodot.y))
+        / (h2.sqrt() * rho.norm_squared());
+    (az_rate.abs(), el_rate.abs())
+}
+
+/// Apparent angular rate of a satellite as seen from a ground site,
+/// e.g. for checking the slew rate of a mechanically-steered antenna
+///
+/// This is the rate of rotation of the line of sight in the Earth-fixed
+/// frame, combining the orbital motion with the rotation of the Earth:
+/// |rho x rho_dot| / |rho|^2, where rho is the topocentric position.
+/// It peaks at closest approach, which for a pass through the zenith is
+/// approximately the satellite's Earth-relative speed over its altitude
+///
+/// # Arguments
+///
+/// * `observer` - Location of the ground site
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+///
+/// # Returns
+///
+/// * Angular rate of the line of sight at the state time, radians / second
+///
+/// # Notes
+///
+/// * This is the total angular rate; the azimuth rate of an
+///   azimuth-elevation mount can be much larger near the zenith
+/// * Uses the approximate GCRF to ITRF rotation, which neglects polar
+///   motion; the effect on the rate is negligible
+pub fn apparent_angular_rate(observer: &ITRFCoord, state: &SatState) -> f64 {
+    let (pos, vel) = pv_gcrf2itrf(&qgcrf2itrf_approx(&state.time), &state.pv);
+    los_angular_rate(observer, &pos, &vel)
+}
+
+/// Maximum of `f` over `[0, span]`, found by scanning at `scan_step`
+/// and refining around the largest sample by golden-section search
+fn scan_max<F>(f: &F, span: f64, scan_step: f64) -> SKResult<f64>
+where
+    F: Fn(f64) -> SKResult<f64>,
+{
+    let nscan = (span / scan_step).ceil().max(1.0) as usize;
+    let dt = span / nscan as f64;
+    let (mut tbest, mut fbest) = (0.0, f(0.0)?);
+    for ix in 1..=nscan {
+        let t = ix as f64 * dt;
+        let v = f(t)?;
+        if v > fbest {
+            (tbest, fbest) = (t, v);
+        }
+    }
+    let (a, b) = ((tbest - dt).max(0.0), (tbest + dt).min(span));
+    let (_, v) = golden_max(f, a, b, CROSSING_TOL)?;
+    Ok(fbest.max(v))
+}
+
+/// Times at which a satellite crosses an elevation threshold, as seen
+/// from a ground site, e.g. for scheduling within a known pass
+///
+/// # Arguments
+///
+/// * `observer` - Location of the ground site
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+/// * `start` - Start of time window to search
+/// * `stop` - End of time window to search
+/// * `target_elevation` - Elevation threshold, radians, above the
+///   local horizon
+/// * `settings` - Propagation settings.  Interpolation is always enabled
+///
+/// # Returns
+///
+/// * Times, in order, at which the geometric elevation crosses the
+///   target.  Crossings alternate between rising & setting; if the
+///   satellite is above the target at `start` the first crossing is
+///   setting.  Empty if the elevation does not cross the target within
+///   the window, e.g. for a pass whose maximum elevation is below it
+///
+/// # Notes
+///
+/// * Elevation is sampled every 5 seconds to find crossings, so a pass
+///   that exceeds the target for less than this may be missed
+/// * Refraction & light-time are not modeled
+pub fn elevation_crossing(
+    observer: &ITRFCoord,
+    state: &SatState,
+    start: &Instant,
+    stop: &Instant,
+    target_elevation: f64,
+    settings: Option<&PropSettings>,
+) -> SKResult<Vec<Instant>> {
+    if stop <= start {
+        return skerror!("Stop time must be after start time");
+    }
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    settings.enable_interp = true;
+
+    // Propagate to start of window (without covariance), then over window
+    let state0 = SatState::from_pv(&state.time, &state.pos_gcrf(), &state.vel_gcrf());
+    let state0 = match state0.time == *start {
+        true => state0,
+        false => state0.propagate(start, Some(&settings))?,
+    };
+    let res = propagate(&state0.pv, start, stop, &settings, None)?;
+
+    let offset = |t: f64| -> SKResult<f64> {
+        let time = *start + Duration::from_seconds(t);
+        let pv = res.interp(&time)?;
+        Ok(elevation(observer, &pv.fixed_rows::<3>(0).into(), &time) - target_elevation)
+    };
+    let roots = find_roots(
+        &offset,
+        (stop - start).as_seconds(),
+        SCAN_STEP,
+        CROSSING_TOL,
+    )?;
+    Ok(roots
+        .into_iter()
+        .map(|root| *start + Duration::from_seconds(root.t))
+        .collect())
+}
+
+/// Peak azimuth & elevation slew rates of an azimuth-elevation antenna
+/// mount tracking a satellite over a pass
+///
+/// # Arguments
+///
+/// * `observer` - Location of the ground site
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+/// * `start` - Start of the pass, e.g. the rising crossing from
+///   [`elevation_crossing`]
+/// * `stop` - End of the pass, e.g. the setting crossing
+/// * `settings` - Propagation settings.  Interpolation is always enabled
+///
+/// # Returns
+///
+/// * Tuple with the following elements:
+///   * Peak azimuth rate, radians / second
+///   * Peak elevation rate, radians / second
+///
+/// # Notes
+///
+/// * Rates are sampled every second, & the largest sample refined, so
+///   the sharp azimuth-rate peak of a pass near the zenith is not missed
+/// * Passes close to the zenith (the "keyhole" of an azimuth-elevation
+///   mount) require very large azimuth rates; the rate is unbounded for
+///   a pass directly overhead
+pub fn pass_peak_slew_rates(
+    observer: &ITRFCoord,
+    state: &SatState,
+    start: &Instant,
+    stop: &Instant,
+    settings: Option<&PropSettings>,
+) -> SKResult<(f64, f64)> {
+    if stop <= start {
+        return skerror!("Stop time must be after start time");
+    }
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    settings.enable_interp = true;
+
+    // Propagate to start of pass (without covariance), then over pass
+    let state0 = SatState::from_pv(&state.time, &state.pos_gcrf(), &state.vel_gcrf());
+    let state0 = match state0.time == *start {
+        true => state0,
+        false => state0.propagate(start, Some(&settings))?,
+    };
+    let res = propagate(&state0.pv, start, stop, &settings, None)?;
+
+    let rates = |t: f64| -> SKResult<(f64, f64)> {
+        let time = *start + Duration::from_seconds(t);
+        let (pos, vel) = pv_gcrf2itrf(&qgcrf2itrf(&time), &res.interp(&time)?);
+        Ok(az_el_rates(observer, &pos, &vel))
+    };
+    let span = (stop - start).as_seconds();
+    Ok((
+        scan_max(&|t| Ok(rates(t)?.0), span, SLEW_SCAN_STEP)?,
+        scan_max(&|t| Ok(rates(t)?.1), span, SLEW_SCAN_STEP)?,
+    ))
+}
+
+/// Whether an azimuth-elevation antenna mount with the given slew rate
+/// limits can track a satellite over a pass
+///
+/// # Arguments
+///
+/// * `observer` - Location of the ground site
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+/// * `start` - Start of the pass
+/// * `stop` - End of the pass
+/// * `az_rate_limit` - Maximum azimuth slew rate, radians / second
+/// * `el_rate_limit` - Maximum elevation slew rate, radians / second
+/// * `settings` - Propagation settings.  Interpolation is always enabled
+///
+/// # Returns
+///
+/// * True if neither peak rate from [`pass_peak_slew_rates`] exceeds its
+///   limit.  Keyhole passes near the zenith are typically infeasible
+///   because of the azimuth rate
+pub fn pass_slew_feasible(
+    observer: &ITRFCoord,
+    state: &SatState,
+    start: &Instant,
+    stop: &Instant,
+    az_rate_limit: f64,
+    el_rate_limit: f64,
+    settings: Option<&PropSettings>,
+) -> SKResult<bool> {
+    let (az_rate, el_rate) = pass_peak_slew_rates(observer, state, start, stop, settings)?;
+    Ok(az_rate <= az_rate_limit && el_rate <= el_rate_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::frametransform::qitrf2gcrf;
+
+    #[test]
+    fn test_angular_rate() {
+        // Straight-line passes at 500 km altitude, heading north at
+        // 7 km/s, with closest approach directly overhead and far off
+        // to the east (a low, grazing pass)
+        let observer = ITRFCoord::from_geodetic_deg(40.0, -75.0, 0.0);
+        let enu2itrf = observer.q_enu2itrf();
+        let (alt, speed) = (500.0e3, 7.0e3);
+        let vel = enu2itrf * Vector3::new(0.0, speed, 0.0);
+        let peak = |east: f64| {
+            (-300..=300)
+                .map(|ix| {
+                    let pos = observer.itrf
+                        + enu2itrf * Vector3::new(east, ix as f64 * 10.0 * speed, alt);
+                    los_angular_rate(&observer, &pos, &vel)
+                })
+                .fold(0.0, f64::max)
+        };
+        let zenith = peak(0.0);
+        let grazing = peak(1500.0e3);
+        assert!((zenith - speed / alt).abs() < 1.0e-9);
+        assert!(grazing < zenith);
+        assert!((grazing - speed / alt.hypot(1500.0e3)).abs() < 1.0e-9);
+
+        // Matches the rate of change of the line-of-sight direction
+        let pos = observer.itrf + enu2itrf * Vector3::new(2.0e5, -4.0e5, alt);
+        let los = |dt: f64| (pos + vel * dt - observer.itrf).normalize();
+        let dt = 1.0e-2;
+        let numerical = (los(dt) - los(-dt)).norm() / (2.0 * dt);
+        assert!((los_angular_rate(&observer, &pos, &vel) - numerical).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_apparent_angular_rate() -> SKResult<()> {
+        // State from an Earth-fixed position & velocity near the site
+        let observer = ITRFCoord::from_geodetic_deg(40.0, -75.0, 0.0);
+        let enu2itrf = observer.q_enu2itrf();
+        let time = Instant::from_datetime(2024, 3, 1, 6, 0, 0.0);
+        let pos_itrf = observer.itrf + enu2itrf * Vector3::new(2.0e5, -4.0e5, 5.0e5);
+        let vel_itrf = enu2itrf * Vector3::new(1.0e3, 7.0e3, -5.0e2);
+        let q = qgcrf2itrf_approx(&time).conjugate();
+        let omega = Vector3::new(0.0, 0.0, consts::OMEGA_EARTH);
+        let state = SatState::from_pv(
+            &time,
+            &(q * pos_itrf),
+            &(q * (vel_itrf + omega.cross(&pos_itrf))),
+        );
+        let rate = apparent_angular_rate(&observer, &state);
+        assert!((rate - los_angular_rate(&observer, &pos_itrf, &vel_itrf)).abs() < 1.0e-12);
+
+        // Matches the rate of change of the line-of-sight direction
+        // along the (inertial) two-body trajectory
+        let los = |dt: f64| -> SKResult<Vector3> {
+            let pv = crate::orbitprop::propagate_twobody(&state.pv, consts::MU_EARTH, dt)?;
+            let t = time + Duration::from_seconds(dt);
+            let pos = qgcrf2itrf_approx(&t) * pv.fixed_rows::<3>(0);
+            Ok((pos - observer.itrf).normalize())
+        };
+        let dt = 1.0e-2;
+        let numerical = (los(dt)? - los(-dt)?).norm() / (2.0 * dt);
+        assert!((rate - numerical).abs() < 1.0e-8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_az_el_rates() -> SKResult<()> {
+        // Matches the rate of change of azimuth & elevation
+        let observer = ITRFCoord::from_geodetic_deg(40.0, -75.0, 0.0);
+        let enu2itrf = observer.q_enu2itrf();
+        let pos = observer.itrf + enu2itrf * Vector3::new(2.0e5, -4.0e5, 5.0e5);
+        let vel = enu2itrf * Vector3::new(1.0e3, 7.0e3, -5.0e2);
+        let azel = |dt: f64| {
+            let (az, el, _) = observer.az_el_range(&ITRFCoord::from(pos + vel * dt));
+            (az, el)
+        };
+        let dt = 1.0e-2;
+        let (az_rate, el_rate) = az_el_rates(&observer, &pos, &vel);
+        assert!((az_rate - ((azel(dt).0 - azel(-dt).0) / (2.0 * dt)).abs()).abs() < 1.0e-9);
+        assert!((el_rate - ((azel(dt).1 - azel(-dt).1) / (2.0 * dt)).abs()).abs() < 1.0e-9);
+
+        // Straight-line passes at 500 km altitude heading north at 7 km/s:
+        // near the zenith (keyhole) the peak azimuth rate is the speed
+        // over the horizontal miss distance, far larger than for a low
+        // pass, while the peak elevation rate, reached just before &
+        // after closest approach, is just below the speed over altitude
+        let (alt, speed) = (500.0e3, 7.0e3);
+        let vel = enu2itrf * Vector3::new(0.0, speed, 0.0);
+        let peak = |east: f64| -> SKResult<(f64, f64)> {
+            let rates = |t: f64| {
+                let pos = observer.itrf + enu2itrf * Vector3::new(east, (t - 300.0) * speed, alt);
+                az_el_rates(&observer, &pos, &vel)
+            };
+            Ok((
+                scan_max(&|t| Ok(rates(t).0), 600.0, SLEW_SCAN_STEP)?,
+                scan_max(&|t| Ok(rates(t).1), 600.0, SLEW_SCAN_STEP)?,
+            ))
+        };
+        let keyhole = peak(1.0e3)?;
+        let low = peak(1.5e6)?;
+        assert!((keyhole.0 - speed / 1.0e3).abs() < 1.0e-3 * speed / 1.0e3);
+        assert!(keyhole.1 < speed / alt && keyhole.1 > 0.99 * speed / alt);
+        assert!(low.0 < 1.0e-3 * keyhole.0);
+        assert!(low.1 < keyhole.1);
+
+        // Directly overhead
+        let (az_rate, el_rate) = az_el_rates(
+            &observer,
+            &(observer.itrf + enu2itrf * Vector3::z() * alt),
+            &vel,
+        );
+        assert!(az_rate.is_infinite());
+        assert!((el_rate - speed / alt).abs() < 1.0e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pass_slew_feasible() -> SKResult<()> {
+        // Satellites in polar orbit pass over the equator near an
+        // observer on the equator: almost through the zenith, and low
+        // in the east
+        let observer = ITRFCoord::from_geodetic_deg(0.0, 0.0, 0.0);
+        let t0 = Instant::from_datetime(2024, 6, 1, 12, 0, 0.0);
+        let q = qitrf2gcrf(&t0);
+        let state = |lon: f64| {
+            let pos = q * ITRFCoord::from_geodetic_deg(0.0, lon, 500.0e3).itrf;
+            let vel = q * Vector3::z() * (consts::MU_EARTH / pos.norm()).sqrt();
+            SatState::from_pv(&t0, &pos, &vel)
+        };
+        let start = t0 - Duration::from_minutes(4.0);
+        let stop = t0 + Duration::from_minutes(4.0);
+        let limit = 3.0_f64.to_radians();
+
+        let keyhole = state(0.05);
+        let (az_rate, el_rate) = pass_peak_slew_rates(&observer, &keyhole, &start, &stop, None)?;
+        assert!(az_rate > 10.0 * limit);
+        assert!(el_rate < limit);
+        assert!(!pass_slew_feasible(
+            &observer, &keyhole, &start, &stop, limit, limit, None
+        )?);
+
+        let low = state(15.0);
+        let (az_rate, el_rate) = pass_peak_slew_rates(&observer, &low, &start, &stop, None)?;
+        assert!(az_rate < limit && el_rate < limit);
+        assert!(pass_slew_feasible(
+            &observer, &low, &start, &stop, limit, limit, None
+        )?);
+
+        assert!(pass_peak_slew_rates(&observer, &low, &stop, &start, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_elevation_crossing() -> SKResult<()> {
+        // Satellite in polar orbit passes over the equator, 1.5 degrees
+        // east of an observer on the equator, for a pass with maximum
+        // elevation near 70 degrees
+        let observer = ITRFCoord::from_geodetic_deg(0.0, 0.0, 0.0);
+        let t0 = Instant::from_datetime(2024, 6, 1, 12, 0, 0.0);
+        let q = qitrf2gcrf(&t0);
+        let sub = ITRFCoord::from_geodetic_deg(0.0, 1.5, 500.0e3);
+        let pos = q * sub.itrf;
+        let vel = q * Vector3::z() * (consts::MU_EARTH / pos.norm()).sqrt();
+        let state = SatState::from_pv(&t0, &pos, &vel);
+
+        let start = t0 - Duration::from_minutes(8.0);
+        let stop = t0 + Duration::from_minutes(8.0);
+        let settings = PropSettings {
+            enable_interp: true,
+            ..PropSettings::default()
+        };
+        let state0 = state.propagate(&start, Some(&settings))?;
+        let res = propagate(&state0.pv, &start, &stop, &settings, None)?;
+        let el = |t: &Instant| -> SKResult<f64> {
+            let pv = res.interp(t)?;
+            Ok(elevation(&observer, &pv.fixed_rows::<3>(0).into(), t))
+        };
+
+        // Time of closest approach (maximum elevation),
+        // by golden-section search
+        let gr = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let (mut a, mut b) = (-120.0, 120.0);
+        while b - a > 1.0e-4 {
+            let c = b - gr * (b - a);
+            let d = a + gr * (b - a);
+            match el(&(t0 + Duration::from_seconds(c)))? > el(&(t0 + Duration::from_seconds(d)))? {
+                true => b = d,
+                false => a = c,
+            }
+        }
+        let tca = t0 + Duration::from_seconds(0.5 * (a + b));
+        let max_el = el(&tca)?.to_degrees();
+        assert!(max_el > 65.0 && max_el < 75.0);
+
+        // 60 degree crossings are symmetric about TCA
+        let target = 60.0_f64.to_radians();
+        let crossings = elevation_crossing(&observer, &state, &start, &stop, target, None)?;
+        assert_eq!(crossings.len(), 2);
+        let rise = (tca - crossings[0]).as_seconds();
+        let set = (crossings[1] - tca).as_seconds();
+        assert!(rise > 10.0);
+        assert!((rise - set).abs() < 0.5);
+        for c in &crossings {
+            assert!((el(c)? - target).abs() < 1.0e-5);
+        }
+
+        // Starting mid-pass gives only the setting crossing
+        let crossings2 = elevation_crossing(&observer, &state, &tca, &stop, target, None)?;
+        assert_eq!(crossings2.len(), 1);
+        assert!((crossings2[0] - crossings[1]).as_seconds().abs() < 1.0e-3);
+
+        // Pass maximum below target elevation
+        let target = 80.0_f64.to_radians();
+        assert!(elevation_crossing(&observer, &state, &start, &stop, target, None)?.is_empty());
+
+        assert!(elevation_crossing(&observer, &state, &stop, &start, target, None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Empirical accelerations for precise orbit determination
+//!
+//! Constant and once-per-revolution (1-CPR) accelerations along the
+//! radial, transverse (along-track), and normal (cross-track) directions.
+//! These are commonly estimated to absorb force-model mismodeling.
+
+use nalgebra as na;
+
+type Vec3 = na::Vector3<f64>;
+
+/// Number of empirical acceleration parameters
+pub const NUM_EMPIRICAL_PARAMS: usize = 9;
+
+/// Partials of empirical acceleration (GCRF) with respect to the parameters
+pub type EmpiricalPartials = na::SMatrix<f64, 3, NUM_EMPIRICAL_PARAMS>;
+
+/// Empirical accelerations in the radial, transverse, normal (RTN) frame
+///
+/// Each direction has three terms, in meters / second^2:
+///
+/// * `[0]` - constant
+/// * `[1]` - amplitude of cos(u)
+/// * `[2]` - amplitude of sin(u)
+///
+/// where u is the argument of latitude, measured from the ascending node
+/// in the GCRF equatorial plane.  For (near-)equatorial orbits, where
+/// the node is undefined, u is measured from the GCRF x axis
+/// (i.e., it is the true longitude)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmpiricalAccel {
+    pub radial: [f64; 3],
+    pub along: [f64; 3],
+    pub cross: [f64; 3],
+}
+
+impl EmpiricalAccel {
+    /// Create from parameter vector, ordered
+    /// radial (const, cos, sin), along-track (const, cos, sin), cross-track (const, cos, sin)
+    pub fn from_params(p: &[f64; NUM_EMPIRICAL_PARAMS]) -> Self {
+        Self {
+            radial: [p[0], p[1], p[2]],
+            along: [p[3], p[4], p[5]],
+            cross: [p[6], p[7], p[8]],
+        }
+    }
+
+    /// Parameter vector, in same order as `from_params`
+    pub fn params(&self) -> [f64; NUM_EMPIRICAL_PARAMS] {
+        let mut p = [0.0; NUM_EMPIRICAL_PARAMS];
+        p[0..3].copy_from_slice(&self.radial);
+        p[3..6].copy_from_slice(&self.along);
+        p[6..9].copy_from_slice(&self.cross);
+        p
+    }
+
+    /// Acceleration in the GCRF frame, meters / second^2
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - GCRF position, meters
+    /// * `vel` - GCRF velocity, meters / second
+    pub fn accel(&self, pos: &Vec3, vel: &Vec3) -> Vec3 {
+        partials(pos, vel) * na::SVector::<f64, NUM_EMPIRICAL_PARAMS>::from(self.params())
+    }
+}
+
+/// Argument of latitude, radians
+///
+/// Angle in the orbit plane from the ascending node to the satellite.
+/// If the orbit is equatorial, the GCRF x axis is used in place
+/// of the ascending node
+pub fn argument_of_latitude(pos: &Vec3, vel: &Vec3) -> f64 {
+    let h = pos.cross(vel).normalize();
+    let node = Vec3::z().cross(&h);
+    let node = match node.norm() < 1.0e-10 {
+        true => Vec3::x(),
+        false => node.normalize(),
+    };
+    let y = h.cross(&node);
+    f64::atan2(pos.dot(&y), pos.dot(&node))
+}
+
+/// Partials of the GCRF empirical acceleration with respect to the
+/// 9 empirical parameters.  Since the acceleration is linear in the
+/// parameters, this also gives the acceleration when multiplied by them
+///
+/// # Arguments
+///
+/// * `pos` - GCRF position, meters
+/// * `vel` - GCRF velocity, meters / second
+pub fn partials(pos: &Vec3, vel: &Vec3) -> EmpiricalPartials {
+    let rhat = pos.normalize();
+    let nhat = pos.cross(vel).normalize();
+    let that = nhat.cross(&rhat);
+    let (su, cu) = argument_of_latitude(pos, vel).sin_cos();
+
+    let mut p = EmpiricalPartials::zeros();
+    for (idx, dir) in [rhat, that, nhat].iter().enumerate() {
+        p.set_column(idx * 3, dir);
+        p.set_column(idx * 3 + 1, &(dir * cu));
+        p.set_column(idx * 3 + 2, &(dir * su));
+    }
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_argument_of_latitude() {
+        // Inclined orbit, satellite 30 degrees past ascending node,
+        // node at 90 degrees RAAN
+        let incl = 50.0_f64.to_radians();
+        let raan = PI / 2.0;
+        let node = Vec3::new(raan.cos(), raan.sin(), 0.0);
+        let h = Vec3::new(incl.sin() * raan.sin(), -incl.sin() * raan.cos(), incl.cos());
+        let y = h.cross(&node);
+        let u = 30.0_f64.to_radians();
+        let pos = (node * u.cos() + y * u.sin()) * 7.0e6;
+        let vel = (-node * u.sin() + y * u.cos()) * 7.5e3;
+        assert!((argument_of_latitude(&pos, &vel) - u).abs() < 1.0e-12);
+
+        // Equatorial orbit: measured from x axis
+        let pos = Vec3::new(0.0, 7.0e6, 0.0);
+        let vel = Vec3::new(-7.5e3, 0.0, 0.0);
+        assert!((argument_of_latitude(&pos, &vel) - PI / 2.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_rtn_accel() {
+        let pos = Vec3::new(7.0e6, 0.0, 0.0);
+        let vel = Vec3::new(0.0, 5.0e3, 5.0e3);
+
+        // Constant along-track acceleration is along velocity for circular orbit
+        let emp = EmpiricalAccel {
+            along: [1.0e-8, 0.0, 0.0],
+            ..Default::default()
+        };
+        let a = emp.accel(&pos, &vel);
+        assert!((a - vel.normalize() * 1.0e-8).norm() < 1.0e-20);
+
+        // Satellite is at ascending node, so u = 0 and cos term is at full amplitude
+        let emp = EmpiricalAccel {
+            radial: [0.0, 2.0e-9, 3.0e-9],
+            ..Default::default()
+        };
+        let a = emp.accel(&pos, &vel);
+        assert!((a - Vec3::new(2.0e-9, 0.0, 0.0)).norm() < 1.0e-20);
+
+        // Parameter round-trip
+        let p = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        assert_eq!(EmpiricalAccel::from_params(&p).params(), p);
+    }
+}
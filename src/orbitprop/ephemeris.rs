@@ -0,0 +1,759 @@
+//! Tabulated satellite ephemeris with Hermite interpolation
+
+use crate::kepler::{eccentricity_vector, equinoctial_to_pv, pv_to_equinoctial, Kepler};
+use crate::skerror;
+use crate::types::{Matrix6, Vector3, Vector6};
+use crate::{Instant, SKResult, TimeScale};
+
+use std::f64::consts::PI;
+use std::io::Write;
+
+/// Eccentricity, and sine of inclination, below which the argument of
+/// perigee, and right ascension of ascending node, are ill-defined.
+/// Eccentricity of 1e-6 moves perigee by about 7 m in low Earth orbit
+const SINGULAR_TOL: f64 = 1.0e-6;
+
+/// Leading bytes identifying the binary ephemeris format
+const BYTES_MAGIC: &[u8; 4] = b"SKEP";
+
+/// Version of the binary ephemeris format written by [`Ephemeris::to_bytes`]
+const BYTES_VERSION: u16 = 1;
+
+/// Binary format flag: node covariances are present
+const BYTES_FLAG_COV: u16 = 0x0001;
+
+/// Size, in bytes, of the binary format header
+const BYTES_HEADER_LEN: usize = 16;
+
+/// Tabulated satellite ephemeris
+///
+/// Ephemeris is a time-ordered list of position & velocity nodes,
+/// in an inertial frame (e.g., GCRF), interpolated between nodes with
+/// cubic Hermite polynomials that match position & velocity at both ends
+/// of each interval
+#[derive(Debug, Clone)]
+pub struct Ephemeris {
+    /// Nodes of (time, [position (m), velocity (m/s)])
+    pub nodes: Vec<(Instant, Vector6)>,
+    /// Optional 6x6 position & velocity covariance at each node.
+    /// Covariance is carried with the nodes, but not interpolated
+    pub covariance: Option<Vec<Matrix6>>,
+}
+
+/// Cubic Hermite interpolation between two position & velocity nodes
+///
+/// `h` is the node spacing & `s` the normalized time in [0, 1]
+fn hermite(pv0: &Vector6, pv1: &Vector6, h: f64, s: f64) -> Vector6 {
+    let p0: Vector3 = pv0.fixed_rows::<3>(0).into();
+    let v0: Vector3 = pv0.fixed_rows::<3>(3).into();
+    let p1: Vector3 = pv1.fixed_rows::<3>(0).into();
+    let v1: Vector3 = pv1.fixed_rows::<3>(3).into();
+
+    let (s2, s3) = (s * s, s * s * s);
+    let pos = p0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+        + v0 * (h * (s3 - 2.0 * s2 + s))
+        + p1 * (-2.0 * s3 + 3.0 * s2)
+        + v1 * (h * (s3 - s2));
+    let vel = p0 * ((6.0 * s2 - 6.0 * s) / h)
+        + v0 * (3.0 * s2 - 4.0 * s + 1.0)
+        + p1 * ((-6.0 * s2 + 6.0 * s) / h)
+        + v1 * (3.0 * s2 - 2.0 * s);
+
+    let mut pv = Vector6::zeros();
+    pv.fixed_rows_mut::<3>(0).copy_from(&pos);
+    pv.fixed_rows_mut::<3>(3).copy_from(&vel);
+    pv
+}
+
+impl Ephemeris {
+    /// Create ephemeris from a list of nodes
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Nodes of (time, [position (m), velocity (m/s)]),
+    ///   with strictly increasing times
+    ///
+    /// # Returns
+    ///
+    /// * Ephemeris, or error if there are fewer than 2 nodes or
+    ///   times are not strictly increasing
+    pub fn new(nodes: Vec<(Instant, Vector6)>) -> SKResult<Self> {
+        if nodes.len() < 2 {
+            return skerror!("Ephemeris must have at least 2 nodes");
+        }
+        if nodes.windows(2).any(|w| w[1].0 <= w[0].0) {
+            return skerror!("Ephemeris node times must be strictly increasing");
+        }
+        Ok(Self {
+            nodes,
+            covariance: None,
+        })
+    }
+
+    /// Attach a covariance to each node
+    ///
+    /// # Arguments
+    ///
+    /// * `covariance` - 6x6 position & velocity covariance at each node,
+    ///   in the same order as the nodes
+    ///
+    /// # Returns
+    ///
+    /// * Ephemeris with covariance, or error if the number of covariances
+    ///   does not match the number of nodes
+    pub fn with_covariance(mut self, covariance: Vec<Matrix6>) -> SKResult<Self> {
+        if covariance.len() != self.nodes.len() {
+            return skerror!(
+                "Number of covariances ({}) must match number of nodes ({})",
+                covariance.len(),
+                self.nodes.len()
+            );
+        }
+        self.covariance = Some(covariance);
+        Ok(self)
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// True if the ephemeris has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Time of first node
+    pub fn start(&self) -> Instant {
+        self.nodes[0].0
+    }
+
+    /// Time of last node
+    pub fn stop(&self) -> Instant {
+        self.nodes[self.nodes.len() - 1].0
+    }
+
+    /// Interpolate between nodes ix and jx
+    fn interp_interval(&self, ix: usize, jx: usize, time: &Instant) -> Vector6 {
+        let (t0, pv0) = &self.nodes[ix];
+        let (t1, pv1) = &self.nodes[jx];
+        let h = (*t1 - *t0).as_seconds();
+        let s = (*time - *t0).as_seconds() / h;
+        hermite(pv0, pv1, h, s)
+    }
+
+    /// Interpolate position & velocity at the given time
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time at which to interpolate.  Must be within
+    ///   the span of the ephemeris
+    ///
+    /// # Returns
+    ///
+    /// * 6-element vector of [position (m), velocity (m/s)]
+    pub fn interp(&self, time: &Instant) -> SKResult<Vector6> {
+        if *time < self.start() || *time > self.stop() {
+            return skerror!("Time is outside of ephemeris span");
+        }
+        let ix = self
+            .nodes
+            .partition_point(|(t, _)| *t <= *time)
+            .clamp(1, self.nodes.len() - 1);
+        Ok(self.interp_interval(ix - 1, ix, time))
+    }
+
+    /// Equinoctial orbital elements at each node
+    ///
+    /// # Arguments
+    ///
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    /// * `retrograde` - Use the retrograde set of elements, for orbits
+    ///   near an inclination of 180 degrees
+    ///
+    /// # Returns
+    ///
+    /// * Nodes of (time, [a, h, k, p, q, λ]); see
+    ///   [`crate::kepler::pv_to_equinoctial`]
+    /// * Error if the orbit at a node is not elliptical, or is
+    ///   singular for the chosen retrograde factor
+    ///
+    /// # Notes
+    ///
+    /// * Unlike classical elements, equinoctial elements are
+    ///   non-singular for circular & equatorial orbits
+    /// * Mean longitude is in [0, 2π), so wraps between nodes
+    pub fn equinoctial_elements(
+        &self,
+        mu: f64,
+        retrograde: bool,
+    ) -> SKResult<Vec<(Instant, Vector6)>> {
+        self.nodes
+            .iter()
+            .map(|(t, pv)| Ok((*t, pv_to_equinoctial(pv, mu, retrograde)?)))
+            .collect()
+    }
+
+    /// Construct from equinoctial orbital elements at each node
+    ///
+    /// Inverse of [`Ephemeris::equinoctial_elements`]
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Time-ordered nodes of (time, [a, h, k, p, q, λ])
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    /// * `retrograde` - Elements are the retrograde set.  Must match
+    ///   the value used to compute the elements
+    ///
+    /// # Returns
+    ///
+    /// * Ephemeris, or error if the elements at a node do not describe
+    ///   an elliptical orbit, or the nodes are not in time order
+    pub fn from_equinoctial(
+        nodes: &[(Instant, Vector6)],
+        mu: f64,
+        retrograde: bool,
+    ) -> SKResult<Self> {
+        Self::new(
+            nodes
+                .iter()
+                .map(|(t, eq)| Ok((*t, equinoctial_to_pv(eq, mu, retrograde)?)))
+                .collect::<SKResult<Vec<_>>>()?,
+        )
+    }
+
+    /// Write osculating orbital elements at each node to a CSV file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the CSV file to write
+    /// * `mu` - Gravitational parameter of the central body, m^3/s^2
+    /// * `scale` - Time scale in which the node times are written,
+    ///   as Modified Julian Date
+    ///
+    /// # Returns
+    ///
+    /// * Error if the file cannot be written, the time scale is invalid,
+    ///   or the orbit at a node is not elliptical
+    ///
+    /// # Notes
+    ///
+    /// * Columns are time, semi-major axis (m), eccentricity, then
+    ///   inclination, right ascension of ascending node, argument of
+    ///   perigee, true anomaly & mean anomaly (degrees), then a flag
+    /// * Where angles are ill-defined, surrogates are written and the
+    ///   row is flagged: for a near-circular orbit ("circular") the
+    ///   argument of perigee is zero & the anomalies are measured from the
+    ///   ascending node (argument of latitude); for a near-equatorial
+    ///   orbit ("equatorial") the ascending node is zero & the argument
+    ///   of perigee is the longitude of perigee
+    pub fn elements_to_csv(
+        &self,
+        path: &std::path::Path,
+        mu: f64,
+        scale: TimeScale,
+    ) -> SKResult<()> {
+        if scale == TimeScale::Invalid {
+            return skerror!("Invalid time scale");
+        }
+        let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            f,
+            "mjd_{},a,eccen,incl,raan,w,nu,mean_anomaly,flag",
+            format!("{:?}", scale).to_lowercase()
+        )?;
+        for (time, pv) in &self.nodes {
+            let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+            let vel: Vector3 = pv.fixed_rows::<3>(3).into();
+            let mut kep = match Kepler::from_pv_with_mu(pos, vel, mu) {
+                Ok(k) if k.a > 0.0 => k,
+                _ => return skerror!("Orbit at {} is not elliptical", time),
+            };
+            let circular = kep.eccen < SINGULAR_TOL;
+            let equatorial = kep.incl.sin() < SINGULAR_TOL;
+
+            // Surrogate angles where the elements are ill-defined, measured
+            // in the orbit plane from the ascending node, or from the x axis
+            // for an equatorial orbit
+            if circular || equatorial {
+                let hhat = pos.cross(&vel).normalize();
+                let nhat = match equatorial {
+                    true => Vector3::x(),
+                    false => Vector3::z().cross(&hhat).normalize(),
+                };
+                let angle = |v: &Vector3| -> f64 {
+                    nhat.cross(v)
+                        .dot(&hhat)
+                        .atan2(nhat.dot(v))
+                        .rem_euclid(2.0 * PI)
+                };
+                if equatorial {
+                    kep.raan = 0.0;
+                }
+                kep.w = match circular {
+                    true => 0.0,
+                    false => angle(&eccentricity_vector(pv, mu)),
+                };
+                kep.nu = (angle(&pos) - kep.w).rem_euclid(2.0 * PI);
+            }
+
+            let flag = match (circular, equatorial) {
+                (true, true) => "circular+equatorial",
+                (true, false) => "circular",
+                (false, true) => "equatorial",
+                (false, false) => "",
+            };
+            writeln!(
+                f,
+                "{:.11},{:.6},{:.12},{:.9},{:.9},{:.9},{:.9},{:.9},{}",
+                time.as_mjd_with_scale(scale),
+                kep.a,
+                kep.eccen,
+                kep.incl.to_degrees(),
+                kep.raan.rem_euclid(2.0 * PI).to_degrees(),
+                kep.w.to_degrees(),
+                kep.nu.to_degrees(),
+                kep.mean_anomaly().rem_euclid(2.0 * PI).to_degrees(),
+                flag
+            )?;
+        }
+        f.flush()?;
+        Ok(())
+    }
+
+    /// Decimate the ephemeris, removing nodes while keeping
+    /// interpolation error within a bound
+    ///
+    /// # Arguments
+    ///
+    /// * `max_error` - Maximum position error, in meters, of Hermite
+    ///   interpolation between retained nodes at each removed node
+    ///
+    /// # Returns
+    ///
+    /// * Decimated ephemeris.  First & last nodes are always retained
+    ///
+    /// # Notes
+    ///
+    /// * Nodes are removed greedily: starting from a retained node,
+    ///   the next retained node is the furthest one for which interpolation
+    ///   reproduces every node in between to within `max_error`.  Regions of
+    ///   high curvature (e.g., perigee) therefore keep more nodes
+    /// * Error is only checked at the original nodes, not between them
+    pub fn decimate(&self, max_error: f64) -> Self {
+        let n = self.nodes.len();
+        if n < 3 {
+            return self.clone();
+        }
+
+        let within_tol = |ix: usize, jx: usize| -> bool {
+            (ix + 1..jx).all(|kx| {
+                let (t, pv) = &self.nodes[kx];
+                let pvi = self.interp_interval(ix, jx, t);
+                (pvi.fixed_rows::<3>(0) - pv.fixed_rows::<3>(0)).norm() <= max_error
+            })
+        };
+
+        let mut keep = vec![0];
+        let mut ix = 0;
+        while ix < n - 1 {
+            let mut jx = ix + 1;
+            while jx < n - 1 && within_tol(ix, jx + 1) {
+                jx += 1;
+            }
+            keep.push(jx);
+            ix = jx;
+        }
+        Self {
+            nodes: keep.iter().map(|ix| self.nodes[*ix]).collect(),
+            covariance: self
+                .covariance
+                .as_ref()
+                .map(|cov| keep.iter().map(|ix| cov[*ix]).collect()),
+        }
+    }
+
+    /// Serialize the ephemeris to a compact binary format
+    ///
+    /// # Returns
+    ///
+    /// * Bytes of the serialized ephemeris, which can be read back
+    ///   exactly with [`Self::from_bytes`]
+    ///
+    /// # Notes
+    ///
+    /// * All values are little-endian.  The 16-byte header is:
+    ///   * bytes 0-3: "SKEP"
+    ///   * bytes 4-5: format version (u16), currently 1
+    ///   * bytes 6-7: flags (u16); bit 0 set if covariance is present
+    ///   * bytes 8-15: number of nodes (u64)
+    /// * Each node follows in order as:
+    ///   * time (i64), microseconds since the Unix epoch, as stored in [`Instant`]
+    ///   * position & velocity (6 x f64)
+    ///   * covariance, if present (36 x f64, column-major)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let flags = match self.covariance {
+            Some(_) => BYTES_FLAG_COV,
+            None => 0,
+        };
+        let mut raw =
+            Vec::with_capacity(BYTES_HEADER_LEN + self.nodes.len() * Self::bytes_node_len(flags));
+        raw.extend_from_slice(BYTES_MAGIC);
+        raw.extend_from_slice(&BYTES_VERSION.to_le_bytes());
+        raw.extend_from_slice(&flags.to_le_bytes());
+        raw.extend_from_slice(&(self.nodes.len() as u64).to_le_bytes());
+        for (ix, (time, pv)) in self.nodes.iter().enumerate() {
+            raw.extend_from_slice(&time.raw.to_le_bytes());
+            pv.iter()
+                .for_each(|v| raw.extend_from_slice(&v.to_le_bytes()));
+            if let Some(cov) = &self.covariance {
+                cov[ix]
+                    .iter()
+                    .for_each(|v| raw.extend_from_slice(&v.to_le_bytes()));
+            }
+        }
+        raw
+    }
+
+    /// Deserialize an ephemeris from the binary format
+    /// written by [`Self::to_bytes`]
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - Bytes of the serialized ephemeris
+    ///
+    /// # Returns
+    ///
+    /// * Ephemeris, or error if the bytes are not a valid serialized
+    ///   ephemeris, or are from a newer, unsupported format version
+    pub fn from_bytes(raw: &[u8]) -> SKResult<Self> {
+        if raw.len() < BYTES_HEADER_LEN || &raw[0..4] != BYTES_MAGIC {
+            return skerror!("Not a binary ephemeris");
+        }
+        let version = u16::from_le_bytes(raw[4..6].try_into()?);
+        if version > BYTES_VERSION {
+            return skerror!(
+                "Unsupported binary ephemeris version {} (max supported is {})",
+                version,
+                BYTES_VERSION
+            );
+        }
+        let flags = u16::from_le_bytes(raw[6..8].try_into()?);
+        if flags & !BYTES_FLAG_COV != 0 {
+            return skerror!("Unsupported binary ephemeris flags: {:#06x}", flags);
+        }
+        let n = u64::from_le_bytes(raw[8..16].try_into()?) as usize;
+        let node_len = Self::bytes_node_len(flags);
+        if Some(raw.len() - BYTES_HEADER_LEN) != n.checked_mul(node_len) {
+            return skerror!(
+                "Binary ephemeris length {} does not match {} nodes",
+                raw.len(),
+                n
+            );
+        }
+
+        let f64_at =
+            |off: usize| -> f64 { f64::from_le_bytes(raw[off..off + 8].try_into().unwrap()) };
+        let mut nodes = Vec::with_capacity(n);
+        let mut covariance = Vec::new();
+        for ix in 0..n {
+            let off = BYTES_HEADER_LEN + ix * node_len;
+            let time = Instant::new(i64::from_le_bytes(raw[off..off + 8].try_into()?));
+            let pv = Vector6::from_fn(|r, _| f64_at(off + 8 + 8 * r));
+            nodes.push((time, pv));
+            if flags & BYTES_FLAG_COV != 0 {
+                covariance.push(Matrix6::from_fn(|r, c| f64_at(off + 56 + 8 * (6 * c + r))));
+            }
+        }
+        let ephem = Self::new(nodes)?;
+        match flags & BYTES_FLAG_COV != 0 {
+            true => ephem.with_covariance(covariance),
+            false => Ok(ephem),
+        }
+    }
+
+    /// Size, in bytes, of each node in the binary format
+    const fn bytes_node_len(flags: u16) -> usize {
+        match flags & BYTES_FLAG_COV != 0 {
+            true => 8 + 6 * 8 + 36 * 8,
+            false => 8 + 6 * 8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::{Anomaly, Kepler};
+    use crate::Duration;
+
+    /// Two-body ephemeris sampled at fixed step, starting at perigee
+    fn kepler_ephem(a: f64, e: f64, step: f64, duration: f64) -> Ephemeris {
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let kep = Kepler::new(a, e, 0.3, 0.0, 0.0, Anomaly::Mean(0.0));
+        let n = (duration / step) as usize;
+        let nodes = (0..=n)
+            .map(|ix| {
+                let dt = Duration::from_seconds(step * ix as f64);
+                let (r, v) = kep.propagate(&dt).to_pv();
+                let mut pv = Vector6::zeros();
+                pv.fixed_rows_mut::<3>(0).copy_from(&r);
+                pv.fixed_rows_mut::<3>(3).copy_from(&v);
+                (start + dt, pv)
+            })
+            .collect();
+        Ephemeris::new(nodes).unwrap()
+    }
+
+    fn max_error(full: &Ephemeris, decimated: &Ephemeris) -> f64 {
+        full.nodes
+            .iter()
+            .map(|(t, pv)| {
+                let pvi = decimated.interp(t).unwrap();
+                (pvi.fixed_rows::<3>(0) - pv.fixed_rows::<3>(0)).norm()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_interp() {
+        let ephem = kepler_ephem(consts::GEO_R, 0.0, 60.0, 3600.0);
+        let (t, pv) = ephem.nodes[10];
+        let pvi = ephem.interp(&t).unwrap();
+        assert!((pvi - pv).norm() < 1.0e-6);
+        assert!(ephem
+            .interp(&(ephem.stop() + Duration::from_seconds(1.0)))
+            .is_err());
+        assert!(Ephemeris::new(vec![ephem.nodes[1], ephem.nodes[0]]).is_err());
+    }
+
+    #[test]
+    fn test_decimate() {
+        let tol = 10.0;
+
+        // Dense GEO ephemeris: most nodes can be removed
+        let geo = kepler_ephem(consts::GEO_R, 0.0, 60.0, 86400.0);
+        let dec = geo.decimate(tol);
+        assert!(dec.len() < geo.len() / 10);
+        assert!(max_error(&geo, &dec) <= tol);
+        assert_eq!(dec.start(), geo.start());
+        assert_eq!(dec.stop(), geo.stop());
+
+        // Eccentric orbit with LEO perigee: nodes are denser near
+        // perigee than apogee
+        let a = consts::EARTH_RADIUS + 3000.0e3;
+        let e = 0.3;
+        let period = 2.0 * std::f64::consts::PI * (a.powi(3) / consts::MU_EARTH).sqrt();
+        let leo = kepler_ephem(a, e, 10.0, period);
+        let dec = leo.decimate(tol);
+        assert!(dec.len() < leo.len());
+        assert!(max_error(&leo, &dec) <= tol);
+
+        let spacing = |t: f64| -> f64 {
+            let t = leo.start() + Duration::from_seconds(t);
+            let ix = dec.nodes.partition_point(|(tn, _)| *tn <= t);
+            (dec.nodes[ix].0 - dec.nodes[ix - 1].0).as_seconds()
+        };
+        assert!(spacing(0.5 * period) > 2.0 * spacing(0.02 * period));
+    }
+
+    #[test]
+    fn test_equinoctial() -> SKResult<()> {
+        // Near-circular prograde orbit, and eccentric retrograde orbit
+        let near_circ = kepler_ephem(consts::EARTH_RADIUS + 700.0e3, 1.0e-4, 60.0, 6000.0);
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let kep = Kepler::new(2.0e7, 0.4, 2.8, 1.0, 2.0, Anomaly::Mean(0.0));
+        let retro = Ephemeris::new(
+            (0..100)
+                .map(|ix| {
+                    let dt = Duration::from_seconds(300.0 * ix as f64);
+                    let (r, v) = kep.propagate(&dt).to_pv();
+                    (start + dt, Vector6::new(r.x, r.y, r.z, v.x, v.y, v.z))
+                })
+                .collect(),
+        )?;
+
+        for (ephem, retrograde) in [(&near_circ, false), (&retro, true)] {
+            let elements = ephem.equinoctial_elements(consts::MU_EARTH, retrograde)?;
+            assert_eq!(elements.len(), ephem.len());
+            let back = Ephemeris::from_equinoctial(&elements, consts::MU_EARTH, retrograde)?;
+            for ((t0, pv0), (t1, pv1)) in ephem.nodes.iter().zip(back.nodes.iter()) {
+                assert_eq!(t0, t1);
+                assert!((pv1.fixed_rows::<3>(0) - pv0.fixed_rows::<3>(0)).norm() < 1.0e-3);
+                assert!((pv1.fixed_rows::<3>(3) - pv0.fixed_rows::<3>(3)).norm() < 1.0e-6);
+            }
+            // Two-body: all but the mean longitude are constant
+            for (_, eq) in &elements {
+                assert!((eq.rows(0, 5) - elements[0].1.rows(0, 5)).norm() < 1.0e-3);
+            }
+        }
+
+        // Retrograde factor must be chosen away from the singularity
+        let equatorial_retro = Ephemeris::new(
+            retro
+                .nodes
+                .iter()
+                .map(|(t, pv)| (*t, Vector6::new(pv[0], pv[1], 0.0, pv[3], pv[4], 0.0)))
+                .collect(),
+        )?;
+        assert!(equatorial_retro
+            .equinoctial_elements(consts::MU_EARTH, false)
+            .is_err());
+        assert!(equatorial_retro
+            .equinoctial_elements(consts::MU_EARTH, true)
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes() -> SKResult<()> {
+        // Sub-microsecond-irregular values to check exact round trip
+        let ephem = kepler_ephem(consts::EARTH_RADIUS + 700.0e3, 0.01, 60.7, 3600.0);
+        let covariance: Vec<Matrix6> = (0..ephem.len())
+            .map(|ix| {
+                let m = Matrix6::from_fn(|r, c| ((r * 6 + c + ix) as f64).sin() / 3.0);
+                m * m.transpose() + Matrix6::identity() * (1.0 + ix as f64 / 7.0)
+            })
+            .collect();
+        let ephem = ephem.with_covariance(covariance)?;
+
+        let raw = ephem.to_bytes();
+        assert_eq!(&raw[0..4], b"SKEP");
+        assert_eq!(raw.len(), 16 + ephem.len() * (8 + 48 + 288));
+        let decoded = Ephemeris::from_bytes(&raw)?;
+        assert_eq!(decoded.nodes.len(), ephem.nodes.len());
+        for ((t0, pv0), (t1, pv1)) in decoded.nodes.iter().zip(ephem.nodes.iter()) {
+            assert_eq!(t0, t1);
+            assert_eq!(pv0, pv1);
+        }
+        assert_eq!(decoded.covariance, ephem.covariance);
+
+        // Without covariance
+        let plain = Ephemeris::new(ephem.nodes.clone())?;
+        let raw_plain = plain.to_bytes();
+        assert_eq!(raw_plain.len(), 16 + plain.len() * 56);
+        let decoded = Ephemeris::from_bytes(&raw_plain)?;
+        assert!(decoded.covariance.is_none());
+        assert_eq!(decoded.nodes, plain.nodes);
+
+        // Invalid input
+        assert!(Ephemeris::from_bytes(&raw[..raw.len() - 1]).is_err());
+        assert!(Ephemeris::from_bytes(b"NOPE").is_err());
+        let mut newer = raw.clone();
+        newer[4..6].copy_from_slice(&2u16.to_le_bytes());
+        assert!(Ephemeris::from_bytes(&newer).is_err());
+        let mut badflags = raw.clone();
+        badflags[6..8].copy_from_slice(&0x0003u16.to_le_bytes());
+        assert!(Ephemeris::from_bytes(&badflags).is_err());
+
+        // Covariance count must match nodes, and decimation keeps
+        // covariance of retained nodes
+        assert!(plain
+            .clone()
+            .with_covariance(vec![Matrix6::identity()])
+            .is_err());
+        let dec = ephem.decimate(10.0);
+        let cov = dec.covariance.as_ref().unwrap();
+        assert_eq!(cov.len(), dec.len());
+        assert_eq!(cov[0], ephem.covariance.as_ref().unwrap()[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_elements_to_csv() -> SKResult<()> {
+        let read_csv = |path: &std::path::Path| -> (String, Vec<Vec<String>>) {
+            let text = std::fs::read_to_string(path).unwrap();
+            let mut lines = text.lines();
+            let header = lines.next().unwrap().to_string();
+            let rows = lines
+                .map(|l| l.split(',').map(|s| s.to_string()).collect())
+                .collect();
+            (header, rows)
+        };
+        let col = |rows: &[Vec<String>], ix: usize| -> Vec<f64> {
+            rows.iter().map(|r| r[ix].parse::<f64>().unwrap()).collect()
+        };
+
+        let a = consts::EARTH_RADIUS + 3000.0e3;
+        let ephem = kepler_ephem(a, 0.1, 60.0, 20000.0);
+        let path = std::env::temp_dir().join("satkit_test_elements.csv");
+        ephem.elements_to_csv(&path, consts::MU_EARTH, TimeScale::TT)?;
+        let (header, rows) = read_csv(&path);
+        assert!(header.starts_with("mjd_tt,a,eccen"));
+        assert_eq!(rows.len(), ephem.len());
+
+        // Two-body: semi-major axis, eccentricity & inclination are constant
+        for (ix, expected) in [(1, a), (2, 0.1), (3, 0.3_f64.to_degrees())] {
+            assert!(col(&rows, ix)
+                .iter()
+                .all(|v| (v - expected).abs() < 1.0e-6 * expected));
+        }
+        assert!(rows.iter().all(|r| r[8].is_empty()));
+
+        // Time is written in the requested scale
+        let mjd = col(&rows, 0);
+        assert!((mjd[0] - ephem.start().as_mjd_with_scale(TimeScale::TT)).abs() < 1.0e-10);
+        assert!((mjd[1] - mjd[0] - 60.0 / 86400.0).abs() < 1.0e-10);
+
+        // Ephemeris with true anomaly stepping by 0.1 radians
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let ephem_at = |eccen: f64, incl: f64| -> SKResult<Ephemeris> {
+            let nodes = (0..10)
+                .map(|ix| {
+                    let kep = Kepler::new(
+                        consts::GEO_R,
+                        eccen,
+                        incl,
+                        0.5,
+                        1.0,
+                        Anomaly::True(0.1 * ix as f64),
+                    );
+                    let (r, v) = kep.to_pv();
+                    let mut pv = Vector6::zeros();
+                    pv.fixed_rows_mut::<3>(0).copy_from(&r);
+                    pv.fixed_rows_mut::<3>(3).copy_from(&v);
+                    (start + Duration::from_seconds(ix as f64), pv)
+                })
+                .collect();
+            Ephemeris::new(nodes)
+        };
+
+        // Circular equatorial orbit: surrogate angles are written and flagged,
+        // with true anomaly measured from the x axis
+        ephem_at(0.0, 0.0)?.elements_to_csv(&path, consts::MU_EARTH, TimeScale::TAI)?;
+        let (_, rows) = read_csv(&path);
+        assert!(rows.iter().all(|r| r[8] == "circular+equatorial"));
+        let nu = col(&rows, 6);
+        for (ix, v) in nu.iter().enumerate() {
+            assert!((v - (1.5 + 0.1 * ix as f64).to_degrees()).abs() < 1.0e-6);
+        }
+
+        // Nearly circular: flagged, with anomaly measured from the ascending node
+        ephem_at(1.0e-7, 0.3)?.elements_to_csv(&path, consts::MU_EARTH, TimeScale::TAI)?;
+        let (_, rows) = read_csv(&path);
+        assert!(rows.iter().all(|r| r[8] == "circular"));
+        assert!(col(&rows, 5).iter().all(|w| *w == 0.0));
+        let nu = col(&rows, 6);
+        for (ix, v) in nu.iter().enumerate() {
+            assert!((v - (1.0 + 0.1 * ix as f64).to_degrees()).abs() < 1.0e-6);
+        }
+
+        // Nearly equatorial: flagged, with argument of perigee
+        // replaced by longitude of perigee
+        ephem_at(0.1, 1.0e-8)?.elements_to_csv(&path, consts::MU_EARTH, TimeScale::TAI)?;
+        let (_, rows) = read_csv(&path);
+        assert!(rows.iter().all(|r| r[8] == "equatorial"));
+        assert!(col(&rows, 4).iter().all(|raan| *raan == 0.0));
+        assert!(col(&rows, 5)
+            .iter()
+            .all(|w| (w - 1.5_f64.to_degrees()).abs() < 1.0e-6));
+
+        // Well-defined elements are not flagged
+        ephem_at(1.0e-4, 1.0e-4)?.elements_to_csv(&path, consts::MU_EARTH, TimeScale::TAI)?;
+        let (_, rows) = read_csv(&path);
+        assert!(rows.iter().all(|r| r[8].is_empty()));
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! User-supplied accelerations, e.g. a thruster model or an
+//! experimental drag model, added to the force model
+
+use nalgebra as na;
+
+use crate::types::{Vector3, Vector6};
+use crate::Instant;
+
+use std::sync::Arc;
+
+/// Position step, meters, for numerical partials of the acceleration
+const PARTIAL_STEP_POS: f64 = 1.0;
+
+/// Velocity step, meters / second, for numerical partials of the acceleration
+const PARTIAL_STEP_VEL: f64 = 1.0e-3;
+
+/// Signature of a user-supplied acceleration: GCRF acceleration,
+/// meters / second^2, as a function of time & GCRF position (meters)
+/// and velocity (meters / second)
+pub type ExtraAccelFn = dyn Fn(&Instant, &Vector6) -> Vector3 + Send + Sync;
+
+/// User-supplied acceleration, summed into the force model
+/// when set in [`crate::orbitprop::PropSettings`]
+///
+/// The function is shared (not copied) when the settings are cloned,
+/// and must be `Send + Sync` so that settings can be used from
+/// multiple threads, e.g. by [`crate::orbitprop::propagate_constellation`]
+///
+/// # Example
+///
+/// ```
+/// use satkit::orbitprop::{ExtraAccel, PropSettings};
+/// use satkit::types::Vector3;
+///
+/// // Constant thrust of 1 mm/s^2 along the velocity
+/// let settings = PropSettings {
+///     extra_acceleration: Some(ExtraAccel::new(|_time, pv| {
+///         1.0e-3 * Vector3::new(pv[3], pv[4], pv[5]).normalize()
+///     })),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct ExtraAccel(Arc<ExtraAccelFn>);
+
+impl ExtraAccel {
+    /// Create from a function of time & GCRF position & velocity
+    /// returning GCRF acceleration, meters / second^2
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&Instant, &Vector6) -> Vector3 + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// Acceleration in the GCRF frame, meters / second^2
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time
+    /// * `pv` - GCRF position (meters) & velocity (meters / second)
+    pub fn accel(&self, time: &Instant, pv: &Vector6) -> Vector3 {
+        (self.0)(time, pv)
+    }
+
+    /// Partials of the acceleration with respect to position & velocity,
+    /// by central difference, for the state transition matrix
+    ///
+    /// Steps are 1 meter in position & 1 mm/s in velocity
+    pub fn partials(&self, time: &Instant, pv: &Vector6) -> na::SMatrix<f64, 3, 6> {
+        let mut p = na::SMatrix::<f64, 3, 6>::zeros();
+        for ix in 0..6 {
+            let step = match ix < 3 {
+                true => PARTIAL_STEP_POS,
+                false => PARTIAL_STEP_VEL,
+            };
+            let mut pvp = *pv;
+            let mut pvm = *pv;
+            pvp[ix] += step;
+            pvm[ix] -= step;
+            p.set_column(
+                ix,
+                &((self.accel(time, &pvp) - self.accel(time, &pvm)) / (2.0 * step)),
+            );
+        }
+        p
+    }
+}
+
+impl std::fmt::Debug for ExtraAccel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ExtraAccel(<function>)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partials() {
+        // Linear in position & velocity: partials are exact
+        let m = na::SMatrix::<f64, 3, 6>::from_fn(|r, c| (r * 6 + c) as f64 * 1.0e-6 - 5.0e-6);
+        let f = ExtraAccel::new(move |_, pv| m * pv);
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let pv = Vector6::new(7.0e6, 1.0e5, -2.0e5, 10.0, 7.5e3, 100.0);
+        assert!((f.accel(&time, &pv) - m * pv).norm() < 1.0e-12);
+        assert!((f.partials(&time, &pv) - m).abs().max() < 1.0e-9);
+
+        // Shared, not copied, when cloned
+        let g = f.clone();
+        assert!(Arc::ptr_eq(&f.0, &g.0));
+        assert_eq!(format!("{:?}", g), "ExtraAccel(<function>)");
+    }
+}
@@ -0,0 +1,145 @@
+//! Geostationary longitude, drift rate & station-keeping box
+
+use super::SatState;
+
+use crate::angles::wrap_to_pi;
+use crate::consts;
+use crate::frametransform;
+use crate::kepler::Kepler;
+use crate::{Instant, SKResult};
+
+/// Number of samples over one sidereal day used to check
+/// the station-keeping box
+const SK_BOX_SAMPLES: usize = 144;
+
+/// Sidereal day, seconds
+const SIDEREAL_DAY: f64 = 2.0 * std::f64::consts::PI / consts::OMEGA_EARTH;
+
+/// Sub-satellite longitude at the given time
+///
+/// # Arguments
+///
+/// * `state` - Satellite state
+/// * `time` - Time at which to compute longitude
+///
+/// # Returns
+///
+/// * East longitude, radians, in [-π, π)
+///
+/// # Notes
+///
+/// * The state is propagated from its epoch to `time` with two-body
+///   (Keplerian) motion, which is adequate over a few days for GEO
+/// * For an inclined near-geostationary orbit, the ground track is a
+///   figure eight and the longitude oscillates over a day about its mean
+pub fn geo_longitude(state: &SatState, time: &Instant) -> SKResult<f64> {
+    let kep = Kepler::from_pv(state.pos_gcrf(), state.vel_gcrf())?;
+    let (pos, _) = kep.propagate(&(*time - state.time)).to_pv();
+    let pitrf = frametransform::qgcrf2itrf(time) * pos;
+    Ok(pitrf[1].atan2(pitrf[0]))
+}
+
+/// Mean longitude drift rate of a near-geostationary satellite
+///
+/// # Arguments
+///
+/// * `state` - Satellite state
+///
+/// # Returns
+///
+/// * Drift rate, radians / second, positive eastward
+///
+/// # Notes
+///
+/// * Drift rate is the difference between the Keplerian mean motion
+///   and the Earth rotation rate, so it describes the mean drift and not
+///   the daily longitude oscillation of inclined or eccentric orbits
+pub fn geo_drift_rate(state: &SatState) -> SKResult<f64> {
+    let kep = Kepler::from_pv(state.pos_gcrf(), state.vel_gcrf())?;
+    Ok(kep.mean_motion() - consts::OMEGA_EARTH)
+}
+
+/// Check if a satellite remains within a longitude station-keeping box
+///
+/// # Arguments
+///
+/// * `state` - Satellite state
+/// * `center_lon` - East longitude of box center, radians
+/// * `half_width` - Half-width of box in longitude, radians
+///
+/// # Returns
+///
+/// * True if the satellite longitude stays within the box over one
+///   sidereal day from the state epoch
+///
+/// # Notes
+///
+/// * Longitude is sampled over a full day, rather than checked only at
+///   the state epoch, so that the daily oscillation of inclined orbits
+///   (figure-eight ground track) is accounted for
+pub fn within_sk_box(state: &SatState, center_lon: f64, half_width: f64) -> SKResult<bool> {
+    for ix in 0..SK_BOX_SAMPLES {
+        let dt = SIDEREAL_DAY * ix as f64 / SK_BOX_SAMPLES as f64;
+        let time = state.time + crate::Duration::from_seconds(dt);
+        let lon = geo_longitude(state, &time)?;
+        if wrap_to_pi(lon - center_lon).abs() > half_width {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Vector3;
+    use crate::Duration;
+
+    /// Circular equatorial orbit at radius r over longitude lon at time
+    fn equatorial_state(time: &Instant, r: f64, lon: f64, incl: f64) -> SatState {
+        let q = frametransform::qitrf2gcrf(time);
+        let pos = q * Vector3::new(lon.cos(), lon.sin(), 0.0) * r;
+        let vdir = q * Vector3::new(-lon.sin(), lon.cos(), 0.0);
+        let h = pos.cross(&vdir).normalize();
+        // Tilt velocity out of the equatorial plane to incline the orbit
+        let vdir = vdir * incl.cos() + h * incl.sin();
+        SatState::from_pv(time, &pos, &(vdir * (consts::MU_EARTH / r).sqrt()))
+    }
+
+    #[test]
+    fn test_geo_longitude() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let lon0 = (-75.0_f64).to_radians();
+
+        // Geostationary: longitude is constant & there is no drift
+        let state = equatorial_state(&time, consts::GEO_R, lon0, 0.0);
+        assert!(geo_drift_rate(&state)?.abs() < 1.0e-10);
+        for hours in [0.0, 6.0, 12.0, 24.0, 72.0] {
+            let lon = geo_longitude(&state, &(time + Duration::from_hours(hours)))?;
+            assert!(wrap_to_pi(lon - lon0).abs() < 1.0e-4);
+        }
+        assert!(within_sk_box(&state, lon0, 0.05_f64.to_radians())?);
+
+        // Slightly below GEO: faster than Earth rotation, so drifts east
+        // at rate -3/2 n da/a
+        let da = -10.0e3;
+        let state = equatorial_state(&time, consts::GEO_R + da, lon0, 0.0);
+        let expected = -1.5 * consts::OMEGA_EARTH * da / consts::GEO_R;
+        let rate = geo_drift_rate(&state)?;
+        assert!((rate - expected).abs() < 1.0e-3 * expected.abs());
+
+        let dt = 5.0 * 86400.0;
+        let lon = geo_longitude(&state, &(time + Duration::from_seconds(dt)))?;
+        assert!((wrap_to_pi(lon - lon0) - expected * dt).abs() < 1.0e-3);
+        assert!(!within_sk_box(&state, lon0, 0.05_f64.to_radians())?);
+
+        // Inclined geosynchronous: longitude oscillates (figure eight),
+        // with no mean drift, so it leaves a box that is too tight
+        let incl = 5.0_f64.to_radians();
+        let state = equatorial_state(&time, consts::GEO_R, lon0, incl);
+        assert!(geo_drift_rate(&state)?.abs() < 1.0e-10);
+        assert!(within_sk_box(&state, lon0, 0.2_f64.to_radians())?);
+        assert!(!within_sk_box(&state, lon0, 0.05_f64.to_radians())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+//! Motion of the subsatellite point over the Earth's surface
+
+use super::SatState;
+
+use crate::consts;
+use crate::frametransform::{pv_gcrf2itrf, qgcrf2itrf_approx};
+use crate::{ITRFCoord, Instant};
+
+/// Speed of the subsatellite (geodetic nadir) point over the ground,
+/// meters / second
+///
+/// The satellite velocity relative to the rotating Earth is split into
+/// local east & north components, and each is scaled from the satellite
+/// height down to the WGS-84 ellipsoid by the ratio of the prime-vertical
+/// (east) and meridional (north) radii of curvature.  The result is the
+/// rate at which the ground track is traced out, accounting for Earth
+/// rotation: slower than the orbital velocity projected to the surface for
+/// prograde orbits, faster for retrograde
+///
+/// # Arguments
+///
+/// * `state` - Satellite state
+/// * `time` - Time at which to evaluate the state's GCRF to ITRF rotation,
+///   normally the state time
+///
+/// # Returns
+///
+/// * Ground speed of the subsatellite point, meters / second
+///
+/// # Notes
+///
+/// * Uses the approximate GCRF to ITRF rotation, which neglects polar
+///   motion; the effect on speed is far below a mm/s
+/// * Well defined everywhere, including at the ground track's turning
+///   points (maximum latitude), where the north component vanishes, and
+///   over the poles, where the east & north radii of curvature are equal
+/// * Vertical velocity does not move the ground point & is ignored
+pub fn ground_track_speed(state: &SatState, time: &Instant) -> f64 {
+    let (pos, vel) = pv_gcrf2itrf(&qgcrf2itrf_approx(time), &state.pv);
+    let coord = ITRFCoord::from(pos);
+    let (lat, _, hae) = coord.to_geodetic_rad();
+    let venu = coord.q_enu2itrf().conjugate() * vel;
+
+    // Radii of curvature of the ellipsoid at the geodetic latitude
+    let e2 = consts::WGS84_F * (2.0 - consts::WGS84_F);
+    let w2 = 1.0 - e2 * lat.sin().powi(2);
+    let rn = consts::WGS84_A / w2.sqrt();
+    let rm = rn * (1.0 - e2) / w2;
+    (venu[0] * rn / (rn + hae)).hypot(venu[1] * rm / (rm + hae))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orbitprop::propagate_twobody;
+    use crate::types::Vector3;
+    use crate::Duration;
+
+    /// Circular orbit state at the given altitude, inclination &
+    /// argument of latitude, ascending node along GCRF x
+    fn circular(time: &Instant, alt: f64, incl: f64, arglat: f64) -> SatState {
+        let r = consts::WGS84_A + alt;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let (su, cu) = arglat.to_radians().sin_cos();
+        let (si, ci) = incl.to_radians().sin_cos();
+        let pos = Vector3::new(cu, su * ci, su * si) * r;
+        let vel = Vector3::new(-su, cu * ci, cu * si) * v;
+        SatState::from_pv(time, &pos, &vel)
+    }
+
+    /// Subsatellite point, ITRF, meters, & geodetic latitude, radians
+    fn ground_point(state: &SatState) -> (Vector3, f64) {
+        let pos = qgcrf2itrf_approx(&state.time) * state.pos_gcrf();
+        let (lat, lon, _) = ITRFCoord::from(pos).to_geodetic_rad();
+        (ITRFCoord::from_geodetic_rad(lat, lon, 0.0).itrf, lat)
+    }
+
+    /// Ground speed & latitude rate by central difference of the
+    /// subsatellite point along the two-body trajectory
+    fn numerical(state: &SatState) -> (f64, f64) {
+        let dt = 0.5;
+        let at = |s: f64| {
+            let mut st = state.clone();
+            st.time = state.time + Duration::from_seconds(s);
+            st.pv = propagate_twobody(&state.pv, consts::MU_EARTH, s).unwrap();
+            ground_point(&st)
+        };
+        let ((gp, latp), (gm, latm)) = (at(dt), at(-dt));
+        ((gp - gm).norm() / (2.0 * dt), (latp - latm) / (2.0 * dt))
+    }
+
+    #[test]
+    fn test_ground_track_speed() {
+        let time = Instant::from_datetime(2024, 3, 1, 6, 0, 0.0);
+        let alt = 500.0e3;
+        let vsurf = (consts::MU_EARTH / (consts::WGS84_A + alt)).sqrt() * consts::WGS84_A
+            / (consts::WGS84_A + alt);
+
+        for (incl, arglat) in [(51.6, 0.0), (51.6, 35.0), (97.4, 200.0), (28.5, 300.0)] {
+            let state = circular(&time, alt, incl, arglat);
+            let speed = ground_track_speed(&state, &time);
+            // Orbital velocity scaled to the surface, within the
+            // equatorial surface speed of the Earth's rotation
+            assert!((speed - vsurf).abs() < 500.0);
+            let (ns, _) = numerical(&state);
+            assert!((speed - ns).abs() < 0.05);
+        }
+
+        // Prograde ground track is slower than retrograde
+        let pro = ground_track_speed(&circular(&time, alt, 30.0, 0.0), &time);
+        let retro = ground_track_speed(&circular(&time, alt, 150.0, 0.0), &time);
+        assert!(pro < vsurf && retro > vsurf);
+
+        // Turning point: speed is almost all east-west (the GCRF orbit
+        // is tilted slightly against the ITRF pole by precession, so the
+        // latitude rate is small rather than zero)
+        let state = circular(&time, alt, 51.6, 90.0);
+        let (ns, latrate) = numerical(&state);
+        assert!(latrate.abs() * consts::WGS84_A < 0.01 * ns);
+        assert!((ground_track_speed(&state, &time) - ns).abs() < 0.05);
+
+        // Polar orbit over the pole
+        let state = circular(&time, alt, 90.0, 90.0);
+        let (ns, _) = numerical(&state);
+        assert!((ground_track_speed(&state, &time) - ns).abs() < 0.05);
+    }
+}
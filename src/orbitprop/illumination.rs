@@ -0,0 +1,555 @@
+//! Satellite illumination (sunlit / eclipse) time series
+
+use super::propagator::propagate;
+use super::search::find_roots;
+use super::{PropSettings, SatState};
+
+use nalgebra as na;
+
+use crate::consts;
+use crate::frametransform::qgcrf2itrf_approx;
+use crate::kepler::Kepler;
+use crate::lpephem::sun::{pos_gcrf as sun_pos_gcrf, shadowfunc};
+use crate::skerror;
+use crate::types::{Quaternion, Vector3};
+use crate::{Duration, ITRFCoord, Instant, SKResult};
+
+/// Satellite is considered sunlit if more than half of the
+/// sunlight reaches it, i.e., it is not in umbra and is in the
+/// outer half of the penumbra
+const SUNLIT_THRESHOLD: f64 = 0.5;
+
+/// Maximum interval, in seconds, at which illumination is sampled to
+/// find eclipse entry & exit, independent of the output step size.
+/// Eclipses shorter than this (grazing passes through the shadow) may
+/// be missed
+const MAX_SCAN_STEP: f64 = 30.0;
+
+/// Precision, in seconds, to which eclipse entry & exit times are found
+const CROSSING_TOL: f64 = 1.0e-3;
+
+/// Illumination of a satellite over a time window
+#[derive(Debug, Clone)]
+pub struct SunlitSeries {
+    /// Time series of (time, sunlit) at the requested step size
+    pub series: Vec<(Instant, bool)>,
+    /// Fraction of the time window in which the satellite is sunlit
+    ///
+    /// This is computed from eclipse entry & exit times, not from
+    /// the sampled series, so is not affected by the step size
+    pub sunlit_fraction: f64,
+}
+
+/// Time (seconds) within start to stop for which sunlit() is true,
+/// from crossings located by scanning at `scan_step` and bisection
+fn sunlit_duration<F>(sunlit: &F, start: &Instant, stop: &Instant, scan_step: f64) -> SKResult<f64>
+where
+    F: Fn(&Instant) -> SKResult<bool>,
+{
+    let total = (stop - start).as_seconds();
+    let lit = |t: f64| -> SKResult<f64> {
+        Ok(match sunlit(&(*start + Duration::from_seconds(t)))? {
+            true => 1.0,
+            false => -1.0,
+        })
+    };
+
+    // Crossings alternate between eclipse exit (rising) & entry
+    let mut duration = 0.0;
+    let (mut t0, mut lit0) = (0.0, sunlit(start)?);
+    for root in find_roots(&lit, total, scan_step, CROSSING_TOL)? {
+        if lit0 {
+            duration += root.t - t0;
+        }
+        (t0, lit0) = (root.t, root.rising);
+    }
+    if lit0 {
+        duration += total - t0;
+    }
+    Ok(duration)
+}
+
+/// Satellite illumination time series, for e.g. power analysis
+///
+/// The satellite state is propagated with the high-precision propagator
+/// over the time window, and the Earth shadow model of
+/// [`crate::lpephem::sun::shadowfunc`] is used to determine if the
+/// satellite is sunlit
+///
+/// # Arguments
+///
+/// * `state` - Satellite state.  Covariance, if set, is ignored
+/// * `start` - Start of time window
+/// * `stop` - End of time window
+/// * `step` - Step size of output time series
+/// * `settings` - Propagation settings.  Interpolation is always enabled
+///
+/// # Returns
+///
+/// * [`SunlitSeries`] with the time series and the fraction of the
+///   window in which the satellite is sunlit
+///
+/// # Notes
+///
+/// * The output series is sampled at `step`, so eclipses shorter than
+///   the step size may not appear in it.  The sunlit fraction is computed
+///   independently, from eclipse entry & exit times found by sampling at
+///   no more than 30 seconds, so is not affected by the step size
+pub fn sunlit_series(
+    state: &SatState,
+    start: &Instant,
+    stop: &Instant,
+    step: &Duration,
+    settings: Option<&PropSettings>,
+) -> SKResult<SunlitSeries> {
+    if stop <= start {
+        return skerror!("Stop time must be after start time");
+    }
+    if step.as_seconds() <= 0.0 {
+        return skerror!("Step size must be positive");
+    }
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    settings.enable_interp = true;
+
+    // Propagate to start of window (without covariance), then over window
+    let state0 = SatState::from_pv(&state.time, &state.pos_gcrf(), &state.vel_gcrf());
+    let state0 = match state0.time == *start {
+        true => state0,
+        false => state0.propagate(start, Some(&settings))?,
+    };
+    let res = propagate(&state0.pv, start, stop, &settings, None)?;
+
+    let sunlit = |t: &Instant| -> SKResult<bool> {
+        let pv = res.interp(t)?;
+        let pos = pv.fixed_view::<3, 1>(0, 0).into();
+        Ok(shadowfunc(&sun_pos_gcrf(t), &pos) > SUNLIT_THRESHOLD)
+    };
+
+    let nsteps = ((stop - start).as_seconds() / step.as_seconds()).floor() as usize;
+    let series = (0..=nsteps)
+        .map(|ix| {
+            let t = *start + Duration::from_seconds(step.as_seconds() * ix as f64);
+            Ok((t, sunlit(&t)?))
+        })
+        .collect::<SKResult<Vec<(Instant, bool)>>>()?;
+
+    let scan_step = step.as_seconds().min(MAX_SCAN_STEP);
+    let sunlit_fraction =
+        sunlit_duration(&sunlit, start, stop, scan_step)? / (stop - start).as_seconds();
+
+    Ok(SunlitSeries {
+        series,
+        sunlit_fraction,
+    })
+}
+
+/// Sun direction in the satellite body frame, for e.g. thermal
+/// and solar-array modeling
+///
+/// # Arguments
+///
+/// * `state` - Satellite state, used for satellite position
+/// * `q_gcrf2body` - Attitude quaternion, rotating vectors from the GCRF
+///   frame into the satellite body frame
+/// * `time` - Time at which to compute the sun position
+///
+/// # Returns
+///
+/// * Tuple with:
+///   * Unit vector from satellite to sun, in the body frame
+///   * True if the satellite is sunlit, false if in eclipse.  The sun
+///     vector is defined either way
+///
+/// # Notes
+///
+/// * Sun position is from the low-precision ephemeris,
+///   [`crate::lpephem::sun::pos_gcrf`]
+pub fn sun_in_body(state: &SatState, q_gcrf2body: &Quaternion, time: &Instant) -> (Vector3, bool) {
+    let sun = sun_pos_gcrf(time);
+    let pos = state.pos_gcrf();
+    let sunlit = shadowfunc(&sun, &pos) > SUNLIT_THRESHOLD;
+    (q_gcrf2body * (sun - pos).normalize(), sunlit)
+}
+
+/// Elevation of the sun at the subsatellite point, i.e. on the ground
+/// directly beneath the satellite, for e.g. whether the ground imaged
+/// by a payload is in daylight
+///
+/// # Arguments
+///
+/// * `state` - Satellite state, used for satellite position
+/// * `time` - Time at which to compute the sun position
+///
+/// # Returns
+///
+/// * Geometric elevation of the sun above the local horizon at the
+///   subsatellite point, radians.  Positive in daylight, negative at
+///   night, and zero at the terminator
+///
+/// # Notes
+///
+/// * The subsatellite point is at the satellite's geodetic latitude
+///   & longitude, on the surface of the ellipsoid
+/// * Sun position is from the low-precision ephemeris,
+///   [`crate::lpephem::sun::pos_gcrf`], rotated to the Earth-fixed
+///   frame with [`crate::frametransform::qgcrf2itrf_approx`]
+/// * Refraction & the angular radius of the sun are not included, so
+///   the ground is lit for slightly negative elevations
+pub fn subsatellite_sun_elevation(state: &SatState, time: &Instant) -> f64 {
+    let q = qgcrf2itrf_approx(time);
+    let (lat, lon, _) = ITRFCoord::from(q * state.pos_gcrf()).to_geodetic_rad();
+    let ground = ITRFCoord::from_geodetic_rad(lat, lon, 0.0);
+    let sun = ITRFCoord::from(q * sun_pos_gcrf(time));
+    ground.az_el_range(&sun).1
+}
+
+/// Coefficients, highest power first, of the quartic in cos(ν) whose
+/// real roots include the crossings of an orbit with the cylindrical
+/// shadow boundary
+///
+/// The boundary condition a + b cos(ν) + c cos²(ν) = d cos(ν) sin(ν) is
+/// squared to eliminate sin(ν), so the quartic also has spurious roots
+fn shadow_quartic(rho2: f64, e: f64, b1: f64, b2: f64) -> [f64; 5] {
+    let a = 1.0 - b2 * b2 - rho2;
+    let b = -2.0 * rho2 * e;
+    let c = b2 * b2 - b1 * b1 - rho2 * e * e;
+    let d = 2.0 * b1 * b2;
+    [
+        c * c + d * d,
+        2.0 * b * c,
+        b * b + 2.0 * a * c - d * d,
+        2.0 * a * b,
+        a * a,
+    ]
+}
+
+/// Real roots of the quartic with the given coefficients (highest
+/// power first)
+///
+/// All four complex roots are found with Durand-Kerner iteration, which,
+/// unlike QR iteration on the companion matrix, converges for the
+/// biquadratic quartics of circular orbits
+fn quartic_real_roots(coef: &[f64; 5]) -> Vec<f64> {
+    let one = na::Complex::new(1.0, 0.0);
+    let poly = |z: na::Complex<f64>| coef[1..].iter().fold(one, |acc, c| acc * z + c / coef[0]);
+
+    // Initial guesses spread around a circle enclosing all the roots
+    let bound = 1.0
+        + coef[1..]
+            .iter()
+            .map(|c| (c / coef[0]).abs())
+            .fold(0.0, f64::max);
+    let mut roots: [na::Complex<f64>; 4] =
+        std::array::from_fn(|ix| na::Complex::new(0.4, 0.9).powu(ix as u32) * bound);
+    for _ in 0..500 {
+        let mut maxstep: f64 = 0.0;
+        for ix in 0..4 {
+            let denom = (0..4)
+                .filter(|jx| *jx != ix)
+                .fold(one, |acc, jx| acc * (roots[ix] - roots[jx]));
+            let step = poly(roots[ix]) / denom;
+            roots[ix] -= step;
+            maxstep = maxstep.max(step.norm());
+        }
+        if maxstep < 1.0e-14 {
+            break;
+        }
+    }
+
+    // Double roots, which occur for circular orbits, converge with
+    // imaginary parts of order sqrt(epsilon)
+    roots
+        .iter()
+        .filter(|root| root.im.abs() <= 1.0e-6)
+        .map(|root| root.re)
+        .collect()
+}
+
+/// True anomalies at which an orbit enters & exits the Earth shadow,
+/// computed analytically for a cylindrical shadow model
+///
+/// # Arguments
+///
+/// * `elements` - Keplerian orbital elements, in the GCRF frame
+/// * `sun_dir_gcrf` - Direction from Earth to sun, GCRF frame.
+///   Need not be normalized
+///
+/// # Returns
+///
+/// * Tuple of (entry, exit) true anomaly, radians, in [0, 2π), or `None`
+///   if the orbit is fully sunlit.  The eclipse spans the true anomalies
+///   from entry increasing to exit, wrapping through 2π if exit < entry
+///
+/// # Notes
+///
+/// * The shadow is a cylinder of radius [`crate::consts::EARTH_RADIUS`]
+///   extending anti-sunward, with no penumbra, so entry & exit are
+///   slightly different from those of [`crate::lpephem::sun::shadowfunc`]
+/// * Crossings are found as roots of a quartic in cos(ν) (Escobal's
+///   shadow equation), keeping only the roots that satisfy the unsquared
+///   equation & lie on the anti-sun side of the Earth
+/// * An orbit that only grazes the shadow, touching the boundary without
+///   crossing it, is considered fully sunlit
+/// * The sun direction is fixed, so over long spans the result should be
+///   recomputed as the sun & orbit move
+/// * Only closed (elliptical) orbits are supported; `None` is returned
+///   for eccentricity of 1 or greater
+pub fn shadow_anomalies(elements: &Kepler, sun_dir_gcrf: &Vector3) -> Option<(f64, f64)> {
+    use std::f64::consts::PI;
+
+    let e = elements.eccen;
+    if !(0.0..1.0).contains(&e) {
+        return None;
+    }
+    let sunhat = sun_dir_gcrf.normalize();
+    let q = Quaternion::from_axis_angle(&Vector3::z_axis(), elements.raan)
+        * Quaternion::from_axis_angle(&Vector3::x_axis(), elements.incl)
+        * Quaternion::from_axis_angle(&Vector3::z_axis(), elements.w);
+    // Sun direction components along perifocal P (periapsis) & Q axes
+    let b1 = (q * Vector3::x()).dot(&sunhat);
+    let b2 = (q * Vector3::y()).dot(&sunhat);
+    // Sun along the orbit normal: orbit never passes behind the Earth
+    if b1.hypot(b2) < 1.0e-12 {
+        return None;
+    }
+
+    let p = elements.a * e.mul_add(-e, 1.0);
+    let rho2 = (consts::EARTH_RADIUS / p).powi(2);
+    // Shadow function, normalized by p²: negative inside the shadow
+    // cylinder, and its derivative with respect to true anomaly
+    let g = |nu: f64| {
+        let (s, c) = nu.sin_cos();
+        1.0 - (b1 * c + b2 * s).powi(2) - rho2 * e.mul_add(c, 1.0).powi(2)
+    };
+    let dg = |nu: f64| {
+        let (s, c) = nu.sin_cos();
+        2.0 * rho2 * e * s * e.mul_add(c, 1.0) - 2.0 * (b1 * c + b2 * s) * (b2 * c - b1 * s)
+    };
+
+    let mut entry: Option<f64> = None;
+    let mut exit: Option<f64> = None;
+    for cnu in quartic_real_roots(&shadow_quartic(rho2, e, b1, b2)) {
+        if cnu.abs() > 1.0 + 1.0e-6 {
+            continue;
+        }
+        let cnu = cnu.clamp(-1.0, 1.0);
+        let snu = (1.0 - cnu * cnu).sqrt();
+        for snu in [snu, -snu] {
+            // Polish with Newton iterations on the unsquared shadow
+            // function, which has simple roots at crossings
+            let mut nu = snu.atan2(cnu);
+            for _ in 0..10 {
+                let slope = dg(nu);
+                if slope == 0.0 {
+                    break;
+                }
+                nu -= g(nu) / slope;
+            }
+            let nu = nu.rem_euclid(2.0 * PI);
+            let slope = dg(nu);
+            // Spurious root of the squared equation, crossing on the
+            // sunward side of the Earth, or tangent to the shadow
+            // boundary (grazing, not a crossing)
+            if g(nu).abs() > 1.0e-10 || b1 * nu.cos() + b2 * nu.sin() >= 0.0 || slope.abs() < 1.0e-9
+            {
+                continue;
+            }
+            match slope < 0.0 {
+                true => entry = Some(nu),
+                false => exit = Some(nu),
+            }
+        }
+    }
+    match (entry, exit) {
+        (Some(nu_in), Some(nu_out)) => Some((nu_in, nu_out)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+
+    #[test]
+    fn test_sunlit_duration() -> SKResult<()> {
+        // 100-second eclipse every 1000 seconds, starting at 450 seconds
+        let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let stop = start + Duration::from_seconds(10000.0);
+        let sunlit = |t: &Instant| -> SKResult<bool> {
+            let s = (*t - start).as_seconds() % 1000.0;
+            Ok(!(450.0..550.0).contains(&s))
+        };
+        let d = sunlit_duration(&sunlit, &start, &stop, MAX_SCAN_STEP)?;
+        assert!((d - 9000.0).abs() < 0.1);
+
+        // Scan step coarser than the eclipse can miss it entirely
+        let d = sunlit_duration(&sunlit, &start, &stop, 1000.0)?;
+        assert!((d - 10000.0).abs() < 0.1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sun_in_body() {
+        let time = Instant::from_datetime(2024, 6, 1, 12, 0, 0.0);
+        let r = consts::EARTH_RADIUS + 600.0e3;
+        let sunhat = sun_pos_gcrf(&time).normalize();
+        let perp = sunhat.cross(&Vector3::z()).normalize();
+        let vel = perp * (consts::MU_EARTH / r).sqrt();
+
+        // Nadir-pointing satellite (body axes aligned with LVLH, z = nadir)
+        // at local noon: sun is along body -z
+        let state = SatState::from_pv(&time, &(sunhat * r), &vel);
+        let (sun, sunlit) = sun_in_body(&state, &state.qgcrf2lvlh(), &time);
+        assert!((sun + Vector3::z()).norm() < 1.0e-6);
+        assert!(sunlit);
+
+        // At local midnight, the satellite is in eclipse and the
+        // sun is along body +z, behind the Earth
+        let state = SatState::from_pv(&time, &(-sunhat * r), &vel);
+        let (sun, sunlit) = sun_in_body(&state, &state.qgcrf2lvlh(), &time);
+        assert!((sun - Vector3::z()).norm() < 1.0e-6);
+        assert!(!sunlit);
+    }
+
+    #[test]
+    fn test_subsatellite_sun_elevation() {
+        let time = Instant::from_datetime(2024, 6, 1, 12, 0, 0.0);
+        let r = consts::EARTH_RADIUS + 600.0e3;
+        let sunhat = sun_pos_gcrf(&time).normalize();
+        let perp = sunhat.cross(&Vector3::z()).normalize();
+        let vel = perp * (consts::MU_EARTH / r).sqrt();
+        let elevation = |pos: Vector3| {
+            subsatellite_sun_elevation(&SatState::from_pv(&time, &pos, &vel), &time).to_degrees()
+        };
+
+        // Over the subsolar point, the sun is overhead to within the
+        // difference between geodetic & geocentric vertical
+        assert!(elevation(sunhat * r) > 89.8);
+        // Over the anti-solar point
+        assert!(elevation(-sunhat * r) < -89.8);
+        // Crossing the terminator, elevation changes sign
+        let tilt = |deg: f64| {
+            let a = deg.to_radians();
+            (perp * a.cos() + sunhat * a.sin()) * r
+        };
+        assert!(elevation(tilt(-0.5)) < 0.0);
+        assert!(elevation(tilt(0.5)) > 0.0);
+        assert!(elevation(tilt(0.0)).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_sunlit_fraction() -> SKResult<()> {
+        // Circular orbit whose plane contains the sun direction,
+        // so beta angle is zero
+        let start = Instant::from_datetime(2024, 3, 20, 0, 0, 0.0);
+        let stop = start + Duration::from_days(1.0);
+        let r = consts::EARTH_RADIUS + 600.0e3;
+        let sunhat = sun_pos_gcrf(&start).normalize();
+        let perp = sunhat.cross(&Vector3::z()).normalize();
+        let state = SatState::from_pv(
+            &start,
+            &(sunhat * r),
+            &(perp * (consts::MU_EARTH / r).sqrt()),
+        );
+
+        // Two-body motion, so the orbit does not precess
+        let settings = PropSettings {
+            gravity_order: 1,
+            ..Default::default()
+        };
+        let res = sunlit_series(
+            &state,
+            &start,
+            &stop,
+            &Duration::from_minutes(10.0),
+            Some(&settings),
+        )?;
+        assert_eq!(res.series.len(), 145);
+        assert!(res.series.iter().any(|(_, lit)| *lit));
+        assert!(res.series.iter().any(|(_, lit)| !*lit));
+
+        // Expected eclipse fraction from cylindrical shadow geometry
+        // for zero beta angle: half-angle of eclipse is asin(Re / r)
+        let expected = 1.0 - (consts::EARTH_RADIUS / r).asin() / std::f64::consts::PI;
+        assert!((res.sunlit_fraction - expected).abs() < 0.005);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shadow_anomalies() {
+        use crate::kepler::Anomaly;
+        use std::f64::consts::PI;
+
+        let sunhat = Vector3::new(0.3, -0.9, 0.2).normalize();
+        let in_shadow = |pos: &Vector3| {
+            let along = pos.dot(&sunhat);
+            along < 0.0 && (pos - sunhat * along).norm() < consts::EARTH_RADIUS
+        };
+        let angle_diff = |a: f64, b: f64| crate::angles::wrap_to_pi(a - b).abs();
+
+        for (a, e, incl) in [
+            (consts::EARTH_RADIUS + 600.0e3, 0.0, 51.6_f64),
+            (consts::EARTH_RADIUS + 800.0e3, 0.1, 60.0),
+            (consts::EARTH_RADIUS + 15000.0e3, 0.6, 20.0),
+        ] {
+            let kep = Kepler::new(a, e, incl.to_radians(), 0.5, 1.0, Anomaly::True(0.0));
+            let (nu_in, nu_out) = shadow_anomalies(&kep, &sunhat).unwrap();
+
+            // Event finding on the orbit propagated from periapsis: scan one period,
+            // then bisect for shadow entry & exit times
+            let period = kep.period();
+            let at = |t: f64| {
+                let ma = (kep.mean_motion() * t).rem_euclid(2.0 * PI);
+                Kepler::new(a, e, incl.to_radians(), 0.5, 1.0, Anomaly::Mean(ma))
+            };
+            let inside = |t: f64| in_shadow(&at(t).to_pv().0);
+            let nscan = 2000;
+            let dt = period / nscan as f64;
+            let (mut found_in, mut found_out) = (None, None);
+            for ix in 0..nscan {
+                let (mut lo, mut hi) = (ix as f64 * dt, (ix + 1) as f64 * dt);
+                let s0 = inside(lo);
+                if s0 == inside(hi) {
+                    continue;
+                }
+                while hi - lo > 1.0e-3 {
+                    let mid = 0.5 * (lo + hi);
+                    match inside(mid) == s0 {
+                        true => lo = mid,
+                        false => hi = mid,
+                    }
+                }
+                let nu = at(lo).nu;
+                match s0 {
+                    false => found_in = Some(nu),
+                    true => found_out = Some(nu),
+                }
+            }
+            assert!(angle_diff(nu_in, found_in.unwrap()) < 1.0_f64.to_radians());
+            assert!(angle_diff(nu_out, found_out.unwrap()) < 1.0_f64.to_radians());
+        }
+
+        // Sun along the orbit normal, and a high orbit well out of the
+        // orbit plane: fully sunlit
+        let kep = Kepler::new(consts::GEO_R, 0.0, 0.0, 0.0, 0.0, Anomaly::True(0.0));
+        assert!(shadow_anomalies(&kep, &Vector3::z()).is_none());
+        let sun = Vector3::new(
+            30.0_f64.to_radians().cos(),
+            0.0,
+            30.0_f64.to_radians().sin(),
+        );
+        assert!(shadow_anomalies(&kep, &sun).is_none());
+
+        // Sun just out of the plane by the angle at which the orbit
+        // grazes the shadow: fully sunlit slightly above that angle,
+        // & a short eclipse slightly below
+        let beta = (consts::EARTH_RADIUS / consts::GEO_R).asin();
+        let sun_at = |beta: f64| Vector3::new(beta.cos(), 0.0, beta.sin());
+        assert!(shadow_anomalies(&kep, &sun_at(beta * 1.001)).is_none());
+        let sun = sun_at(beta * 0.999);
+        let (nu_in, nu_out) = shadow_anomalies(&kep, &sun).unwrap();
+        assert!(nu_in < PI && nu_out > PI && nu_out - nu_in < 0.1);
+    }
+}
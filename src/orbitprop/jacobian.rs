@@ -0,0 +1,591 @@
+//! Jacobians of simple orbital dynamics, for setting up analytic
+//! variational equations, and the analytic two-body state transition
+//! matrix
+
+use nalgebra as na;
+
+use crate::kepler::stumpff;
+use crate::skerror;
+use crate::types::{Matrix3, Matrix6, Vector3, Vector6};
+use crate::SKResult;
+
+use std::f64::consts::PI;
+
+/// Maximum iterations solving Kepler's equation for the universal anomaly
+const MAX_ITER: usize = 200;
+
+/// Assemble the 6x6 Jacobian from the partial of acceleration
+/// with respect to position
+///
+/// Position derivative is velocity, so the upper-right block is
+/// identity; acceleration does not depend on velocity, so the
+/// lower-right block is zero
+fn jacobian_from_dadr(dadr: &Matrix3) -> Matrix6 {
+    let mut a = Matrix6::zeros();
+    a.fixed_view_mut::<3, 3>(0, 3)
+        .copy_from(&Matrix3::identity());
+    a.fixed_view_mut::<3, 3>(3, 0).copy_from(dadr);
+    a
+}
+
+/// Partial of two-body acceleration with respect to position
+fn twobody_dadr(pos: &Vector3, mu: f64) -> Matrix3 {
+    let r = pos.norm();
+    let rhat = pos / r;
+    -mu / r.powi(3) * (Matrix3::identity() - 3.0 * rhat * rhat.transpose())
+}
+
+/// Partial of J2 acceleration with respect to position
+///
+/// The J2 acceleration is a_i = -k (c_i r_i / r^5 - 5 r_i z^2 / r^7),
+/// with k = 1.5 J2 mu Re^2 and c = (1, 1, 3)
+fn j2_dadr(pos: &Vector3, mu: f64, j2: f64, re: f64) -> Matrix3 {
+    let k = 1.5 * j2 * mu * re * re;
+    let r2 = pos.norm_squared();
+    let r = r2.sqrt();
+    let z = pos[2];
+    let z2 = z * z;
+    let c = [1.0, 1.0, 3.0];
+    Matrix3::from_fn(|i, j| {
+        let dij = if i == j { 1.0 } else { 0.0 };
+        let dj3 = if j == 2 { 1.0 } else { 0.0 };
+        let ri = pos[i];
+        let rj = pos[j];
+        -k * (c[i] * dij / r.powi(5)
+            - 5.0 * c[i] * ri * rj / r.powi(7)
+            - 5.0
+                * (dij * z2 / r.powi(7) + 2.0 * z * ri * dj3 / r.powi(7)
+                    - 7.0 * ri * rj * z2 / r.powi(9)))
+    })
+}
+
+/// Jacobian of two-body dynamics
+///
+/// The 6x6 matrix A for which the derivative of a small deviation
+/// in position & velocity, dx, is A * dx
+///
+/// # Arguments
+///
+/// * `pv` - Position (m) & velocity (m/s)
+/// * `mu` - Gravitational parameter, m^3/s^2
+///
+/// # Returns
+///
+/// * 6x6 Jacobian: upper-right block is identity, lower-left block is
+///   the partial of acceleration with respect to position, and the
+///   other blocks are zero
+pub fn twobody_jacobian(pv: &Vector6, mu: f64) -> Matrix6 {
+    let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+    jacobian_from_dadr(&twobody_dadr(&pos, mu))
+}
+
+/// Jacobian of two-body dynamics plus the J2 perturbation
+///
+/// The 6x6 matrix A for which the derivative of a small deviation
+/// in position & velocity, dx, is A * dx
+///
+/// # Arguments
+///
+/// * `pv` - Position (m) & velocity (m/s), in a frame with z along
+///   the central body spin axis
+/// * `mu` - Gravitational parameter, m^3/s^2
+/// * `j2` - J2 coefficient (positive for an oblate body; this is the
+///   negative of unnormalized C20, e.g. `-consts::JGM3_J2`)
+/// * `re` - Reference radius of the central body, m
+///
+/// # Returns
+///
+/// * 6x6 Jacobian: upper-right block is identity, lower-left block is
+///   the partial of acceleration with respect to position, and the
+///   other blocks are zero
+pub fn j2_jacobian(pv: &Vector6, mu: f64, j2: f64, re: f64) -> Matrix6 {
+    let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+    jacobian_from_dadr(&(twobody_dadr(&pos, mu) + j2_dadr(&pos, mu, j2, re)))
+}
+
+/// Goodyear's G-functions G_0 through G_5 of universal anomaly `s`:
+/// G_n = s^n c_n(beta s^2)
+fn gfuncs(s: f64, beta: f64) -> [f64; 6] {
+    let c = stumpff(beta * s * s);
+    let mut g = [0.0; 6];
+    let mut sn = 1.0;
+    for n in 0..6 {
+        g[n] = sn * c[n];
+        sn *= s;
+    }
+    g
+}
+
+/// Solve the universal form of Kepler's equation,
+/// dt = r0 G_1 + sigma0 G_2 + mu G_3, for universal anomaly s
+///
+/// For elliptical orbits, whole revolutions are first removed from the
+/// time of flight; each adds one period in s, 2 pi / sqrt(beta), so the
+/// iteration is over less than one revolution however long the span.
+/// The initial guess depends on the orbit type (Vallado, Algorithm 8):
+/// dt beta / mu for elliptical orbits, which is exact for circular
+/// orbits; a logarithmic estimate for hyperbolic orbits; and dt / r0
+/// for parabolic orbits
+///
+/// The right side increases monotonically in s (its derivative is the
+/// radius), so Newton iterations are safeguarded by bisection within
+/// a bracket that is tightened at each iteration
+///
+/// # Arguments
+///
+/// * `r0` - Initial radius, m
+/// * `sigma0` - Dot product of initial position & velocity, m^2/s
+/// * `beta` - 2 mu / r0 - v0^2, m^2/s^2; positive for elliptical orbits
+/// * `mu` - Gravitational parameter, m^3/s^2
+/// * `dt` - Time of flight, seconds; may be negative
+///
+/// # Returns
+///
+/// * Tuple with universal anomaly & G-functions at that anomaly
+pub(crate) fn universal_anomaly(
+    r0: f64,
+    sigma0: f64,
+    beta: f64,
+    mu: f64,
+    dt: f64,
+) -> SKResult<(f64, [f64; 6])> {
+    // Whole revolutions, and the remaining time of flight, which has
+    // the same sign as the total
+    let (nrev, dtr, srev) = match beta > 0.0 {
+        true => {
+            let period = 2.0 * PI * mu / beta.powf(1.5);
+            let nrev = (dt / period).trunc();
+            (nrev, dt - nrev * period, 2.0 * PI / beta.sqrt())
+        }
+        false => (0.0, dt, f64::INFINITY),
+    };
+
+    // Bracket: within one revolution of zero, in the direction of dt
+    let (mut lo, mut hi) = match (dtr >= 0.0, srev.is_finite()) {
+        (true, true) => (Some(0.0), Some(srev)),
+        (true, false) => (Some(0.0), None),
+        (false, true) => (Some(-srev), Some(0.0)),
+        (false, false) => (None, Some(0.0)),
+    };
+    let in_bracket = |s: f64, lo: Option<f64>, hi: Option<f64>| {
+        s.is_finite() && lo.is_none_or(|lo| s > lo) && hi.is_none_or(|hi| s < hi)
+    };
+
+    let mut s = if beta > 0.0 {
+        dtr * beta / mu
+    } else if beta < 0.0 {
+        let a = mu / beta;
+        let sgn = dtr.signum();
+        sgn * (-a / mu).sqrt()
+            * (-2.0 * beta * dtr / (sigma0 + sgn * (-mu * a).sqrt() * (1.0 - r0 / a))).ln()
+    } else {
+        dtr / r0
+    };
+    if dtr == 0.0 {
+        s = 0.0;
+    } else if !in_bracket(s, lo, hi) {
+        s = match (lo, hi) {
+            (Some(lo), Some(hi)) => 0.5 * (lo + hi),
+            _ => dtr / r0,
+        };
+    }
+
+    for _ in 0..MAX_ITER {
+        let g = gfuncs(s, beta);
+        let f = r0 * g[1] + sigma0 * g[2] + mu * g[3] - dtr;
+        let r = r0 * g[0] + sigma0 * g[1] + mu * g[2];
+        let mut snext = s;
+        if f != 0.0 {
+            if !f.is_finite() || !r.is_finite() || f > 0.0 {
+                hi = Some(s);
+            } else {
+                lo = Some(s);
+            }
+            snext = s - f / r;
+            if !in_bracket(snext, lo, hi) {
+                snext = match (lo, hi) {
+                    (Some(lo), Some(hi)) => 0.5 * (lo + hi),
+                    _ => return skerror!("Universal anomaly did not converge"),
+                };
+            }
+        }
+        if (snext - s).abs() <= 4.0 * f64::EPSILON * snext.abs() {
+            let s = match nrev == 0.0 {
+                true => snext,
+                false => snext + nrev * srev,
+            };
+            return Ok((s, gfuncs(s, beta)));
+        }
+        s = snext;
+    }
+    skerror!("Universal anomaly did not converge")
+}
+
+/// Row vector with the partials of a scalar with respect to position
+/// & velocity
+fn gradient(dpos: &Vector3, dvel: &Vector3) -> na::RowVector6<f64> {
+    let mut d = na::RowVector6::<f64>::zeros();
+    d.fixed_columns_mut::<3>(0).copy_from(&dpos.transpose());
+    d.fixed_columns_mut::<3>(3).copy_from(&dvel.transpose());
+    d
+}
+
+/// Two-body propagation, by the universal-variable form of Kepler's
+/// equation, for elliptical, parabolic, and hyperbolic orbits alike
+///
+/// See [`twobody_stm`] for the formulation.  Whole revolutions of
+/// elliptical orbits are removed before solving Kepler's equation, so
+/// accuracy does not degrade over spans of many revolutions
+///
+/// # Arguments
+///
+/// * `pv` - Initial position (m) & velocity (m/s)
+/// * `mu` - Gravitational parameter, m^3/s^2
+/// * `dt` - Time of flight, seconds; may be negative
+///
+/// # Returns
+///
+/// * Position (m) & velocity (m/s) after `dt`, or error if Kepler's
+///   equation could not be solved
+pub fn propagate_twobody(pv: &Vector6, mu: f64, dt: f64) -> SKResult<Vector6> {
+    let r0v: Vector3 = pv.fixed_rows::<3>(0).into();
+    let v0v: Vector3 = pv.fixed_rows::<3>(3).into();
+    let r0 = r0v.norm();
+    let sigma0 = r0v.dot(&v0v);
+    let beta = 2.0 * mu / r0 - v0v.norm_squared();
+
+    let (_, g) = universal_anomaly(r0, sigma0, beta, mu, dt)?;
+    let r = r0 * g[0] + sigma0 * g[1] + mu * g[2];
+
+    // Lagrange coefficients
+    let f = 1.0 - mu * g[2] / r0;
+    let gg = r0 * g[1] + sigma0 * g[2];
+    let fdot = -mu * g[1] / (r * r0);
+    let gdot = 1.0 - mu * g[2] / r;
+
+    let mut pv1 = Vector6::zeros();
+    pv1.fixed_rows_mut::<3>(0).copy_from(&(f * r0v + gg * v0v));
+    pv1.fixed_rows_mut::<3>(3)
+        .copy_from(&(fdot * r0v + gdot * v0v));
+    Ok(pv1)
+}
+
+/// Analytic two-body state transition matrix
+///
+/// Partials of position & velocity after time `dt` with respect to
+/// initial position & velocity, in the universal-variable formulation
+/// of Goodyear, as reformulated by Shepperd (Celestial Mechanics 35,
+/// 1985), valid for elliptical, parabolic, and hyperbolic orbits alike
+///
+/// With beta = 2 mu / r0 - v0^2 (mu over the semimajor axis) and
+/// sigma0 = r0 . v0, the G-functions of the universal anomaly s are
+/// G_n(s) = s^n c_n(beta s^2), where c_n are the Stumpff functions.
+/// Kepler's equation, dt = r0 G_1 + sigma0 G_2 + mu G_3, is solved for s,
+/// and the propagated state is
+///
+/// * r = F r0 + G v0, with F = 1 - mu G_2 / r0, G = r0 G_1 + sigma0 G_2
+/// * v = Fdot r0 + Gdot v0, with Fdot = -mu G_1 / (r r0),
+///   Gdot = 1 - mu G_2 / r
+///
+/// The STM follows by differentiating these with respect to the initial
+/// state, through r0, sigma0, beta, and (holding Kepler's equation fixed)
+/// s, using dG_n/ds = G_(n-1) and dG_n/dbeta = -(s G_(n+1) - n G_(n+2)) / 2
+///
+/// The G-functions are smooth through beta = 0; Stumpff functions are
+/// evaluated by series near zero, so near-parabolic orbits keep full
+/// precision
+///
+/// # Arguments
+///
+/// * `pv` - Initial position (m) & velocity (m/s)
+/// * `mu` - Gravitational parameter, m^3/s^2
+/// * `dt` - Time of flight, seconds; may be negative
+///
+/// # Returns
+///
+/// * 6x6 state transition matrix, or error if Kepler's equation
+///   could not be solved
+///
+/// # Example
+///
+/// ```
+/// use satkit::consts;
+/// use satkit::orbitprop::twobody_stm;
+/// use satkit::types::Vector6;
+///
+/// let r = consts::EARTH_RADIUS + 500.0e3;
+/// let v = (consts::MU_EARTH / r).sqrt();
+/// let pv = Vector6::new(r, 0.0, 0.0, 0.0, v, 0.0);
+/// let phi = twobody_stm(&pv, consts::MU_EARTH, 600.0).unwrap();
+///
+/// // Hamiltonian flow preserves phase-space volume
+/// assert!((phi.determinant() - 1.0).abs() < 1.0e-9);
+/// ```
+pub fn twobody_stm(pv: &Vector6, mu: f64, dt: f64) -> SKResult<Matrix6> {
+    let r0v: Vector3 = pv.fixed_rows::<3>(0).into();
+    let v0v: Vector3 = pv.fixed_rows::<3>(3).into();
+    let r0 = r0v.norm();
+    let sigma0 = r0v.dot(&v0v);
+    let beta = 2.0 * mu / r0 - v0v.norm_squared();
+
+    let (s, g) = universal_anomaly(r0, sigma0, beta, mu, dt)?;
+    let r = r0 * g[0] + sigma0 * g[1] + mu * g[2];
+
+    // Lagrange coefficients
+    let f = 1.0 - mu * g[2] / r0;
+    let gg = r0 * g[1] + sigma0 * g[2];
+    let fdot = -mu * g[1] / (r * r0);
+    let gdot = 1.0 - mu * g[2] / r;
+
+    // Partials of r0, sigma0 & beta with respect to the initial state
+    let dr0 = gradient(&(r0v / r0), &Vector3::zeros());
+    let dsigma0 = gradient(&v0v, &r0v);
+    let dbeta = gradient(&(-2.0 * mu / r0.powi(3) * r0v), &(-2.0 * v0v));
+
+    // Partials of the G-functions with respect to beta
+    let dgdb: [f64; 4] = std::array::from_fn(|n| -0.5 * (s * g[n + 1] - n as f64 * g[n + 2]));
+
+    // Partial of s, holding time of flight fixed; partial of
+    // time of flight with respect to s is the radius
+    let dtdb = r0 * dgdb[1] + sigma0 * dgdb[2] + mu * dgdb[3];
+    let ds = -(g[1] * dr0 + g[2] * dsigma0 + dtdb * dbeta) / r;
+
+    // Partials of the G-functions, with dG_0/ds = -beta G_1
+    let dg0 = -beta * g[1] * ds + dgdb[0] * dbeta;
+    let dg1 = g[0] * ds + dgdb[1] * dbeta;
+    let dg2 = g[1] * ds + dgdb[2] * dbeta;
+
+    // Partials of the radius & Lagrange coefficients
+    let dr = g[0] * dr0 + r0 * dg0 + g[1] * dsigma0 + sigma0 * dg1 + mu * dg2;
+    let df = mu * (g[2] * dr0 / r0 - dg2) / r0;
+    let dgg = g[1] * dr0 + r0 * dg1 + g[2] * dsigma0 + sigma0 * dg2;
+    let dfdot = -mu * (dg1 - g[1] * (dr / r + dr0 / r0)) / (r * r0);
+    let dgdot = mu * (g[2] * dr / r - dg2) / r;
+
+    let mut phi = Matrix6::zeros();
+    phi.fixed_view_mut::<3, 6>(0, 0)
+        .copy_from(&(r0v * df + v0v * dgg));
+    phi.fixed_view_mut::<3, 6>(3, 0)
+        .copy_from(&(r0v * dfdot + v0v * dgdot));
+    for ix in 0..3 {
+        phi[(ix, ix)] += f;
+        phi[(ix, ix + 3)] += gg;
+        phi[(ix + 3, ix)] += fdot;
+        phi[(ix + 3, ix + 3)] += gdot;
+    }
+    Ok(phi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+
+    /// Time derivative of position & velocity under two-body + J2
+    fn deriv(pv: &Vector6, j2: f64) -> Vector6 {
+        let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+        let r = pos.norm();
+        let zr2 = (pos[2] / r).powi(2);
+        let k = -1.5 * j2 * consts::MU_EARTH * consts::EARTH_RADIUS.powi(2) / r.powi(5);
+        let accel = -consts::MU_EARTH / r.powi(3) * pos
+            + k * Vector3::new(
+                pos[0] * (1.0 - 5.0 * zr2),
+                pos[1] * (1.0 - 5.0 * zr2),
+                pos[2] * (3.0 - 5.0 * zr2),
+            );
+        let mut d = Vector6::zeros();
+        d.fixed_rows_mut::<3>(0).copy_from(&pv.fixed_rows::<3>(3));
+        d.fixed_rows_mut::<3>(3).copy_from(&accel);
+        d
+    }
+
+    /// Single RK4 step
+    fn rk4(pv: &Vector6, dt: f64, j2: f64) -> Vector6 {
+        let k1 = deriv(pv, j2);
+        let k2 = deriv(&(pv + 0.5 * dt * k1), j2);
+        let k3 = deriv(&(pv + 0.5 * dt * k2), j2);
+        let k4 = deriv(&(pv + dt * k3), j2);
+        pv + dt / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4)
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let r = consts::EARTH_RADIUS + 500.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let pv = Vector6::new(r * 0.6, r * 0.3, r * 0.74, -v * 0.5, v * 0.8, v * 0.2);
+        let j2 = -consts::JGM3_J2;
+        let dt = 0.01;
+
+        for (j2, a) in [
+            (0.0, twobody_jacobian(&pv, consts::MU_EARTH)),
+            (
+                j2,
+                j2_jacobian(&pv, consts::MU_EARTH, j2, consts::EARTH_RADIUS),
+            ),
+        ] {
+            // Structure: identity in upper right, zero on the diagonal blocks
+            assert_eq!(a.fixed_view::<3, 3>(0, 3), Matrix3::identity());
+            assert_eq!(a.fixed_view::<3, 3>(0, 0), Matrix3::zeros());
+            assert_eq!(a.fixed_view::<3, 3>(3, 3), Matrix3::zeros());
+
+            // STM over a tiny step, by central differences of the
+            // numerically propagated state
+            let mut phi = Matrix6::zeros();
+            for ix in 0..6 {
+                let delta = match ix < 3 {
+                    true => 100.0,
+                    false => 1.0,
+                };
+                let mut dp = Vector6::zeros();
+                dp[ix] = delta;
+                let col = (rk4(&(pv + dp), dt, j2) - rk4(&(pv - dp), dt, j2)) / (2.0 * delta);
+                phi.set_column(ix, &col);
+            }
+
+            // STM is I + A dt to first order.  The second-order term,
+            // A^2 dt^2 / 2, is zero in the lower-left block, leaving only
+            // the change in A along the trajectory, so the partials of
+            // acceleration are checked to much higher precision
+            let diff = (phi - Matrix6::identity()) / dt - a;
+            assert!(diff.abs().max() < 1.0e-6);
+            assert!(diff.fixed_view::<3, 3>(3, 0).abs().max() < 1.0e-10);
+        }
+
+        // J2 changes the partials by much more than the above tolerance
+        let a0 = twobody_jacobian(&pv, consts::MU_EARTH);
+        let a1 = j2_jacobian(&pv, consts::MU_EARTH, j2, consts::EARTH_RADIUS);
+        assert!((a1 - a0).abs().max() > 1.0e-9);
+    }
+
+    /// Numerically propagated two-body state & STM, integrating the
+    /// variational equations with the two-body Jacobian
+    fn numerical_stm(pv: &Vector6, dt: f64) -> (Vector6, Matrix6) {
+        use crate::ode::RKAdaptive;
+        type StmState = na::SMatrix<f64, 6, 7>;
+        let ydot = |_x: f64, y: &StmState| -> crate::ode::ODEResult<StmState> {
+            let pv: Vector6 = y.fixed_columns::<1>(0).into();
+            let pos: Vector3 = pv.fixed_rows::<3>(0).into();
+            let accel = -consts::MU_EARTH / pos.norm().powi(3) * pos;
+            let mut dy = StmState::zeros();
+            dy.fixed_view_mut::<3, 1>(0, 0)
+                .copy_from(&pv.fixed_rows::<3>(3));
+            dy.fixed_view_mut::<3, 1>(3, 0).copy_from(&accel);
+            dy.fixed_columns_mut::<6>(1)
+                .copy_from(&(twobody_jacobian(&pv, consts::MU_EARTH) * y.fixed_columns::<6>(1)));
+            Ok(dy)
+        };
+        let mut y0 = StmState::zeros();
+        y0.fixed_columns_mut::<1>(0).copy_from(pv);
+        y0.fixed_columns_mut::<6>(1).copy_from(&Matrix6::identity());
+        let settings = crate::ode::RKAdaptiveSettings {
+            abserror: 1.0e-10,
+            relerror: 1.0e-14,
+            ..Default::default()
+        };
+        let sol = crate::ode::solvers::RKV98::integrate(0.0, dt, &y0, ydot, &settings).unwrap();
+        (
+            sol.y.fixed_columns::<1>(0).into(),
+            sol.y.fixed_columns::<6>(1).into(),
+        )
+    }
+
+    #[test]
+    fn test_twobody_stm() -> SKResult<()> {
+        let mu = consts::MU_EARTH;
+        let r = consts::EARTH_RADIUS + 1000.0e3;
+        let vesc = (2.0 * mu / r).sqrt();
+        let dir = Vector3::new(0.2, 0.9, 0.3).normalize();
+
+        // Speeds relative to escape: elliptical, near-parabolic on
+        // either side, exactly parabolic, and hyperbolic
+        for (vscale, dt) in [
+            (0.75, 9000.0),
+            (0.75, -4000.0),
+            (1.0 - 1.0e-9, 5000.0),
+            (1.0, 5000.0),
+            (1.0 + 1.0e-9, 5000.0),
+            (1.5, 20000.0),
+        ] {
+            let v = vscale * vesc * dir;
+            let pv = Vector6::new(r * 0.8, 0.0, r * 0.6, v[0], v[1], v[2]);
+            let phi = twobody_stm(&pv, mu, dt)?;
+            let (pv1, phi_num) = numerical_stm(&pv, dt);
+
+            // Blocks compared relative to their magnitude
+            for (row, col) in [(0, 0), (0, 3), (3, 0), (3, 3)] {
+                let a = phi.fixed_view::<3, 3>(row, col);
+                let n = phi_num.fixed_view::<3, 3>(row, col);
+                assert!((a - n).abs().max() < 1.0e-11 * n.abs().max());
+            }
+
+            // Analytic propagation matches, to integration error
+            let pv1a = propagate_twobody(&pv, mu, dt)?;
+            assert!((pv1a - pv1).fixed_rows::<3>(0).norm() < 1.0e-5);
+            assert!((pv1a - pv1).fixed_rows::<3>(3).norm() < 1.0e-8);
+
+            // Propagating back from the final state inverts the STM
+            let phi_back = twobody_stm(&pv1, mu, -dt)?;
+            assert!((phi_back * phi - Matrix6::identity()).abs().max() < 1.0e-8);
+        }
+
+        // Zero time of flight
+        let pv = Vector6::new(r, 0.0, 0.0, 0.0, 0.5 * vesc, 0.3 * vesc);
+        assert!(
+            (twobody_stm(&pv, mu, 0.0)? - Matrix6::identity())
+                .abs()
+                .max()
+                < 1.0e-14
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagate_twobody() -> SKResult<()> {
+        use crate::kepler::{Anomaly, Kepler};
+        let mu = consts::MU_EARTH;
+
+        for (a, e) in [(7.0e6, 0.001), (2.4e7, 0.7), (4.2e7, 0.0)] {
+            let kep = Kepler::new(a, e, 0.9, 0.3, 1.2, Anomaly::True(2.0));
+            let (r, v) = kep.to_pv();
+            let pv = Vector6::new(r[0], r[1], r[2], v[0], v[1], v[2]);
+
+            // 1000 periods forward & back recovers the initial state
+            let dt = 1000.0 * kep.period();
+            let pv1 = propagate_twobody(&pv, mu, dt)?;
+            let pv2 = propagate_twobody(&pv1, mu, -dt)?;
+            assert!((pv2 - pv).fixed_rows::<3>(0).norm() < 1.0e-3);
+            assert!((pv2 - pv).fixed_rows::<3>(3).norm() < 1.0e-6);
+
+            // Off a whole number of periods, consistent with propagating
+            // the orbital elements (to the precision of the latter)
+            let dt = 1000.3 * kep.period();
+            let pv1 = propagate_twobody(&pv, mu, dt)?;
+            let (r1, v1) = kep.propagate(&crate::Duration::from_seconds(dt)).to_pv();
+            assert!((pv1.fixed_rows::<3>(0) - r1).norm() < 1.0e-2);
+            assert!((pv1.fixed_rows::<3>(3) - v1).norm() < 1.0e-5);
+            let pv2 = propagate_twobody(&pv1, mu, -dt)?;
+            assert!((pv2 - pv).fixed_rows::<3>(0).norm() < 1.0e-3);
+        }
+
+        // Hyperbolic, over a long span.  Returning from far away, terms
+        // of Kepler's equation are much larger than the time of flight,
+        // so precision is relative to the distance travelled
+        let r = consts::EARTH_RADIUS + 300.0e3;
+        let pv = Vector6::new(r, 0.0, 0.0, 0.0, 1.2 * (2.0 * mu / r).sqrt(), 100.0);
+        let dt = 1.0e7;
+        let pv1 = propagate_twobody(&pv, mu, dt)?;
+        let pv2 = propagate_twobody(&pv1, mu, -dt)?;
+        let dist = pv1.fixed_rows::<3>(0).norm();
+        assert!((pv2 - pv).fixed_rows::<3>(0).norm() < 1.0e-11 * dist);
+        assert!((pv2 - pv).fixed_rows::<3>(3).norm() < 1.0e-7 * pv.fixed_rows::<3>(3).norm());
+
+        // Energy & angular momentum are conserved
+        let energy = |pv: &Vector6| {
+            0.5 * pv.fixed_rows::<3>(3).norm_squared() - mu / pv.fixed_rows::<3>(0).norm()
+        };
+        let angmom = |pv: &Vector6| pv.fixed_rows::<3>(0).cross(&pv.fixed_rows::<3>(3));
+        assert!((energy(&pv1) / energy(&pv) - 1.0).abs() < 1.0e-9);
+        assert!((angmom(&pv1) - angmom(&pv)).norm() < 1.0e-9 * angmom(&pv).norm());
+        Ok(())
+    }
+}
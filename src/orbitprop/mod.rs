@@ -2,15 +2,60 @@ mod precomputed;
 pub mod propagator;
 mod satproperties;
 mod satstate;
+mod shared_propagator;
 /// Propagator Settings
 mod settings;
 
+mod arg_latitude;
+mod close_approach;
+mod collision;
+mod constellation;
+mod cr3bp;
 mod drag;
+mod element_rates;
+mod elevation;
+mod empirical;
+mod ephemeris;
+mod extra_accel;
+mod geo;
+mod ground_track;
+mod residuals;
+mod illumination;
+mod jacobian;
+mod propagate_times;
+mod relativity;
+mod search;
+mod tides;
 mod point_gravity;
 
+pub use arg_latitude::{propagate_to_arg_latitude, propagate_to_node};
+pub use close_approach::{find_tca, screen_tca, CloseApproach};
+pub use collision::collision_probability;
+#[cfg(feature = "montecarlo")]
+pub use collision::collision_probability_mc;
+pub use constellation::propagate_constellation;
+pub use cr3bp::{Cr3bp, RotatingFrame};
+pub use elevation::{
+    apparent_angular_rate, elevation_crossing, pass_peak_slew_rates, pass_slew_feasible,
+};
+pub use element_rates::{element_rates_gauss, element_rates_numerical, ElementRates};
+pub use empirical::{EmpiricalAccel, NUM_EMPIRICAL_PARAMS};
+pub use ephemeris::Ephemeris;
+pub use extra_accel::{ExtraAccel, ExtraAccelFn};
+pub use geo::{geo_drift_rate, geo_longitude, within_sk_box};
+pub use ground_track::ground_track_speed;
+pub use jacobian::{j2_jacobian, propagate_twobody, twobody_jacobian, twobody_stm};
+pub use illumination::{
+    shadow_anomalies, subsatellite_sun_elevation, sun_in_body, sunlit_series, SunlitSeries,
+};
 pub use precomputed::*;
+pub use propagate_times::{propagate_grid, propagate_to_times};
 pub use propagator::*;
+pub use residuals::{compute_residuals, Measurement, Residual, TrackingObservation};
 pub use satproperties::SatProperties;
 pub use satproperties::SatPropertiesStatic;
-pub use satstate::{SatState, StateCov};
+pub use satstate::{ConsiderCov, CovFrame, ErrorEllipse, RicPlane, SatState, StateCov};
+pub use shared_propagator::Propagator;
 pub use settings::PropSettings;
+pub(crate) use jacobian::universal_anomaly;
+pub(crate) use tides::{OCEAN_TIDE_FILE, OCEAN_TIDE_URL};
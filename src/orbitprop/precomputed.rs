@@ -15,11 +15,28 @@ pub struct Precomputed {
     pub stop: Instant,
     pub step: f64,
     data: Vec<InterpType>,
+    frozen_sun_vel: Option<Vector3>,
 }
 
 impl Precomputed {
     pub fn new(start: &Instant, stop: &Instant) -> SKResult<Self> {
+        Self::build(start, stop, false)
+    }
+
+    /// Precomputed terms with the sun & moon positions (and sun velocity)
+    /// evaluated once, at the midpoint of start & stop, rather than
+    /// sampled over the span.  The Earth orientation is still sampled
+    ///
+    /// This saves the ephemeris evaluations at the cost of third-body
+    /// accuracy: the moon moves about 0.55 degrees per hour, so the
+    /// error grows with the length of the span
+    pub fn new_frozen(start: &Instant, stop: &Instant) -> SKResult<Self> {
+        Self::build(start, stop, true)
+    }
+
+    fn build(start: &Instant, stop: &Instant, freeze: bool) -> SKResult<Self> {
         let step: f64 = 60.0;
+        let step_us = (step * 1.0e6) as i64;
 
         let (pstart, pstop) = match stop > start {
             true => (
@@ -31,6 +48,21 @@ impl Precomputed {
                 start + Duration::from_seconds(240.0),
             ),
         };
+        // Samples lie on a fixed global grid, so that interpolated values
+        // at a given time do not depend on the span precomputed
+        let pstart = Instant::new(pstart.raw.div_euclid(step_us) * step_us);
+        let pstop = Instant::new(-((-pstop.raw).div_euclid(step_us)) * step_us);
+
+        // Sun & moon at the midpoint, if frozen
+        let frozen = match freeze {
+            true => {
+                let mid = *start + Duration::from_seconds(0.5 * (*stop - *start).as_seconds());
+                let (psun, vsun) = jplephem::geocentric_state(SolarSystem::Sun, &mid)?;
+                let pmoon = jplephem::geocentric_pos(SolarSystem::Moon, &mid)?;
+                Some((psun, pmoon, vsun))
+            }
+            false => None,
+        };
 
         Ok(Self {
             start: pstart,
@@ -43,15 +75,35 @@ impl Precomputed {
                 for idx in 0..nsteps {
                     let t = pstart + Duration::from_seconds((idx as f64) * step);
                     let q = qgcrf2itrf_approx(&t);
-                    let psun = jplephem::geocentric_pos(SolarSystem::Sun, &t)?;
-                    let pmoon = jplephem::geocentric_pos(SolarSystem::Moon, &t)?;
+                    let (psun, pmoon) = match frozen {
+                        Some((psun, pmoon, _)) => (psun, pmoon),
+                        None => (
+                            jplephem::geocentric_pos(SolarSystem::Sun, &t)?,
+                            jplephem::geocentric_pos(SolarSystem::Moon, &t)?,
+                        ),
+                    };
                     data.push((q, psun, pmoon));
                 }
                 data
             },
+            frozen_sun_vel: frozen.map(|f| f.2),
         })
     }
 
+    /// Whether the sun & moon positions are frozen; see [`Self::new_frozen`]
+    pub const fn is_frozen(&self) -> bool {
+        self.frozen_sun_vel.is_some()
+    }
+
+    /// Number of ephemeris evaluations (one per body per epoch)
+    /// made in computing the terms
+    pub fn ephemeris_evals(&self) -> usize {
+        match self.is_frozen() {
+            true => 2,
+            false => 2 * self.data.len(),
+        }
+    }
+
     pub fn interp(&self, t: &Instant) -> SKResult<InterpType> {
         if *t < self.start || *t > self.stop {
             return skerror!(
@@ -62,13 +114,36 @@ impl Precomputed {
             );
         }
 
-        let idx = (t - self.start).as_seconds() / self.step;
-        let delta = idx - idx.floor();
-        let idx = idx.floor() as usize;
+        // Index & fraction from integer microseconds, so they are exact
+        // on the global grid
+        let step_us = (self.step * 1.0e6) as i64;
+        let dt_us = (*t - self.start).as_microseconds();
+        let delta = (dt_us % step_us) as f64 / step_us as f64;
+        let idx = (dt_us / step_us) as usize;
 
         let q = self.data[idx].0.slerp(&self.data[idx + 1].0, delta);
         let psun = self.data[idx].1 + (self.data[idx + 1].1 - self.data[idx].1) * delta;
         let pmoon = self.data[idx].2 + (self.data[idx + 1].2 - self.data[idx].2) * delta;
         Ok((q, psun, pmoon))
     }
+
+    /// Geocentric velocity of the sun, meters / second, from the
+    /// difference of the samples bracketing the input time, or as
+    /// evaluated at the midpoint if frozen
+    pub fn sun_velocity(&self, t: &Instant) -> SKResult<Vector3> {
+        if *t < self.start || *t > self.stop {
+            return skerror!(
+                "Precomputed::sun_velocity: time {} is outside of precomputed range : {} to {}",
+                *t,
+                self.start,
+                self.stop
+            );
+        }
+        if let Some(v) = self.frozen_sun_vel {
+            return Ok(v);
+        }
+        let step_us = (self.step * 1.0e6) as i64;
+        let idx = ((*t - self.start).as_microseconds() / step_us) as usize;
+        Ok((self.data[idx + 1].1 - self.data[idx].1) / self.step)
+    }
 }
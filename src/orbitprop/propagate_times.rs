@@ -0,0 +1,357 @@
+//! Propagation of a satellite state to many times with a single
+//! integration over the span, and of many states to the same times
+
+use super::propagator::{propagate, StateType};
+use super::NUM_CONSIDER_PARAMS;
+use super::{ConsiderCov, ConsiderCovState, CovFrame, CovState, PropSettings, SatState, StateCov};
+
+use crate::{Instant, SKResult};
+
+use nalgebra as na;
+
+use std::borrow::Cow;
+
+/// Integrate from the epoch to the earliest & latest of the times,
+/// and map the interpolated integrator state at each time to a
+/// satellite state
+fn sample<const C: usize, F>(
+    y0: &StateType<C>,
+    epoch: &Instant,
+    times: &[Instant],
+    settings: &PropSettings,
+    to_state: F,
+) -> SKResult<Vec<SatState>>
+where
+    F: Fn(&Instant, &StateType<C>) -> SatState,
+{
+    let mut first = *epoch;
+    let mut last = *epoch;
+    for t in times {
+        if *t < first {
+            first = *t;
+        }
+        if *t > last {
+            last = *t;
+        }
+    }
+    let before = match first < *epoch {
+        true => Some(propagate(y0, epoch, &first, settings, None)?),
+        false => None,
+    };
+    let after = match last > *epoch {
+        true => Some(propagate(y0, epoch, &last, settings, None)?),
+        false => None,
+    };
+
+    times
+        .iter()
+        .map(|t| {
+            let res = match (t < epoch, t > epoch) {
+                (true, _) => before.as_ref(),
+                (_, true) => after.as_ref(),
+                _ => None,
+            };
+            let y = match res {
+                Some(r) => r.interp(t)?,
+                None => *y0,
+            };
+            Ok(to_state(t, &y))
+        })
+        .collect()
+}
+
+/// Propagate a satellite state to each of a list of times
+///
+/// The equations of motion are integrated once from the state epoch
+/// to the latest time (and once backward to the earliest time, if any
+/// times precede the epoch), and the dense output is interpolated to
+/// each requested time
+///
+/// # Arguments
+///
+/// * `state` - Satellite state.  Covariance & consider parameters, if set,
+///   are propagated, and expressed in the covariance frame of `state`
+/// * `times` - Times at which to compute the state.  These may be before
+///   or after the state epoch, and need not be sorted
+/// * `settings` - Propagation settings.  Default settings are used if `None`.
+///   Interpolation is always enabled
+///
+/// # Returns
+///
+/// * Satellite states at each of the requested times, in the order requested
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::orbitprop::{propagate_to_times, SatState};
+/// use satkit::{Duration, Instant};
+///
+/// let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+/// let r = satkit::consts::EARTH_RADIUS + 500.0e3;
+/// let v = (satkit::consts::MU_EARTH / r).sqrt();
+/// let state = SatState::from_pv(&time, &[r, 0.0, 0.0].into(), &[0.0, v, 0.0].into());
+/// let times: Vec<Instant> = (-10..=10)
+///     .map(|ix| time + Duration::from_minutes(ix as f64 * 6.0))
+///     .collect();
+/// let states = propagate_to_times(&state, &times, None).unwrap();
+/// ```
+pub fn propagate_to_times(
+    state: &SatState,
+    times: &[Instant],
+    settings: Option<&PropSettings>,
+) -> SKResult<Vec<SatState>> {
+    if state.cov_frame != CovFrame::GCRF {
+        let states = propagate_to_times(&state.in_frame(CovFrame::GCRF), times, settings)?;
+        return Ok(states.iter().map(|s| s.in_frame(state.cov_frame)).collect());
+    }
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    settings.enable_interp = true;
+
+    // Consider parameters set: also propagate sensitivity to them
+    if let Some(consider) = &state.consider {
+        let mut y0 = ConsiderCovState::zeros();
+        y0.fixed_view_mut::<6, 1>(0, 0).copy_from(&state.pv);
+        y0.fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+        y0.fixed_view_mut::<6, NUM_CONSIDER_PARAMS>(0, 7)
+            .copy_from(&consider.sensitivity);
+        return sample(&y0, &state.time, times, &settings, |t, y| {
+            let phi = y.fixed_view::<6, 6>(0, 1);
+            SatState {
+                time: *t,
+                pv: y.fixed_view::<6, 1>(0, 0).into(),
+                cov: match state.cov {
+                    StateCov::None => StateCov::None,
+                    StateCov::PVCov(cov) => StateCov::PVCov(phi * cov * phi.transpose()),
+                },
+                consider: Some(ConsiderCov {
+                    cov: consider.cov,
+                    sensitivity: y.fixed_view::<6, NUM_CONSIDER_PARAMS>(0, 7).into(),
+                }),
+                cov_frame: CovFrame::GCRF,
+            }
+        });
+    }
+
+    match state.cov {
+        StateCov::None => sample(&state.pv, &state.time, times, &settings, |t, y| SatState {
+            time: *t,
+            pv: *y,
+            cov: StateCov::None,
+            consider: None,
+            cov_frame: CovFrame::GCRF,
+        }),
+        StateCov::PVCov(cov) => {
+            let mut y0 = CovState::zeros();
+            y0.fixed_view_mut::<6, 1>(0, 0).copy_from(&state.pv);
+            y0.fixed_view_mut::<6, 6>(0, 1)
+                .copy_from(&na::Matrix6::<f64>::identity());
+            sample(&y0, &state.time, times, &settings, |t, y| {
+                let phi = y.fixed_view::<6, 6>(0, 1);
+                SatState {
+                    time: *t,
+                    pv: y.fixed_view::<6, 1>(0, 0).into(),
+                    cov: StateCov::PVCov(phi * cov * phi.transpose()),
+                    consider: None,
+                    cov_frame: CovFrame::GCRF,
+                }
+            })
+        }
+    }
+}
+
+/// Propagate each of a list of satellite states to the same list of
+/// times, e.g. for sensitivity studies over a grid of initial states
+///
+/// Each state is integrated once over the span of the times, as in
+/// [`propagate_to_times`], with the states propagated in parallel.
+/// Precomputed terms (Earth orientation, sun & moon positions) are
+/// computed once, covering all of the state epochs and times, and
+/// shared between the states
+///
+/// # Arguments
+///
+/// * `states` - Initial satellite states.  Covariance & consider
+///   parameters, if set, are propagated
+/// * `times` - Times at which to compute each state.  These may be
+///   before or after the state epochs, and need not be sorted
+/// * `settings` - Propagation settings.  Default settings are used if `None`.
+///   Interpolation is always enabled
+///
+/// # Returns
+///
+/// * One row per initial state, in the order of `states`, each with
+///   the states at the requested times, in the order requested.  A
+///   failure propagating one state (e.g. a state that re-enters) is
+///   returned in its row only; the outer error is for failures common
+///   to all states, e.g. in computing the shared terms
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::orbitprop::{propagate_grid, SatState};
+/// use satkit::{Duration, Instant};
+///
+/// let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+/// let r = satkit::consts::EARTH_RADIUS + 500.0e3;
+/// let v = (satkit::consts::MU_EARTH / r).sqrt();
+/// let states: Vec<SatState> = [-1.0, 0.0, 1.0]
+///     .iter()
+///     .map(|dv| SatState::from_pv(&time, &[r, 0.0, 0.0].into(), &[0.0, v + dv, 0.0].into()))
+///     .collect();
+/// let times: Vec<Instant> = (1..=4)
+///     .map(|ix| time + Duration::from_hours(ix as f64))
+///     .collect();
+/// let grid = propagate_grid(&states, &times, None).unwrap();
+/// for row in grid {
+///     assert_eq!(row.unwrap().len(), times.len());
+/// }
+/// ```
+pub fn propagate_grid(
+    states: &[SatState],
+    times: &[Instant],
+    settings: Option<&PropSettings>,
+) -> SKResult<Vec<SKResult<Vec<SatState>>>> {
+    let Some(mut first) = states.first().map(|s| s.time) else {
+        return Ok(Vec::new());
+    };
+    let mut last = first;
+    for t in states.iter().map(|s| &s.time).chain(times.iter()) {
+        if *t < first {
+            first = *t;
+        }
+        if *t > last {
+            last = *t;
+        }
+    }
+
+    let mut settings = settings.cloned().unwrap_or_default();
+    settings.enable_interp = true;
+    if let Some(Cow::Owned(p)) = settings.precomputed_covering(&first, &last)? {
+        settings.precomputed = Some(p);
+    }
+
+    let nthreads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(states.len());
+    let settings = &settings;
+    Ok(std::thread::scope(|scope| {
+        let handles: Vec<_> = states
+            .chunks(states.len().div_ceil(nthreads))
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|state| propagate_to_times(state, times, Some(settings)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::{Anomaly, Kepler};
+    use crate::Duration;
+
+    #[test]
+    fn test_propagate_to_times() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 5, 1, 0, 0, 0.0);
+        let kep = Kepler::new(
+            consts::EARTH_RADIUS + 500.0e3,
+            0.01,
+            97.5_f64.to_radians(),
+            0.3,
+            0.5,
+            Anomaly::True(1.0),
+        );
+        let (pos, vel) = kep.to_pv();
+        let mut state = SatState::from_pv(&time, &pos, &vel);
+
+        // Unsorted, on both sides of the epoch, and including the epoch
+        let times: Vec<Instant> = [3.7, -1.2, 0.0, 0.25, -5.5, 2.0]
+            .iter()
+            .map(|h| time + Duration::from_hours(*h))
+            .collect();
+
+        let states = propagate_to_times(&state, &times, None)?;
+        assert_eq!(states.len(), times.len());
+        for (s, t) in states.iter().zip(times.iter()) {
+            assert_eq!(s.time, *t);
+            let single = state.propagate(t, None)?;
+            assert!((s.pos_gcrf() - single.pos_gcrf()).norm() < 1.0e-2);
+            assert!((s.vel_gcrf() - single.vel_gcrf()).norm() < 1.0e-5);
+        }
+        assert_eq!(states[2].pv, state.pv);
+
+        // Covariance is propagated as well
+        state.set_lvlh_pos_uncertainty(&na::vector![10.0, 20.0, 30.0]);
+        let states = propagate_to_times(&state, &times, None)?;
+        for (s, t) in states.iter().zip(times.iter()) {
+            let single = state.propagate(t, None)?;
+            let (StateCov::PVCov(c1), StateCov::PVCov(c2)) = (s.cov(), single.cov()) else {
+                return crate::skerror!("Covariance not propagated");
+            };
+            assert!((c1 - c2).norm() < 1.0e-6 * c2.norm());
+        }
+
+        // No times gives no states
+        assert!(propagate_to_times(&state, &[], None)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagate_grid() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 5, 1, 0, 0, 0.0);
+        let kep = Kepler::new(
+            consts::EARTH_RADIUS + 500.0e3,
+            0.01,
+            97.5_f64.to_radians(),
+            0.3,
+            0.5,
+            Anomaly::True(1.0),
+        );
+        let (pos, vel) = kep.to_pv();
+        // Perturbed initial states, one at a different epoch
+        let states = [
+            SatState::from_pv(&time, &pos, &vel),
+            SatState::from_pv(&time, &(pos + na::vector![100.0, 0.0, 0.0]), &vel),
+            SatState::from_pv(&time, &pos, &vel)
+                .propagate(&(time + Duration::from_minutes(30.0)), None)?,
+        ];
+        let times: Vec<Instant> = [2.5, -1.0, 0.0, 4.0]
+            .iter()
+            .map(|h| time + Duration::from_hours(*h))
+            .collect();
+
+        let grid = propagate_grid(&states, &times, None)?;
+        assert_eq!(grid.len(), states.len());
+        for (row, state) in grid.iter().zip(states.iter()) {
+            let row = row.as_ref().map_err(|e| e.to_string())?;
+            assert_eq!(row.len(), times.len());
+            for (s, t) in row.iter().zip(times.iter()) {
+                assert_eq!(s.time, *t);
+                let single = state.propagate(t, None)?;
+                assert!((s.pos_gcrf() - single.pos_gcrf()).norm() < 1.0e-2);
+                assert!((s.vel_gcrf() - single.vel_gcrf()).norm() < 1.0e-5);
+            }
+        }
+
+        // A state that cannot be propagated fails only its own row
+        let bad = SatState::from_pv(&time, &na::Vector3::zeros(), &vel);
+        let grid = propagate_grid(&[states[0].clone(), bad], &times, None)?;
+        assert!(grid[0].is_ok());
+        assert!(grid[1].is_err());
+
+        assert!(propagate_grid(&[], &times, None)?.is_empty());
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 use super::drag::{drag_and_partials, drag_force};
 use super::point_gravity::{point_gravity, point_gravity_and_partials};
+use super::empirical::{self, NUM_EMPIRICAL_PARAMS};
 use super::settings::PropSettings;
+use super::relativity;
+use super::search::golden_max;
+use super::tides;
 
 use crate::earthgravity;
 use crate::lpephem;
@@ -17,7 +21,6 @@ use crate::SKResult;
 
 use num_traits::identities::Zero;
 
-use crate::consts;
 use crate::orbitprop::SatProperties;
 
 use thiserror::Error;
@@ -36,12 +39,202 @@ pub struct PropagationResult<const T: usize> {
     pub rejected_steps: u32,
     pub num_eval: u32,
     pub odesol: Option<ode::ODESolution<Matrix<6, T>>>,
+    /// Work done by atmospheric drag over the propagation, per unit mass
+    /// of the satellite, J/kg.  This is the integral of drag acceleration
+    /// dotted with velocity, taken forward in time over the propagated arc,
+    /// so is negative (drag removes orbital energy) for both forward and
+    /// backward propagation.  Zero if drag is not modeled, or if
+    /// `compute_drag_work` is not set in the settings
+    #[serde(default)]
+    pub drag_work: f64,
+    /// Time and position & velocity at the end of each accepted
+    /// integrator step, if `output_steps` is set in the settings
+    #[serde(default)]
+    pub steps: Option<Vec<(Instant, Vector6)>>,
 }
 
 impl<const T: usize> PropagationResult<T> {
     pub fn interp(&self, time: &Instant) -> SKResult<Matrix<6, T>> {
         interp_propresult(self, time)
     }
+
+    /// State transition matrix from the start time to the given time,
+    /// interpolated from the dense output of the variational equations
+    ///
+    /// Requires a state that includes the STM (e.g., [`CovState`])
+    /// propagated with `enable_interp` set in the settings
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time at which to compute the STM
+    ///
+    /// # Returns
+    ///
+    /// * 6x6 state transition matrix Phi(time, time_start)
+    pub fn stm_at(&self, time: &Instant) -> SKResult<na::Matrix6<f64>> {
+        if T < 7 {
+            return PropagationError::InvalidStateColumns { c: T }.into();
+        }
+        Ok(self.interp(time)?.fixed_view::<6, 6>(0, 1).into())
+    }
+
+    /// State transition matrix between two times within the propagation
+    ///
+    /// Computed by chaining the interpolated STMs from the start time:
+    /// Phi(t2, t1) = Phi(t2, t0) * Phi(t1, t0)^-1
+    ///
+    /// # Arguments
+    ///
+    /// * `t1` - Time from which to map the state
+    /// * `t2` - Time to which the state is mapped
+    ///
+    /// # Returns
+    ///
+    /// * 6x6 state transition matrix Phi(t2, t1)
+    pub fn stm_between(&self, t1: &Instant, t2: &Instant) -> SKResult<na::Matrix6<f64>> {
+        let phi1 = self.stm_at(t1)?;
+        let phi2 = self.stm_at(t2)?;
+        match phi1.try_inverse() {
+            Some(phi1inv) => Ok(phi2 * phi1inv),
+            None => skerror!("State transition matrix at {} is singular", t1),
+        }
+    }
+
+    /// State transition matrices at each integrator step
+    ///
+    /// Requires a state that includes the STM, propagated with
+    /// `enable_interp` set in the settings
+    ///
+    /// # Returns
+    ///
+    /// * Vector of (time, Phi(time, time_start)) tuples, including
+    ///   the end time
+    pub fn stm_history(&self) -> SKResult<Vec<(Instant, na::Matrix6<f64>)>> {
+        if T < 7 {
+            return PropagationError::InvalidStateColumns { c: T }.into();
+        }
+        let dense = match self.odesol.as_ref().and_then(|s| s.dense.as_ref()) {
+            Some(d) => d,
+            None => return PropagationError::NoDenseOutputInSolution.into(),
+        };
+        Ok(dense
+            .x
+            .iter()
+            .zip(dense.y.iter())
+            .map(|(x, y)| (self.time_start + Duration::from_seconds(*x), *y))
+            .chain(std::iter::once((self.time_end, self.state_end)))
+            .map(|(t, y)| (t, y.fixed_view::<6, 6>(0, 1).into()))
+            .collect())
+    }
+
+    /// Minimum & maximum radius and speed over the propagation arc,
+    /// e.g. to confirm perigee & apogee or catch a diverging propagation
+    ///
+    /// Requires propagation with `enable_interp` set in the settings
+    ///
+    /// # Returns
+    ///
+    /// * Extremes of the GCRF radius & speed, and the times at which
+    ///   they occur
+    ///
+    /// # Notes
+    ///
+    /// * The dense output is sampled at 8 points within each integrator
+    ///   step, and at the end, and each extreme is refined by golden-section
+    ///   search between the neighboring samples, to a precision of 1 ms.
+    ///   A single-step arc is handled the same way
+    /// * Extremes may be at the start or end of the arc
+    pub fn extrema(&self) -> SKResult<ArcExtrema> {
+        let Some(sol) = self.odesol.as_ref().filter(|s| s.dense.is_some()) else {
+            return PropagationError::NoDenseOutputInSolution.into();
+        };
+        let dense = sol.dense.as_ref().unwrap();
+        let interp = |x: f64| -> SKResult<StateType<T>> {
+            Ok(crate::ode::solvers::RKV98::interpolate(x, sol)?)
+        };
+
+        let xs: Vec<f64> = dense
+            .x
+            .iter()
+            .zip(dense.h.iter())
+            .flat_map(|(x, h)| {
+                (0..EXTREMA_SAMPLES).map(move |k| h.mul_add(k as f64 / EXTREMA_SAMPLES as f64, *x))
+            })
+            .chain(std::iter::once(sol.x))
+            .collect();
+        let samples = xs
+            .iter()
+            .map(|x| interp(*x))
+            .collect::<SKResult<Vec<_>>>()?;
+
+        // Largest value of sign * f over the arc
+        let extremum = |f: &dyn Fn(&StateType<T>) -> f64, sign: f64| -> SKResult<ArcExtremum> {
+            let (ix, mut best) = samples
+                .iter()
+                .map(|y| sign * f(y))
+                .enumerate()
+                .fold((0, f64::NEG_INFINITY), |acc, (i, v)| match v > acc.1 {
+                    true => (i, v),
+                    false => acc,
+                });
+            let mut xbest = xs[ix];
+
+            // Golden-section search between neighboring samples
+            let g = |x: f64| -> SKResult<f64> { Ok(sign * f(&interp(x)?)) };
+            let (x, gx) = golden_max(
+                &g,
+                xs[ix.saturating_sub(1)],
+                xs[(ix + 1).min(xs.len() - 1)],
+                EXTREMA_TOL,
+            )?;
+            if gx > best {
+                (xbest, best) = (x, gx);
+            }
+            Ok(ArcExtremum {
+                time: self.time_start + Duration::from_seconds(xbest),
+                value: sign * best,
+            })
+        };
+
+        let radius = |y: &StateType<T>| y.fixed_view::<3, 1>(0, 0).norm();
+        let speed = |y: &StateType<T>| y.fixed_view::<3, 1>(3, 0).norm();
+        Ok(ArcExtrema {
+            min_radius: extremum(&radius, -1.0)?,
+            max_radius: extremum(&radius, 1.0)?,
+            min_speed: extremum(&speed, -1.0)?,
+            max_speed: extremum(&speed, 1.0)?,
+        })
+    }
+}
+
+/// Number of points within each integrator step at which the
+/// dense output is sampled to bracket extremes
+const EXTREMA_SAMPLES: usize = 8;
+
+/// Precision, in seconds, to which times of extremes are found
+const EXTREMA_TOL: f64 = 1.0e-3;
+
+/// Extreme value of a quantity over a propagation arc
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcExtremum {
+    /// Time of the extreme
+    pub time: Instant,
+    /// Extreme value
+    pub value: f64,
+}
+
+/// Minimum & maximum radius and speed over a propagation arc;
+/// see [`PropagationResult::extrema`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcExtrema {
+    /// Minimum GCRF radius, meters
+    pub min_radius: ArcExtremum,
+    /// Maximum GCRF radius, meters
+    pub max_radius: ArcExtremum,
+    /// Minimum GCRF speed, meters / second
+    pub min_speed: ArcExtremum,
+    /// Maximum GCRF speed, meters / second
+    pub max_speed: ArcExtremum,
 }
 
 pub type StateType<const C: usize> = na::SMatrix<f64, 6, C>;
@@ -52,6 +245,18 @@ pub type SimpleState = StateType<1>;
 // Covariance State in includes
 pub type CovState = StateType<7>;
 
+// Covariance state, with additional columns for sensitivity of
+// position & velocity to the empirical acceleration parameters
+pub type EmpiricalCovState = StateType<{ 7 + NUM_EMPIRICAL_PARAMS }>;
+
+/// Number of "consider" parameters: coefficient of drag times area over
+/// mass, and coefficient of radiation pressure times area over mass
+pub const NUM_CONSIDER_PARAMS: usize = 2;
+
+// Covariance state, with additional columns for sensitivity of
+// position & velocity to the consider parameters
+pub type ConsiderCovState = StateType<{ 7 + NUM_CONSIDER_PARAMS }>;
+
 #[derive(Debug, Error)]
 pub enum PropagationError {
     #[error("Invalid number of columns: {c}")]
@@ -116,6 +321,9 @@ impl<T> From<PropagationError> for SKResult<T> {
 ///       The state transition matrix should be initialized to identity when running
 ///       The output of the state transition matrix can be used to compute the evolution of the
 ///       state covariance  (see Montenbruck and Gill for details)
+///    * `EmpiricalCovState` - a 6x16 matrix where the first 7 columns are the same as `CovState` above,
+///      and columns 8-16 represent the sensitivity of the state to the 9 empirical acceleration
+///      parameters (see `EmpiricalAccel`).  The sensitivity should be initialized to zero
 ///  * `start` - The time at the initial state
 ///  * `stop` - The time at which to propagate for computing new states
 ///  * `step_seconds` - An optional value representing intervals between `start` and `stop` at which
@@ -126,7 +334,7 @@ impl<T> From<PropagationError> for SKResult<T> {
 ///
 /// # Returns
 /// * `PropagationResult` object with details of the propagation compute, the final state, and intermediate states if step size
-///    is set
+///    is set, and the work done by drag over the propagation
 ///
 /// # Example:
 ///
@@ -199,20 +407,50 @@ pub fn propagate<const C: usize>(
     stop: &Instant,
     settings: &PropSettings,
     satprops: Option<&dyn SatProperties>,
+) -> SKResult<PropagationResult<C>> {
+    propagate_with_precomputed(
+        state,
+        start,
+        stop,
+        settings,
+        settings.precomputed.as_ref(),
+        satprops,
+    )
+}
+
+/// Propagate, using the given precomputed terms (if they cover the
+/// time span) in place of those in the settings
+pub(crate) fn propagate_with_precomputed<const C: usize>(
+    state: &StateType<C>,
+    start: &Instant,
+    stop: &Instant,
+    settings: &PropSettings,
+    precomputed: Option<&Precomputed>,
+    satprops: Option<&dyn SatProperties>,
 ) -> SKResult<PropagationResult<C>> {
     // Duration to end of integration, in seconds
     let x_end: f64 = (*stop - *start).as_seconds();
 
+    // Drag work, if requested, is integrated over the dense output
+    let drag_work = settings.compute_drag_work && satprops.is_some();
+    let dense_output = settings.enable_interp || drag_work;
+
     let odesettings = crate::ode::RKAdaptiveSettings {
         abserror: settings.abs_error,
+        abserror_vec: settings.abs_error_for_state(6 * C)?,
         relerror: settings.rel_error,
-        dense_output: settings.enable_interp,
+        dense_output,
+        step_output: settings.output_steps,
+        max_steps: settings.max_steps,
         ..Default::default()
     };
 
-    // Get or create data for interpolation
+    // Get or create data for interpolation.  Frozen third bodies are
+    // evaluated at the midpoint of this arc, so are always created here
     let interp: &Precomputed = {
-        if let Some(sinterp) = &settings.precomputed {
+        if settings.freeze_third_bodies {
+            &Precomputed::new_frozen(start, stop)?
+        } else if let Some(sinterp) = precomputed.filter(|p| !p.is_frozen()) {
             if stop > start {
                 if (*start >= sinterp.start) && (*stop <= sinterp.stop) {
                     sinterp
@@ -229,6 +467,69 @@ pub fn propagate<const C: usize>(
         }
     };
 
+    // Earth gravity model, consistent with the constant set
+    let gravity = earthgravity::jgm3_with_constants(&settings.constant_set);
+
+    // Load ocean tide model up front, so a missing coefficient file
+    // is reported clearly rather than from within the integrator
+    let ocean_tides = match settings.ocean_tides {
+        true => Some(tides::ocean_tides()?),
+        false => None,
+    };
+
+    // Normalized C20 change converting gravity model to requested tide system
+    let tide_system_c20 = match settings.tide_system {
+        Some(ts) => gravity.tide_system_c20_delta(ts)?,
+        None => 0.0,
+    };
+
+    // Acceleration in ITRF frame from permanent tide conversion and from
+    // ocean & pole tide perturbations to the geopotential
+    // (partials with respect to position are small, and neglected)
+    let tide_accel = |time: &Instant, pos_itrf: &Vector3| -> ODEResult<Vector3> {
+        if ocean_tides.is_none() && !settings.pole_tide && tide_system_c20 == 0.0 {
+            return Ok(Vector3::zeros());
+        }
+        let mut dc = earthgravity::CoeffTable::zeros(
+            tides::MAX_TIDE_DEGREE + 1,
+            tides::MAX_TIDE_DEGREE + 1,
+        );
+        dc[(2, 0)] = tide_system_c20 * earthgravity::normalization(2, 0);
+        if let Some(ot) = ocean_tides {
+            ot.add_delta_coeffs(time, &mut dc);
+        }
+        if settings.pole_tide {
+            let eop = crate::earth_orientation_params::get(time).ok_or_else(|| {
+                ODEError::YDotError(format!(
+                    "No Earth orientation parameters for pole tide at {}",
+                    time
+                ))
+            })?;
+            tides::add_pole_tide_coeffs(time, eop[1], eop[2], &mut dc);
+        }
+        Ok(gravity.accel_delta(pos_itrf, &dc))
+    };
+
+    // Relativistic accelerations, in the GCRF frame
+    // (partials with respect to position are small, and neglected)
+    let relativistic_accel = |time: &Instant,
+                              pos_gcrf: &Vector3,
+                              vel_gcrf: &Vector3,
+                              sun_gcrf: &Vector3,
+                              qitrf2gcrf: &Quaternion|
+     -> ODEResult<Vector3> {
+        if !settings.relativity {
+            return Ok(Vector3::zeros());
+        }
+        let sun_vel = interp
+            .sun_velocity(time)
+            .map_err(|e| ODEError::YDotError(e.to_string()))?;
+        let j = qitrf2gcrf * Vector3::new(0.0, 0.0, relativity::EARTH_ANGULAR_MOMENTUM);
+        Ok(relativity::relativistic_accel(
+            pos_gcrf, vel_gcrf, &j, sun_gcrf, &sun_vel,
+        ))
+    };
+
     let ydot = |x: f64, y: &Matrix<6, C>| -> ODEResult<Matrix<6, C>> {
         // The time variable in the ODE is in seconds
         let time: Instant = *start + Duration::from_seconds(x);
@@ -253,6 +554,12 @@ pub fn propagate<const C: usize>(
         const fn is_seven<const C2: usize>() -> bool {
             C2 == 7
         }
+        const fn is_empirical_cov<const C2: usize>() -> bool {
+            C2 == 7 + NUM_EMPIRICAL_PARAMS
+        }
+        const fn is_consider_cov<const C2: usize>() -> bool {
+            C2 == 7 + NUM_CONSIDER_PARAMS
+        }
 
         // Propagating a "simple" 6-dof (position, velocity) state
         if is_one::<C>() {
@@ -260,16 +567,32 @@ pub fn propagate<const C: usize>(
 
             // Gravity in the ITRF frame
             let gravity_itrf =
-                earthgravity::jgm3().accel(&pos_itrf, settings.gravity_order as usize);
+                gravity.accel(&pos_itrf, settings.gravity_order as usize);
 
             // Gravity in the GCRS frame
             accel += qitrf2gcrf * gravity_itrf;
 
+            // Ocean & pole tides
+            accel += qitrf2gcrf * tide_accel(&time, &pos_itrf)?;
+
+            // Relativistic corrections
+            accel += relativistic_accel(&time, &pos_gcrf, &vel_gcrf, &sun_gcrf, &qitrf2gcrf)?;
+
+            // Empirical accelerations
+            if let Some(emp) = &settings.empirical {
+                accel += emp.accel(&pos_gcrf, &vel_gcrf);
+            }
+
+            // User-supplied acceleration
+            if let Some(extra) = &settings.extra_acceleration {
+                accel += extra.accel(&time, &y.fixed_view::<6, 1>(0, 0).into());
+            }
+
             // Acceleration due to moon
-            accel += point_gravity(&pos_gcrf, &moon_gcrf, crate::consts::MU_MOON);
+            accel += point_gravity(&pos_gcrf, &moon_gcrf, settings.constant_set.mu_moon());
 
             // Acceleration due to sun
-            accel += point_gravity(&pos_gcrf, &sun_gcrf, crate::consts::MU_SUN);
+            accel += point_gravity(&pos_gcrf, &sun_gcrf, settings.constant_set.mu_sun());
 
             // Add solar pressure & drag if that is defined in satellite properties
             if let Some(props) = satprops {
@@ -295,6 +618,7 @@ pub fn propagate<const C: usize>(
                             &time,
                             cd_a_over_m,
                             settings.use_spaceweather,
+                            settings.zonal_wind,
                         );
                     }
                 }
@@ -310,17 +634,27 @@ pub fn propagate<const C: usize>(
             Ok(dy)
         }
         // If C==7, we are also integrating the state transition matrix
-        else if is_seven::<C>() {
+        // If C==16, we are also integrating sensitivity to empirical acceleration parameters
+        // If C==9, we are also integrating sensitivity to the consider parameters
+        else if is_seven::<C>() || is_empirical_cov::<C>() || is_consider_cov::<C>() {
             // For state transition matrix, we need to compute force partials with respect to position
             // (for all forces but drag, partial with respect to velocity are zero)
             let (gravity_accel, gravity_partials) =
-                earthgravity::jgm3().accel_and_partials(&pos_itrf, settings.gravity_order as usize);
+                gravity.accel_and_partials(&pos_itrf, settings.gravity_order as usize);
             let (sun_accel, sun_partials) =
-                point_gravity_and_partials(&pos_gcrf, &sun_gcrf, consts::MU_SUN);
+                point_gravity_and_partials(&pos_gcrf, &sun_gcrf, settings.constant_set.mu_sun());
             let (moon_accel, moon_partials) =
-                point_gravity_and_partials(&pos_gcrf, &moon_gcrf, consts::MU_MOON);
+                point_gravity_and_partials(&pos_gcrf, &moon_gcrf, settings.constant_set.mu_moon());
+
+            let mut accel = qitrf2gcrf * (gravity_accel + tide_accel(&time, &pos_itrf)?)
+                + sun_accel
+                + moon_accel
+                + relativistic_accel(&time, &pos_gcrf, &vel_gcrf, &sun_gcrf, &qitrf2gcrf)?;
 
-            let mut accel = qitrf2gcrf * gravity_accel + sun_accel + moon_accel;
+            // Empirical accelerations; partials with respect to position are neglected
+            if let Some(emp) = &settings.empirical {
+                accel += emp.accel(&pos_gcrf, &vel_gcrf);
+            }
 
             // Equation 7.42 in Montenbruck & Gill
             let mut dfdy: StateType<6> = StateType::<6>::zeros();
@@ -361,6 +695,7 @@ pub fn propagate<const C: usize>(
                             &time,
                             cd_a_over_m,
                             settings.use_spaceweather,
+                            settings.zonal_wind,
                         );
 
                         // Add acceleration from drag to accel vector
@@ -376,6 +711,16 @@ pub fn propagate<const C: usize>(
                     }
                 }
             }
+
+            // User-supplied acceleration, with numerical partials
+            if let Some(extra) = &settings.extra_acceleration {
+                let pv: Vector6 = y.fixed_view::<6, 1>(0, 0).into();
+                accel += extra.accel(&time, &pv);
+                let partials = extra.partials(&time, &pv);
+                dadr += partials.fixed_view::<3, 3>(0, 0);
+                let mut dadv = dfdy.fixed_view_mut::<3, 3>(3, 3);
+                dadv += partials.fixed_view::<3, 3>(0, 3);
+            }
             dfdy.fixed_view_mut::<3, 3>(3, 0).copy_from(&dadr);
 
             // Derivative of state transition matrix is dfdy * state transition matrix
@@ -386,50 +731,199 @@ pub fn propagate<const C: usize>(
             dy.fixed_view_mut::<3, 1>(0, 0).copy_from(&vel_gcrf);
             dy.fixed_view_mut::<3, 1>(3, 0).copy_from(&accel);
             dy.fixed_view_mut::<6, 6>(0, 1).copy_from(&dphi);
+
+            // Sensitivity to empirical parameters, S, evolves as
+            // dS/dt = dfdy * S + dfdp
+            // (see Equation 7.43 in Montenbruck & Gill)
+            if is_empirical_cov::<C>() {
+                let emp_partials = empirical::partials(&pos_gcrf, &vel_gcrf);
+                let mut dsdt = dfdy * y.fixed_view::<6, NUM_EMPIRICAL_PARAMS>(0, 7);
+                let mut dvdp = dsdt.fixed_view_mut::<3, NUM_EMPIRICAL_PARAMS>(3, 0);
+                dvdp += emp_partials;
+                dy.fixed_view_mut::<6, NUM_EMPIRICAL_PARAMS>(0, 7)
+                    .copy_from(&dsdt);
+            }
+
+            // Sensitivity to consider parameters evolves the same way.
+            // Drag and radiation pressure are linear in their coefficients,
+            // so the partials are the accelerations for unit coefficients.
+            // These are computed whether or not satellite properties are set,
+            // since the nominal coefficient may be zero
+            if is_consider_cov::<C>() {
+                let mut dadp = na::SMatrix::<f64, 3, NUM_CONSIDER_PARAMS>::zeros();
+                if pos_gcrf.norm() < 700.0e3 + crate::consts::EARTH_RADIUS {
+                    dadp.set_column(
+                        0,
+                        &drag_force(
+                            &pos_gcrf,
+                            &pos_itrf,
+                            &vel_gcrf,
+                            &time,
+                            1.0,
+                            settings.use_spaceweather,
+                            settings.zonal_wind,
+                        ),
+                    );
+                }
+                dadp.set_column(
+                    1,
+                    &(-shadowfunc(&sun_gcrf, &pos_gcrf) * 4.56e-6 * sun_gcrf / sun_gcrf.norm()),
+                );
+                let mut dsdt = dfdy * y.fixed_view::<6, NUM_CONSIDER_PARAMS>(0, 7);
+                let mut dvdp = dsdt.fixed_view_mut::<3, NUM_CONSIDER_PARAMS>(3, 0);
+                dvdp += dadp;
+                dy.fixed_view_mut::<6, NUM_CONSIDER_PARAMS>(0, 7)
+                    .copy_from(&dsdt);
+            }
             Ok(dy)
         } else {
             ODEError::YDotError(PropagationError::InvalidStateColumns { c: C }.to_string()).into()
         }
     };
 
-    match settings.enable_interp {
-        false => {
-            let res = match crate::ode::solvers::RKV98NoInterp::integrate(
-                0.0,
-                x_end,
-                state,
-                ydot,
-                &odesettings,
-            ) {
-                Ok(res) => res,
-                Err(e) => return PropagationError::ODEError(e).into(),
-            };
-
-            Ok(PropagationResult {
-                time_start: *start,
-                state_start: *state,
-                time_end: *stop,
-                state_end: res.y,
-                accepted_steps: res.naccept as u32,
-                rejected_steps: res.nreject as u32,
-                num_eval: res.nevals as u32,
-                odesol: Some(res),
-            })
+    // Power delivered by drag per unit mass, W/kg, matching the
+    // drag force used in the ydot function above
+    let drag_power = |x: f64, y: &Matrix<6, C>| -> ODEResult<f64> {
+        let Some(props) = satprops else {
+            return Ok(0.0);
+        };
+        let time: Instant = *start + Duration::from_seconds(x);
+        let pos_gcrf: na::Vector3<f64> = y.fixed_view::<3, 1>(0, 0).into();
+        let vel_gcrf: na::Vector3<f64> = y.fixed_view::<3, 1>(3, 0).into();
+        if pos_gcrf.norm() >= 700.0e3 + crate::consts::EARTH_RADIUS {
+            return Ok(0.0);
+        }
+        let cd_a_over_m = props.cd_a_over_m(&time, &y.fixed_view::<6, 1>(0, 0).into());
+        if cd_a_over_m <= 1e-6 {
+            return Ok(0.0);
         }
-        true => {
-            let res = crate::ode::solvers::RKV98::integrate(0.0, x_end, state, ydot, &odesettings)?;
-            Ok(PropagationResult {
-                time_start: *start,
-                state_start: *state,
-                time_end: *stop,
-                state_end: res.y,
-                accepted_steps: res.naccept as u32,
-                rejected_steps: res.nreject as u32,
-                num_eval: res.nevals as u32,
-                odesol: Some(res),
+        let qgcrf2itrf = match interp.interp(&time) {
+            Ok(v) => v.0,
+            Err(e) => return Err(ODEError::YDotError(e.to_string())),
+        };
+        Ok(drag_force(
+            &pos_gcrf,
+            &(qgcrf2itrf * pos_gcrf),
+            &vel_gcrf,
+            &time,
+            cd_a_over_m,
+            settings.use_spaceweather,
+            settings.zonal_wind,
+        )
+        .dot(&vel_gcrf))
+    };
+
+    let res = match dense_output {
+        false => crate::ode::solvers::RKV98NoInterp::integrate(
+            0.0,
+            x_end,
+            state,
+            ydot,
+            &odesettings,
+        ),
+        true => crate::ode::solvers::RKV98::integrate(0.0, x_end, state, ydot, &odesettings),
+    };
+    let mut res = match res {
+        Ok(res) => res,
+        Err(ODEError::MaxStepsExceeded { steps, x }) => {
+            log::warn!(
+                target: "satkit::orbitprop",
+                "Propagation from {} to {} stopped after {} steps",
+                start,
+                stop,
+                steps
+            );
+            return Err(Box::new(SKErr::IntegratorFailed {
+                steps,
+                time: *start + Duration::from_seconds(x),
+            }))
+        }
+        Err(e) if dense_output => return Err(e.into()),
+        Err(e) => return PropagationError::ODEError(e).into(),
+    };
+    let drag_work = match drag_work {
+        true => integrate_drag_power(&res, drag_power)?,
+        false => 0.0,
+    };
+    if !settings.enable_interp {
+        res.dense = None;
+    }
+    let steps = res.steps.take().map(|steps| {
+        steps
+            .iter()
+            .map(|(x, y)| {
+                (
+                    *start + Duration::from_seconds(*x),
+                    y.fixed_view::<6, 1>(0, 0).into(),
+                )
             })
+            .collect()
+    });
+
+    // Single-step propagations (e.g. many short hops when stepping a
+    // filter) are summarized at trace level only, to avoid flooding logs
+    let level = match res.naccept > 1 {
+        true => log::Level::Debug,
+        false => log::Level::Trace,
+    };
+    log::log!(
+        target: "satkit::orbitprop",
+        level,
+        "Propagated from {} to {}: {} accepted steps, {} rejected, {} evaluations",
+        start,
+        stop,
+        res.naccept,
+        res.nreject,
+        res.nevals
+    );
+
+    Ok(PropagationResult {
+        time_start: *start,
+        state_start: *state,
+        time_end: *stop,
+        state_end: res.y,
+        accepted_steps: res.naccept as u32,
+        rejected_steps: res.nreject as u32,
+        num_eval: res.nevals as u32,
+        odesol: Some(res),
+        drag_work,
+        steps,
+    })
+}
+
+/// Gauss-Legendre nodes on [-1, 1] & weights used to integrate
+/// the drag power over each integrator step
+const DRAG_QUADRATURE: [(f64, f64); 5] = [
+    (0.0, 128.0 / 225.0),
+    (-0.538_469_310_105_683, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_08),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_08),
+];
+
+/// Integral of the drag power over the propagation, using the
+/// dense output of the solution to interpolate the state within
+/// each integrator step.  Steps are integrated forward in time,
+/// so the sign does not depend on the propagation direction
+fn integrate_drag_power<const C: usize, F>(
+    sol: &ode::ODESolution<Matrix<6, C>>,
+    drag_power: F,
+) -> ODEResult<f64>
+where
+    F: Fn(f64, &Matrix<6, C>) -> ODEResult<f64>,
+{
+    let Some(dense) = &sol.dense else {
+        return ODEError::NoDenseOutputInSolution.into();
+    };
+    let mut work = 0.0;
+    for (x, h) in dense.x.iter().zip(dense.h.iter()) {
+        for (node, weight) in DRAG_QUADRATURE {
+            let xq = (0.5 * h).mul_add(1.0 + node, *x);
+            let y = crate::ode::solvers::RKV98::interpolate(xq, sol)?;
+            work += 0.5 * h.abs() * weight * drag_power(xq, &y)?;
         }
     }
+    Ok(work)
 }
 
 pub fn interp_propresult<const C: usize>(
@@ -460,6 +954,140 @@ mod tests {
     use crate::Duration;
     use std::io::{self, BufRead};
 
+    /// Two-body propagation result, integrated directly so that
+    /// no data files are needed
+    fn twobody_result(
+        pv: &SimpleState,
+        seconds: f64,
+        settings: &crate::ode::RKAdaptiveSettings,
+    ) -> PropagationResult<1> {
+        let ydot = |_x: f64, y: &SimpleState| -> ODEResult<SimpleState> {
+            let r = y.fixed_view::<3, 1>(0, 0);
+            let a = -consts::MU_EARTH / r.norm().powi(3) * r;
+            Ok(SimpleState::new(y[3], y[4], y[5], a[0], a[1], a[2]))
+        };
+        let sol = crate::ode::solvers::RKV98::integrate(0.0, seconds, pv, ydot, settings).unwrap();
+        let t0 = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        PropagationResult {
+            time_start: t0,
+            state_start: *pv,
+            time_end: t0 + Duration::from_seconds(seconds),
+            state_end: sol.y,
+            accepted_steps: sol.naccept as u32,
+            rejected_steps: sol.nreject as u32,
+            num_eval: sol.nevals as u32,
+            odesol: Some(sol),
+            drag_work: 0.0,
+            steps: None,
+        }
+    }
+
+    #[test]
+    fn test_extrema() -> SKResult<()> {
+        // Eccentric orbit started between perigee & apogee, over
+        // more than one period: extremes are at perigee & apogee,
+        let (a, e) = (1.2e7, 0.3);
+        use crate::kepler::{Anomaly, Kepler};
+        let kep = Kepler::new(a, e, 0.5, 0.2, 0.3, Anomaly::True(1.0));
+        let (r, v) = kep.to_pv();
+        let pv = SimpleState::new(r.x, r.y, r.z, v.x, v.y, v.z);
+        let mut settings = crate::ode::RKAdaptiveSettings {
+            dense_output: true,
+            ..Default::default()
+        };
+        let res = twobody_result(&pv, 1.3 * kep.period(), &settings);
+        let ext = res.extrema()?;
+
+        // to within the integration error
+        let (rp, ra) = (a * (1.0 - e), a * (1.0 + e));
+        assert!((ext.min_radius.value - rp).abs() < 0.1);
+        assert!((ext.max_radius.value - ra).abs() < 0.1);
+        // Vis-viva at perigee & apogee
+        let vis_viva = |r: f64| (consts::MU_EARTH * (2.0 / r - 1.0 / a)).sqrt();
+        assert!((ext.max_speed.value - vis_viva(rp)).abs() < 1.0e-4);
+        assert!((ext.min_speed.value - vis_viva(ra)).abs() < 1.0e-4);
+
+        // Times match the anomaly of perigee & apogee
+        let anomaly_at = |t: &Instant| {
+            kep.propagate(&(*t - res.time_start)).nu.rem_euclid(2.0 * PI)
+        };
+        let dnu = |nu: f64, target: f64| crate::angles::wrap_to_pi(nu - target).abs();
+        assert!(dnu(anomaly_at(&ext.min_radius.time), 0.0) < 1.0e-6);
+        assert!(dnu(anomaly_at(&ext.max_radius.time), PI) < 1.0e-6);
+        assert!((ext.max_speed.time - ext.min_radius.time).as_seconds().abs() < 1.0e-2);
+
+        // Single short step: extremes are within the sampled range
+        settings.first_step = Some(10.0);
+        let res = twobody_result(&pv, 10.0, &settings);
+        assert_eq!(res.accepted_steps, 1);
+        let ext = res.extrema()?;
+        let (r0, r1) = (pv.fixed_rows::<3>(0).norm(), res.state_end.fixed_rows::<3>(0).norm());
+        assert!(ext.min_radius.value <= r0.min(r1) + 1.0e-6);
+        assert!(ext.max_radius.value >= r0.max(r1) - 1.0e-6);
+        // Moving away from perigee, so radius increases over the step
+        assert_eq!(ext.min_radius.time, res.time_start);
+        assert!((ext.max_radius.time - res.time_end).as_seconds().abs() < 1.0e-3);
+
+        // Requires dense output
+        settings.dense_output = false;
+        assert!(twobody_result(&pv, 100.0, &settings).extrema().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_interp_backward() -> SKResult<()> {
+        use crate::kepler::{Anomaly, Kepler};
+        use crate::orbitprop::propagate_twobody;
+        let kep = Kepler::new(8.0e6, 0.1, 0.9, 0.3, 0.2, Anomaly::True(1.0));
+        let (r, v) = kep.to_pv();
+        let pv = SimpleState::new(r.x, r.y, r.z, v.x, v.y, v.z);
+        let settings = crate::ode::RKAdaptiveSettings {
+            dense_output: true,
+            abserror: 1.0e-10,
+            relerror: 1.0e-13,
+            ..Default::default()
+        };
+        // Whole seconds, so the end time is exactly representable
+        let span = (0.8 * kep.period()).round();
+        let bwd = twobody_result(&pv, -span, &settings);
+        let fwd = twobody_result(&pv, span, &settings);
+        assert!(bwd.time_end < bwd.time_start);
+        assert!(bwd.accepted_steps > 2);
+
+        // Interior times, including dense-output nodes, match the analytic
+        // solution, and forward propagation from the end of the backward
+        // propagation
+        let mut times: Vec<f64> = (1..20).map(|ix| -span * ix as f64 / 20.0).collect();
+        let nodes = &bwd.odesol.as_ref().unwrap().dense.as_ref().unwrap().x;
+        times.extend(nodes.iter().skip(1).take(3));
+        let from_end = twobody_result(&bwd.state_end, span, &settings);
+        for dt in times {
+            // Instant resolution is one microsecond
+            let time = bwd.time_start + Duration::from_seconds(dt);
+            let dt = (time - bwd.time_start).as_seconds();
+            let interp = bwd.interp(&time)?;
+            let exact = propagate_twobody(&pv, consts::MU_EARTH, dt)?;
+            assert!((interp - exact).fixed_rows::<3>(0).norm() < 1.0e-5);
+            let end_interp = from_end.interp(&(from_end.time_start + (time - bwd.time_end)))?;
+            assert!((interp - end_interp).fixed_rows::<3>(0).norm() < 1.0e-5);
+
+            // Mirror of the forward propagation, with which it agrees
+            // to the same precision
+            let fwd_exact = propagate_twobody(&pv, consts::MU_EARTH, -dt)?;
+            let fwd_interp = fwd.interp(&(fwd.time_start - (time - bwd.time_start)))?;
+            assert!((fwd_interp - fwd_exact).fixed_rows::<3>(0).norm() < 1.0e-5);
+        }
+
+        // Endpoints are the start & end states
+        assert!((bwd.interp(&bwd.time_start)? - pv).norm() < 1.0e-9);
+        assert!((bwd.interp(&bwd.time_end)? - bwd.state_end).norm() < 1.0e-5);
+
+        // Outside the reversed interval, on either side
+        assert!(bwd.interp(&(bwd.time_start + Duration::from_seconds(1.0))).is_err());
+        assert!(bwd.interp(&(bwd.time_end - Duration::from_seconds(1.0))).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_short_propagate() -> SKResult<()> {
         let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
@@ -482,6 +1110,105 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_freeze_third_bodies() -> SKResult<()> {
+        // Geostationary, where third-body perturbations are largest
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let mut state: SimpleState = SimpleState::zeros();
+        state[0] = consts::GEO_R;
+        state[4] = (consts::MU_EARTH / consts::GEO_R).sqrt();
+
+        let full = PropSettings::default();
+        let frozen = PropSettings {
+            freeze_third_bodies: true,
+            ..Default::default()
+        };
+        let diff = |stoptime: &Instant| -> SKResult<f64> {
+            let res1 = propagate(&state, &starttime, stoptime, &full, None)?;
+            let res2 = propagate(&state, &starttime, stoptime, &frozen, None)?;
+            Ok((res1.state_end - res2.state_end).fixed_rows::<3>(0).norm())
+        };
+
+        // Short arc agrees to sub-meter, with far fewer ephemeris evaluations
+        let stoptime = starttime + Duration::from_hours(1.0);
+        let short = diff(&stoptime)?;
+        assert!(short < 1.0);
+        let n_full = Precomputed::new(&starttime, &stoptime)?.ephemeris_evals();
+        let n_frozen = Precomputed::new_frozen(&starttime, &stoptime)?.ephemeris_evals();
+        assert_eq!(n_frozen, 2);
+        assert!(n_full > 10 * n_frozen);
+
+        // Error grows with arc length
+        assert!(diff(&(starttime + Duration::from_days(3.0)))? > 10.0 * short);
+
+        // Precomputed terms in the settings are not used when frozen
+        let mut frozen_pre = frozen.clone();
+        frozen_pre.precompute_terms(&starttime, &stoptime)?;
+        let res1 = propagate(&state, &starttime, &stoptime, &frozen, None)?;
+        let res2 = propagate(&state, &starttime, &stoptime, &frozen_pre, None)?;
+        assert_eq!(res1.state_end, res2.state_end);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_steps() -> SKResult<()> {
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_days(10.0);
+
+        let mut state: SimpleState = SimpleState::zeros();
+        state[0] = consts::EARTH_RADIUS + 500.0e3;
+        state[4] = (consts::MU_EARTH / state[0]).sqrt();
+
+        let settings = PropSettings {
+            max_steps: Some(10),
+            ..Default::default()
+        };
+        let err = propagate(&state, &starttime, &stoptime, &settings, None).unwrap_err();
+        match err.downcast_ref::<SKErr>() {
+            Some(SKErr::IntegratorFailed { steps, time }) => {
+                assert_eq!(*steps, 10);
+                assert!(*time > starttime && *time < stoptime);
+            }
+            _ => return crate::skerror!("Unexpected error: {}", err),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_steps() -> SKResult<()> {
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let mut state: SimpleState = SimpleState::zeros();
+        state[0] = consts::EARTH_RADIUS + 500.0e3;
+        state[4] = (consts::MU_EARTH / state[0]).sqrt();
+
+        let mut settings = PropSettings {
+            output_steps: true,
+            enable_interp: false,
+            ..Default::default()
+        };
+        for days in [0.25, -0.25] {
+            let stoptime = starttime + Duration::from_days(days);
+            let res = propagate(&state, &starttime, &stoptime, &settings, None)?;
+            let steps = res.steps.as_ref().unwrap();
+            assert_eq!(steps.len(), res.accepted_steps as usize);
+            // Strictly increasing, or decreasing when propagating backward
+            assert!(steps
+                .windows(2)
+                .all(|w| (w[1].0 - w[0].0).as_seconds() * days.signum() > 0.0));
+            let (tlast, pvlast) = steps.last().unwrap();
+            assert_eq!(*tlast, stoptime);
+            assert_eq!(*pvlast, res.state_end);
+        }
+
+        // Opt-in only
+        settings.output_steps = false;
+        let stoptime = starttime + Duration::from_days(0.25);
+        assert!(propagate(&state, &starttime, &stoptime, &settings, None)?
+            .steps
+            .is_none());
+        Ok(())
+    }
+
     #[test]
     fn test_propagate() -> SKResult<()> {
         let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
@@ -606,6 +1333,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_stm_between() -> SKResult<()> {
+        // STM between two interior times, chained from STMs relative to
+        // the start time, should match a direct propagation of the STM
+        // over the sub-interval
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_days(1.0);
+
+        let mut state: CovState = CovState::zeros();
+        let theta = PI / 6.0;
+        state[0] = consts::GEO_R * theta.cos();
+        state[2] = consts::GEO_R * theta.sin();
+        state[4] = (consts::MU_EARTH / consts::GEO_R).sqrt() * theta.cos();
+        state[5] = (consts::MU_EARTH / consts::GEO_R).sqrt() * theta.sin();
+        state
+            .fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+
+        let settings = PropSettings {
+            abs_error: 1.0e-9,
+            rel_error: 1.0e-14,
+            gravity_order: 4,
+            ..Default::default()
+        };
+
+        let res = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let t1 = starttime + Duration::from_hours(4.0);
+        let t2 = starttime + Duration::from_hours(10.0);
+        let phi = res.stm_between(&t1, &t2)?;
+
+        // Directly propagate from t1 to t2, starting with identity STM
+        let mut state1 = res.interp(&t1)?;
+        state1
+            .fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+        let res2 = propagate(&state1, &t1, &t2, &settings, None)?;
+        let phi_direct = res2.state_end.fixed_view::<6, 6>(0, 1);
+
+        let maxdiff = (phi - phi_direct).abs().max();
+        assert!(maxdiff / phi_direct.abs().max() < 1.0e-6);
+
+        // STM history ends at the final STM
+        let hist = res.stm_history()?;
+        assert!(hist.len() > 2);
+        let (tend, phiend) = hist.last().unwrap();
+        assert_eq!(*tend, stoptime);
+        assert_eq!(*phiend, res.state_end.fixed_view::<6, 6>(0, 1));
+
+        // No STM in a simple state
+        let sstate: SimpleState = state.fixed_view::<6, 1>(0, 0).into();
+        let res3 = propagate(&sstate, &starttime, &t1, &settings, None)?;
+        assert!(res3.stm_at(&t1).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_state_transition_drag() -> SKResult<()> {
         // Check the state transition matrix:
@@ -778,4 +1561,304 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_extra_acceleration() -> SKResult<()> {
+        use crate::orbitprop::ExtraAccel;
+
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_seconds(120.0);
+
+        // Circular LEO
+        let r = consts::EARTH_RADIUS + 700.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let state = SimpleState::new(r, 0.0, 0.0, 0.0, v * 0.5, v * 0.75_f64.sqrt());
+
+        let mut settings = PropSettings::default();
+        let res1 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+
+        // Constant acceleration gives position offset a t^2 / 2, to
+        // within the (small) change in gravity over the offset
+        let a0 = Vector3::new(1.0e-3, -2.0e-3, 5.0e-4);
+        settings.extra_acceleration = Some(ExtraAccel::new(move |_, _| a0));
+        let res2 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let t = (stoptime - starttime).as_seconds();
+        let offset = res2.state_end.fixed_rows::<3>(0) - res1.state_end.fixed_rows::<3>(0);
+        let expected = 0.5 * a0 * t * t;
+        assert!((offset - expected).norm() < 0.01 * expected.norm());
+        let dv = res2.state_end.fixed_rows::<3>(3) - res1.state_end.fixed_rows::<3>(3);
+        assert!((dv - a0 * t).norm() < 0.01 * (a0 * t).norm());
+
+        // State transition matrix includes the numerical partials:
+        // a position-dependent extra acceleration changes the STM,
+        // consistent with a finite difference
+        let k = 1.0e-6;
+        settings.extra_acceleration = Some(ExtraAccel::new(move |_, pv| {
+            -k * Vector3::new(pv[0], pv[1], pv[2])
+        }));
+        let mut cstate = CovState::zeros();
+        cstate.fixed_view_mut::<6, 1>(0, 0).copy_from(&state);
+        cstate
+            .fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+        let res3 = propagate(&cstate, &starttime, &stoptime, &settings, None)?;
+        let dx = 10.0;
+        let mut perturbed = state;
+        perturbed[0] += dx;
+        let res4 = propagate(&perturbed, &starttime, &stoptime, &settings, None)?;
+        let res5 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let fd = (res4.state_end - res5.state_end) / dx;
+        let phi = res3.state_end.fixed_view::<6, 1>(0, 1);
+        assert!((phi - fd).norm() < 1.0e-4 * fd.norm());
+        Ok(())
+    }
+
+    #[test]
+    fn test_empirical_along_track() -> SKResult<()> {
+        use crate::kepler::Kepler;
+        use crate::orbitprop::EmpiricalAccel;
+
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_days(1.0);
+
+        // Circular LEO
+        let r = consts::EARTH_RADIUS + 700.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let mut state = SimpleState::zeros();
+        state[0] = r;
+        state[4] = v * 0.5;
+        state[5] = v * 0.75_f64.sqrt();
+
+        let mut settings = PropSettings::default();
+        settings.precompute_terms(&starttime, &stoptime)?;
+        let res1 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+
+        let atrack = 1.0e-7;
+        settings.empirical = Some(EmpiricalAccel {
+            along: [atrack, 0.0, 0.0],
+            ..Default::default()
+        });
+        let res2 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+
+        // From Gauss's equations, da/dt = 2 * a_T / n for circular orbit
+        let a1 = Kepler::from_pv(
+            res1.state_end.fixed_rows::<3>(0).into(),
+            res1.state_end.fixed_rows::<3>(3).into(),
+        )?
+        .a;
+        let a2 = Kepler::from_pv(
+            res2.state_end.fixed_rows::<3>(0).into(),
+            res2.state_end.fixed_rows::<3>(3).into(),
+        )?
+        .a;
+        let n = (consts::MU_EARTH / r.powi(3)).sqrt();
+        let expected = 2.0 * atrack / n * (stoptime - starttime).as_seconds();
+        assert!(((a2 - a1) - expected).abs() / expected < 0.05);
+
+        // Sensitivity to along-track parameter matches finite difference
+        let mut cstate = EmpiricalCovState::zeros();
+        cstate.fixed_view_mut::<6, 1>(0, 0).copy_from(&state);
+        cstate
+            .fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+        settings.empirical = None;
+        let res3 = propagate(&cstate, &starttime, &stoptime, &settings, None)?;
+        let sens = res3.state_end.fixed_view::<6, 1>(0, 10) * atrack;
+        let fd = res2.state_end - res1.state_end;
+        for ix in 0..3 {
+            assert!((sens[ix] - fd[ix]).abs() < 0.01 * fd.fixed_rows::<3>(0).norm());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_constant_set() -> SKResult<()> {
+        use crate::consts::ConstantSet;
+
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_hours(6.0);
+
+        // Circular LEO
+        let r = consts::EARTH_RADIUS + 600.0e3;
+        let v = (ConstantSet::Egm96.mu_earth() / r).sqrt();
+        let mut state = SimpleState::zeros();
+        state[0] = r;
+        state[4] = v * 0.5;
+        state[5] = v * 0.75_f64.sqrt();
+
+        let mut settings = PropSettings::default();
+        settings.precompute_terms(&starttime, &stoptime)?;
+        let res1 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        settings.constant_set = ConstantSet::Egm2008;
+        let res2 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        settings.constant_set = ConstantSet::De440;
+        let res3 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+
+        // EGM2008 adopted the EGM96 constants
+        assert_eq!(res1.state_end, res2.state_end);
+
+        // The difference is dominated by the Earth gravitational parameter.
+        // Starting from the same state, a fractional change eps in GM changes
+        // the mean motion by 2 * eps, for an along-track drift of
+        // 2 * eps * r * (n * t - sin(n * t)).  Sun & moon differences
+        // contribute centimeters
+        let eps = ConstantSet::De440.mu_earth() / ConstantSet::Egm96.mu_earth() - 1.0;
+        let nt = v / r * (stoptime - starttime).as_seconds();
+        let expected = 2.0 * eps.abs() * r * (nt - nt.sin());
+        let diff = (res3.state_end - res1.state_end).fixed_rows::<3>(0).norm();
+        assert!(diff > 0.0);
+        assert!((diff - expected).abs() / expected < 0.25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tide_system() -> SKResult<()> {
+        use crate::earthgravity::TideSystem;
+
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_hours(6.0);
+
+        let r = consts::EARTH_RADIUS + 600.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let mut state = SimpleState::zeros();
+        state[0] = r;
+        state[4] = v * 0.5;
+        state[5] = v * 0.75_f64.sqrt();
+
+        let mut settings = PropSettings::default();
+        settings.precompute_terms(&starttime, &stoptime)?;
+        let res = propagate(&state, &starttime, &stoptime, &settings, None)?;
+
+        // JGM3 is tide-free, so this is no change
+        settings.tide_system = Some(TideSystem::TideFree);
+        let res_tf = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        assert_eq!(res.state_end, res_tf.state_end);
+
+        // Zero-tide & mean-tide C20 differ from tide-free by k20 * A0 * H0
+        // and (1 + k20) * A0 * H0, changing J2 by about 1e-5 of its value,
+        // for meters of along-track difference
+        settings.tide_system = Some(TideSystem::ZeroTide);
+        let res_zt = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        settings.tide_system = Some(TideSystem::MeanTide);
+        let res_mt = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let dzt = (res_zt.state_end - res.state_end).fixed_rows::<3>(0).norm();
+        let dmt = (res_mt.state_end - res.state_end).fixed_rows::<3>(0).norm();
+        assert!(dzt > 0.1 && dzt < 100.0);
+        assert!((dmt / dzt - 1.3019 / 0.3019).abs() < 0.5);
+
+        // Unspecified tide system cannot be converted to
+        settings.tide_system = Some(TideSystem::Unspecified);
+        assert!(propagate(&state, &starttime, &stoptime, &settings, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_integrate_drag_power() -> SKResult<()> {
+        use crate::ode::RKAdaptiveSettings;
+
+        // Harmonic oscillator with power taken as the square of the
+        // velocity, so the integral has a closed form
+        let ydot = |_x: f64, y: &SimpleState| -> ODEResult<SimpleState> {
+            let mut dy = SimpleState::zeros();
+            dy[0] = y[3];
+            dy[3] = -y[0];
+            Ok(dy)
+        };
+        let power = |_x: f64, y: &SimpleState| -> ODEResult<f64> { Ok(y[3] * y[3]) };
+        let mut y0 = SimpleState::zeros();
+        y0[3] = 1.0;
+        let odesettings = RKAdaptiveSettings {
+            dense_output: true,
+            ..Default::default()
+        };
+        for x_end in [20.0_f64, -20.0] {
+            let sol = crate::ode::solvers::RKV98::integrate(0.0, x_end, &y0, ydot, &odesettings)?;
+            // Integrated forward in time, whichever the direction
+            let expected = x_end.signum() * (0.5 * x_end + 0.25 * (2.0 * x_end).sin());
+            let work = integrate_drag_power(&sol, power)?;
+            assert!((work - expected).abs() < 1.0e-6);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_drag_work() -> SKResult<()> {
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+        let stoptime = starttime + Duration::from_hours(6.0);
+
+        // Circular LEO, low enough for drag to be significant
+        let r = consts::EARTH_RADIUS + 350.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let mut state = SimpleState::zeros();
+        state[0] = r;
+        state[4] = v * 0.5;
+        state[5] = v * 0.75_f64.sqrt();
+
+        let mut settings = PropSettings::default();
+        settings.precompute_terms(&starttime, &stoptime)?;
+        let satprops = SatPropertiesStatic::new(2.2 * 1.0 / 100.0, 0.0);
+
+        // Not computed unless requested
+        let res = propagate(&state, &starttime, &stoptime, &settings, Some(&satprops))?;
+        assert_eq!(res.drag_work, 0.0);
+
+        settings.compute_drag_work = true;
+        let res_nodrag = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let res_drag = propagate(&state, &starttime, &stoptime, &settings, Some(&satprops))?;
+        assert_eq!(res_nodrag.drag_work, 0.0);
+        assert!(res_drag.drag_work < 0.0);
+        assert!((res.state_end - res_drag.state_end).norm() < 1.0e-3);
+
+        // Specific orbital energy, including the J2 term of the geopotential,
+        // which otherwise dominates the energy difference between the
+        // (slightly different) end positions
+        let energy = |res: &PropagationResult<1>| -> f64 {
+            let pos: Vector3 = res.state_end.fixed_rows::<3>(0).into();
+            let vel: Vector3 = res.state_end.fixed_rows::<3>(3).into();
+            let pos_itrf = crate::frametransform::qgcrf2itrf(&res.time_end) * pos;
+            let rn = pos.norm();
+            let sinlat = pos_itrf[2] / rn;
+            let p2 = 1.5f64.mul_add(sinlat * sinlat, -0.5);
+            let j2 = earthgravity::jgm3().j2() * (consts::EARTH_RADIUS / rn).powi(2) * p2;
+            0.5 * vel.norm_squared() - consts::MU_EARTH / rn * (1.0 - j2)
+        };
+
+        // Semi-major axis decrease from vis-viva matches that from
+        // the work done by drag
+        let a_nodrag = -consts::MU_EARTH / (2.0 * energy(&res_nodrag));
+        let a_drag = -consts::MU_EARTH / (2.0 * energy(&res_drag));
+        let expected = 2.0 * r * r * res_drag.drag_work / consts::MU_EARTH;
+        assert!(a_drag < a_nodrag);
+        assert!(((a_drag - a_nodrag) - expected).abs() / expected.abs() < 0.05);
+
+        // Drag is not modeled above 700 km
+        let mut state_high = state;
+        state_high[0] = consts::EARTH_RADIUS + 800.0e3;
+        let res = propagate(
+            &state_high,
+            &starttime,
+            &stoptime,
+            &settings,
+            Some(&satprops),
+        )?;
+        assert_eq!(res.drag_work, 0.0);
+
+        // Work is the same without dense output in the settings
+        settings.enable_interp = false;
+        let res = propagate(&state, &starttime, &stoptime, &settings, Some(&satprops))?;
+        assert!(res.odesol.as_ref().unwrap().dense.is_none());
+        assert!((res.drag_work - res_drag.drag_work).abs() < 1.0e-6 * res_drag.drag_work.abs());
+
+        // Same sign and (nearly) same value propagating back over the arc
+        let res = propagate(
+            &res_drag.state_end,
+            &stoptime,
+            &starttime,
+            &settings,
+            Some(&satprops),
+        )?;
+        assert!(res.drag_work < 0.0);
+        assert!((res.drag_work - res_drag.drag_work).abs() < 1.0e-3 * res_drag.drag_work.abs());
+        Ok(())
+    }
 }
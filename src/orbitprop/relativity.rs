@@ -0,0 +1,120 @@
+//! Relativistic corrections to the equations of motion
+//!
+//! See IERS 2010 Conventions (Technical Note 36), Equation 10.12,
+//! with PPN parameters beta = gamma = 1
+
+use nalgebra as na;
+
+use crate::consts;
+
+type Vec3 = na::Vector3<f64>;
+
+/// Earth's angular momentum per unit mass, m^2/s (IERS 2010, Section 10.3)
+pub const EARTH_ANGULAR_MOMENTUM: f64 = 9.8e8;
+
+const C2: f64 = consts::C * consts::C;
+
+/// Schwarzschild (point-mass) relativistic acceleration
+///
+/// # Arguments
+///
+/// * `pos` - Geocentric position, meters
+/// * `vel` - Geocentric velocity, meters / second
+pub fn schwarzschild(pos: &Vec3, vel: &Vec3) -> Vec3 {
+    let r = pos.norm();
+    let fac = consts::MU_EARTH / (C2 * r.powi(3));
+    (pos * (4.0 * consts::MU_EARTH / r - vel.norm_squared()) + vel * (4.0 * pos.dot(vel))) * fac
+}
+
+/// Lense-Thirring (frame-dragging) acceleration
+///
+/// # Arguments
+///
+/// * `pos` - Geocentric position, meters
+/// * `vel` - Geocentric velocity, meters / second
+/// * `j` - Earth's angular momentum per unit mass, m^2/s, in same frame as `pos`
+pub fn lense_thirring(pos: &Vec3, vel: &Vec3, j: &Vec3) -> Vec3 {
+    let r = pos.norm();
+    let fac = 2.0 * consts::MU_EARTH / (C2 * r.powi(3));
+    (pos.cross(vel) * (3.0 * pos.dot(j) / (r * r)) + vel.cross(j)) * fac
+}
+
+/// de Sitter (geodesic precession) acceleration, due to the motion
+/// of the Earth about the sun
+///
+/// # Arguments
+///
+/// * `vel` - Geocentric velocity, meters / second
+/// * `sun_pos` - Geocentric position of sun, meters
+/// * `sun_vel` - Geocentric velocity of sun, meters / second
+pub fn de_sitter(vel: &Vec3, sun_pos: &Vec3, sun_vel: &Vec3) -> Vec3 {
+    // Position & velocity of Earth relative to sun
+    let rearth = -sun_pos;
+    let vearth = -sun_vel;
+    let rs = rearth.norm();
+    let omega = vearth.cross(&(rearth * (-consts::MU_SUN / (C2 * rs.powi(3)))));
+    omega.cross(vel) * 3.0
+}
+
+/// Sum of Schwarzschild, Lense-Thirring, and de Sitter accelerations
+///
+/// Terms are summed from smallest to largest, so that the
+/// result is accurate before being added to much larger forces
+///
+/// # Arguments
+///
+/// * `pos` - Geocentric position, meters
+/// * `vel` - Geocentric velocity, meters / second
+/// * `j` - Earth's angular momentum per unit mass, m^2/s, in same frame as `pos`
+/// * `sun_pos` - Geocentric position of sun, meters
+/// * `sun_vel` - Geocentric velocity of sun, meters / second
+pub fn relativistic_accel(
+    pos: &Vec3,
+    vel: &Vec3,
+    j: &Vec3,
+    sun_pos: &Vec3,
+    sun_vel: &Vec3,
+) -> Vec3 {
+    lense_thirring(pos, vel, j) + de_sitter(vel, sun_pos, sun_vel) + schwarzschild(pos, vel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gps_magnitudes() {
+        // Circular GPS orbit, inclined 55 degrees
+        let r = 26560.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let incl = 55.0_f64.to_radians();
+        let pos = Vec3::new(r, 0.0, 0.0);
+        let vel = Vec3::new(0.0, v * incl.cos(), v * incl.sin());
+        let j = Vec3::new(0.0, 0.0, EARTH_ANGULAR_MOMENTUM);
+
+        // Sun at 1 AU, moving ~30 km/s relative to Earth
+        let sun_pos = Vec3::new(0.0, consts::AU, 0.0);
+        let sun_vel = Vec3::new(29.8e3, 0.0, 0.0);
+
+        // Expected orders of magnitude, see e.g. Table 3.7 of
+        // Montenbruck & Gill, and Sosnica et al. (2021)
+        let a_s = schwarzschild(&pos, &vel).norm();
+        assert!(a_s > 1.0e-10 && a_s < 1.0e-9, "Schwarzschild: {}", a_s);
+
+        let a_ds = de_sitter(&vel, &sun_pos, &sun_vel).norm();
+        assert!(a_ds > 5.0e-12 && a_ds < 5.0e-11, "de Sitter: {}", a_ds);
+
+        let a_lt = lense_thirring(&pos, &vel, &j).norm();
+        assert!(a_lt > 1.0e-13 && a_lt < 5.0e-12, "Lense-Thirring: {}", a_lt);
+
+        // Schwarzschild term is radial for circular orbit
+        assert!(schwarzschild(&pos, &vel).normalize().dot(&pos.normalize()) > 0.999999);
+
+        // Sum equals individual terms
+        let total = relativistic_accel(&pos, &vel, &j, &sun_pos, &sun_vel);
+        let parts = schwarzschild(&pos, &vel)
+            + de_sitter(&vel, &sun_pos, &sun_vel)
+            + lense_thirring(&pos, &vel, &j);
+        assert!((total - parts).norm() < 1.0e-24);
+    }
+}
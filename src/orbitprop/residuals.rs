@@ -0,0 +1,218 @@
+//! Measurement residuals of tracking observations against a reference ephemeris
+
+use super::Ephemeris;
+
+use crate::angles::wrap_to_pi;
+use crate::frametransform::{qgcrf2itrf, qitrf2gcrf};
+use crate::skerror;
+use crate::types::Vector3;
+use crate::{ITRFCoord, Instant, SKResult};
+
+/// Measured quantity of a tracking observation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Measurement {
+    /// Slant range from site to satellite, meters
+    Range(f64),
+    /// Azimuth (clockwise from north) & geometric elevation, radians
+    AzEl { az: f64, el: f64 },
+    /// Topocentric right ascension & declination relative to
+    /// the GCRF axes, radians
+    RaDec { ra: f64, dec: f64 },
+}
+
+/// Tracking observation of a satellite from a ground site
+#[derive(Debug, Clone)]
+pub struct TrackingObservation {
+    /// Time of observation
+    pub time: Instant,
+    /// Location of observer
+    pub site: ITRFCoord,
+    /// Measured value
+    pub measurement: Measurement,
+}
+
+/// Residual of a tracking observation, observed minus computed
+#[derive(Debug, Clone)]
+pub struct Residual {
+    /// Time of observation
+    pub time: Instant,
+    /// Observed minus computed measurement, of the same type as the
+    /// observation.  Azimuth & right ascension differences are wrapped
+    /// to [-π, π)
+    pub residual: Measurement,
+}
+
+impl TrackingObservation {
+    /// Measurement model: the measurement that would be made of a
+    /// satellite at the given position
+    ///
+    /// # Arguments
+    ///
+    /// * `pos_gcrf` - Satellite position at the observation time, GCRF frame, meters
+    ///
+    /// # Returns
+    ///
+    /// * Computed measurement, of the same type as the observation
+    ///
+    /// # Notes
+    ///
+    /// * Light-time, aberration & refraction are not modeled
+    pub fn compute(&self, pos_gcrf: &Vector3) -> Measurement {
+        match self.measurement {
+            Measurement::Range(_) => {
+                let site = qitrf2gcrf(&self.time) * self.site.itrf;
+                Measurement::Range((pos_gcrf - site).norm())
+            }
+            Measurement::AzEl { .. } => {
+                let target = ITRFCoord::from(qgcrf2itrf(&self.time) * pos_gcrf);
+                let (az, el, _) = self.site.az_el_range(&target);
+                Measurement::AzEl { az, el }
+            }
+            Measurement::RaDec { .. } => {
+                let los = pos_gcrf - qitrf2gcrf(&self.time) * self.site.itrf;
+                Measurement::RaDec {
+                    ra: los[1].atan2(los[0]),
+                    dec: (los[2] / los.norm()).asin(),
+                }
+            }
+        }
+    }
+
+    /// Residual of this observation, observed minus computed
+    ///
+    /// # Arguments
+    ///
+    /// * `pos_gcrf` - Satellite position at the observation time, GCRF frame, meters
+    pub fn residual(&self, pos_gcrf: &Vector3) -> Residual {
+        let residual = match (self.measurement, self.compute(pos_gcrf)) {
+            (Measurement::Range(obs), Measurement::Range(calc)) => Measurement::Range(obs - calc),
+            (Measurement::AzEl { az, el }, Measurement::AzEl { az: caz, el: cel }) => {
+                Measurement::AzEl {
+                    az: wrap_to_pi(az - caz),
+                    el: el - cel,
+                }
+            }
+            (Measurement::RaDec { ra, dec }, Measurement::RaDec { ra: cra, dec: cdec }) => {
+                Measurement::RaDec {
+                    ra: wrap_to_pi(ra - cra),
+                    dec: dec - cdec,
+                }
+            }
+            _ => unreachable!("computed measurement type matches observation"),
+        };
+        Residual {
+            time: self.time,
+            residual,
+        }
+    }
+}
+
+/// Measurement residuals of tracking observations against a
+/// reference ephemeris, e.g. for orbit determination validation
+///
+/// # Arguments
+///
+/// * `observations` - Tracking observations
+/// * `reference` - Reference ephemeris, GCRF frame, interpolated
+///   to each observation time
+///
+/// # Returns
+///
+/// * Residual (observed minus computed) for each observation, in order,
+///   or error if any observation time is outside the span of the
+///   reference ephemeris
+pub fn compute_residuals(
+    observations: &[TrackingObservation],
+    reference: &Ephemeris,
+) -> SKResult<Vec<Residual>> {
+    observations
+        .iter()
+        .map(|obs| -> SKResult<Residual> {
+            if obs.time < reference.start() || obs.time > reference.stop() {
+                return skerror!(
+                    "Observation at {} is outside reference ephemeris span",
+                    obs.time
+                );
+            }
+            let pv = reference.interp(&obs.time)?;
+            Ok(obs.residual(&pv.fixed_rows::<3>(0).into()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::kepler::{Anomaly, Kepler};
+    use crate::types::Vector6;
+    use crate::Duration;
+
+    #[test]
+    fn test_residuals() -> SKResult<()> {
+        // Reference two-body LEO ephemeris, 30-second nodes
+        let start = Instant::from_datetime(2024, 3, 1, 0, 0, 0.0);
+        let kep = Kepler::new(
+            consts::EARTH_RADIUS + 700.0e3,
+            0.001,
+            98.0_f64.to_radians(),
+            0.3,
+            0.0,
+            Anomaly::Mean(0.0),
+        );
+        let nodes = (0..=200)
+            .map(|ix| {
+                let dt = Duration::from_seconds(30.0 * ix as f64);
+                let (r, v) = kep.propagate(&dt).to_pv();
+                let mut pv = Vector6::zeros();
+                pv.fixed_rows_mut::<3>(0).copy_from(&r);
+                pv.fixed_rows_mut::<3>(3).copy_from(&v);
+                (start + dt, pv)
+            })
+            .collect();
+        let reference = Ephemeris::new(nodes)?;
+
+        // Observations synthesized from the exact orbit between nodes
+        let site = ITRFCoord::from_geodetic_deg(42.466, -71.1516, 150.0);
+        let observations: Vec<TrackingObservation> = (0..30)
+            .flat_map(|ix| {
+                let time = start + Duration::from_seconds(15.0 + 180.0 * ix as f64);
+                let (pos, _) = kep.propagate(&(time - start)).to_pv();
+                [
+                    Measurement::Range(0.0),
+                    Measurement::AzEl { az: 0.0, el: 0.0 },
+                    Measurement::RaDec { ra: 0.0, dec: 0.0 },
+                ]
+                .map(|m| {
+                    let mut obs = TrackingObservation {
+                        time,
+                        site,
+                        measurement: m,
+                    };
+                    obs.measurement = obs.compute(&pos);
+                    obs
+                })
+            })
+            .collect();
+
+        let residuals = compute_residuals(&observations, &reference)?;
+        assert_eq!(residuals.len(), observations.len());
+        for r in residuals.iter() {
+            match r.residual {
+                Measurement::Range(dr) => assert!(dr.abs() < 1.0),
+                Measurement::AzEl { az, el } => {
+                    assert!(az.abs() < 1.0e-5 && el.abs() < 1.0e-5)
+                }
+                Measurement::RaDec { ra, dec } => {
+                    assert!(ra.abs() < 1.0e-5 && dec.abs() < 1.0e-5)
+                }
+            }
+        }
+
+        // Observation outside of the ephemeris span is an error
+        let mut late = observations[0].clone();
+        late.time = reference.stop() + Duration::from_seconds(1.0);
+        assert!(compute_residuals(&[late], &reference).is_err());
+        Ok(())
+    }
+}
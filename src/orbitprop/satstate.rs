@@ -1,11 +1,14 @@
 use nalgebra as na;
 
 use crate::orbitprop;
-use crate::orbitprop::PropSettings;
+use crate::orbitprop::{PropSettings, NUM_CONSIDER_PARAMS};
+use crate::itrfcoord::RangeRate;
+use crate::ITRFCoord;
 use crate::Instant;
 use crate::SKResult;
 
 type PVCovType = na::SMatrix<f64, 6, 6>;
+type ConsiderParamCovType = na::SMatrix<f64, NUM_CONSIDER_PARAMS, NUM_CONSIDER_PARAMS>;
 
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -14,6 +17,84 @@ pub enum StateCov {
     PVCov(PVCovType),
 }
 
+/// Uncertainty in "consider" parameters: parameters that are not
+/// estimated, but whose uncertainty contributes to the uncertainty
+/// of the propagated state
+///
+/// The consider parameters are, in order, the coefficient of drag times
+/// area over mass, and the coefficient of radiation pressure times area
+/// over mass, both in m^2/kg
+#[derive(Clone, Debug)]
+pub struct ConsiderCov {
+    /// Covariance of the consider parameters, (m^2/kg)^2
+    pub cov: ConsiderParamCovType,
+    /// Sensitivity of position & velocity to the consider parameters,
+    /// accumulated since the consider covariance was set
+    pub sensitivity: na::SMatrix<f64, 6, NUM_CONSIDER_PARAMS>,
+}
+
+impl ConsiderCov {
+    /// Contribution of the consider parameters to the
+    /// 6x6 position & velocity covariance
+    pub fn pv_cov(&self) -> PVCovType {
+        self.sensitivity * self.cov * self.sensitivity.transpose()
+    }
+}
+
+/// Frame in which the covariance of a [`SatState`] is expressed
+///
+/// The local frames are defined by the satellite position & velocity
+/// at the state time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CovFrame {
+    /// Geocentric Celestial Reference Frame
+    #[default]
+    GCRF,
+    /// Local-Vertical, Local-Horizontal; see [`SatState::qgcrf2lvlh`]
+    LVLH,
+    /// Radial, In-track, Cross-track:
+    /// x axis = r (radial),
+    /// z axis = h (h = p cross v),
+    /// y axis such that x cross y = z (in-track)
+    RIC,
+}
+
+/// Plane of the RIC frame (see [`CovFrame::RIC`]) in which a
+/// position error ellipse is computed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RicPlane {
+    /// Radial & in-track, i.e. the orbit plane
+    RadialInTrack,
+    /// Radial & cross-track
+    RadialCrossTrack,
+    /// In-track & cross-track
+    InTrackCrossTrack,
+}
+
+impl RicPlane {
+    /// Indices of the RIC axes spanning the plane
+    const fn axes(&self) -> (usize, usize) {
+        match self {
+            Self::RadialInTrack => (0, 1),
+            Self::RadialCrossTrack => (0, 2),
+            Self::InTrackCrossTrack => (1, 2),
+        }
+    }
+}
+
+/// Position error ellipse in a plane of the RIC frame;
+/// see [`SatState::position_error_ellipse`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ErrorEllipse {
+    /// Semi-major axis, meters
+    pub semi_major: f64,
+    /// Semi-minor axis, meters
+    pub semi_minor: f64,
+    /// Angle of the semi-major axis from the first axis of the plane
+    /// toward the second, radians, in (-π/2, π/2]
+    pub angle: f64,
+}
+
 ///
 /// A Satellite State object
 ///
@@ -31,11 +112,22 @@ pub enum StateCov {
 /// If the state is propagated, the state uncertainty will be propagated as well
 /// via the state transition matrix
 ///
+/// Uncertainty in unestimated drag and radiation pressure coefficients can
+/// be included as "consider" parameters.  Their contribution to the state
+/// uncertainty is kept separate from the state covariance
+///
+/// The covariance, and sensitivity to the consider parameters, may be
+/// expressed in a local frame (see [`SatState::in_frame`]); `cov_frame`
+/// records which.  Position & velocity are always in the GCRF
+///
 #[derive(Clone, Debug)]
 pub struct SatState {
     pub time: Instant,
     pub pv: na::Vector6<f64>,
     pub cov: StateCov,
+    pub consider: Option<ConsiderCov>,
+    /// Frame of the covariance & consider sensitivity.  Defaults to GCRF
+    pub cov_frame: CovFrame,
 }
 
 impl SatState {
@@ -44,6 +136,8 @@ impl SatState {
             time: *time,
             pv: na::vector![pos[0], pos[1], pos[2], vel[0], vel[1], vel[2]],
             cov: StateCov::None,
+            consider: None,
+            cov_frame: CovFrame::GCRF,
         }
     }
 
@@ -61,7 +155,7 @@ impl SatState {
     ///
     /// * `cov` -  Covariance matrix.  6x6 or larger if including terms like drag.
     ///            Upper-left 6x6 is covariance for position & velocity, in units of
-    ///            meters and meters / second
+    ///            meters and meters / second, in the frame given by `cov_frame`
     ///
     pub fn set_cov(&mut self, cov: StateCov) {
         self.cov = cov;
@@ -85,18 +179,329 @@ impl SatState {
         q2 * q1
     }
 
+    /// Rotation matrix from the gcrf to the given frame
+    fn dcm_gcrf2frame(&self, frame: CovFrame) -> na::Matrix3<f64> {
+        match frame {
+            CovFrame::GCRF => na::Matrix3::identity(),
+            CovFrame::LVLH => self.qgcrf2lvlh().to_rotation_matrix().into_inner(),
+            CovFrame::RIC => {
+                let r = self.pos_gcrf().normalize();
+                let c = self.pos_gcrf().cross(&self.vel_gcrf()).normalize();
+                let i = c.cross(&r);
+                na::Matrix3::from_rows(&[r.transpose(), i.transpose(), c.transpose()])
+            }
+        }
+    }
+
+    /// Copy of the state with covariance expressed in another frame
+    ///
+    /// The 6x6 covariance is rotated by the 3x3 rotation from the current
+    /// frame (`cov_frame`) to the new frame, applied to both position and
+    /// velocity, as is the sensitivity to the consider parameters
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Frame in which to express the covariance
+    ///
+    /// # Returns
+    ///
+    /// * Copy of the state with `cov_frame` set to `frame`
+    ///
+    /// # Notes
+    ///
+    /// * Position & velocity are not rotated; they stay in the GCRF, so the
+    ///   state can still be propagated, and the local frames (which are
+    ///   defined by the position & velocity) can be recomputed at any time.
+    ///   In particular, with [`StateCov::None`] and no consider parameters,
+    ///   only the frame tag changes
+    /// * The rotation does not include the rotation rate of the local frame,
+    ///   so velocity covariance is that of the GCRF velocity, along the
+    ///   local axes
+    /// * Propagation, [`SatState::mahalanobis`], and the filters convert
+    ///   the covariance to the GCRF as needed; propagated states keep the
+    ///   covariance frame of the original
+    pub fn in_frame(&self, frame: CovFrame) -> Self {
+        let dcm = self.dcm_gcrf2frame(frame) * self.dcm_gcrf2frame(self.cov_frame).transpose();
+        let mut r = na::Matrix6::<f64>::zeros();
+        r.fixed_view_mut::<3, 3>(0, 0).copy_from(&dcm);
+        r.fixed_view_mut::<3, 3>(3, 3).copy_from(&dcm);
+
+        Self {
+            time: self.time,
+            pv: self.pv,
+            cov: match self.cov {
+                StateCov::None => StateCov::None,
+                StateCov::PVCov(cov) => StateCov::PVCov(r * cov * r.transpose()),
+            },
+            consider: self.consider.as_ref().map(|c| ConsiderCov {
+                cov: c.cov,
+                sensitivity: r * c.sensitivity,
+            }),
+            cov_frame: frame,
+        }
+    }
+
+    /// Express the covariance & consider sensitivity in the GCRF,
+    /// before the covariance is replaced with one set in the GCRF
+    fn cov_frame_to_gcrf(&mut self) {
+        if self.cov_frame != CovFrame::GCRF {
+            *self = self.in_frame(CovFrame::GCRF);
+        }
+    }
+
+    /// Range-rate and East-North-Up velocity of the satellite relative to a
+    /// ground site, for e.g. Doppler tracking
+    ///
+    /// The satellite velocity relative to the site includes the rotation
+    /// of the Earth, i.e. it is the velocity in the Earth-fixed ITRF frame
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - Location of ground site
+    ///
+    /// # Returns
+    ///
+    /// * [`RangeRate`], with partials with respect to the satellite
+    ///   GCRF position & velocity
+    pub fn range_rate(&self, site: &ITRFCoord) -> RangeRate {
+        let q = crate::frametransform::qgcrf2itrf(&self.time);
+        let omega = na::Vector3::<f64>::new(0.0, 0.0, crate::consts::OMEGA_EARTH);
+        let (pos_itrf, vel_itrf) = crate::frametransform::pv_gcrf2itrf(&q, &self.pv);
+
+        let mut rr = site.range_rate(&pos_itrf, &vel_itrf);
+        let qinv = q.conjugate();
+        rr.drange_rate_dpos = qinv * (rr.drange_rate_dpos + omega.cross(&rr.drange_rate_dvel));
+        rr.drange_rate_dvel = qinv * rr.drange_rate_dvel;
+        rr
+    }
+
+    /// Position & velocity of another satellite relative to this one
+    /// (the "chief"), in this satellite's RIC (radial, in-track,
+    /// cross-track) frame, e.g. for formation-flying analysis
+    ///
+    /// The relative velocity is as seen in the rotating RIC frame, i.e. it
+    /// excludes the frame rotation at the chief's orbital rate, h / r^2,
+    /// about the cross-track axis.  This is the relative velocity of the
+    /// Hill / Clohessy-Wiltshire equations, and is zero for two satellites
+    /// in the same circular orbit
+    ///
+    /// # Arguments
+    ///
+    /// * `deputy` - State of the other satellite, at the same time
+    ///
+    /// # Returns
+    ///
+    /// * RIC relative position (meters) & velocity (meters / second), or
+    ///   error if the states are at different times
+    pub fn relative_state(&self, deputy: &Self) -> SKResult<na::Vector6<f64>> {
+        if deputy.time != self.time {
+            return crate::skerror!(
+                "States must be at the same time: chief at {}, deputy at {}",
+                self.time,
+                deputy.time
+            );
+        }
+        let dcm = self.dcm_gcrf2frame(CovFrame::RIC);
+        let pos = dcm * (deputy.pos_gcrf() - self.pos_gcrf());
+        let omega = na::Vector3::<f64>::new(
+            0.0,
+            0.0,
+            self.pos_gcrf().cross(&self.vel_gcrf()).norm() / self.pos_gcrf().norm_squared(),
+        );
+        let vel = dcm * (deputy.vel_gcrf() - self.vel_gcrf()) - omega.cross(&pos);
+        Ok(na::Vector6::<f64>::new(pos[0], pos[1], pos[2], vel[0], vel[1], vel[2]))
+    }
+
+    /// Position & velocity of the satellite relative to the Moon, e.g.
+    /// for cislunar analysis
+    ///
+    /// The frame is Moon-centered & inertial, with axes parallel to the
+    /// GCRF.  The relative velocity is the satellite GCRF velocity minus
+    /// the Moon's, i.e. the velocity as seen from the Moon
+    ///
+    /// # Returns
+    ///
+    /// * Moon-relative position (meters) & velocity (meters / second), or
+    ///   error if the Moon ephemeris cannot be computed
+    ///
+    /// # Notes
+    ///
+    /// * Moon position & velocity are from the JPL ephemeris,
+    ///   [`crate::jplephem::geocentric_state`]
+    pub fn moon_relative(&self) -> SKResult<na::Vector6<f64>> {
+        let (pmoon, vmoon) =
+            crate::jplephem::geocentric_state(crate::SolarSystem::Moon, &self.time)?;
+        let pos = self.pos_gcrf() - pmoon;
+        let vel = self.vel_gcrf() - vmoon;
+        Ok(na::Vector6::<f64>::new(pos[0], pos[1], pos[2], vel[0], vel[1], vel[2]))
+    }
+
     pub fn cov(&self) -> StateCov {
         self.cov.clone()
     }
 
+    /// Set uncertainty in the consider parameters
+    ///
+    /// Sensitivity of the state to the consider parameters is
+    /// reset to zero, i.e., the parameter uncertainty contributes
+    /// to the state uncertainty only as the state is propagated
+    ///
+    /// # Arguments
+    ///
+    /// * `cov` - Covariance of coefficient of drag times area over mass, and
+    ///   coefficient of radiation pressure times area over mass, (m^2/kg)^2
+    pub fn set_consider_cov(&mut self, cov: &ConsiderParamCovType) {
+        self.consider = Some(ConsiderCov {
+            cov: *cov,
+            sensitivity: na::SMatrix::<f64, 6, NUM_CONSIDER_PARAMS>::zeros(),
+        });
+    }
+
+    /// Contribution of the consider parameters to the
+    /// 6x6 position & velocity covariance, or None if not set
+    pub fn consider_cov(&self) -> Option<PVCovType> {
+        self.consider.as_ref().map(|c| c.pv_cov())
+    }
+
+    /// Total 6x6 position & velocity covariance: the state covariance
+    /// plus the contribution of the consider parameters, in the frame
+    /// given by `cov_frame`
+    ///
+    /// Returns None if neither is set
+    pub fn total_cov(&self) -> Option<PVCovType> {
+        match (&self.cov, self.consider_cov()) {
+            (StateCov::None, None) => None,
+            (StateCov::PVCov(cov), None) => Some(*cov),
+            (StateCov::None, Some(ccov)) => Some(ccov),
+            (StateCov::PVCov(cov), Some(ccov)) => Some(cov + ccov),
+        }
+    }
+
+    /// Mahalanobis distance of a "truth" position & velocity from this
+    /// state, using the total covariance (see [`SatState::total_cov`])
+    ///
+    /// This is the number of standard deviations of the difference, for
+    /// use in e.g. covariance realism analysis
+    ///
+    /// # Arguments
+    ///
+    /// * `truth_pv` - Truth GCRF position (meters) & velocity (meters / second)
+    ///
+    /// # Returns
+    ///
+    /// * Mahalanobis distance, or error if there is no covariance or the
+    ///   covariance is singular
+    pub fn mahalanobis(&self, truth_pv: &na::Vector6<f64>) -> SKResult<f64> {
+        if self.cov_frame != CovFrame::GCRF {
+            return self.in_frame(CovFrame::GCRF).mahalanobis(truth_pv);
+        }
+        let Some(cov) = self.total_cov() else {
+            return crate::skerror!("State has no covariance");
+        };
+        let Some(chol) = na::Cholesky::new(cov) else {
+            return crate::skerror!("Covariance is singular or not positive definite");
+        };
+        let d = truth_pv - self.pv;
+        Ok(d.dot(&chol.solve(&d)).sqrt())
+    }
+
+    /// Check if a "truth" position & velocity is within the
+    /// `sigma`-standard-deviation ellipsoid of this state
+    ///
+    /// For a Gaussian error, the squared Mahalanobis distance has a
+    /// chi-square distribution with 6 degrees of freedom, so the truth is
+    /// within the 1-sigma ellipsoid with probability 1.4%, and within the
+    /// 3-sigma ellipsoid with probability 82.6%
+    ///
+    /// # Arguments
+    ///
+    /// * `truth_pv` - Truth GCRF position (meters) & velocity (meters / second)
+    /// * `sigma` - Size of ellipsoid, in standard deviations
+    ///
+    /// # Returns
+    ///
+    /// * True if within the ellipsoid, or error if there is no covariance
+    ///   or the covariance is singular
+    pub fn within_ellipsoid(&self, truth_pv: &na::Vector6<f64>, sigma: f64) -> SKResult<bool> {
+        Ok(self.mahalanobis(truth_pv)? <= sigma)
+    }
+
+    /// Position error ellipse, at a given confidence level, in a plane
+    /// of the RIC (radial, in-track, cross-track) frame, e.g. for plotting
+    ///
+    /// The ellipse axes are along the eigenvectors of the 2x2 position
+    /// covariance in the plane, with lengths equal to the square root of
+    /// the eigenvalues scaled by the square root of the chi-square quantile
+    /// for 2 degrees of freedom, -2 ln(1 - confidence)
+    ///
+    /// # Arguments
+    ///
+    /// * `plane` - Plane of the RIC frame
+    /// * `confidence` - Probability, in (0, 1), that the position error
+    ///   in the plane is within the ellipse, e.g. 0.95.  The 1-sigma
+    ///   ellipse has probability 1 - exp(-1/2), approx. 0.393
+    ///
+    /// # Returns
+    ///
+    /// * Error ellipse, using the total covariance (see
+    ///   [`SatState::total_cov`]).  Error if there is no covariance, the
+    ///   confidence is out of range, or the covariance in the plane is not
+    ///   positive semi-definite
+    ///
+    /// # Notes
+    ///
+    /// * A degenerate covariance (zero uncertainty along a direction in
+    ///   the plane) gives a semi-minor axis of zero; with zero uncertainty
+    ///   in the plane both axes and the angle are zero
+    pub fn position_error_ellipse(
+        &self,
+        plane: RicPlane,
+        confidence: f64,
+    ) -> SKResult<ErrorEllipse> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return crate::skerror!("Confidence must be between 0 and 1");
+        }
+        let Some(cov) = self.in_frame(CovFrame::RIC).total_cov() else {
+            return crate::skerror!("State has no covariance");
+        };
+        let (ix, iy) = plane.axes();
+        let (a, b, c) = (cov[(ix, ix)], cov[(ix, iy)], cov[(iy, iy)]);
+
+        // Eigenvalues of the symmetric 2x2 covariance
+        let mean = 0.5 * (a + c);
+        let rad = (0.5 * (a - c)).hypot(b);
+        let (lmax, lmin) = (mean + rad, mean - rad);
+        if lmin < -1.0e-12 * lmax.abs().max(f64::MIN_POSITIVE) {
+            return crate::skerror!("Covariance is not positive semi-definite");
+        }
+        let angle = match rad > 0.0 {
+            true => 0.5 * (2.0 * b).atan2(a - c),
+            false => 0.0,
+        };
+
+        let scale = (-2.0 * (-confidence).ln_1p()).sqrt();
+        Ok(ErrorEllipse {
+            semi_major: scale * lmax.max(0.0).sqrt(),
+            semi_minor: scale * lmin.max(0.0).sqrt(),
+            angle: match angle <= -std::f64::consts::FRAC_PI_2 {
+                true => angle + std::f64::consts::PI,
+                false => angle,
+            },
+        })
+    }
+
     /// Set position uncertainty (1-sigma, meters) in the
     /// lvlh (local-vertical, local-horizontal) frame
     ///
+    /// The covariance is stored in the GCRF, i.e. `cov_frame` is set to
+    /// [`CovFrame::GCRF`], and any consider sensitivity is rotated to the GCRF
+    ///
     /// # Arguments
     ///
     /// * `sigma_lvlh` - 3-vector with 1-sigma position uncertainty in LVLH frame
     ///
     pub fn set_lvlh_pos_uncertainty(&mut self, sigma_lvlh: &na::Vector3<f64>) {
+        self.cov_frame_to_gcrf();
         let dcm = self.qgcrf2lvlh().to_rotation_matrix();
 
         let mut pcov = na::Matrix3::<f64>::zeros();
@@ -111,11 +516,15 @@ impl SatState {
     /// Set velocity uncertainty (1-sigma, meters/second) in the
     /// lvlh (local-vertical, local-horizontal) frame
     ///
+    /// The covariance is stored in the GCRF, i.e. `cov_frame` is set to
+    /// [`CovFrame::GCRF`], and any consider sensitivity is rotated to the GCRF
+    ///
     /// # Arguments
     ///
     /// * `sigma_lvlh` - 3-vector with 1-sigma velocity uncertainty in LVLH frame
     ///
     pub fn set_lvlh_vel_uncertainty(&mut self, sigma_lvlh: &na::Vector3<f64>) {
+        self.cov_frame_to_gcrf();
         let dcm = self.qgcrf2lvlh().to_rotation_matrix();
 
         let mut pcov = na::Matrix3::<f64>::zeros();
@@ -130,11 +539,15 @@ impl SatState {
     /// Set position uncertainty (1-sigma, meters) in the
     /// gcrf (Geocentric Celestial Reference Frame)
     ///
+    /// The covariance is stored in the GCRF, i.e. `cov_frame` is set to
+    /// [`CovFrame::GCRF`], and any consider sensitivity is rotated to the GCRF
+    ///
     /// # Arguments
     ///
     /// * `sigma_gcrf` - 3-vector with 1-sigma position uncertainty in GCRF frame    
     ///
     pub fn set_gcrf_pos_uncertainty(&mut self, sigma_cart: &na::Vector3<f64>) {
+        self.cov_frame_to_gcrf();
         self.cov = StateCov::PVCov({
             let mut m = PVCovType::zeros();
             let mut diag = na::Vector3::<f64>::zeros();
@@ -145,17 +558,21 @@ impl SatState {
             pcov.set_diagonal(&diag);
             m.fixed_view_mut::<3, 3>(0, 0).copy_from(&pcov);
             m
-        })
+        });
     }
 
     /// Set velocity uncertainty (1-sigma, meters / second) in the
     /// gcrf (Geocentric Celestial Reference Frame)
     ///
+    /// The covariance is stored in the GCRF, i.e. `cov_frame` is set to
+    /// [`CovFrame::GCRF`], and any consider sensitivity is rotated to the GCRF
+    ///
     /// # Arguments
     ///
     /// * `sigma_gcrf` - 3-vector with 1-sigma velocity uncertainty in GCRF frame    
     ///
     pub fn set_gcrf_vel_uncertainty(&mut self, sigma_cart: &na::Vector3<f64>) {
+        self.cov_frame_to_gcrf();
         self.cov = StateCov::PVCov({
             let mut m = PVCovType::zeros();
             let mut diag = na::Vector3::<f64>::zeros();
@@ -166,7 +583,7 @@ impl SatState {
             pcov.set_diagonal(&diag);
             m.fixed_view_mut::<3, 3>(3, 3).copy_from(&pcov);
             m
-        })
+        });
     }
 
     ///
@@ -188,45 +605,160 @@ impl SatState {
     ) -> SKResult<Self> {
         let default = orbitprop::PropSettings::default();
         let settings = option_settings.unwrap_or(&default);
-        match self.cov {
+        self.propagate_with_precomputed(time, settings, settings.precomputed.as_ref(), None)
+    }
+
+    /// Propagate state to a new time, and also return the state
+    /// transition matrix
+    ///
+    /// The state transition matrix is computed whether or not the
+    /// covariance is set, e.g. to map a covariance that is only known later
+    ///
+    /// # Arguments:
+    ///
+    /// * `time` - Time for which to compute new state
+    /// * `settings` - Settings for the propagator
+    ///
+    /// # Returns:
+    ///
+    /// * Tuple with:
+    ///   * New satellite state, as returned by [`Self::propagate`]
+    ///   * 6x6 state transition matrix of position & velocity, in the
+    ///     GCRF frame, from the current time to the new time
+    ///
+    pub fn propagate_with_stm(
+        &self,
+        time: &Instant,
+        option_settings: Option<&PropSettings>,
+    ) -> SKResult<(Self, na::Matrix6<f64>)> {
+        let default = orbitprop::PropSettings::default();
+        let settings = option_settings.unwrap_or(&default);
+        self.propagate_stm_with_precomputed(time, settings, settings.precomputed.as_ref(), None)
+    }
+
+    /// Propagate state to a new time using the given precomputed
+    /// terms and satellite properties
+    pub(crate) fn propagate_with_precomputed(
+        &self,
+        time: &Instant,
+        settings: &PropSettings,
+        precomputed: Option<&orbitprop::Precomputed>,
+        satprops: Option<&dyn orbitprop::SatProperties>,
+    ) -> SKResult<Self> {
+        match (&self.consider, &self.cov) {
             // Simple case: do not compute state transition matrix, since covariance is not set
-            StateCov::None => {
-                let res = orbitprop::propagate(&self.pv, &self.time, time, settings, None)?;
+            (None, StateCov::None) => {
+                let res = orbitprop::propagator::propagate_with_precomputed(
+                    &self.pv,
+                    &self.time,
+                    time,
+                    settings,
+                    precomputed,
+                    satprops,
+                )?;
                 Ok(Self {
                     time: *time,
                     pv: res.state_end,
                     cov: StateCov::None,
+                    consider: None,
+                    cov_frame: self.cov_frame,
                 })
             }
             // Compute state transition matrix & propagate covariance as well
-            StateCov::PVCov(cov) => {
-                let mut state = na::SMatrix::<f64, 6, 7>::zeros();
+            _ => Ok(self
+                .propagate_stm_with_precomputed(time, settings, precomputed, satprops)?
+                .0),
+        }
+    }
 
-                // First row of state is 6-element position & velocity
-                state.fixed_view_mut::<6, 1>(0, 0).copy_from(&self.pv);
+    /// Propagate state & state transition matrix to a new time using
+    /// the given precomputed terms and satellite properties
+    fn propagate_stm_with_precomputed(
+        &self,
+        time: &Instant,
+        settings: &PropSettings,
+        precomputed: Option<&orbitprop::Precomputed>,
+        satprops: Option<&dyn orbitprop::SatProperties>,
+    ) -> SKResult<(Self, na::Matrix6<f64>)> {
+        // Covariance in a local frame: propagate in the gcrf, and express
+        // the result in the same local frame at the new time
+        if self.cov_frame != CovFrame::GCRF {
+            let (state, phi) = self
+                .in_frame(CovFrame::GCRF)
+                .propagate_stm_with_precomputed(time, settings, precomputed, satprops)?;
+            return Ok((state.in_frame(self.cov_frame), phi));
+        }
 
-                // See equation 7.42 of Montenbruck & Gill
-                // State transition matrix initializes to identity matrix
-                // State transition matrix is columns 1-7 of state (0-based)
-                state
-                    .fixed_view_mut::<6, 6>(0, 1)
-                    .copy_from(&na::Matrix6::<f64>::identity());
+        // Consider parameters set: also propagate sensitivity to them
+        if let Some(consider) = &self.consider {
+            let mut state = orbitprop::ConsiderCovState::zeros();
+            state.fixed_view_mut::<6, 1>(0, 0).copy_from(&self.pv);
+            state
+                .fixed_view_mut::<6, 6>(0, 1)
+                .copy_from(&na::Matrix6::<f64>::identity());
+            state
+                .fixed_view_mut::<6, NUM_CONSIDER_PARAMS>(0, 7)
+                .copy_from(&consider.sensitivity);
 
-                // Propagate
-                let res = orbitprop::propagate(&state, &self.time, time, settings, None)?;
+            let res = orbitprop::propagator::propagate_with_precomputed(
+                &state, &self.time, time, settings, precomputed, satprops,
+            )?;
+            let phi: na::Matrix6<f64> = res.state_end.fixed_view::<6, 6>(0, 1).into();
 
-                Ok(Self {
+            return Ok((
+                Self {
                     time: *time,
                     pv: res.state_end.fixed_view::<6, 1>(0, 0).into(),
-                    cov: {
-                        // Extract state transition matrix from the propagated state
-                        let phi = res.state_end.fixed_view::<6, 6>(0, 1);
-                        // Evolve the covariance
-                        StateCov::PVCov(phi * cov * phi.transpose())
+                    cov: match self.cov {
+                        StateCov::None => StateCov::None,
+                        StateCov::PVCov(cov) => StateCov::PVCov(phi * cov * phi.transpose()),
                     },
-                })
-            }
+                    consider: Some(ConsiderCov {
+                        cov: consider.cov,
+                        sensitivity: res
+                            .state_end
+                            .fixed_view::<6, NUM_CONSIDER_PARAMS>(0, 7)
+                            .into(),
+                    }),
+                    cov_frame: CovFrame::GCRF,
+                },
+                phi,
+            ));
         }
+
+        let mut state = na::SMatrix::<f64, 6, 7>::zeros();
+
+        // First row of state is 6-element position & velocity
+        state.fixed_view_mut::<6, 1>(0, 0).copy_from(&self.pv);
+
+        // See equation 7.42 of Montenbruck & Gill
+        // State transition matrix initializes to identity matrix
+        // State transition matrix is columns 1-7 of state (0-based)
+        state
+            .fixed_view_mut::<6, 6>(0, 1)
+            .copy_from(&na::Matrix6::<f64>::identity());
+
+        // Propagate
+        let res = orbitprop::propagator::propagate_with_precomputed(
+            &state, &self.time, time, settings, precomputed, satprops,
+        )?;
+
+        // Extract state transition matrix from the propagated state
+        let phi: na::Matrix6<f64> = res.state_end.fixed_view::<6, 6>(0, 1).into();
+        Ok((
+            Self {
+                time: *time,
+                pv: res.state_end.fixed_view::<6, 1>(0, 0).into(),
+                cov: match self.cov {
+                    StateCov::None => StateCov::None,
+                    // Evolve the covariance
+                    StateCov::PVCov(cov) => StateCov::PVCov(phi * cov * phi.transpose()),
+                },
+                consider: None,
+                cov_frame: CovFrame::GCRF,
+            },
+            phi,
+        ))
     }
 }
 
@@ -242,15 +774,26 @@ impl std::fmt::Display for SatState {
         match self.cov {
             StateCov::None => {}
             StateCov::PVCov(cov) => {
+                let frame = self.cov_frame;
                 s1.push_str(
                     format!(
                         r#"
+      Covariance Frame: {frame:?}
             Covariance: {cov:+8.2e}"#
                     )
                     .as_str(),
                 );
             }
         }
+        if let Some(ccov) = self.consider_cov() {
+            s1.push_str(
+                format!(
+                    r#"
+   Consider Covariance: {ccov:+8.2e}"#
+                )
+                .as_str(),
+            );
+        }
         write!(f, "{}", s1)
     }
 }
@@ -261,6 +804,77 @@ mod test {
     use crate::consts;
     use approx::{assert_abs_diff_eq, assert_relative_eq};
 
+    #[test]
+    fn test_relative_state() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let r = consts::EARTH_RADIUS + 500.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let pos = na::Vector3::<f64>::new(r * 0.6, r * 0.8, 0.0);
+        // Velocity perpendicular to position: circular, inclined orbit
+        let vel = na::Vector3::<f64>::new(-0.8, 0.6, 0.5).normalize() * v;
+        let chief = SatState::from_pv(&time, &pos, &vel);
+
+        // Same circular orbit, leading by a small angle: offset is
+        // in-track (and slightly radial), with no relative velocity
+        // in the rotating frame
+        let angle = 1.0e-4;
+        let h = pos.cross(&vel).normalize();
+        let rot = na::Rotation3::from_axis_angle(&na::Unit::new_normalize(h), angle);
+        let deputy = SatState::from_pv(&time, &(rot * pos), &(rot * vel));
+        let rel = chief.relative_state(&deputy)?;
+        assert_relative_eq!(rel[0], r * (angle.cos() - 1.0), max_relative = 1.0e-6);
+        assert_relative_eq!(rel[1], r * angle.sin(), max_relative = 1.0e-9);
+        assert_abs_diff_eq!(rel[2], 0.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(rel.fixed_rows::<3>(3).norm(), 0.0, epsilon = 1.0e-9);
+
+        // Radial & cross-track offsets, with an along-track velocity
+        let c = pos.cross(&vel).normalize();
+        let deputy = SatState::from_pv(
+            &time,
+            &(pos + 10.0 * pos.normalize() + 5.0 * c),
+            &(vel + 0.1 * vel.normalize()),
+        );
+        let rel = chief.relative_state(&deputy)?;
+        assert_abs_diff_eq!(rel[0], 10.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(rel[1], 0.0, epsilon = 1.0e-6);
+        assert_abs_diff_eq!(rel[2], 5.0, epsilon = 1.0e-6);
+        let n = v / r;
+        assert_abs_diff_eq!(rel[3], 0.0, epsilon = 1.0e-9);
+        assert_abs_diff_eq!(rel[4], 0.1 - n * 10.0, epsilon = 1.0e-9);
+
+        // Times must match
+        let later = SatState::from_pv(&(time + crate::Duration::from_seconds(1.0)), &pos, &vel);
+        assert!(chief.relative_state(&later).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_moon_relative() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 3, 1, 0, 0, 0.0);
+        let r = crate::consts::EARTH_RADIUS + 500.0e3;
+        let v = (crate::consts::MU_EARTH / r).sqrt();
+        let pos = na::vector![r, 0.0, 0.0];
+        let vel = na::vector![0.0, v, 0.0];
+        let state = SatState::from_pv(&time, &pos, &vel);
+        let rel = state.moon_relative()?;
+
+        // LEO satellite is within an Earth-Moon distance, plus or minus
+        // its orbit radius, of the Moon
+        let (pmoon, vmoon) = crate::jplephem::geocentric_state(crate::SolarSystem::Moon, &time)?;
+        let dist = rel.fixed_rows::<3>(0).norm();
+        assert!(dist > 3.5e8 && dist < 4.1e8);
+        assert!((dist - pmoon.norm()).abs() <= r);
+        // Consistent with the low-precision ephemeris (accurate to a
+        // fraction of a degree)
+        let lp = crate::lpephem::moon::pos_gcrf(&time);
+        assert!((pos - rel.fixed_rows::<3>(0) - lp).norm() < 1.0e-2 * lp.norm());
+
+        // Velocity includes that of the Moon, about 1 km/s
+        assert!(vmoon.norm() > 900.0 && vmoon.norm() < 1.1e3);
+        assert!((vel - rel.fixed_rows::<3>(3) - vmoon).norm() < 1.0e-9);
+        Ok(())
+    }
+
     #[test]
     fn test_qgcrf2lvlh() -> SKResult<()> {
         let satstate = SatState::from_pv(
@@ -284,6 +898,88 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_in_frame() -> SKResult<()> {
+        let r = consts::EARTH_RADIUS + 500.0e3;
+        let v = (consts::MU_EARTH / r).sqrt();
+        let mut state = SatState::from_pv(
+            &Instant::from_datetime(2024, 1, 1, 0, 0, 0.0),
+            &na::vector![r * 0.6, r * 0.8, 0.0],
+            &na::vector![-v * 0.8 * 0.5, v * 0.6 * 0.5, v * 0.75_f64.sqrt()],
+        );
+
+        // Without covariance, only the frame tag changes
+        let lvlh = state.in_frame(CovFrame::LVLH);
+        assert_eq!(lvlh.cov_frame, CovFrame::LVLH);
+        assert_eq!(lvlh.pv, state.pv);
+        assert!(matches!(lvlh.cov, StateCov::None));
+
+        let l = na::Matrix6::<f64>::from_fn(|i, j| ((i * 7 + j * 3) % 5) as f64 - 2.0);
+        let cov = l * l.transpose() + na::Matrix6::<f64>::identity();
+        state.set_cov(StateCov::PVCov(cov));
+        state.set_consider_cov(&na::Matrix2::<f64>::identity());
+        state.consider.as_mut().unwrap().sensitivity =
+            na::SMatrix::<f64, 6, NUM_CONSIDER_PARAMS>::from_fn(|i, j| (i + 2 * j) as f64);
+
+        // Round trip GCRF -> LVLH -> GCRF
+        let back = state.in_frame(CovFrame::LVLH).in_frame(CovFrame::GCRF);
+        assert_eq!(back.cov_frame, CovFrame::GCRF);
+        assert_eq!(back.pv, state.pv);
+        let StateCov::PVCov(cov_back) = back.cov else {
+            return crate::skerror!("Covariance lost in frame transform");
+        };
+        assert_abs_diff_eq!(cov_back, cov, epsilon = 1.0e-10);
+        assert_abs_diff_eq!(
+            back.consider_cov().unwrap(),
+            state.consider_cov().unwrap(),
+            epsilon = 1.0e-10
+        );
+
+        // Radial variance in RIC is the variance along the position
+        let ric = state.in_frame(CovFrame::RIC);
+        let StateCov::PVCov(cov_ric) = ric.cov else {
+            return crate::skerror!("Covariance lost in frame transform");
+        };
+        let rhat = state.pos_gcrf().normalize();
+        let prad = rhat.transpose() * cov.fixed_view::<3, 3>(0, 0) * rhat;
+        assert_relative_eq!(cov_ric[(0, 0)], prad[(0, 0)], max_relative = 1.0e-12);
+
+        // LVLH axes are (in-track, -cross-track, -radial), so variances
+        // match RIC variances, permuted; LVLH -> RIC directly is the same
+        // as through the GCRF
+        let StateCov::PVCov(cov_lvlh) = state.in_frame(CovFrame::LVLH).cov else {
+            return crate::skerror!("Covariance lost in frame transform");
+        };
+        for (il, ir) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)] {
+            assert_relative_eq!(cov_lvlh[(il, il)], cov_ric[(ir, ir)], max_relative = 1.0e-9);
+        }
+        let StateCov::PVCov(cov_ric2) = state.in_frame(CovFrame::LVLH).in_frame(CovFrame::RIC).cov
+        else {
+            return crate::skerror!("Covariance lost in frame transform");
+        };
+        assert_abs_diff_eq!(cov_ric2, cov_ric, epsilon = 1.0e-10);
+
+        // Mahalanobis distance does not depend on covariance frame
+        let truth = state.pv + na::Vector6::new(1.0, -2.0, 0.5, 0.01, 0.02, -0.03);
+        assert_relative_eq!(
+            ric.mahalanobis(&truth)?,
+            state.mahalanobis(&truth)?,
+            max_relative = 1.0e-9
+        );
+
+        // Setting uncertainty puts the covariance in the GCRF, with the
+        // consider sensitivity rotated to match
+        let mut reset = ric.clone();
+        reset.set_lvlh_pos_uncertainty(&na::vector![1.0, 2.0, 3.0]);
+        assert_eq!(reset.cov_frame, CovFrame::GCRF);
+        assert_abs_diff_eq!(
+            reset.consider_cov().unwrap(),
+            state.consider_cov().unwrap(),
+            epsilon = 1.0e-10
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_satstate() -> SKResult<()> {
         let mut satstate = SatState::from_pv(
@@ -316,6 +1012,195 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_mahalanobis() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let mut state = SatState::from_pv(
+            &time,
+            &na::vector![consts::GEO_R, 0.0, 0.0],
+            &na::vector![0.0, (consts::MU_EARTH / consts::GEO_R).sqrt(), 0.0],
+        );
+        let truth = state.pv + na::vector![0.0, 3.0, 0.0, 0.0, 0.0, 0.0];
+        assert!(state.mahalanobis(&truth).is_err());
+
+        // Truth at exactly 1 sigma along one axis
+        let sigma = na::vector![1.0, 3.0, 5.0, 0.01, 0.02, 0.03];
+        state.set_cov(StateCov::PVCov(na::Matrix6::from_diagonal(
+            &sigma.component_mul(&sigma),
+        )));
+        assert_relative_eq!(state.mahalanobis(&truth)?, 1.0, epsilon = 1.0e-12);
+        assert!(state.within_ellipsoid(&truth, 1.5)?);
+        assert!(!state.within_ellipsoid(&(state.pv + (truth - state.pv) * 2.0), 1.5)?);
+
+        // Correlated covariance matches explicit inverse
+        let a = na::Matrix6::<f64>::from_fn(|r, c| ((r * 7 + c * 3) % 5) as f64 + 0.1);
+        let cov = a * a.transpose() + na::Matrix6::identity();
+        state.set_cov(StateCov::PVCov(cov));
+        let d = na::vector![1.0, -2.0, 0.5, 0.3, -0.1, 0.2];
+        let expected = (d.transpose() * cov.try_inverse().unwrap() * d)[0].sqrt();
+        assert_relative_eq!(state.mahalanobis(&(state.pv + d))?, expected, max_relative = 1.0e-10);
+
+        // Singular covariance
+        state.set_cov(StateCov::PVCov(na::Matrix6::from_diagonal(&na::vector![
+            1.0, 1.0, 0.0, 1.0, 1.0, 1.0
+        ])));
+        assert!(state.mahalanobis(&truth).is_err());
+        assert!(state.within_ellipsoid(&truth, 3.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_error_ellipse() -> SKResult<()> {
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let mut state = SatState::from_pv(
+            &time,
+            &na::vector![consts::GEO_R, 0.0, 0.0],
+            &na::vector![0.0, (consts::MU_EARTH / consts::GEO_R).sqrt(), 0.0],
+        );
+        assert!(state.position_error_ellipse(RicPlane::RadialInTrack, 0.95).is_err());
+
+        // Correlated radial & in-track covariance; GCRF axes are aligned
+        // with RIC for this state
+        let mut cov = na::Matrix6::<f64>::from_diagonal(&na::vector![
+            4.0, 25.0, 9.0, 1.0e-4, 1.0e-4, 1.0e-4
+        ]);
+        cov[(0, 1)] = 6.0;
+        cov[(1, 0)] = 6.0;
+        state.set_cov(StateCov::PVCov(cov));
+        let eig = na::Matrix2::<f64>::new(4.0, 6.0, 6.0, 25.0).symmetric_eigen();
+        let (lmin, lmax) = (eig.eigenvalues.min(), eig.eigenvalues.max());
+
+        // 1-sigma ellipse has axes equal to the square root of the eigenvalues
+        let one_sigma = 1.0 - (-0.5_f64).exp();
+        let e1 = state.position_error_ellipse(RicPlane::RadialInTrack, one_sigma)?;
+        assert_relative_eq!(e1.semi_major, lmax.sqrt(), max_relative = 1.0e-12);
+        assert_relative_eq!(e1.semi_minor, lmin.sqrt(), max_relative = 1.0e-12);
+        let imax = eig.eigenvalues.imax();
+        let v = eig.eigenvectors.column(imax);
+        assert_relative_eq!(e1.angle.tan(), v[1] / v[0], max_relative = 1.0e-10);
+        assert!(e1.angle > 0.0 && e1.angle < std::f64::consts::FRAC_PI_2);
+
+        // 95% ellipse is larger by the square root of the chi-square
+        // quantile for 2 degrees of freedom, 5.991
+        let e95 = state.position_error_ellipse(RicPlane::RadialInTrack, 0.95)?;
+        let k = 5.991_f64.sqrt();
+        assert_relative_eq!(e95.semi_major / e1.semi_major, k, max_relative = 1.0e-4);
+        assert_relative_eq!(e95.semi_minor / e1.semi_minor, k, max_relative = 1.0e-4);
+        assert_eq!(e95.angle, e1.angle);
+
+        // Uncorrelated plane, with the larger variance along the second axis
+        let e = state.position_error_ellipse(RicPlane::RadialCrossTrack, one_sigma)?;
+        assert_relative_eq!(e.semi_major, 3.0, max_relative = 1.0e-12);
+        assert_relative_eq!(e.semi_minor, 2.0, max_relative = 1.0e-12);
+        assert_relative_eq!(e.angle, std::f64::consts::FRAC_PI_2);
+
+        // Degenerate covariance: zero cross-track uncertainty
+        cov[(2, 2)] = 0.0;
+        state.set_cov(StateCov::PVCov(cov));
+        let e = state.position_error_ellipse(RicPlane::InTrackCrossTrack, one_sigma)?;
+        assert_relative_eq!(e.semi_major, 5.0, max_relative = 1.0e-12);
+        assert_eq!(e.semi_minor, 0.0);
+        assert_eq!(e.angle, 0.0);
+
+        // Not positive semi-definite, and invalid confidence
+        cov[(2, 2)] = -1.0;
+        state.set_cov(StateCov::PVCov(cov));
+        assert!(state.position_error_ellipse(RicPlane::InTrackCrossTrack, 0.95).is_err());
+        assert!(state.position_error_ellipse(RicPlane::RadialInTrack, 1.0).is_err());
+        assert!(state.position_error_ellipse(RicPlane::RadialInTrack, 0.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_rate() {
+        // Geostationary satellite has ~zero range-rate from any site
+        let time = Instant::from_datetime(2024, 3, 20, 12, 0, 0.0);
+        let geo = ITRFCoord::from_geodetic_deg(0.0, 30.0, consts::GEO_R - consts::EARTH_RADIUS);
+        let q = crate::frametransform::qitrf2gcrf(&time);
+        let omega = na::vector![0.0, 0.0, consts::OMEGA_EARTH];
+        let state = SatState::from_pv(&time, &(q * geo.itrf), &(q * omega.cross(&geo.itrf)));
+        for (lat, lon) in [(0.0, 30.0), (42.0, -10.0), (-35.0, 80.0)] {
+            let rr = state.range_rate(&ITRFCoord::from_geodetic_deg(lat, lon, 0.0));
+            assert!(rr.range_rate.abs() < 1.0e-6);
+            assert!(rr.vel_enu.norm() < 1.0e-6);
+        }
+
+        // Partials with respect to GCRF position & velocity
+        // match finite differences
+        let site = ITRFCoord::from_geodetic_deg(42.0, -71.0, 0.0);
+        let pos = q * ITRFCoord::from_geodetic_deg(40.0, -65.0, 500.0e3).itrf;
+        let vel = q * na::vector![-3000.0, 6000.0, 3000.0];
+        let rr0 = SatState::from_pv(&time, &pos, &vel).range_rate(&site);
+        for ix in 0..3 {
+            let mut d = na::Vector3::<f64>::zeros();
+            d[ix] = 1.0;
+            let rrp = SatState::from_pv(&time, &(pos + d), &vel).range_rate(&site);
+            assert_abs_diff_eq!(
+                rrp.range_rate - rr0.range_rate,
+                rr0.drange_rate_dpos[ix],
+                epsilon = 1.0e-7
+            );
+            let rrv = SatState::from_pv(&time, &pos, &(vel + d)).range_rate(&site);
+            assert_abs_diff_eq!(
+                rrv.range_rate - rr0.range_rate,
+                rr0.drange_rate_dvel[ix],
+                epsilon = 1.0e-7
+            );
+        }
+    }
+
+    #[test]
+    fn test_consider_drag() -> SKResult<()> {
+        // Low-Earth orbit, where drag matters
+        let r = consts::EARTH_RADIUS + 400.0e3;
+        let mut satstate = SatState::from_pv(
+            &Instant::from_datetime(2015, 3, 20, 0, 0, 0.0),
+            &na::vector![r, 0.0, 0.0],
+            &na::vector![0.0, (consts::MU_EARTH / r).sqrt(), 0.0],
+        );
+        satstate.set_lvlh_pos_uncertainty(&na::vector![1.0, 1.0, 1.0]);
+
+        // 10% uncertainty in Cd A / m of 0.01 m^2/kg
+        let mut cstate = satstate.clone();
+        cstate.set_consider_cov(&na::matrix![1.0e-6, 0.0; 0.0, 0.0]);
+        assert_eq!(cstate.consider_cov(), Some(PVCovType::zeros()));
+
+        // Along-track position variance from the consider parameters,
+        // in the LVLH frame (x axis is along track)
+        let along_var = |s: &SatState| -> f64 {
+            let dcm = s.qgcrf2lvlh().to_rotation_matrix();
+            let ccov = s.consider_cov().unwrap();
+            let pcov = dcm * ccov.fixed_view::<3, 3>(0, 0) * dcm.transpose();
+            pcov[(0, 0)]
+        };
+
+        let t1 = satstate.time + crate::Duration::from_hours(6.0);
+        let t2 = satstate.time + crate::Duration::from_hours(24.0);
+        let s1 = cstate.propagate(&t1, None)?;
+        let s2 = s1.propagate(&t2, None)?;
+        let var1 = along_var(&s1);
+        let var2 = along_var(&s2);
+        assert!(var1 > 0.0);
+        assert!(var2 > var1);
+
+        // State covariance is unchanged by consider parameters,
+        // and the total includes the consider contribution
+        let nocon = satstate.propagate(&t2, None)?;
+        let (cov_nocon, cov_con) = match (nocon.cov(), s2.cov()) {
+            (StateCov::PVCov(a), StateCov::PVCov(b)) => (a, b),
+            _ => return crate::skerror!("cov is not set"),
+        };
+        assert_relative_eq!(cov_nocon, cov_con, max_relative = 1.0e-6);
+        let total = s2.total_cov().unwrap();
+        assert_relative_eq!(total, cov_con + s2.consider_cov().unwrap());
+        let dcm = s2.qgcrf2lvlh().to_rotation_matrix();
+        let along_total = (dcm * total.fixed_view::<3, 3>(0, 0) * dcm.transpose())[(0, 0)];
+        let along_state = (dcm * cov_con.fixed_view::<3, 3>(0, 0) * dcm.transpose())[(0, 0)];
+        assert!(along_total > along_state);
+
+        Ok(())
+    }
+
     #[test]
     fn test_satcov() -> SKResult<()> {
         let mut satstate = SatState::from_pv(
@@ -330,4 +1215,57 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_propagate_with_stm() -> SKResult<()> {
+        let state = SatState::from_pv(
+            &Instant::from_datetime(2015, 3, 20, 0, 0, 0.0),
+            &na::vector![consts::EARTH_RADIUS + 600.0e3, 0.0, 0.0],
+            &na::vector![0.0, 5.0e3, 5.0e3],
+        );
+        let stop = state.time + crate::Duration::from_hours(3.0);
+
+        // State transition matrix is returned without covariance,
+        // and the automatic behavior is unchanged
+        let (s2, phi) = state.propagate_with_stm(&stop, None)?;
+        assert!(matches!(s2.cov, StateCov::None));
+        let s2_auto = state.propagate(&stop, None)?;
+        assert!(matches!(s2_auto.cov, StateCov::None));
+        assert!((s2.pv - s2_auto.pv).norm() < 1.0e-3);
+        assert!((phi - na::Matrix6::<f64>::identity()).norm() > 1.0);
+
+        // Mapping a covariance with the state transition matrix matches
+        // built-in covariance propagation
+        let mut with_cov = state.clone();
+        with_cov.set_gcrf_pos_uncertainty(&na::vector![10.0, 20.0, 30.0]);
+        with_cov.set_gcrf_vel_uncertainty(&na::vector![0.01, 0.02, 0.03]);
+        let pv_cov = |s: &SatState| match s.cov {
+            StateCov::PVCov(cov) => cov,
+            StateCov::None => panic!("covariance not set"),
+        };
+        let s2_cov = with_cov.propagate(&stop, None)?;
+        let mapped = phi * pv_cov(&with_cov) * phi.transpose();
+        assert!((pv_cov(&s2_cov) - mapped).norm() < 1.0e-9 * mapped.norm());
+
+        // Covariance is also propagated when set
+        let (s2_stm, phi2) = with_cov.propagate_with_stm(&stop, None)?;
+        assert_eq!(pv_cov(&s2_stm), pv_cov(&s2_cov));
+        assert_eq!(phi2, phi);
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_literal() {
+        // All fields are public, so states can be built directly
+        let time = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+        let state = SatState {
+            time,
+            pv: na::Vector6::<f64>::new(7.0e6, 0.0, 0.0, 0.0, 7.5e3, 0.0),
+            cov: StateCov::None,
+            consider: None,
+            cov_frame: CovFrame::default(),
+        };
+        assert_eq!(state.cov_frame, CovFrame::GCRF);
+        assert_eq!(state.pv, SatState::from_pv(&time, &state.pos_gcrf(), &state.vel_gcrf()).pv);
+    }
 }
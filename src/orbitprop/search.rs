@@ -0,0 +1,123 @@
+//! Root & maximum search over a time span, for finding events (e.g.
+//! crossings, close approaches & peaks) along a propagated arc
+
+use crate::SKResult;
+
+/// Sign change of a function, found by [`find_roots`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Root {
+    /// Time of the sign change, seconds from the start of the span
+    pub t: f64,
+    /// True if the function goes from negative to non-negative
+    pub rising: bool,
+}
+
+/// Times, in seconds from the start, at which `f` changes sign
+/// within `(0, span]`
+///
+/// `f` is sampled every (at most) `step` seconds, and each sign change
+/// between samples is refined by bisection to within `tol` seconds.
+/// Sign changes closer together than `step` may be missed
+pub(crate) fn find_roots<F>(f: &F, span: f64, step: f64, tol: f64) -> SKResult<Vec<Root>>
+where
+    F: Fn(f64) -> SKResult<f64>,
+{
+    let nscan = (span / step).ceil().max(1.0) as usize;
+    let dt = span / nscan as f64;
+    let mut result = Vec::new();
+    let mut neg0 = f(0.0)? < 0.0;
+    for ix in 1..=nscan {
+        let t1 = ix as f64 * dt;
+        let neg1 = f(t1)? < 0.0;
+        if neg0 != neg1 {
+            let (mut lo, mut hi) = ((ix - 1) as f64 * dt, t1);
+            while hi - lo > tol {
+                let mid = 0.5 * (lo + hi);
+                match (f(mid)? < 0.0) == neg0 {
+                    true => lo = mid,
+                    false => hi = mid,
+                }
+            }
+            result.push(Root {
+                t: 0.5 * (lo + hi),
+                rising: neg0,
+            });
+        }
+        neg0 = neg1;
+    }
+    Ok(result)
+}
+
+/// Maximum of `f` between `a` and `b`, which must bracket a single peak,
+/// refined by golden-section search to within `tol`
+///
+/// `b` may be less than `a`, e.g. for a backward-propagated arc.
+/// Returns the location of the maximum & the value of `f` there
+pub(crate) fn golden_max<F>(f: &F, mut a: f64, mut b: f64, tol: f64) -> SKResult<(f64, f64)>
+where
+    F: Fn(f64) -> SKResult<f64>,
+{
+    let gr = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut c = b - gr * (b - a);
+    let mut d = a + gr * (b - a);
+    let (mut fc, mut fd) = (f(c)?, f(d)?);
+    // Each iteration keeps one interior probe, which falls at the
+    // golden section of the narrowed bracket
+    while (b - a).abs() > tol {
+        if fc > fd {
+            (b, d, fd) = (d, c, fc);
+            c = b - gr * (b - a);
+            fc = f(c)?;
+        } else {
+            (a, c, fc) = (c, d, fd);
+            d = a + gr * (b - a);
+            fd = f(d)?;
+        }
+    }
+    let x = 0.5 * (a + b);
+    Ok((x, f(x)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_roots() -> SKResult<()> {
+        // Both directions of crossing, including one within
+        // the first scan interval
+        let f = |t: f64| -> SKResult<f64> { Ok((t - 0.5).sin()) };
+        let roots = find_roots(&f, 10.0, 2.0, 1.0e-6)?;
+        assert_eq!(roots.len(), 4);
+        for (ix, r) in roots.iter().enumerate() {
+            assert!((r.t - (0.5 + std::f64::consts::PI * ix as f64)).abs() < 1.0e-6);
+            assert_eq!(r.rising, ix % 2 == 0);
+        }
+
+        // Maximum below zero: no crossings
+        let f = |t: f64| -> SKResult<f64> { Ok(-0.1 - (t - 5.0).powi(2)) };
+        assert!(find_roots(&f, 10.0, 1.0, 1.0e-6)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_golden_max() -> SKResult<()> {
+        let count = std::cell::Cell::new(0);
+        let f = |t: f64| -> SKResult<f64> {
+            count.set(count.get() + 1);
+            Ok(2.0 - (t - 0.3).powi(2))
+        };
+        let (t, v) = golden_max(&f, -1.0, 2.0, 1.0e-6)?;
+        assert!((t - 0.3).abs() < 1.0e-6);
+        assert!((v - 2.0).abs() < 1.0e-12);
+        // One evaluation per iteration, plus the two initial probes
+        // and the final value
+        let niter = (3.0e6_f64.ln() / (2.0 / (5.0_f64.sqrt() - 1.0)).ln()).ceil() as usize;
+        assert_eq!(count.get(), niter + 3);
+
+        // Reversed bracket
+        let (t, _) = golden_max(&f, 2.0, -1.0, 1.0e-6)?;
+        assert!((t - 0.3).abs() < 1.0e-6);
+        Ok(())
+    }
+}
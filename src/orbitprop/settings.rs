@@ -1,9 +1,16 @@
 //! Orbit Propagation Settings
 
+use crate::consts::ConstantSet;
+use crate::earthgravity::TideSystem;
+use crate::orbitprop::EmpiricalAccel;
+use crate::orbitprop::ExtraAccel;
 use crate::orbitprop::Precomputed;
+use crate::skerror;
 use crate::Instant;
 use crate::SKResult;
 
+use std::borrow::Cow;
+
 /// Propagation settings
 ///
 /// These include
@@ -11,8 +18,44 @@ use crate::SKResult;
 /// * `gravity_order` - integer gravity order to use when computing Earth gravity.  Default is 4
 /// * `gravity_interp_dt_seconds` - Interpolation interval for rotation to ITRF frame for gravity calc.  Default is 60 seconds
 /// * `abs_error` - the maximum absolute error for the infinity norm of the state in Runga-Kutta integrator.  Default is 1e-8
+/// * `abs_error_vec` - Optional per-component absolute error, overriding `abs_error`.  Length is 6 (position & velocity;
+///   `abs_error` is then used for the state transition matrix, if present), or 42 (6x7 state with
+///   state transition matrix, column-major).  Default is None
 /// * `rel_error` - the maximum relative error for the infinity norm of the state in Runga-Kutta integrator.  Default is 1e-8
 /// * `use_spaceweather` -  Do we use space weather when computing the atmospheric density.  Default is true
+/// * `zonal_wind` - Constant horizontal zonal (east-west) thermospheric wind, in m/s, positive eastward,
+///   relative to the co-rotating atmosphere used for drag.  Default is 0
+/// * `ocean_tides` - Include ocean tide perturbations to the geopotential.  Ocean tide
+///   coefficient file "fes2004_Cnm-Snm.dat" is downloaded to the data directory if not
+///   present.  Default is false
+/// * `tide_system` - Permanent tide system of the Earth gravity model.  If set, C20 of the model
+///   is converted to this system (see [`crate::earthgravity::Gravity::with_tide_system`]).
+///   Default is None, using the model as distributed (tide-free for JGM3)
+/// * `pole_tide` - Include solid Earth and ocean pole tide perturbations to the geopotential.
+///   Uses Earth orientation parameters.  Default is false
+/// * `relativity` - Include relativistic (Schwarzschild, de Sitter, and Lense-Thirring) accelerations.
+///   Default is false
+/// * `empirical` - Optional empirical (constant & once-per-rev) accelerations in the radial,
+///   along-track, cross-track frame.  Default is None
+/// * `extra_acceleration` - Optional user-supplied GCRF acceleration, as a function of time and
+///   GCRF position & velocity, e.g. for a thruster model.  Its partials for the state transition
+///   matrix are computed numerically.  Default is None
+/// * `constant_set` - Set of gravitational constants for Earth gravity, sun, and moon.
+///   Default is [`ConstantSet::Egm96`], consistent with the JGM3 gravity model
+/// * `freeze_third_bodies` - Evaluate the sun & moon positions once, at the midpoint of each
+///   propagation arc, rather than sampling them over the arc, saving ephemeris evaluations.
+///   Intended for short arcs: the moon moves about 0.55 degrees per hour & the sun about 0.04,
+///   so the third-body error grows with arc length.  Precomputed terms are not used when set.
+///   Default is false
+/// * `compute_drag_work` - Compute the work done by drag, in
+///   [`crate::orbitprop::PropagationResult::drag_work`], when satellite properties are given.
+///   This integrates the drag power over each step, so costs extra atmospheric density
+///   evaluations and requires dense output.  Default is false
+/// * `max_steps` - Maximum number of accepted integrator steps.  Propagation fails with
+///   [`crate::SKErr::IntegratorFailed`] if this is exceeded.  Default is None (unbounded)
+/// * `output_steps` - Record the time and position & velocity at the end of each accepted
+///   integrator step in [`crate::orbitprop::PropagationResult::steps`].  Storage grows with
+///   the number of steps, so this is opt-in.  Default is false
 /// * `enable_interp` - Do we enable interpolation of the state between start and stop times.  Default is true
 ///                     slight comptuation savings if set to false
 ///
@@ -20,9 +63,22 @@ use crate::SKResult;
 pub struct PropSettings {
     pub gravity_order: u16,
     pub abs_error: f64,
+    pub abs_error_vec: Option<Vec<f64>>,
     pub rel_error: f64,
     pub use_spaceweather: bool,
+    pub zonal_wind: f64,
+    pub ocean_tides: bool,
+    pub tide_system: Option<TideSystem>,
+    pub pole_tide: bool,
+    pub relativity: bool,
+    pub empirical: Option<EmpiricalAccel>,
+    pub extra_acceleration: Option<ExtraAccel>,
+    pub constant_set: ConstantSet,
+    pub freeze_third_bodies: bool,
+    pub compute_drag_work: bool,
     pub enable_interp: bool,
+    pub max_steps: Option<usize>,
+    pub output_steps: bool,
     pub precomputed: Option<Precomputed>,
 }
 
@@ -31,9 +87,22 @@ impl Default for PropSettings {
         Self {
             gravity_order: 4,
             abs_error: 1e-8,
+            abs_error_vec: None,
             rel_error: 1e-8,
             use_spaceweather: true,
+            zonal_wind: 0.0,
+            ocean_tides: false,
+            tide_system: None,
+            pole_tide: false,
+            relativity: false,
+            empirical: None,
+            extra_acceleration: None,
+            constant_set: ConstantSet::default(),
+            freeze_third_bodies: false,
+            compute_drag_work: false,
             enable_interp: true,
+            max_steps: None,
+            output_steps: false,
             precomputed: None,
         }
     }
@@ -44,6 +113,45 @@ impl PropSettings {
         self.precomputed = Some(Precomputed::new(start, stop)?);
         Ok(())
     }
+
+    /// Precomputed terms covering `start` to `stop`, for sharing between
+    /// propagations: those in the settings if they cover the span, else
+    /// newly computed.  None if third bodies are frozen, as each
+    /// propagation then computes its own
+    pub(crate) fn precomputed_covering(
+        &self,
+        start: &Instant,
+        stop: &Instant,
+    ) -> SKResult<Option<Cow<'_, Precomputed>>> {
+        if self.freeze_third_bodies {
+            return Ok(None);
+        }
+        match &self.precomputed {
+            Some(p) if p.start <= *start && p.stop >= *stop => Ok(Some(Cow::Borrowed(p))),
+            _ => Ok(Some(Cow::Owned(Precomputed::new(start, stop)?))),
+        }
+    }
+
+    /// Per-component absolute error for an integrated state
+    /// with `nelem` elements (6 times number of state columns), or None if not set
+    pub(crate) fn abs_error_for_state(&self, nelem: usize) -> SKResult<Option<Vec<f64>>> {
+        let Some(v) = &self.abs_error_vec else {
+            return Ok(None);
+        };
+        match (v.len(), nelem) {
+            (n, m) if n == m => Ok(Some(v.clone())),
+            (6, m) if m % 6 == 0 => {
+                let mut r = vec![self.abs_error; m];
+                r[0..6].copy_from_slice(v);
+                Ok(Some(r))
+            }
+            (n, m) => skerror!(
+                "Absolute error vector has length {}; must be 6 or match state size {}",
+                n,
+                m
+            ),
+        }
+    }
 }
 
 impl std::fmt::Display for PropSettings {
@@ -52,16 +160,49 @@ impl std::fmt::Display for PropSettings {
             f,
             r#"Orbit Propagation Settings
             Gravity Order: {},
-            Max Abs Error: {:e},
+            Max Abs Error: {},
             Max Rel Error: {:e},
             Space Weather: {},
-            Interpolation: {}
+            Zonal Wind: {} m/s,
+            Ocean Tides: {},
+            Tide System: {},
+            Pole Tide: {},
+            Relativity: {},
+            Empirical Accel: {},
+            Extra Accel: {},
+            Constant Set: {:?},
+            Freeze Third Bodies: {},
+            Compute Drag Work: {},
+            Interpolation: {},
+            Max Steps: {},
+            Output Steps: {}
             {}"#,
             self.gravity_order,
-            self.abs_error,
+            self.abs_error_vec
+                .as_ref()
+                .map_or_else(|| format!("{:e}", self.abs_error), |v| format!("{:?}", v)),
             self.rel_error,
             self.use_spaceweather,
+            self.zonal_wind,
+            self.ocean_tides,
+            self.tide_system
+                .map_or_else(|| "Model".to_string(), |t| format!("{:?}", t)),
+            self.pole_tide,
+            self.relativity,
+            self.empirical
+                .as_ref()
+                .map_or_else(|| "None".to_string(), |e| format!("{:?}", e.params())),
+            match self.extra_acceleration {
+                Some(_) => "Set",
+                None => "None",
+            },
+            self.constant_set,
+            self.freeze_third_bodies,
+            self.compute_drag_work,
             self.enable_interp,
+            self.max_steps
+                .map_or_else(|| "Unbounded".to_string(), |m| m.to_string()),
+            self.output_steps,
             self.precomputed.as_ref().map_or_else(
                 || "No Precomputed".to_string(),
                 |p| format!("Precomputed: {} to {}", p.start, p.stop)
@@ -79,4 +220,27 @@ mod test {
         let props = PropSettings::default();
         println!("props = {}", props);
     }
+
+    #[test]
+    fn test_abs_error_for_state() {
+        let mut props = PropSettings::default();
+        assert!(props.abs_error_for_state(6).unwrap().is_none());
+
+        props.abs_error_vec = Some(vec![1.0e-3, 1.0e-3, 1.0e-3, 1.0e-6, 1.0e-6, 1.0e-6]);
+        assert_eq!(props.abs_error_for_state(6).unwrap().unwrap().len(), 6);
+
+        // 6-element vector is expanded for state with state transition matrix
+        let v = props.abs_error_for_state(42).unwrap().unwrap();
+        assert_eq!(v.len(), 42);
+        assert_eq!(v[3], 1.0e-6);
+        assert_eq!(v[6], props.abs_error);
+
+        props.abs_error_vec = Some(vec![1.0e-6; 42]);
+        assert!(props.abs_error_for_state(42).is_ok());
+        assert!(props.abs_error_for_state(6).is_err());
+
+        props.abs_error_vec = Some(vec![1.0e-6; 5]);
+        assert!(props.abs_error_for_state(6).is_err());
+        assert!(props.abs_error_for_state(42).is_err());
+    }
 }
@@ -0,0 +1,201 @@
+//! Reusable, thread-safe high-precision propagator
+
+use super::propagator::{propagate_with_precomputed, PropagationResult, StateType};
+use super::{PropSettings, Precomputed, SatProperties, SatState};
+
+use crate::Instant;
+use crate::SKResult;
+
+use std::sync::{Arc, RwLock};
+
+/// Reusable high-precision orbit propagator
+///
+/// Holds propagation settings, optional satellite properties, and a cache
+/// of precomputed terms (Earth orientation, sun & moon positions) so that
+/// repeated propagations over overlapping time spans do not recompute them
+///
+/// Earth gravity and JPL ephemerides are process-wide singletons that are
+/// loaded once on first use, so are not held here
+///
+/// `Propagator` is `Send` and `Sync`, and can be shared between threads,
+/// e.g. in an `Arc`
+///
+/// # Example
+///
+/// ```no_run
+/// use satkit::orbitprop::{Propagator, PropSettings, SimpleState};
+/// use satkit::{Instant, Duration};
+///
+/// let prop = Propagator::new(&PropSettings::default(), None);
+/// let start = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+/// let stop = start + Duration::from_hours(6.0);
+/// let mut state = SimpleState::zeros();
+/// state[0] = satkit::consts::GEO_R;
+/// state[4] = (satkit::consts::MU_EARTH / satkit::consts::GEO_R).sqrt();
+/// let res = prop.propagate(&state, &start, &stop).unwrap();
+/// ```
+pub struct Propagator {
+    settings: PropSettings,
+    satprops: Option<Arc<dyn SatProperties + Send + Sync>>,
+    cache: RwLock<Option<Arc<Precomputed>>>,
+}
+
+impl Propagator {
+    /// Create a new propagator
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Propagation settings.  If these include precomputed
+    ///   terms, they seed the cache
+    /// * `satprops` - Optional satellite properties (drag & radiation pressure)
+    pub fn new(
+        settings: &PropSettings,
+        satprops: Option<Arc<dyn SatProperties + Send + Sync>>,
+    ) -> Self {
+        let mut settings = settings.clone();
+        let cache = settings.precomputed.take().map(Arc::new);
+        Self {
+            settings,
+            satprops,
+            cache: RwLock::new(cache),
+        }
+    }
+
+    /// Propagation settings used by this propagator
+    pub const fn settings(&self) -> &PropSettings {
+        &self.settings
+    }
+
+    /// Precompute terms covering the given time span,
+    /// replacing any previously cached terms
+    pub fn precompute_terms(&self, start: &Instant, stop: &Instant) -> SKResult<()> {
+        let p = Arc::new(Precomputed::new(start, stop)?);
+        *self.cache.write().unwrap() = Some(p);
+        Ok(())
+    }
+
+    /// Return cached precomputed terms covering start to stop,
+    /// computing and caching them if necessary
+    ///
+    /// Precomputed samples lie on a fixed global time grid, so results
+    /// do not depend on which span happens to be cached
+    fn precomputed(&self, start: &Instant, stop: &Instant) -> SKResult<Arc<Precomputed>> {
+        let (t0, t1) = match stop > start {
+            true => (start, stop),
+            false => (stop, start),
+        };
+        if let Some(p) = self.cache.read().unwrap().as_ref() {
+            if *t0 >= p.start && *t1 <= p.stop {
+                return Ok(p.clone());
+            }
+        }
+
+        // Compute outside of lock; other threads may race to do the
+        // same, which is harmless
+        let p = Arc::new(Precomputed::new(start, stop)?);
+        *self.cache.write().unwrap() = Some(p.clone());
+        Ok(p)
+    }
+
+    /// Propagate a state from start to stop time
+    ///
+    /// See [`crate::orbitprop::propagate`] for a description of the state
+    /// and result
+    pub fn propagate<const C: usize>(
+        &self,
+        state: &StateType<C>,
+        start: &Instant,
+        stop: &Instant,
+    ) -> SKResult<PropagationResult<C>> {
+        let precomputed = self.precomputed(start, stop)?;
+        propagate_with_precomputed(
+            state,
+            start,
+            stop,
+            &self.settings,
+            Some(precomputed.as_ref()),
+            self.satprops.as_deref().map(|s| s as &dyn SatProperties),
+        )
+    }
+
+    /// Propagate a satellite state (and covariance, if set) to the given time
+    pub fn propagate_state(&self, state: &SatState, time: &Instant) -> SKResult<SatState> {
+        let precomputed = self.precomputed(&state.time, time)?;
+        state.propagate_with_precomputed(
+            time,
+            &self.settings,
+            Some(precomputed.as_ref()),
+            self.satprops.as_deref().map(|s| s as &dyn SatProperties),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::orbitprop::{SatPropertiesStatic, SimpleState};
+    use crate::Duration;
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Propagator>();
+    }
+
+    #[test]
+    fn test_threaded() -> SKResult<()> {
+        let starttime = Instant::from_datetime(2015, 3, 20, 0, 0, 0.0);
+
+        let mut state = SimpleState::zeros();
+        state[0] = consts::GEO_R;
+        state[4] = (consts::MU_EARTH / consts::GEO_R).sqrt();
+
+        let prop = Propagator::new(
+            &PropSettings::default(),
+            Some(Arc::new(SatPropertiesStatic::new(0.0, 0.01))),
+        );
+
+        // Single-threaded results, with some stop times outside of the
+        // initially-cached span
+        let stoptimes: Vec<Instant> = (0..8)
+            .map(|i| starttime + Duration::from_hours(3.0 * (i + 1) as f64))
+            .collect();
+        let expected: Vec<SimpleState> = stoptimes
+            .iter()
+            .map(|stop| Ok(prop.propagate(&state, &starttime, stop)?.state_end))
+            .collect::<SKResult<_>>()?;
+
+        // Same calculations, sharing propagator across threads
+        let prop = Arc::new(Propagator::new(
+            &PropSettings::default(),
+            Some(Arc::new(SatPropertiesStatic::new(0.0, 0.01))),
+        ));
+        let handles: Vec<_> = stoptimes
+            .iter()
+            .map(|stop| {
+                let prop = prop.clone();
+                let stop = *stop;
+                std::thread::spawn(move || {
+                    prop.propagate(&state, &starttime, &stop)
+                        .map(|r| r.state_end)
+                        .map_err(|e| e.to_string())
+                })
+            })
+            .collect();
+        for (h, exp) in handles.into_iter().zip(expected.iter()) {
+            let s = h.join().unwrap()?;
+            assert_eq!(s, *exp);
+        }
+
+        // Same results with a cache seeded over a different, unaligned span
+        prop.precompute_terms(
+            &(starttime - Duration::from_seconds(3917.3)),
+            &(starttime + Duration::from_days(2.0)),
+        )?;
+        for (stop, exp) in stoptimes.iter().zip(expected.iter()) {
+            assert_eq!(prop.propagate(&state, &starttime, stop)?.state_end, *exp);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Ocean tide and pole tide corrections to the geopotential
+//!
+//! Corrections are computed as changes to the (unnormalized) Stokes
+//! coefficients of the geopotential, following Chapter 6 of the IERS 2010 Conventions
+//! (IERS Technical Note 36)
+
+use crate::earthgravity::{normalization, CoeffTable};
+use crate::frametransform::{gmst, lunisolar_arguments};
+use crate::utils::{datadir, download_if_not_exist};
+use crate::{skerror, Instant, SKResult, TimeScale};
+
+use once_cell::sync::OnceCell;
+use std::f64::consts::PI;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+
+/// Maximum degree of tidal corrections
+pub const MAX_TIDE_DEGREE: usize = 4;
+
+/// File with ocean tide coefficients
+pub(crate) const OCEAN_TIDE_FILE: &str = "fes2004_Cnm-Snm.dat";
+
+/// Location of ocean tide coefficient file, from the IERS Conventions Centre
+pub(crate) const OCEAN_TIDE_URL: &str =
+    "https://iers-conventions.obspm.fr/content/chapter6/additional_info/tidemodels/";
+
+/// Load Love numbers, k'_n, for degrees 0 to 4 (IERS 2010 Table 6.6)
+const LOAD_LOVE: [f64; MAX_TIDE_DEGREE + 1] = [0.0, 0.0, -0.3075, -0.195, -0.132];
+
+/// A single ocean tide constituent for a single degree & order
+#[derive(Debug, Clone)]
+struct OceanTideConstituent {
+    /// Doodson multipliers of the 6 Doodson arguments
+    doodson: [f64; 6],
+    n: usize,
+    m: usize,
+    /// Prograde & retrograde cosine & sine amplitudes, meters
+    cp: f64,
+    sp: f64,
+    cm: f64,
+    sm: f64,
+}
+
+/// Ocean tide model, as a set of constituents
+#[derive(Debug, Clone)]
+pub struct OceanTides {
+    constituents: Vec<OceanTideConstituent>,
+}
+
+///
+/// Singleton for ocean tide model
+///
+/// The coefficient file is downloaded to the data directory if not
+/// present; returns an error if it cannot be downloaded
+///
+pub fn ocean_tides() -> SKResult<&'static OceanTides> {
+    static INSTANCE: OnceCell<OceanTides> = OnceCell::new();
+    INSTANCE.get_or_try_init(|| {
+        let path = datadir().unwrap_or(PathBuf::from(".")).join(OCEAN_TIDE_FILE);
+        download_if_not_exist(&path, Some(OCEAN_TIDE_URL))?;
+        OceanTides::from_file(&path)
+    })
+}
+
+impl OceanTides {
+    /// Load ocean tide coefficients from file
+    ///
+    /// File is in the format of the IERS Conventions
+    /// "fes2004_Cnm-Snm.dat" file: each line contains the Doodson number,
+    /// Darwin name, degree, order, and C+, S+, C-, S- in centimeters.
+    /// Only terms up to degree 4 are kept.
+    pub fn from_file(path: &std::path::Path) -> SKResult<Self> {
+        if !path.is_file() {
+            return skerror!(
+                "Ocean tide coefficient file {} not found; ocean tides require this file in the data directory",
+                path.display()
+            );
+        }
+        let file = std::fs::File::open(path)?;
+        let lines: Vec<String> = io::BufReader::new(file)
+            .lines()
+            .map(|x| x.unwrap_or(String::from("")))
+            .collect();
+        Self::from_lines(&lines)
+    }
+
+    fn from_lines(lines: &[String]) -> SKResult<Self> {
+        let mut constituents = Vec::new();
+        for line in lines {
+            let s: Vec<&str> = line.split_whitespace().collect();
+            // Skip header & comment lines
+            if s.len() < 8 {
+                continue;
+            }
+            let Some(doodson) = parse_doodson(s[0]) else {
+                continue;
+            };
+            let n: usize = s[2].parse()?;
+            let m: usize = s[3].parse()?;
+            if n > MAX_TIDE_DEGREE || m > n {
+                continue;
+            }
+            constituents.push(OceanTideConstituent {
+                doodson,
+                n,
+                m,
+                cp: s[4].parse::<f64>()? * 1.0e-2,
+                sp: s[5].parse::<f64>()? * 1.0e-2,
+                cm: s[6].parse::<f64>()? * 1.0e-2,
+                sm: s[7].parse::<f64>()? * 1.0e-2,
+            });
+        }
+        if constituents.is_empty() {
+            return skerror!("No ocean tide constituents found in file");
+        }
+        Ok(Self { constituents })
+    }
+
+    /// Add ocean tide corrections at the given time to the table
+    /// of unnormalized coefficients
+    ///
+    /// See IERS 2010 Conventions, Equation 6.15
+    pub fn add_delta_coeffs(&self, tm: &Instant, dc: &mut CoeffTable) {
+        let beta = doodson_arguments(tm);
+
+        // 4 pi G rho_w / g_e
+        const G: f64 = 6.67428e-11;
+        const RHO_W: f64 = 1025.0;
+        const G_E: f64 = 9.7803278;
+        let fac = 4.0 * PI * G * RHO_W / G_E;
+
+        for c in self.constituents.iter() {
+            let theta: f64 = c.doodson.iter().zip(beta.iter()).map(|(a, b)| a * b).sum();
+            let (stheta, ctheta) = theta.sin_cos();
+            // F_nm of equation 6.15; the square-root term there converts
+            // to normalized coefficients, and is omitted since the table
+            // is unnormalized
+            let fnm = fac * (1.0 + LOAD_LOVE[c.n]) / 2.0f64.mul_add(c.n as f64, 1.0);
+            dc[(c.n, c.m)] += fnm * (c.cp + c.cm).mul_add(ctheta, (c.sp + c.sm) * stheta);
+            if c.m > 0 {
+                dc[(c.m - 1, c.n)] += fnm * (c.sp - c.sm).mul_add(ctheta, -(c.cp - c.cm) * stheta);
+            }
+        }
+    }
+}
+
+/// Parse Doodson number, e.g. "255.555", into the 6 integer
+/// multipliers of the Doodson arguments
+fn parse_doodson(s: &str) -> Option<[f64; 6]> {
+    let (a, b) = s.split_once('.')?;
+    if a.is_empty() || a.len() > 3 || b.len() != 3 {
+        return None;
+    }
+    let digits: Vec<u32> = format!("{:0>3}{}", a, b)
+        .chars()
+        .map(|c| c.to_digit(10))
+        .collect::<Option<Vec<u32>>>()?;
+    let mut r = [0.0; 6];
+    for (i, (ri, di)) in r.iter_mut().zip(digits.iter()).enumerate() {
+        *ri = match i {
+            0 => *di as f64,
+            _ => *di as f64 - 5.0,
+        };
+    }
+    Some(r)
+}
+
+/// The six Doodson arguments (tau, s, h, p, N', p_s), in radians
+///
+/// See IERS 2010 Conventions, Section 6.2
+fn doodson_arguments(tm: &Instant) -> [f64; 6] {
+    let t_tt = (tm.as_mjd_with_scale(TimeScale::TT) - 51544.5) / 36525.0;
+    let [l, lp, f, d, om] = lunisolar_arguments(t_tt);
+    let s = f + om;
+    [
+        gmst(tm) + PI - s,
+        s,
+        s - d,
+        s - l,
+        -om,
+        s - d - lp,
+    ]
+}
+
+/// Add solid Earth and ocean pole tide corrections to the table
+/// of unnormalized coefficients
+///
+/// See IERS 2010 Conventions, Sections 6.4 & 6.5, with the
+/// linear mean pole of the 2018 update to the conventions
+///
+/// # Arguments
+///
+/// * `tm` - Time
+/// * `xp` - x polar motion, arcseconds
+/// * `yp` - y polar motion, arcseconds
+/// * `dc` - Unnormalized coefficient table to which corrections are added
+pub fn add_pole_tide_coeffs(tm: &Instant, xp: f64, yp: f64, dc: &mut CoeffTable) {
+    let (m1, m2) = pole_tide_wobble(tm, xp, yp);
+
+    // Solid Earth pole tide, equation 6.22
+    let mut dc21 = -1.333e-9 * 0.0115f64.mul_add(m2, m1);
+    let mut ds21 = -1.333e-9 * 0.0115f64.mul_add(-m1, m2);
+
+    // Ocean pole tide, equation 6.24
+    dc21 += -2.1778e-10 * 0.01724f64.mul_add(-m2, m1);
+    ds21 += -1.7232e-10 * 0.03365f64.mul_add(-m1, m2);
+
+    let scale = normalization(2, 1);
+    dc[(2, 1)] += dc21 * scale;
+    dc[(0, 2)] += ds21 * scale;
+}
+
+/// Wobble variables m1, m2 (arcseconds): polar motion relative
+/// to the (linear) mean pole
+fn pole_tide_wobble(tm: &Instant, xp: f64, yp: f64) -> (f64, f64) {
+    let years = (tm.as_mjd_with_scale(TimeScale::TT) - 51544.5) / 365.25;
+    let xpbar = 1.677f64.mul_add(years, 55.0) * 1.0e-3;
+    let ypbar = 3.460f64.mul_add(years, 320.5) * 1.0e-3;
+    (xp - xpbar, -(yp - ypbar))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::orbitprop::{propagate, PropSettings, SimpleState};
+    use crate::Duration;
+
+    #[test]
+    fn test_parse_doodson() {
+        assert_eq!(
+            parse_doodson("255.555").unwrap(),
+            [2.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            parse_doodson("55.565").unwrap(),
+            [0.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+        );
+        assert!(parse_doodson("Doodson").is_none());
+    }
+
+    #[test]
+    fn test_ocean_tide_coeffs() {
+        // M2 tide, degree 2 order 2; amplitude from FES2004
+        let lines = vec![
+            "Doodson Darw  l   m    DelC+     DelS+       DelC-      DelS-".to_string(),
+            "255.555 M2    2   2   0.646271  -0.255940   0.000000   0.000000".to_string(),
+            "255.555 M2    5   2   0.1        0.1        0.0        0.0".to_string(),
+        ];
+        let tides = OceanTides::from_lines(&lines).unwrap();
+        // Degree-5 term is dropped
+        assert_eq!(tides.constituents.len(), 1);
+
+        let tm = Instant::from_datetime(2020, 1, 1, 0, 0, 0.0);
+        let mut dc = CoeffTable::zeros(MAX_TIDE_DEGREE + 1, MAX_TIDE_DEGREE + 1);
+        tides.add_delta_coeffs(&tm, &mut dc);
+
+        // Normalized magnitude of M2 correction is ~1e-10
+        let c = dc[(2, 2)] / normalization(2, 2);
+        let s = dc[(1, 2)] / normalization(2, 2);
+        let mag = c.hypot(s);
+        assert!(mag > 2.0e-11 && mag < 5.0e-10);
+
+        // No other terms
+        assert_eq!(dc[(2, 0)], 0.0);
+        assert_eq!(dc[(2, 1)], 0.0);
+    }
+
+    #[test]
+    fn test_ocean_tide_file_missing() {
+        let r = OceanTides::from_file(std::path::Path::new("/nonexistent/fes.dat"));
+        assert!(r.unwrap_err().to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_pole_tide() {
+        // At the mean pole, there is no pole tide
+        let tm = Instant::from_datetime(2000, 1, 1, 12, 0, 0.0);
+        let mut dc = CoeffTable::zeros(3, 3);
+        add_pole_tide_coeffs(&tm, 0.055, 0.3205, &mut dc);
+        assert!(dc.norm() < 1.0e-16);
+
+        // 0.1 arcsec offset gives correction of order 1e-10
+        add_pole_tide_coeffs(&tm, 0.155, 0.3205, &mut dc);
+        let c21 = dc[(2, 1)] / normalization(2, 1);
+        assert!((c21 + 0.1 * (1.333e-9 + 2.1778e-10)).abs() < 1.0e-14);
+        assert!(dc[(0, 2)].abs() > 0.0);
+    }
+
+    #[test]
+    fn test_ocean_tide_leo() -> SKResult<()> {
+        // Ocean tides should perturb a LEO orbit at the level of
+        // a few mm per orbit
+        let starttime = Instant::from_datetime(2020, 3, 20, 0, 0, 0.0);
+        let r = consts::EARTH_RADIUS + 500.0e3;
+        let period = 2.0 * PI * (r.powi(3) / consts::MU_EARTH).sqrt();
+        let stoptime = starttime + Duration::from_seconds(period);
+
+        let mut state = SimpleState::zeros();
+        state[0] = r;
+        state[4] = (consts::MU_EARTH / r).sqrt() * (51.6_f64).to_radians().cos();
+        state[5] = (consts::MU_EARTH / r).sqrt() * (51.6_f64).to_radians().sin();
+
+        let mut settings = PropSettings {
+            gravity_order: 8,
+            ..Default::default()
+        };
+        let res1 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        settings.ocean_tides = true;
+        let res2 = propagate(&state, &starttime, &stoptime, &settings, None)?;
+        let dpos = (res1.state_end - res2.state_end).fixed_rows::<3>(0).norm();
+        assert!(dpos > 1.0e-4);
+        assert!(dpos < 5.0e-2);
+        Ok(())
+    }
+}
@@ -61,6 +61,8 @@ fn jplephem(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 fn sun(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(pylpephem_sun::pos_gcrf, m)?)
         .unwrap();
+    m.add_function(wrap_pyfunction!(pylpephem_sun::pos_itrf, m)?)
+        .unwrap();
     m.add_function(wrap_pyfunction!(pylpephem_sun::pos_mod, m)?)
         .unwrap();
     m.add_function(wrap_pyfunction!(pylpephem_sun::rise_set, m)?)
@@ -75,6 +77,8 @@ fn sun(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 fn moon(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(pylpephem_moon::pos_gcrf, m)?)
         .unwrap();
+    m.add_function(wrap_pyfunction!(pylpephem_moon::pos_itrf, m)?)
+        .unwrap();
     Ok(())
 }
 
@@ -92,6 +96,9 @@ fn frametransform(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(pyft::earth_rotation_angle, m)?)
         .unwrap();
     m.add_function(wrap_pyfunction!(pyft::gast, m)?).unwrap();
+    m.add_function(wrap_pyfunction!(pyft::last, m)?).unwrap();
+    m.add_function(wrap_pyfunction!(pyft::local_hour_angle, m)?)
+        .unwrap();
     m.add_function(wrap_pyfunction!(pyft::gmst, m)?).unwrap();
     m.add_function(wrap_pyfunction!(pyft::eqeq, m)?).unwrap();
     m.add_function(wrap_pyfunction!(pyft::qitrf2tirs, m)?)
@@ -126,6 +133,8 @@ pub fn satkit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Quaternion>()?;
     m.add_class::<pyframes::PyFrame>()?;
     m.add_function(wrap_pyfunction!(pysgp4::sgp4, m)?).unwrap();
+    m.add_function(wrap_pyfunction!(pysgp4::catalog_states, m)?)
+        .unwrap();
 
     m.add_class::<pygravity::GravModel>()?;
     m.add_class::<pysgp4::GravConst>()?;
@@ -143,18 +152,31 @@ pub fn satkit(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<pyconsts::Consts>()?;
     m.add_class::<SolarSystem>()?;
     m.add_class::<pytle::PyTLE>()?;
+    m.add_function(wrap_pyfunction!(pytle::bstar_to_ballistic, m)?)
+        .unwrap();
+    m.add_function(wrap_pyfunction!(pytle::ballistic_to_bstar, m)?)
+        .unwrap();
+    m.add_function(wrap_pyfunction!(pytle::select_tle, m)?)
+        .unwrap();
 
     m.add_class::<PyITRFCoord>()?;
 
     m.add_class::<PyKepler>()?;
     m.add_class::<PySatState>()?;
+    m.add_function(wrap_pyfunction!(pysatstate::cov_gcrf2ric, m)?)
+        .unwrap();
+    m.add_function(wrap_pyfunction!(pysatstate::relative_state, m)?)
+        .unwrap();
 
     m.add_class::<PyPropSettings>()?;
     m.add_class::<pysatproperties::PySatProperties>()?;
     m.add_class::<pypropresult::PyPropResult>()?;
     m.add_class::<pypropresult::PyPropStats>()?;
+    m.add_class::<pypropresult::PyPropResultIter>()?;
     m.add_function(wrap_pyfunction!(pypropagate::propagate, m)?)
         .unwrap();
+    m.add_function(wrap_pyfunction!(pypropagate::propagate_cov, m)?)
+        .unwrap();
 
     m.add_wrapped(wrap_pymodule!(frametransform))?;
     m.add_wrapped(wrap_pymodule!(jplephem))?;
@@ -1,7 +1,9 @@
+use super::pyinstant::ToTimeVec;
 use super::pyutils::*;
 use super::PyInstant;
 use crate::frametransform as ft;
 use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
 
 /// Greenwich Mean Sidereal Time
 ///
@@ -38,6 +40,53 @@ pub fn gast(tm: &Bound<'_, PyAny>) -> PyResult<PyObject> {
     py_func_of_time_arr(ft::gast, tm)
 }
 
+/// Local apparent sidereal time, radians
+///
+/// Args:
+///     longitude (float): Site longitude, radians, positive east of Greenwich
+///     tm (satkit.time|datetime.datetime|list|numpy.array): Time[s] at which to calculate LAST
+///
+/// Returns:
+///     float|numpy.array: LAST at input time[s] in radians, in range [0, 2π)
+#[pyfunction]
+pub fn last(longitude: f64, tm: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let tm = tm.to_time_vec()?;
+    pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+        match tm.len() {
+            1 => ft::last(longitude, &tm[0]).into_py_any(py),
+            _ => tm
+                .iter()
+                .map(|t| ft::last(longitude, t))
+                .collect::<Vec<f64>>()
+                .into_py_any(py),
+        }
+    })
+}
+
+/// Local hour angle of an object, radians
+///
+/// Args:
+///     ra (float): Apparent right ascension of object, radians
+///     longitude (float): Site longitude, radians, positive east of Greenwich
+///     tm (satkit.time|datetime.datetime|list|numpy.array): Time[s] at which to calculate hour angle
+///
+/// Returns:
+///     float|numpy.array: Hour angle at input time[s] in radians, in range [-π, π)
+#[pyfunction]
+pub fn local_hour_angle(ra: f64, longitude: f64, tm: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let tm = tm.to_time_vec()?;
+    pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+        match tm.len() {
+            1 => ft::local_hour_angle(ra, longitude, &tm[0]).into_py_any(py),
+            _ => tm
+                .iter()
+                .map(|t| ft::local_hour_angle(ra, longitude, t))
+                .collect::<Vec<f64>>()
+                .into_py_any(py),
+        }
+    })
+}
+
 /// Earth Rotation Angle
 ///
 ///
@@ -1,6 +1,6 @@
 use pyo3::prelude::*;
 
-use crate::earthgravity::{accel, accel_and_partials, GravityModel};
+use crate::earthgravity::{accel_and_partials, gravity_acceleration, GravityModel};
 
 use super::pyitrfcoord::PyITRFCoord;
 use crate::itrfcoord::ITRFCoord;
@@ -50,13 +50,16 @@ impl From<GravModel> for GravityModel {
 /// Keyword Args:
 ///     model (satkit.gravmodel): gravity model to use.  Default is satkit.gravmodel.jgm3
 ///     order (int): order of gravity model to use.  Default is 6, maximum is 16
+///     degree (int): degree of gravity model to use.  Default is equal to order
 ///
 /// Notes:
 ///     * For details of calculation, see Chapter 3.2 of "Satellite Orbits: Models, Methods, Applications", O. Montenbruck and B. Gill, Springer, 2012.
+///     * The expansion is evaluated, with no error, at points inside the Earth's reference sphere, but the result there becomes unreliable as degree increases
 #[pyfunction]
 #[pyo3(signature=(pos, **kwds))]
 pub fn gravity(pos: &Bound<'_, PyAny>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<PyObject> {
     let mut order: usize = 6;
+    let mut degree: Option<usize> = None;
     let mut model: GravModel = GravModel::jgm3;
     if let Some(kw) = kwds {
         if let Some(v) = kw.get_item("model")? {
@@ -65,12 +68,16 @@ pub fn gravity(pos: &Bound<'_, PyAny>, kwds: Option<&Bound<'_, PyDict>>) -> PyRe
         if let Some(v) = kw.get_item("order")? {
             order = v.extract::<usize>()?;
         }
+        if let Some(v) = kw.get_item("degree")? {
+            degree = Some(v.extract::<usize>()?);
+        }
     }
+    let degree = degree.unwrap_or(order);
 
     if pos.is_instance_of::<PyITRFCoord>() {
         let pyitrf: PyRef<PyITRFCoord> = pos.extract()?;
         let itrf: ITRFCoord = pyitrf.0;
-        let v = accel(&itrf.itrf, order, model.into());
+        let v = gravity_acceleration(&itrf.itrf, degree, order, model.into());
         pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
             let vpy = np::PyArray1::<f64>::from_slice(py, v.as_slice());
             vpy.into_py_any(py)
@@ -83,7 +90,7 @@ pub fn gravity(pos: &Bound<'_, PyAny>, kwds: Option<&Bound<'_, PyDict>>) -> PyRe
             ));
         }
         let v: na::Vector3<f64> = na::Vector3::<f64>::from_row_slice(vpy.as_slice().unwrap());
-        let a = accel(&v, order, model.into());
+        let a = gravity_acceleration(&v, degree, order, model.into());
         pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
             let vpy = np::PyArray1::<f64>::from_slice(py, a.as_slice());
             vpy.into_py_any(py)
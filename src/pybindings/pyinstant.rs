@@ -573,6 +573,55 @@ impl PyInstant {
         self.0.as_unixtime()
     }
 
+    /// Leap-second offset, TAI - UTC, at this time
+    ///
+    /// During a leap second (23:59:60 UTC) the offset prior to the
+    /// leap second is returned.  Returns 0 before 1972.
+    ///
+    /// Returns:
+    ///     int: Number of seconds by which TAI is ahead of UTC
+    fn tai_minus_utc(&self) -> i32 {
+        self.0.tai_minus_utc()
+    }
+
+    /// Round to the nearest multiple of a step from an origin
+    ///
+    /// Args:
+    ///     step (satkit.duration): Cadence to round to, e.g. 10 seconds
+    ///     origin (satkit.time, optional): Origin of the cadence.  Default is the start of the UTC day
+    ///
+    /// Returns:
+    ///     satkit.time: Time on the cadence nearest to this time
+    #[pyo3(signature=(step, origin=None))]
+    fn round_to(&self, step: &PyDuration, origin: Option<&Self>) -> Self {
+        Self(self.0.round_to(&step.0, origin.map(|o| &o.0)))
+    }
+
+    /// Truncate (round down) to a multiple of a step from an origin
+    ///
+    /// Args:
+    ///     step (satkit.duration): Cadence to truncate to, e.g. 10 seconds
+    ///     origin (satkit.time, optional): Origin of the cadence.  Default is the start of the UTC day
+    ///
+    /// Returns:
+    ///     satkit.time: Time on the cadence at or before this time
+    #[pyo3(signature=(step, origin=None))]
+    fn truncate_to(&self, step: &PyDuration, origin: Option<&Self>) -> Self {
+        Self(self.0.truncate_to(&step.0, origin.map(|o| &o.0)))
+    }
+
+    /// Integer index of the bin of a cadence containing this time
+    ///
+    /// Args:
+    ///     step (satkit.duration): Width of the bins, e.g. 60 seconds
+    ///     origin (satkit.time): Start of bin 0
+    ///
+    /// Returns:
+    ///     int: Bin index; negative before the origin
+    fn bin_key(&self, step: &PyDuration, origin: &Self) -> i64 {
+        self.0.bin_key(&step.0, &origin.0)
+    }
+
     #[staticmethod]
     fn from_gps_week_and_second(week: i32, seconds: f64) -> Self {
         Self(Instant::from_gps_week_and_second(week, seconds))
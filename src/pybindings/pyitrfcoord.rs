@@ -272,6 +272,32 @@ impl PyITRFCoord {
         })
     }
 
+    /// Look angles (azimuth, elevation, range) from this coordinate to another
+    ///
+    /// Args:
+    ///     other (itrfcoord): ITRF coordinate of target
+    ///     refraction (bool, optional): Correct elevation for atmospheric refraction.  Default is False
+    ///     pressure (float, optional): Atmospheric pressure at observer for refraction, hPa.  Default is 1010
+    ///     temperature (float, optional): Air temperature at observer for refraction, Celsius.  Default is 10
+    ///
+    /// Returns:
+    ///     tuple: (azimuth in radians clockwise from north, elevation in radians, range in meters)
+    #[pyo3(signature=(other, refraction=false, pressure=crate::refraction::STANDARD_PRESSURE, temperature=crate::refraction::STANDARD_TEMPERATURE))]
+    fn az_el_range(
+        &self,
+        other: &Self,
+        refraction: bool,
+        pressure: f64,
+        temperature: f64,
+    ) -> (f64, f64, f64) {
+        match refraction {
+            true => self
+                .0
+                .az_el_range_apparent(&other.0, pressure, temperature),
+            false => self.0.az_el_range(&other.0),
+        }
+    }
+
     /// Compute geodesic distance:
     ///
     /// Notes:
@@ -314,6 +340,23 @@ impl PyITRFCoord {
         Self(self.0.move_with_heading(distance, heading_rad))
     }
 
+    /// Offset this coordinate by a vector in the local East-North-Up (ENU) frame
+    ///
+    /// Notes:
+    ///     * Offset is in the local tangent plane, so is intended for small offsets (up to a few km)
+    ///     * A horizontal offset of d meters also raises the height by about d^2 / 2R
+    ///
+    /// Args:
+    ///     east (float): Offset to the east, meters
+    ///     north (float): Offset to the north, meters
+    ///     up (float): Offset along the local vertical, meters
+    ///
+    /// Returns:
+    ///     itrfcoord: New ITRF coordinate at the offset location
+    fn offset_enu(&self, east: f64, north: f64, up: f64) -> Self {
+        Self(self.0.offset_enu(east, north, up))
+    }
+
     fn __getnewargs_ex__<'a>(&self, py: Python<'a>) -> (Bound<'a, PyTuple>, Bound<'a, PyDict>) {
         let d = PyDict::new(py);
         let tp = PyTuple::new(py, vec![0.0, 0.0, 0.0]).unwrap();
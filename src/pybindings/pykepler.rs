@@ -38,6 +38,18 @@ use super::pyutils::py_to_smatrix;
 #[derive(Clone)]
 pub struct PyKepler(pub Kepler);
 
+/// Position & velocity as a tuple of numpy arrays
+fn pv_to_py(
+    (r, v): (nalgebra::Vector3<f64>, nalgebra::Vector3<f64>),
+) -> PyResult<(PyObject, PyObject)> {
+    pyo3::Python::with_gil(|py| -> PyResult<(PyObject, PyObject)> {
+        Ok((
+            numpy::PyArray::from_slice(py, r.as_slice()).into_py_any(py)?,
+            numpy::PyArray::from_slice(py, v.as_slice()).into_py_any(py)?,
+        ))
+    })
+}
+
 #[pymethods]
 impl PyKepler {
     #[new]
@@ -111,13 +123,35 @@ impl PyKepler {
     /// Convert Keplerian elements to Cartesian
     /// position (meters) and velocity (meters/second)
     fn to_pv(&self) -> PyResult<(PyObject, PyObject)> {
-        let (r, v) = self.0.to_pv();
-        pyo3::Python::with_gil(|py| -> PyResult<(PyObject, PyObject)> {
-            Ok((
-                numpy::PyArray::from_slice(py, r.as_slice()).into_py_any(py)?,
-                numpy::PyArray::from_slice(py, v.as_slice()).into_py_any(py)?,
-            ))
-        })
+        pv_to_py(self.0.to_pv())
+    }
+
+    /// Position (meters) and velocity (meters/second) at the given
+    /// true anomaly, treating the motion as purely Keplerian
+    #[pyo3(signature=(nu, mu=crate::consts::MU_EARTH))]
+    fn state_at_true_anomaly(&self, nu: f64, mu: f64) -> PyResult<(PyObject, PyObject)> {
+        match self.0.state_at_true_anomaly(nu, mu) {
+            Ok(pv) => pv_to_py(pv),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+        }
+    }
+
+    /// Position (meters) and velocity (meters/second) at perigee
+    #[pyo3(signature=(mu=crate::consts::MU_EARTH))]
+    fn perigee_state(&self, mu: f64) -> PyResult<(PyObject, PyObject)> {
+        match self.0.perigee_state(mu) {
+            Ok(pv) => pv_to_py(pv),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+        }
+    }
+
+    /// Position (meters) and velocity (meters/second) at apogee
+    #[pyo3(signature=(mu=crate::consts::MU_EARTH))]
+    fn apogee_state(&self, mu: f64) -> PyResult<(PyObject, PyObject)> {
+        match self.0.apogee_state(mu) {
+            Ok(pv) => pv_to_py(pv),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+        }
     }
 
     /// Convert Cartesian elements to kepler
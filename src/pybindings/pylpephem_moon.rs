@@ -1,4 +1,5 @@
 use super::pyutils;
+use crate::frametransform::qgcrf2itrf;
 use crate::lpephem::moon;
 use pyo3::prelude::*;
 
@@ -18,3 +19,19 @@ use pyo3::prelude::*;
 pub fn pos_gcrf(time: &Bound<'_, PyAny>) -> PyResult<PyObject> {
     pyutils::py_vec3_of_time_arr(&moon::pos_gcrf, time)
 }
+
+/// Approximate Moon position in the ITRF Frame
+///
+/// Notes:
+///   * Moon position in GCRF from `pos_gcrf`, rotated into the Earth-fixed ITRF frame
+///   * Valid with accuracy of 0.3 degree in ecliptic longitude, 0.2 degree in ecliptic latitude, and 1275 km in range
+///
+/// Args:
+///     time (satkit.time|numpy.ndarray|list): time[s] at which to compute position
+///
+/// Returns:
+///     numpy.ndarray: 3-element numpy array or Nx3 numpy array representing moon position in ITRF frame at input time[s].  Units are meters
+#[pyfunction]
+pub fn pos_itrf(time: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    pyutils::py_vec3_of_time_arr(&|t| qgcrf2itrf(t) * moon::pos_gcrf(t), time)
+}
@@ -1,6 +1,7 @@
 use super::pyinstant::PyInstant;
 use super::pyitrfcoord::PyITRFCoord;
 use super::pyutils;
+use crate::frametransform::qgcrf2itrf;
 use crate::lpephem::sun;
 use pyo3::prelude::*;
 use pyo3::IntoPyObjectExt;
@@ -21,6 +22,22 @@ pub fn pos_gcrf(time: &Bound<'_, PyAny>) -> PyResult<PyObject> {
     pyutils::py_vec3_of_time_arr(&sun::pos_gcrf, time)
 }
 
+/// Sun position in the International Terrestrial Reference Frame (ITRF)
+///
+/// Notes:
+///    * Sun position in GCRF from `pos_gcrf`, rotated into the Earth-fixed ITRF frame
+///    * Valid with accuracy of .01 degrees from 1950 to 2050
+///
+/// Args:
+///     time (satkit.time, numpy array, or list): time[s] at which to compute position
+///
+/// Returns:
+///     numpy.ndarray: 3-element array or Nx3 array representing sun position in ITRF frame at input time[s]
+#[pyfunction]
+pub fn pos_itrf(time: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    pyutils::py_vec3_of_time_arr(&|t| qgcrf2itrf(t) * sun::pos_gcrf(t), time)
+}
+
 /// Sun position in the Mean-of-Date Frame
 ///
 /// Notes:
@@ -207,3 +207,80 @@ pub fn propagate(
         })
     }
 }
+
+/// Relative tolerance for symmetry of input covariance
+const COV_SYMMETRY_TOL: f64 = 1.0e-9;
+
+/// Propagate satellite state and covariance
+///
+/// The covariance is propagated with the state transition matrix:
+/// P(stop) = Phi * P(start) * Phi^T
+///
+/// Args:
+///     state0 (npt.ArrayLike[float]): 6-element numpy array representing satellite GCRF position & velocity in meters and meters/second
+///     cov0 (npt.ArrayLike[float]): 6x6 symmetric covariance of state0, in meters and meters/second
+///     start (satkit.time): Time of "state0"
+///     stop (satkit.time): Time to which state and covariance are propagated
+///
+/// Keyword Args:
+///     propsettings (satkit.propsettings): Settings for the propagation.  If left out, default will be used
+///     satproperties (satkit.satproperties_static): Drag and radiation pressure succeptibility of satellite.  If left out, drag and radiation pressure are neglected
+///
+/// Returns:
+///     tuple[npt.ArrayLike[float], npt.ArrayLike[float]]: 6-element state and 6x6 covariance at "stop"
+///
+/// Raises:
+///     ValueError: If covariance is not 6x6 and symmetric
+#[pyfunction(signature=(state0, cov0, start, stop, propsettings=None, satproperties=None))]
+pub fn propagate_cov(
+    state0: &Bound<'_, PyAny>,
+    cov0: &Bound<'_, PyAny>,
+    start: PyInstant,
+    stop: PyInstant,
+    propsettings: Option<PyPropSettings>,
+    satproperties: Option<PySatProperties>,
+) -> PyResult<(PyObject, PyObject)> {
+    let state0: Vector6 = py_to_smatrix(state0)?;
+    let cov0: Matrix6 = {
+        let arr = cov0
+            .extract::<numpy::PyReadonlyArray2<f64>>()
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Covariance must be 6x6"))?;
+        let arr = arr.as_array();
+        if arr.shape() != [6, 6] {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Covariance must be 6x6",
+            ));
+        }
+        Matrix6::from_fn(|r, c| arr[(r, c)])
+    };
+    let scale = cov0.amax().max(f64::MIN_POSITIVE);
+    if (cov0 - cov0.transpose()).amax() > COV_SYMMETRY_TOL * scale {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Covariance must be symmetric",
+        ));
+    }
+
+    let propsettings = propsettings.map_or_else(crate::orbitprop::PropSettings::default, |p| p.0);
+    let satproperties = satproperties.map(|p| p.0);
+
+    let mut pv = na::SMatrix::<f64, 6, 7>::zeros();
+    pv.fixed_view_mut::<6, 1>(0, 0).copy_from(&state0);
+    pv.fixed_view_mut::<6, 6>(0, 1)
+        .copy_from(&Matrix6::identity());
+    let res = crate::orbitprop::propagate(
+        &pv,
+        &start.0,
+        &stop.0,
+        &propsettings,
+        satproperties.as_ref().map(|p| p as &dyn SatProperties),
+    )
+    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+    let state: Vector6 = res.state_end.fixed_view::<6, 1>(0, 0).into();
+    let phi: Matrix6 = res.state_end.fixed_view::<6, 6>(0, 1).into();
+    let cov = phi * cov0 * phi.transpose();
+    pyo3::Python::with_gil(|py| -> PyResult<(PyObject, PyObject)> {
+        Ok((vec2py(py, &state)?, mat2py(py, &cov)))
+    })
+}
+
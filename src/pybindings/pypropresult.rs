@@ -1,5 +1,6 @@
 use pyo3::prelude::*;
 
+use super::pyduration::PyDuration;
 use super::pyinstant::PyInstant;
 use super::pyutils::*;
 
@@ -86,22 +87,60 @@ impl PyPropStats {
 #[derive(Debug, Clone)]
 pub struct PyPropResult(pub PyPropResultType);
 
-fn to_string<const T: usize>(r: &PropagationResult<T>) -> String {
+/// Values with magnitude at or above this are printed in scientific
+/// notation, as fixed decimals are not meaningful
+const SCIENTIFIC_THRESHOLD: f64 = 1.0e10;
+
+fn fmt_value(v: f64, precision: usize) -> String {
+    if v.abs() >= SCIENTIFIC_THRESHOLD {
+        format!("{:.*e}", precision, v)
+    } else {
+        format!("{:.*}", precision, v)
+    }
+}
+
+fn fmt_vec3(v: &[f64], scale: f64, precision: usize) -> String {
+    format!(
+        "[{}, {}, {}]",
+        fmt_value(v[0] * scale, precision),
+        fmt_value(v[1] * scale, precision),
+        fmt_value(v[2] * scale, precision)
+    )
+}
+
+/// Output units for position & velocity
+struct Units {
+    pos_scale: f64,
+    pos_unit: &'static str,
+    vel_scale: f64,
+    vel_unit: &'static str,
+}
+
+/// Units of the default string representation: position in km,
+/// velocity in m/s
+const DEFAULT_UNITS: Units = Units {
+    pos_scale: 1.0e-3,
+    pos_unit: "km",
+    vel_scale: 1.0,
+    vel_unit: "m/s",
+};
+
+fn to_string<const T: usize>(r: &PropagationResult<T>, units: &Units, precision: usize) -> String {
     let mut s = "Propagation Results\n".to_string();
     s.push_str(format!("  Time: {}\n", r.time_end).as_str());
     s.push_str(
         format!(
-            "   Pos: [{:.3}, {:.3}, {:.3}] km\n",
-            r.state_end[0] * 1.0e-3,
-            r.state_end[1] * 1.0e-3,
-            r.state_end[2] * 1.0e-3
+            "   Pos: {} {}\n",
+            fmt_vec3(&r.state_end.as_slice()[0..3], units.pos_scale, precision),
+            units.pos_unit
         )
         .as_str(),
     );
     s.push_str(
         format!(
-            "   Vel: [{:.3}, {:.3}, {:.3}] m/s\n",
-            r.state_end[3], r.state_end[4], r.state_end[5]
+            "   Vel: {} {}\n",
+            fmt_vec3(&r.state_end.as_slice()[3..6], units.vel_scale, precision),
+            units.vel_unit
         )
         .as_str(),
     );
@@ -131,6 +170,8 @@ impl PyPropResult {
             accepted_steps: 0,
             rejected_steps: 0,
             odesol: None,
+            drag_work: 0.0,
+            steps: None,
         })))
     }
 
@@ -177,6 +218,34 @@ impl PyPropResult {
         }
     }
 
+    /// Work done by drag per unit mass over the propagation, J/kg
+    #[getter]
+    fn drag_work(&self) -> f64 {
+        match &self.0 {
+            PyPropResultType::R1(r) => r.drag_work,
+            PyPropResultType::R7(r) => r.drag_work,
+        }
+    }
+
+    /// Time and state at the end of each accepted integrator step,
+    /// if `output_steps` was set in the propagation settings
+    #[getter]
+    fn steps(&self) -> PyResult<Option<Vec<(PyInstant, PyObject)>>> {
+        let steps = match &self.0 {
+            PyPropResultType::R1(r) => r.steps.as_ref(),
+            PyPropResultType::R7(r) => r.steps.as_ref(),
+        };
+        pyo3::Python::with_gil(|py| {
+            steps
+                .map(|s| {
+                    s.iter()
+                        .map(|(t, pv)| Ok((PyInstant(*t), vec2py(py, pv)?)))
+                        .collect()
+                })
+                .transpose()
+        })
+    }
+
     #[getter]
     fn pos(&self) -> PyResult<PyObject> {
         pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
@@ -271,11 +340,47 @@ impl PyPropResult {
 
     fn __str__(&self) -> String {
         match &self.0 {
-            PyPropResultType::R1(r) => to_string::<1>(r),
-            PyPropResultType::R7(r) => to_string::<7>(r),
+            PyPropResultType::R1(r) => to_string::<1>(r, &DEFAULT_UNITS, 3),
+            PyPropResultType::R7(r) => to_string::<7>(r, &DEFAULT_UNITS, 3),
         }
     }
 
+    /// String representation with selectable units and precision
+    ///
+    /// Args:
+    ///     units (str | None): "m" for meters and meters/second, or "km" for kilometers and kilometers/second.  Default is None, for kilometers and meters/second as in the default string representation
+    ///     precision (int): number of decimal places.  Default is 3
+    ///
+    /// Returns:
+    ///     str: String representation of the result.  Values of magnitude 1e10 or larger are in scientific notation
+    #[pyo3(signature=(units=None, precision=3))]
+    fn format(&self, units: Option<&str>, precision: usize) -> PyResult<String> {
+        let units = match units {
+            None => DEFAULT_UNITS,
+            Some("m") => Units {
+                pos_scale: 1.0,
+                pos_unit: "m",
+                vel_scale: 1.0,
+                vel_unit: "m/s",
+            },
+            Some("km") => Units {
+                pos_scale: 1.0e-3,
+                pos_unit: "km",
+                vel_scale: 1.0e-3,
+                vel_unit: "km/s",
+            },
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "units must be \"m\" or \"km\"",
+                ))
+            }
+        };
+        Ok(match &self.0 {
+            PyPropResultType::R1(r) => to_string::<1>(r, &units, precision),
+            PyPropResultType::R7(r) => to_string::<7>(r, &units, precision),
+        })
+    }
+
     #[getter]
     const fn can_interp(&self) -> bool {
         match &self.0 {
@@ -329,6 +434,79 @@ impl PyPropResult {
             },
         }
     }
+
+    fn iter(&self, step: PyDuration) -> PyResult<PyPropResultIter> {
+        let (start, end, has_interp) = match &self.0 {
+            PyPropResultType::R1(r) => (r.time_start, r.time_end, r.odesol.is_some()),
+            PyPropResultType::R7(r) => (r.time_start, r.time_end, r.odesol.is_some()),
+        };
+        if !has_interp {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Propagation result does not include dense output for interpolation",
+            ));
+        }
+        let step = step.0.as_seconds();
+        if step <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Step must be positive",
+            ));
+        }
+        // Iterate in direction of propagation, stopping at or before the end
+        let span = (end - start).as_seconds();
+        Ok(PyPropResultIter {
+            result: self.clone(),
+            start,
+            step: step * span.signum(),
+            index: 0,
+            count: (span.abs() / step).floor() as usize + 1,
+        })
+    }
+
+    fn stm_between(&self, t1: PyInstant, t2: PyInstant) -> PyResult<PyObject> {
+        let res = match &self.0 {
+            PyPropResultType::R1(r) => r.stm_between(&t1.0, &t2.0),
+            PyPropResultType::R7(r) => r.stm_between(&t1.0, &t2.0),
+        };
+        match res {
+            Ok(phi) => pyo3::Python::with_gil(|py| -> PyResult<PyObject> { Ok(mat2py(py, &phi)) }),
+            Err(e) => Err(pyo3::exceptions::PyValueError::new_err(e.to_string())),
+        }
+    }
+}
+
+/// Iterator over interpolated states of a propagation result
+///
+/// Yields (satkit.time, numpy.ndarray) tuples of time and
+/// 6-element GCRF state, at a fixed step from the start time
+#[pyclass(name = "propresult_iter", module = "satkit")]
+pub struct PyPropResultIter {
+    result: PyPropResult,
+    start: Instant,
+    step: f64,
+    index: usize,
+    count: usize,
+}
+
+#[pymethods]
+impl PyPropResultIter {
+    const fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<(PyInstant, PyObject)>> {
+        if self.index >= self.count {
+            return Ok(None);
+        }
+        let time = self.start + crate::Duration::from_seconds(self.step * self.index as f64);
+        self.index += 1;
+        let state: Result<Vector<6>, _> = match &self.result.0 {
+            PyPropResultType::R1(r) => r.interp(&time).map(|v| v.fixed_rows::<6>(0).into()),
+            PyPropResultType::R7(r) => r.interp(&time).map(|v| v.fixed_view::<6, 1>(0, 0).into()),
+        };
+        let state = state.map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let state = pyo3::Python::with_gil(|py| -> PyResult<PyObject> { vec2py(py, &state) })?;
+        Ok(Some((PyInstant(time), state)))
+    }
 }
 
 #[cfg(test)]
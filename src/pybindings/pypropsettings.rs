@@ -1,9 +1,39 @@
 use pyo3::prelude::*;
 
-use crate::orbitprop::PropSettings;
+use crate::orbitprop::{EmpiricalAccel, PropSettings, NUM_EMPIRICAL_PARAMS};
 use crate::pybindings::PyInstant;
 
 use pyo3::types::{PyDict, PyString};
+use pyo3::IntoPyObjectExt;
+
+/// Absolute error may be a scalar, or a per-component list
+fn set_abs_error(ps: &mut PropSettings, val: &Bound<'_, PyAny>) -> PyResult<()> {
+    if let Ok(v) = val.extract::<f64>() {
+        ps.abs_error = v;
+        ps.abs_error_vec = None;
+    } else {
+        let v = val.extract::<Vec<f64>>()?;
+        if v.is_empty() || v.len() % 6 != 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "abs_error must be a float or a list of floats with length a multiple of 6",
+            ));
+        }
+        ps.abs_error_vec = Some(v);
+    }
+    Ok(())
+}
+
+/// Empirical accelerations are None, or a list of 9 parameters
+fn extract_empirical(val: &Bound<'_, PyAny>) -> PyResult<Option<EmpiricalAccel>> {
+    if val.is_none() {
+        return Ok(None);
+    }
+    let v = val.extract::<Vec<f64>>()?;
+    let p: [f64; NUM_EMPIRICAL_PARAMS] = v.try_into().map_err(|_| {
+        pyo3::exceptions::PyValueError::new_err("empirical must be a list of 9 floats")
+    })?;
+    Ok(Some(EmpiricalAccel::from_params(&p)))
+}
 
 #[pyclass(name = "propsettings")]
 #[derive(Clone, Debug)]
@@ -17,7 +47,7 @@ impl PyPropSettings {
         let mut ps = PropSettings::default();
         if let Some(kw) = kwargs {
             if let Some(abserr) = kw.get_item("abs_error")? {
-                ps.abs_error = abserr.extract::<f64>()?;
+                set_abs_error(&mut ps, &abserr)?;
                 kw.del_item("abs_error")?;
             }
             if let Some(relerr) = kw.get_item("rel_error")? {
@@ -36,6 +66,42 @@ impl PyPropSettings {
                 ps.use_spaceweather = sw.extract::<bool>()?;
                 kw.del_item("use_spaceweather")?;
             }
+            if let Some(zw) = kw.get_item("zonal_wind")? {
+                ps.zonal_wind = zw.extract::<f64>()?;
+                kw.del_item("zonal_wind")?;
+            }
+            if let Some(ot) = kw.get_item("ocean_tides")? {
+                ps.ocean_tides = ot.extract::<bool>()?;
+                kw.del_item("ocean_tides")?;
+            }
+            if let Some(pt) = kw.get_item("pole_tide")? {
+                ps.pole_tide = pt.extract::<bool>()?;
+                kw.del_item("pole_tide")?;
+            }
+            if let Some(rel) = kw.get_item("relativity")? {
+                ps.relativity = rel.extract::<bool>()?;
+                kw.del_item("relativity")?;
+            }
+            if let Some(ms) = kw.get_item("max_steps")? {
+                ps.max_steps = ms.extract::<Option<usize>>()?;
+                kw.del_item("max_steps")?;
+            }
+            if let Some(dw) = kw.get_item("compute_drag_work")? {
+                ps.compute_drag_work = dw.extract::<bool>()?;
+                kw.del_item("compute_drag_work")?;
+            }
+            if let Some(ft) = kw.get_item("freeze_third_bodies")? {
+                ps.freeze_third_bodies = ft.extract::<bool>()?;
+                kw.del_item("freeze_third_bodies")?;
+            }
+            if let Some(os) = kw.get_item("output_steps")? {
+                ps.output_steps = os.extract::<bool>()?;
+                kw.del_item("output_steps")?;
+            }
+            if let Some(emp) = kw.get_item("empirical")? {
+                ps.empirical = extract_empirical(&emp)?;
+                kw.del_item("empirical")?;
+            }
             if !kw.is_empty() {
                 let keystring: String = kw.iter().fold(String::from(""), |acc, (k, _v)| {
                     let mut a2 = acc.clone();
@@ -52,14 +118,16 @@ impl PyPropSettings {
     }
 
     #[getter]
-    fn get_abs_error(&self) -> f64 {
-        self.0.abs_error
+    fn get_abs_error(&self, py: Python) -> PyResult<PyObject> {
+        match &self.0.abs_error_vec {
+            Some(v) => v.clone().into_py_any(py),
+            None => self.0.abs_error.into_py_any(py),
+        }
     }
 
     #[setter(abs_error)]
-    fn set_abs_error(&mut self, val: f64) -> PyResult<()> {
-        self.0.abs_error = val;
-        Ok(())
+    fn set_abs_error(&mut self, val: &Bound<'_, PyAny>) -> PyResult<()> {
+        set_abs_error(&mut self.0, val)
     }
 
     #[getter]
@@ -106,6 +174,105 @@ impl PyPropSettings {
         Ok(())
     }
 
+    #[getter]
+    fn get_zonal_wind(&self) -> f64 {
+        self.0.zonal_wind
+    }
+
+    #[setter(zonal_wind)]
+    fn set_zonal_wind(&mut self, val: f64) -> PyResult<()> {
+        self.0.zonal_wind = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_ocean_tides(&self) -> bool {
+        self.0.ocean_tides
+    }
+
+    #[setter(ocean_tides)]
+    fn set_ocean_tides(&mut self, val: bool) -> PyResult<()> {
+        self.0.ocean_tides = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_pole_tide(&self) -> bool {
+        self.0.pole_tide
+    }
+
+    #[setter(pole_tide)]
+    fn set_pole_tide(&mut self, val: bool) -> PyResult<()> {
+        self.0.pole_tide = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_relativity(&self) -> bool {
+        self.0.relativity
+    }
+
+    #[setter(relativity)]
+    fn set_relativity(&mut self, val: bool) -> PyResult<()> {
+        self.0.relativity = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_empirical(&self) -> Option<Vec<f64>> {
+        self.0.empirical.as_ref().map(|e| e.params().to_vec())
+    }
+
+    #[setter(empirical)]
+    fn set_empirical(&mut self, val: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.0.empirical = extract_empirical(val)?;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_max_steps(&self) -> Option<usize> {
+        self.0.max_steps
+    }
+
+    #[setter(max_steps)]
+    fn set_max_steps(&mut self, val: Option<usize>) -> PyResult<()> {
+        self.0.max_steps = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_compute_drag_work(&self) -> bool {
+        self.0.compute_drag_work
+    }
+
+    #[setter(compute_drag_work)]
+    fn set_compute_drag_work(&mut self, val: bool) -> PyResult<()> {
+        self.0.compute_drag_work = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_freeze_third_bodies(&self) -> bool {
+        self.0.freeze_third_bodies
+    }
+
+    #[setter(freeze_third_bodies)]
+    fn set_freeze_third_bodies(&mut self, val: bool) -> PyResult<()> {
+        self.0.freeze_third_bodies = val;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_output_steps(&self) -> bool {
+        self.0.output_steps
+    }
+
+    #[setter(output_steps)]
+    fn set_output_steps(&mut self, val: bool) -> PyResult<()> {
+        self.0.output_steps = val;
+        Ok(())
+    }
+
     fn __str__(&self) -> String {
         self.0.to_string()
     }
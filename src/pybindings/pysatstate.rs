@@ -1,6 +1,7 @@
 use super::pyinstant::PyInstant;
 use super::pypropsettings::PyPropSettings;
 use super::pyquaternion::Quaternion;
+use super::pyutils::{mat2py, vec2py};
 
 use nalgebra as na;
 use numpy as np;
@@ -11,10 +12,35 @@ use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyNone, PyTuple};
 use pyo3::IntoPyObjectExt;
 
-use crate::orbitprop::{PropSettings, SatState, StateCov};
+use crate::orbitprop::{
+    ConsiderCov, CovFrame, PropSettings, SatState, StateCov, NUM_CONSIDER_PARAMS,
+};
 use crate::pybindings::PyDuration;
 use crate::Instant;
 
+/// Pickled state flag: covariance is present
+const PICKLE_FLAG_COV: u64 = 0x01;
+
+/// Pickled state flag: consider parameters are present
+const PICKLE_FLAG_CONSIDER: u64 = 0x02;
+
+/// Bit offset of covariance frame in pickled state flags
+const PICKLE_FRAME_SHIFT: u64 = 8;
+
+/// Append values to a pickle buffer, little-endian
+fn extend_f64(buffer: &mut Vec<u8>, v: &[f64]) {
+    for x in v {
+        buffer.extend_from_slice(&x.to_le_bytes());
+    }
+}
+
+/// Values from a pickle buffer, little-endian
+fn bytes_to_f64(b: &[u8]) -> Vec<f64> {
+    b.chunks_exact(8)
+        .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+        .collect()
+}
+
 #[pyclass(name = "satstate", module = "satkit")]
 #[derive(Clone, Debug)]
 pub struct PySatState(SatState);
@@ -165,6 +191,56 @@ impl PySatState {
         })
     }
 
+    /// Set covariance of the "consider" parameters
+    ///
+    /// Consider parameters are not estimated, but their uncertainty
+    /// contributes to the state uncertainty as the state is propagated.
+    /// Their contribution is kept separate from the state covariance
+    ///
+    /// Args:
+    ///     cov (numpy.ndarray): 2x2 covariance of coefficient of drag times area over mass, and coefficient of radiation pressure times area over mass, (m^2/kg)^2
+    ///
+    /// Returns:
+    ///     None
+    fn set_consider_cov(&mut self, cov: &Bound<'_, np::PyArray2<f64>>) -> PyResult<()> {
+        if cov.readonly().shape()[0] != 2 || cov.readonly().shape()[1] != 2 {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "Consider covariance must be 2x2 numpy array",
+            ));
+        }
+        let na_cov = na::Matrix2::from_row_slice(unsafe { cov.as_slice().unwrap() });
+        self.0.set_consider_cov(&na_cov);
+        Ok(())
+    }
+
+    /// Contribution of consider parameters to the 6x6 state covariance
+    ///
+    /// Returns:
+    ///     numpy.ndarray: 6x6 numpy array with covariance contribution for position (meters) and velocity (m/s), or None if not set
+    #[getter]
+    fn get_consider_cov(&self) -> Py<PyAny> {
+        pyo3::Python::with_gil(|py| -> Py<PyAny> {
+            match self.0.consider_cov() {
+                None => PyNone::get(py).into_py_any(py).unwrap(),
+                Some(cov) => mat2py(py, &cov),
+            }
+        })
+    }
+
+    /// Total 6x6 state covariance, including contribution of consider parameters
+    ///
+    /// Returns:
+    ///     numpy.ndarray: 6x6 numpy array with total covariance for position (meters) and velocity (m/s), or None if not set
+    #[getter]
+    fn get_total_cov(&self) -> Py<PyAny> {
+        pyo3::Python::with_gil(|py| -> Py<PyAny> {
+            match self.0.total_cov() {
+                None => PyNone::get(py).into_py_any(py).unwrap(),
+                Some(cov) => mat2py(py, &cov),
+            }
+        })
+    }
+
     /// Quaternion to go from gcrf (Geocentric Celestial Reference Frame) to lvlh (Local-Vertical, Local-Horizontal) frame
     ///
     /// Notes:
@@ -215,30 +291,7 @@ impl PySatState {
         timedur: &Bound<'_, PyAny>,
         kwargs: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<Self> {
-        let time: Instant = {
-            if timedur.is_instance_of::<PyInstant>() {
-                timedur.extract::<PyInstant>()?.0
-            } else if timedur.is_instance_of::<PyDuration>() {
-                let dur = timedur.extract::<PyDuration>()?;
-                self.0.time + dur.0
-            } else {
-                return Err(pyo3::exceptions::PyTypeError::new_err(
-                    "timedur must be satkit.time or satkit.duration",
-                ));
-            }
-        };
-
-        let propsettings: Option<PropSettings> = match kwargs.is_some() {
-            true => {
-                let kw = kwargs.unwrap();
-                match kw.get_item("propsettings")? {
-                    None => None,
-                    Some(v) => Some(v.extract::<PyPropSettings>()?.0),
-                }
-            }
-            false => None,
-        };
-
+        let (time, propsettings) = self.time_and_settings(timedur, kwargs)?;
         match self.0.propagate(&time, propsettings.as_ref()) {
             Ok(s) => Ok(Self(s)),
             Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
@@ -248,6 +301,45 @@ impl PySatState {
         }
     }
 
+    /// Propagate state to a new time, and also return the state
+    /// transition matrix, whether or not the covariance is set
+    ///
+    /// Args:
+    ///     time (satkit.time|satkit.duration): Time for which to compute new state or alternatively
+    ///     a duration to propagate from the current time
+    ///
+    /// Returns:
+    ///     tuple[satkit.satstate, numpy.ndarray]: New state at input time, and 6x6 state
+    ///     transition matrix in the GCRF frame
+    #[pyo3(signature=(timedur, **kwargs))]
+    fn propagate_with_stm(
+        &self,
+        timedur: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<(Self, PyObject)> {
+        let (time, propsettings) = self.time_and_settings(timedur, kwargs)?;
+        match self.0.propagate_with_stm(&time, propsettings.as_ref()) {
+            Ok((s, phi)) => Ok((Self(s), Python::with_gil(|py| mat2py(py, &phi)))),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Error propagating state: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Position & velocity relative to the Moon, in a Moon-centered
+    /// inertial frame with axes parallel to the GCRF
+    ///
+    /// Returns:
+    ///     numpy.ndarray: 6-element Moon-relative position (meters) & velocity (meters / second)
+    fn moon_relative(&self) -> PyResult<PyObject> {
+        let rel = self
+            .0
+            .moon_relative()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| vec2py(py, &rel))
+    }
+
     fn __getnewargs_ex__<'a>(&self, py: Python<'a>) -> (Bound<'a, PyTuple>, Bound<'a, PyDict>) {
         let d = PyDict::new(py);
         let tm = PyInstant(Instant::INVALID).into_py_any(py).unwrap();
@@ -273,47 +365,90 @@ impl PySatState {
             crate::TimeScale::TAI,
         );
 
-        let pv = na::Vector6::<f64>::from_row_slice(unsafe {
-            std::slice::from_raw_parts(state[8..56].as_ptr() as *const f64, 6)
-        });
+        let pv = na::Vector6::<f64>::from_column_slice(&bytes_to_f64(&state[8..56]));
         self.0.time = time;
         self.0.pv = pv;
-        if state.len() >= 92 {
-            let cov = na::Matrix6::<f64>::from_row_slice(unsafe {
-                std::slice::from_raw_parts(state[56..].as_ptr() as *const f64, 36)
-            });
+        self.0.cov = StateCov::None;
+        self.0.consider = None;
+        self.0.cov_frame = CovFrame::GCRF;
+
+        // Earlier format: time, position & velocity, then optional covariance
+        if state.len() == 56 + 36 * 8 {
+            let cov = na::Matrix6::<f64>::from_column_slice(&bytes_to_f64(&state[56..]));
             self.0.cov = StateCov::PVCov(cov);
+            return Ok(());
+        } else if state.len() == 56 {
+            return Ok(());
+        }
+
+        // Current format: flags, then optional covariance & consider parameters
+        let bad_len = || pyo3::exceptions::PyRuntimeError::new_err("Invalid state length");
+        let flags = u64::from_le_bytes(state.get(56..64).ok_or_else(bad_len)?.try_into().unwrap());
+        self.0.cov_frame = match (flags >> PICKLE_FRAME_SHIFT) & 0xFF {
+            0 => CovFrame::GCRF,
+            1 => CovFrame::LVLH,
+            2 => CovFrame::RIC,
+            _ => {
+                return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                    "Invalid covariance frame",
+                ))
+            }
+        };
+        let mut offset = 64;
+        let mut take = |n: usize| -> PyResult<Vec<f64>> {
+            let v = bytes_to_f64(state.get(offset..offset + n * 8).ok_or_else(bad_len)?);
+            offset += n * 8;
+            Ok(v)
+        };
+        if flags & PICKLE_FLAG_COV != 0 {
+            self.0.cov = StateCov::PVCov(na::Matrix6::<f64>::from_column_slice(&take(36)?));
+        }
+        if flags & PICKLE_FLAG_CONSIDER != 0 {
+            type ConsiderParamCov = na::SMatrix<f64, NUM_CONSIDER_PARAMS, NUM_CONSIDER_PARAMS>;
+            type Sensitivity = na::SMatrix<f64, 6, NUM_CONSIDER_PARAMS>;
+            let cov = take(NUM_CONSIDER_PARAMS * NUM_CONSIDER_PARAMS)?;
+            let sensitivity = take(6 * NUM_CONSIDER_PARAMS)?;
+            self.0.consider = Some(ConsiderCov {
+                cov: ConsiderParamCov::from_column_slice(&cov),
+                sensitivity: Sensitivity::from_column_slice(&sensitivity),
+            });
+        }
+        if offset != state.len() {
+            return Err(bad_len());
         }
         Ok(())
     }
 
     fn __getstate__(&self, py: Python) -> PyResult<PyObject> {
-        let len: usize = 56
-            + match self.0.cov {
-                StateCov::None => 0,
-                StateCov::PVCov(_) => 36,
-            };
-        let mut buffer: Vec<u8> = vec![0; len];
-        buffer[0..8].clone_from_slice(
+        let mut buffer: Vec<u8> = Vec::new();
+        buffer.extend_from_slice(
             &self
                 .0
                 .time
                 .as_mjd_with_scale(crate::TimeScale::TAI)
                 .to_le_bytes(),
         );
-        unsafe {
-            buffer[8..56].clone_from_slice(std::slice::from_raw_parts(
-                self.0.pv.as_ptr() as *const u8,
-                48,
-            ));
+        extend_f64(&mut buffer, self.0.pv.as_slice());
+
+        let mut flags: u64 = match self.0.cov_frame {
+            CovFrame::GCRF => 0,
+            CovFrame::LVLH => 1,
+            CovFrame::RIC => 2,
+        } << PICKLE_FRAME_SHIFT;
+        if matches!(self.0.cov, StateCov::PVCov(_)) {
+            flags |= PICKLE_FLAG_COV;
         }
-        if let StateCov::PVCov(cov) = self.0.cov {
-            unsafe {
-                buffer[56..].clone_from_slice(std::slice::from_raw_parts(
-                    cov.as_ptr() as *const u8,
-                    36 * 8,
-                ));
-            }
+        if self.0.consider.is_some() {
+            flags |= PICKLE_FLAG_CONSIDER;
+        }
+        buffer.extend_from_slice(&flags.to_le_bytes());
+
+        if let StateCov::PVCov(cov) = &self.0.cov {
+            extend_f64(&mut buffer, cov.as_slice());
+        }
+        if let Some(consider) = &self.0.consider {
+            extend_f64(&mut buffer, consider.cov.as_slice());
+            extend_f64(&mut buffer, consider.sensitivity.as_slice());
         }
         pyo3::types::PyBytes::new(py, &buffer).into_py_any(py)
     }
@@ -322,3 +457,153 @@ impl PySatState {
         self.0.to_string()
     }
 }
+
+impl PySatState {
+    /// Target time & propagation settings from the "propagate" arguments
+    fn time_and_settings(
+        &self,
+        timedur: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<(Instant, Option<PropSettings>)> {
+        let time: Instant = {
+            if timedur.is_instance_of::<PyInstant>() {
+                timedur.extract::<PyInstant>()?.0
+            } else if timedur.is_instance_of::<PyDuration>() {
+                let dur = timedur.extract::<PyDuration>()?;
+                self.0.time + dur.0
+            } else {
+                return Err(pyo3::exceptions::PyTypeError::new_err(
+                    "timedur must be satkit.time or satkit.duration",
+                ));
+            }
+        };
+
+        let propsettings: Option<PropSettings> = match kwargs.is_some() {
+            true => {
+                let kw = kwargs.unwrap();
+                match kw.get_item("propsettings")? {
+                    None => None,
+                    Some(v) => Some(v.extract::<PyPropSettings>()?.0),
+                }
+            }
+            false => None,
+        };
+        Ok((time, propsettings))
+    }
+}
+
+/// Rotate position & velocity covariances from the GCRF to the
+/// RIC (radial, in-track, cross-track) frame of the corresponding states
+///
+/// Args:
+///     states (list[satkit.satstate] | numpy.ndarray): States defining the RIC frames, as a list of satstate objects, or an Nx6 array of GCRF position (meters) & velocity (meters / second).  A single satstate or 6-element array is also accepted
+///     covs (numpy.ndarray): Nx6x6 array of GCRF position & velocity covariances, or a single 6x6 covariance.  Covariances of satstate objects are ignored
+///
+/// Returns:
+///     numpy.ndarray: Nx6x6 array of covariances in the RIC frame, or 6x6 if a single covariance is input
+///
+/// Note:
+///     The same 3x3 rotation is applied to position & velocity; see satstate
+#[pyfunction]
+pub fn cov_gcrf2ric(
+    states: &Bound<'_, PyAny>,
+    covs: np::PyReadonlyArrayDyn<f64>,
+) -> PyResult<PyObject> {
+    let pvs: Vec<na::Vector6<f64>> = if let Ok(s) = states.extract::<PyRef<PySatState>>() {
+        vec![s.0.pv]
+    } else if let Ok(v) = states.extract::<Vec<PyRef<PySatState>>>() {
+        v.iter().map(|s| s.0.pv).collect()
+    } else if let Ok(arr) = states.extract::<np::PyReadonlyArrayDyn<f64>>() {
+        let arr = arr.as_array();
+        match arr.shape() {
+            [6] => vec![na::Vector6::<f64>::from_iterator(arr.iter().copied())],
+            [_, 6] => arr
+                .rows()
+                .into_iter()
+                .map(|r| na::Vector6::<f64>::from_iterator(r.iter().copied()))
+                .collect(),
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "states array must have shape (N, 6) or (6,)",
+                ))
+            }
+        }
+    } else {
+        return Err(pyo3::exceptions::PyTypeError::new_err(
+            "states must be a satstate, list of satstate, or numpy array",
+        ));
+    };
+
+    let covs = covs.as_array();
+    let single = match covs.shape() {
+        [6, 6] => true,
+        [_, 6, 6] => false,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "covs must have shape (N, 6, 6) or (6, 6)",
+            ))
+        }
+    };
+    let ncov = if single { 1 } else { covs.shape()[0] };
+    if ncov != pvs.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Number of states ({}) does not match number of covariances ({})",
+            pvs.len(),
+            ncov
+        )));
+    }
+
+    // Covariances in row-major order
+    let cov_in: Vec<f64> = covs.iter().copied().collect();
+    let ric: Vec<na::Matrix6<f64>> = pvs
+        .iter()
+        .zip(cov_in.chunks_exact(36))
+        .map(|(pv, c)| {
+            // RIC frame depends only on position & velocity, not time
+            let mut state = SatState::from_pv(
+                &Instant::J2000,
+                &pv.fixed_rows::<3>(0).into(),
+                &pv.fixed_rows::<3>(3).into(),
+            );
+            state.set_cov(StateCov::PVCov(na::Matrix6::from_row_slice(c)));
+            match state.in_frame(CovFrame::RIC).cov {
+                StateCov::PVCov(cov) => cov,
+                StateCov::None => unreachable!(),
+            }
+        })
+        .collect();
+
+    pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+        if single {
+            return Ok(mat2py(py, &ric[0]));
+        }
+        let flat: Vec<f64> = ric
+            .iter()
+            .flat_map(|m| m.transpose().iter().copied().collect::<Vec<f64>>())
+            .collect();
+        np::PyArray1::from_vec(py, flat)
+            .reshape([ncov, 6, 6])?
+            .into_py_any(py)
+    })
+}
+
+/// Position & velocity of a deputy satellite relative to a chief, in
+/// the chief's RIC (radial, in-track, cross-track) frame
+///
+/// Args:
+///     chief (satkit.satstate): State of the chief satellite, defining the RIC frame
+///     deputy (satkit.satstate): State of the deputy satellite, at the same time
+///
+/// Returns:
+///     numpy.ndarray: 6-element RIC relative position (meters) & velocity (meters / second)
+///
+/// Note:
+///     Relative velocity is as seen in the rotating RIC frame, as in the Clohessy-Wiltshire equations
+#[pyfunction]
+pub fn relative_state(chief: PyRef<PySatState>, deputy: PyRef<PySatState>) -> PyResult<PyObject> {
+    let rel = chief
+        .0
+        .relative_state(&deputy.0)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    pyo3::Python::with_gil(|py| vec2py(py, &rel))
+}
@@ -184,9 +184,13 @@ pub fn sgp4(
     } else if tle.is_instance_of::<PyList>() {
         let mut tles = tle.extract::<Vec<PyRefMut<PyTLE>>>()?;
         let tmarray = time.to_time_vec()?;
+        let gravconst: psgp4::GravConst = gravconst.into();
+        let opsmode: psgp4::OpsMode = opsmode.into();
         let results: Vec<psgp4::SGP4State> = tles
             .iter_mut()
-            .map(|tle| psgp4::sgp4(&mut tle.0, tmarray.as_slice()))
+            .map(|tle| {
+                psgp4::sgp4_full(&mut tle.0, tmarray.as_slice(), gravconst, opsmode)
+            })
             .collect();
 
         pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
@@ -263,3 +267,74 @@ pub fn sgp4(
         ))
     }
 }
+
+/// Position & velocity of each satellite in a TLE catalog at a common time
+///
+/// Note:
+///     SGP4 is run on each TLE, and the results are gathered into a single
+///     array, for e.g. analysis pipelines.  Satellites for which SGP4 fails
+///     have NaN rows in the output, and are listed in the failure output
+///
+/// Args:
+///     catalog (list[TLE]): TLE catalog, e.g. as loaded with TLE.from_file
+///     time (satkit.time): time at which to compute position and velocity
+///
+/// Keyword Args:
+///     gravconst (satkit.sgp4_gravconst): gravity constant to use.  Default is gravconst.wgs72
+///     opsmode (satkit.sgp4_opsmode): opsmode.afspc (Air Force Space Command) or opsmode.improved.  Default is opsmode.afspc
+///
+/// Returns:
+///     tuple[npt.ArrayLike[np.int32], npt.ArrayLike[np.float64], list[tuple[int, satkit.sgp4_error]]]: satellite numbers (N), Nx6 array of TEME position (meters) and velocity (meters/second), and list of (satellite number, error) for satellites on which SGP4 failed
+#[pyfunction]
+#[pyo3(signature=(catalog, time, **kwds))]
+pub fn catalog_states(
+    catalog: &Bound<'_, PyList>,
+    time: &Bound<'_, PyAny>,
+    kwds: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let mut opsmode: OpsMode = OpsMode::afspc;
+    let mut gravconst: GravConst = GravConst::wgs72;
+    if let Some(kw) = kwds {
+        if let Some(v) = kw.get_item("opsmode")? {
+            opsmode = v.extract::<OpsMode>()?;
+        }
+        if let Some(v) = kw.get_item("gravconst")? {
+            gravconst = v.extract::<GravConst>()?;
+        }
+    }
+    let tm = time.to_time_vec()?;
+    if tm.len() != 1 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "time must be a single time",
+        ));
+    }
+
+    let mut tles = catalog.extract::<Vec<PyRefMut<PyTLE>>>()?;
+    let mut ids: Vec<i32> = Vec::with_capacity(tles.len());
+    let mut states: Vec<f64> = Vec::with_capacity(tles.len() * 6);
+    let mut failures: Vec<(i32, PySGP4Error)> = Vec::new();
+    for tle in tles.iter_mut() {
+        let (p, v, e) = psgp4::sgp4_full(
+            &mut tle.0,
+            tm.as_slice(),
+            gravconst.clone().into(),
+            opsmode.clone().into(),
+        );
+        ids.push(tle.0.sat_num);
+        if e[0] == psgp4::SGP4Error::SGP4Success {
+            states.extend(p.iter().chain(v.iter()));
+        } else {
+            states.extend([f64::NAN; 6]);
+            failures.push((tle.0.sat_num, e[0].clone().into()));
+        }
+    }
+
+    pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+        (
+            PyArray1::from_slice(py, ids.as_slice()),
+            PyArray1::from_slice(py, states.as_slice()).reshape([tles.len(), 6])?,
+            failures,
+        )
+            .into_py_any(py)
+    })
+}
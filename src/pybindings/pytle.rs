@@ -1,6 +1,7 @@
 use pyo3::prelude::*;
 use pyo3::IntoPyObjectExt;
 
+use super::PyInstant;
 use crate::tle::TLE;
 use std::fs::File;
 use std::io::{self, BufRead};
@@ -80,6 +81,37 @@ impl PyTLE {
         }
     }
 
+    /// Return TLE(s) loaded from Space-Track or CelesTrak GP JSON text
+    ///
+    /// # Arguments:
+    ///
+    /// * `text` - JSON text of a single GP record or a list of records
+    ///
+    /// # Returns:
+    ///
+    /// * `tle` - a TLE object for a single record, or a list of TLE
+    ///           objects for a list of records
+    #[staticmethod]
+    fn from_spacetrack_json(text: &str) -> PyResult<PyObject> {
+        let load = || -> crate::SKResult<PyObject> {
+            let value = json::parse(text)?;
+            pyo3::Python::with_gil(|py| -> crate::SKResult<PyObject> {
+                match value.is_array() {
+                    true => Ok(value
+                        .members()
+                        .map(crate::tle::tle_from_spacetrack_json)
+                        .collect::<crate::SKResult<Vec<TLE>>>()?
+                        .into_py_any(py)?),
+                    false => Ok(crate::tle::tle_from_spacetrack_json(&value)?.into_py_any(py)?),
+                }
+            })
+        };
+        load().map_err(|e| {
+            let serr = format!("Error loading TLEs: {}", e);
+            pyo3::exceptions::PyValueError::new_err(serr)
+        })
+    }
+
     /// Satellite NORAD Catalog Number
     #[getter]
     const fn get_satnum(&self) -> PyResult<i32> {
@@ -242,3 +274,29 @@ impl<'b> From<&'b mut PyTLE> for &'b mut TLE {
         &mut s.0
     }
 }
+
+/// Convert SGP4 starred ballistic coefficient (B*), in inverse
+/// Earth radii, to coefficient of drag times area over mass, m^2/kg
+#[pyfunction]
+pub fn bstar_to_ballistic(bstar: f64) -> f64 {
+    crate::tle::bstar_to_ballistic(bstar)
+}
+
+/// Convert coefficient of drag times area over mass, m^2/kg, to
+/// SGP4 starred ballistic coefficient (B*), in inverse Earth radii
+#[pyfunction]
+pub fn ballistic_to_bstar(bc: f64) -> f64 {
+    crate::tle::ballistic_to_bstar(bc)
+}
+
+/// Select the TLE with epoch nearest a given time; the earlier of two
+/// equally distant, or None if the list is empty
+#[pyfunction]
+pub fn select_tle(py: Python, tles: Vec<Py<PyTLE>>, time: &PyInstant) -> Option<Py<PyTLE>> {
+    tles.iter()
+        .min_by_key(|tle| {
+            let dt = (tle.borrow(py).0.epoch - time.0).as_microseconds();
+            (dt.abs(), dt)
+        })
+        .map(|tle| tle.clone_ref(py))
+}
@@ -57,39 +57,25 @@ where
     }
 }
 
+/// Evaluate a vector function of time over a time or array of times
+///
+/// The function is evaluated with the GIL released.  A single time
+/// gives a 3-element array; multiple times give an Nx3 array
 pub fn py_vec3_of_time_arr(
-    cfunc: &dyn Fn(&Instant) -> Vector3,
+    cfunc: &(dyn Fn(&Instant) -> Vector3 + Sync),
     tmarr: &Bound<'_, PyAny>,
 ) -> PyResult<PyObject> {
     let tm = tmarr.to_time_vec()?;
-    match tm.len() {
-        1 => {
-            let v: Vec3 = cfunc(&tm[0]);
-            pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
-                np::PyArray1::from_slice(py, v.as_slice()).into_py_any(py)
-            })
-        }
-        _ => {
-            let n = tm.len();
-            pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
-                let out = np::PyArray2::<f64>::zeros(py, (n, 3), false);
-                for (idx, time) in tm.iter().enumerate() {
-                    let v: Vec3 = cfunc(time);
-                    // I cannot figure out how to do this with a "safe" function,
-                    // but... careful checking of dimensions above so this should
-                    // never fail
-                    unsafe {
-                        std::ptr::copy_nonoverlapping(
-                            v.as_ptr(),
-                            out.as_raw_array_mut().as_mut_ptr().offset(idx as isize * 3),
-                            3,
-                        );
-                    }
-                }
-                out.into_py_any(py)
-            })
+    pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+        let v: Vec<Vec3> = py.allow_threads(|| tm.iter().map(cfunc).collect());
+        match v.len() {
+            1 => np::PyArray1::from_slice(py, v[0].as_slice()).into_py_any(py),
+            n => {
+                let flat: Vec<f64> = v.iter().flat_map(|x| x.iter().copied()).collect();
+                slice2py2d(py, &flat, n, 3)
+            }
         }
-    }
+    })
 }
 
 pub fn py_vec3_of_time_result_arr(
@@ -0,0 +1,145 @@
+//! Atmospheric refraction of elevation angles
+//!
+//! Refraction bends light from objects near the horizon, so that they
+//! appear higher than their geometric elevation.  Formulas are from
+//! Meeus, "Astronomical Algorithms", Chapter 16, and are accurate to
+//! about 0.1 arcminute for elevations above the horizon
+//!
+//! Below about -1 degree of elevation the formulas are not valid; the
+//! refraction is tapered linearly from its -1 degree value to zero at
+//! -2 degrees, so that the correction remains continuous, and far below
+//! the horizon the geometric elevation is returned unchanged
+
+/// Standard atmospheric pressure for refraction formulas, hPa (millibar)
+pub const STANDARD_PRESSURE: f64 = 1010.0;
+
+/// Standard atmospheric temperature for refraction formulas, degrees Celsius
+pub const STANDARD_TEMPERATURE: f64 = 10.0;
+
+/// Lowest elevation, in degrees, at which the formulas are evaluated
+const MIN_ELEVATION_DEG: f64 = -1.0;
+
+/// Width, in degrees, of the taper to zero refraction below
+/// [`MIN_ELEVATION_DEG`]
+const TAPER_WIDTH_DEG: f64 = 1.0;
+
+/// Evaluate refraction formula, in arcminutes, at elevation `h` in degrees,
+/// tapering to zero below the valid range
+fn tapered(h: f64, formula: impl Fn(f64) -> f64) -> f64 {
+    if h >= MIN_ELEVATION_DEG {
+        return formula(h);
+    }
+    let weight = ((h - MIN_ELEVATION_DEG) / TAPER_WIDTH_DEG + 1.0).max(0.0);
+    formula(MIN_ELEVATION_DEG) * weight
+}
+
+/// Scale factor for non-standard pressure & temperature
+fn conditions_scale(pressure: f64, temperature: f64) -> f64 {
+    (pressure / STANDARD_PRESSURE) * ((273.0 + STANDARD_TEMPERATURE) / (273.0 + temperature))
+}
+
+/// Atmospheric refraction for a given geometric elevation
+///
+/// Uses the formula of Saemundsson (1986)
+///
+/// # Arguments
+///
+/// * `el` - Geometric (true) elevation, radians
+/// * `pressure` - Atmospheric pressure at the observer, hPa (millibar)
+/// * `temperature` - Air temperature at the observer, degrees Celsius
+///
+/// # Returns
+///
+/// * Refraction, radians.  Add to the geometric elevation to get the
+///   apparent (observed) elevation.  Tapers to zero from -1 to -2 degrees
+///   of elevation, below the valid range of the formula, and is zero below
+///
+/// # Example
+///
+/// ```
+/// use satkit::refraction::{refraction, STANDARD_PRESSURE, STANDARD_TEMPERATURE};
+///
+/// // About half a degree at the horizon
+/// let r = refraction(0.0, STANDARD_PRESSURE, STANDARD_TEMPERATURE);
+/// assert!((r.to_degrees() - 0.48).abs() < 0.01);
+/// ```
+pub fn refraction(el: f64, pressure: f64, temperature: f64) -> f64 {
+    let r_arcmin = tapered(el.to_degrees(), |h| {
+        1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan()
+    });
+    (r_arcmin / 60.0).to_radians().max(0.0) * conditions_scale(pressure, temperature)
+}
+
+/// Atmospheric refraction for a given apparent elevation
+///
+/// Uses the formula of Bennett (1982).  This is the inverse of
+/// [`refraction`], to within ~0.1 arcminute
+///
+/// # Arguments
+///
+/// * `el` - Apparent (observed) elevation, radians
+/// * `pressure` - Atmospheric pressure at the observer, hPa (millibar)
+/// * `temperature` - Air temperature at the observer, degrees Celsius
+///
+/// # Returns
+///
+/// * Refraction, radians.  Subtract from the apparent elevation to get
+///   the geometric elevation.  Tapers to zero from -1 to -2 degrees of
+///   elevation, below the valid range of the formula, and is zero below
+pub fn refraction_apparent(el: f64, pressure: f64, temperature: f64) -> f64 {
+    let r_arcmin = tapered(el.to_degrees(), |h| {
+        1.0 / (h + 7.31 / (h + 4.4)).to_radians().tan()
+    });
+    (r_arcmin / 60.0).to_radians().max(0.0) * conditions_scale(pressure, temperature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const ARCMIN: f64 = PI / 180.0 / 60.0;
+
+    #[test]
+    fn test_refraction() {
+        let p = STANDARD_PRESSURE;
+        let t = STANDARD_TEMPERATURE;
+
+        // Near zero at zenith
+        assert!(refraction(PI / 2.0, p, t) < 0.01 * ARCMIN);
+        assert!(refraction_apparent(PI / 2.0, p, t) < 0.01 * ARCMIN);
+
+        // About half a degree near the horizon
+        assert!((refraction(0.0, p, t).to_degrees() - 0.5).abs() < 0.05);
+        assert!((refraction_apparent(0.0, p, t).to_degrees() - 0.57).abs() < 0.02);
+
+        // About 1 arcminute at 45 degrees
+        assert!((refraction(PI / 4.0, p, t) / ARCMIN - 1.0).abs() < 0.05);
+
+        // Formulas are mutually consistent, including near the horizon
+        // where refraction changes rapidly with elevation
+        for eldeg in [-0.5, 0.0, 0.5, 2.0, 5.0, 15.0, 45.0, 80.0] {
+            let el: f64 = f64::to_radians(eldeg);
+            let app = el + refraction(el, p, t);
+            let el2 = app - refraction_apparent(app, p, t);
+            assert!((el2 - el).abs() < 0.1 * ARCMIN, "elevation {}", eldeg);
+        }
+
+        // Below the valid range, tapers continuously to zero
+        let rlow = refraction(-1.0_f64.to_radians(), p, t);
+        assert!(rlow.is_finite() && rlow > refraction(0.0, p, t));
+        let rjust = refraction((-1.0_f64 - 1.0e-9).to_radians(), p, t);
+        assert!((rjust - rlow).abs() < 1.0e-6 * rlow);
+        let rmid = refraction(-1.5_f64.to_radians(), p, t);
+        assert!((rmid - 0.5 * rlow).abs() < 1.0e-12);
+        for eldeg in [-2.0, -10.0, -90.0] {
+            assert_eq!(refraction(f64::to_radians(eldeg), p, t), 0.0);
+            assert_eq!(refraction_apparent(f64::to_radians(eldeg), p, t), 0.0);
+        }
+
+        // Scales with pressure & inversely with absolute temperature
+        let r0 = refraction(0.1, p, t);
+        assert!((refraction(0.1, p / 2.0, t) - r0 / 2.0).abs() < 1.0e-15);
+        assert!(refraction(0.1, p, t + 30.0) < r0);
+    }
+}
@@ -13,8 +13,14 @@
 ///
 pub use self::satrec::SatRec;
 
-#[derive(PartialEq, PartialOrd, Clone, Debug, Eq, Copy)]
+/// Gravity constant set used by SGP4
+///
+/// TLEs are generated with the WGS72 constants, so WGS72 (the default)
+/// should be used to reproduce NORAD results.  Other constant sets give
+/// small but real differences in position
+#[derive(PartialEq, PartialOrd, Clone, Debug, Eq, Copy, Default)]
 pub enum GravConst {
+    #[default]
     WGS72,
     WGS72OLD,
     WGS84,
@@ -38,7 +44,10 @@ mod sgp4_lowlevel;
 mod sgp4init;
 
 pub use sgp4_impl::sgp4;
+pub use sgp4_impl::sgp4_checked;
 pub use sgp4_impl::sgp4_full;
 pub use sgp4_impl::SGP4Error;
 pub use sgp4_impl::SGP4Result;
 pub use sgp4_impl::SGP4State;
+pub use sgp4_impl::tle_epoch_state;
+pub use sgp4_impl::tle_seed_from_state;
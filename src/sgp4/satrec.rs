@@ -1,4 +1,4 @@
-use super::OpsMode;
+use super::{GravConst, OpsMode};
 
 #[derive(PartialEq, PartialOrd, Clone, Debug)]
 pub struct SatRec {
@@ -6,6 +6,7 @@ pub struct SatRec {
     pub epochtynumrev: i32,
     pub error: i32,
     pub operationmode: OpsMode,
+    pub gravconst: GravConst,
     pub init: char,
     pub method: char,
 
@@ -147,6 +148,7 @@ impl SatRec {
             epochtynumrev: 0,
             error: 0,
             operationmode: OpsMode::IMPROVED,
+            gravconst: GravConst::WGS72,
             init: 'n',
             method: 'm',
 
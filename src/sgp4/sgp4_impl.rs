@@ -1,9 +1,13 @@
 use super::sgp4_lowlevel::sgp4_lowlevel; // propagator
 use super::sgp4init::sgp4init;
 
+use crate::frametransform::qteme2gcrf;
+use crate::kepler::Kepler;
+use crate::orbitprop::SatState;
+use crate::skerror;
 use crate::tle::TLE;
-use crate::{Instant, TimeScale};
-use nalgebra::{Const, Dyn, OMatrix};
+use crate::{Instant, SKErr, SKResult, TimeScale};
+use nalgebra::{Const, Dyn, OMatrix, Vector3};
 
 use thiserror::Error;
 
@@ -66,7 +70,8 @@ use super::{GravConst, OpsMode};
 /// output satellite position and velocity at given time
 /// in the "TEME" coordinate system
 ///
-/// This is a shortcut to run sgp4_full with the WGS84 gravity model and IMPROVED ops mode
+/// This is a shortcut to run sgp4_full with the WGS72 gravity model and IMPROVED ops mode.
+/// WGS72 is the constant set with which TLEs are generated, so matches NORAD results
 ///
 /// A detailed description is
 /// [here](https://celestrak.org/publications/AIAA/2008-6770/AIAA-2008-6770.pdf)
@@ -89,7 +94,10 @@ use super::{GravConst, OpsMode};
 ///
 /// # Note:
 ///
-/// This is a shortcut to run sgp4_full with the WGS84 gravity model and IMPROVED ops mode
+/// This is a shortcut to run sgp4_full with the WGS72 gravity model and IMPROVED ops mode
+///
+/// Versions up to 0.5.5 used the WGS84 gravity model here; call [`sgp4_full`] with
+/// [`GravConst::WGS84`] to reproduce those results
 ///
 /// # Example
 ///
@@ -126,7 +134,7 @@ use super::{GravConst, OpsMode};
 ///
 #[inline]
 pub fn sgp4(tle: &mut TLE, tm: &[Instant]) -> SGP4State {
-    sgp4_full(tle, tm, GravConst::WGS84, OpsMode::IMPROVED)
+    sgp4_full(tle, tm, GravConst::default(), OpsMode::IMPROVED)
 }
 
 ///
@@ -146,7 +154,7 @@ pub fn sgp4(tle: &mut TLE, tm: &[Instant]) -> SGP4State {
 /// * `tm` -  The time at which to compute position and velocity
 ///           Input as a slice for convenience.
 ///
-/// * `gravconst` - The gravitational constant to use.
+/// * `gravconst` - The gravitational constant to use.  WGS72 (the default) matches NORAD
 ///
 /// * `opsmode` - The operational mode to use.
 ///
@@ -201,7 +209,13 @@ pub fn sgp4_full(
 ) -> SGP4State {
     const TWOPI: f64 = PI * 2.0;
 
-    if tle.satrec.is_none() {
+    // (Re-)initialize if not yet initialized, or initialized
+    // with a different constant set or ops mode
+    let reinit = match &tle.satrec {
+        Some(s) => s.gravconst != gravconst || s.operationmode != opsmode,
+        None => true,
+    };
+    if reinit {
         let no = tle.mean_motion / (1440.0 / TWOPI);
         let bstar = tle.bstar;
         let ndot = tle.mean_motion_dot / (1440.0 * 1440.0 / TWOPI);
@@ -262,13 +276,155 @@ pub fn sgp4_full(
     (rarr * 1.0e3, varr * 1.0e3, earr)
 }
 
+/// Run SGP4, returning an error if it fails at any of the times
+///
+/// This is [`sgp4_full`], with SGP4 error codes surfaced as structured
+/// errors rather than a per-time list of error codes
+///
+/// # Arguments
+///
+/// * `tle` - The TLE on which to operate.  SGP-4 metadata is stored
+///   after each propagation
+/// * `tm` - Times at which to compute position & velocity
+/// * `gravconst` - Gravity constant set
+/// * `opsmode` - Ops mode
+///
+/// # Returns
+///
+/// * Position (m) & velocity (m/s) 3xN matrices in the TEME frame, or,
+///   for the first of the times (in the order given) at which SGP4 fails,
+///   [`SKErr::Sgp4Decayed`](crate::SKErr::Sgp4Decayed) if the satellite
+///   has decayed, or [`SKErr::Sgp4BadElements`](crate::SKErr::Sgp4BadElements)
+///   for other failures
+pub fn sgp4_checked(
+    tle: &mut TLE,
+    tm: &[Instant],
+    gravconst: GravConst,
+    opsmode: OpsMode,
+) -> SKResult<(StateArr, StateArr)> {
+    let (pos, vel, errs) = sgp4_full(tle, tm, gravconst, opsmode);
+    for (time, e) in tm.iter().zip(errs) {
+        match e {
+            SGP4Error::SGP4Success => {}
+            SGP4Error::SGP4ErrorOrbitDecay => {
+                return Err(Box::new(SKErr::Sgp4Decayed { time: *time }))
+            }
+            error => return Err(Box::new(SKErr::Sgp4BadElements { time: *time, error })),
+        }
+    }
+    Ok((pos, vel))
+}
+
+/// Satellite state at the TLE epoch, in the GCRF frame
+///
+/// Runs SGP4 at the TLE epoch (zero time since epoch) and rotates the
+/// resulting TEME position & velocity into the GCRF frame
+///
+/// # Arguments
+///
+/// * `tle` - Two-line element set
+///
+/// # Returns
+///
+/// * Osculating satellite state at the TLE epoch, with no covariance,
+///   or error if SGP4 initialization fails (e.g., invalid elements)
+///
+/// # Notes
+///
+/// * Uses the WGS72 gravity model and IMPROVED ops mode, as [`sgp4`]
+pub fn tle_epoch_state(tle: &TLE) -> SKResult<SatState> {
+    let mut tle = tle.clone();
+    let epoch = tle.epoch;
+    let (pteme, vteme) = sgp4_checked(&mut tle, &[epoch], GravConst::default(), OpsMode::IMPROVED)?;
+    let q = qteme2gcrf(&epoch);
+    let pos = q * pteme.fixed_view::<3, 1>(0, 0);
+    let vel = q * vteme.fixed_view::<3, 1>(0, 0);
+    Ok(SatState::from_pv(&epoch, &pos, &vel))
+}
+
+/// Maximum number of iterations in [`tle_seed_from_state`]
+const SEED_MAX_ITER: usize = 50;
+
+/// Position convergence tolerance, meters, in [`tle_seed_from_state`]
+const SEED_POS_TOL: f64 = 1.0e-3;
+
+/// TEME position & velocity at the TLE epoch
+fn teme_epoch_pv(tle: &TLE) -> SKResult<(Vector3<f64>, Vector3<f64>)> {
+    let mut tle = tle.clone();
+    tle.satrec = None;
+    let epoch = tle.epoch;
+    let (pteme, vteme) = sgp4_checked(&mut tle, &[epoch], GravConst::default(), OpsMode::IMPROVED)?;
+    Ok((
+        pteme.fixed_view::<3, 1>(0, 0).into(),
+        vteme.fixed_view::<3, 1>(0, 0).into(),
+    ))
+}
+
+/// TLE with elements set from the osculating elements of a TEME state
+fn tle_from_osculating(
+    pos: &Vector3<f64>,
+    vel: &Vector3<f64>,
+    epoch: &Instant,
+    bstar: f64,
+) -> SKResult<TLE> {
+    let kep = Kepler::from_pv(*pos, *vel)?;
+    let mut tle = TLE::new();
+    tle.epoch = *epoch;
+    tle.bstar = bstar;
+    // Revolutions per day
+    tle.mean_motion = kep.mean_motion() * 86400.0 / (2.0 * PI);
+    tle.eccen = kep.eccen;
+    tle.inclination = kep.incl.to_degrees();
+    tle.raan = kep.raan.to_degrees().rem_euclid(360.0);
+    tle.arg_of_perigee = kep.w.to_degrees().rem_euclid(360.0);
+    tle.mean_anomaly = kep.mean_anomaly().to_degrees().rem_euclid(360.0);
+    Ok(tle)
+}
+
+/// Initial TLE approximating a satellite state, e.g. as a starting
+/// point for fitting a TLE to a numerically propagated trajectory
+///
+/// # Arguments
+///
+/// * `state` - Satellite state, GCRF frame.  The state time is the TLE epoch
+/// * `bstar_guess` - Drag term (B*) of the TLE, 1 / Earth radii
+///
+/// # Returns
+///
+/// * TLE whose SGP4 state at epoch matches the input state,
+///   or error if the iteration does not converge
+///
+/// # Notes
+///
+/// * TLE elements are mean elements, with mean motion in the Kozai
+///   convention, so the osculating elements of the state are not a good
+///   TLE.  Instead, the osculating state used to set the elements is
+///   iteratively corrected by the difference between the input state and
+///   the SGP4 state at epoch, until they agree to within 1 mm
+/// * Name, catalog number & other metadata of the returned TLE are defaults
+pub fn tle_seed_from_state(state: &SatState, bstar_guess: f64) -> SKResult<TLE> {
+    let q = qteme2gcrf(&state.time).conjugate();
+    let pos_target = q * state.pos_gcrf();
+    let vel_target = q * state.vel_gcrf();
+
+    let (mut pos, mut vel) = (pos_target, vel_target);
+    for _iter in 0..SEED_MAX_ITER {
+        let tle = tle_from_osculating(&pos, &vel, &state.time, bstar_guess)?;
+        let (psgp4, vsgp4) = teme_epoch_pv(&tle)?;
+        let dpos = pos_target - psgp4;
+        if dpos.norm() < SEED_POS_TOL {
+            return Ok(tle);
+        }
+        pos += dpos;
+        vel += vel_target - vsgp4;
+    }
+    skerror!("TLE seed did not converge in {} iterations", SEED_MAX_ITER)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::skerror;
-    use crate::tle::TLE;
     use crate::utils::test;
-    use crate::SKResult;
     use std::io::BufRead;
 
     #[test]
@@ -285,6 +441,137 @@ mod tests {
         assert!(err[0] == SGP4Error::SGP4Success);
     }
 
+    #[test]
+    fn test_gravconst() {
+        // Vallado SGP4 verification case 00005, which uses WGS72 constants
+        let line1 = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+        let line2 = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+        let mut tle = TLE::load_2line(line1, line2).unwrap();
+        let tm = [tle.epoch, tle.epoch + crate::Duration::from_days(1.0)];
+
+        // Default constant set is WGS72, and matches the reference
+        let (pos72, vel72, err) = sgp4(&mut tle, &tm);
+        assert!(err[0] == SGP4Error::SGP4Success);
+        let pref = [7022.46529266, -1400.08296755, 0.03995155];
+        let vref = [1.893841015, 6.405893759, 4.534807250];
+        for idx in 0..3 {
+            assert!((pos72[idx] * 1.0e-3 - pref[idx]).abs() < 1.0e-6);
+            assert!((vel72[idx] * 1.0e-3 - vref[idx]).abs() < 1.0e-9);
+        }
+
+        // WGS84 differs by a small amount: tens of meters
+        let (pos84, _, _) = sgp4_full(&mut tle, &tm, GravConst::WGS84, OpsMode::IMPROVED);
+        for idx in 0..tm.len() {
+            let diff = (pos84.column(idx) - pos72.column(idx)).norm();
+            assert!(diff > 1.0 && diff < 1.0e3);
+        }
+
+        // Switching back re-initializes with the requested constants
+        let (pos, _, _) = sgp4_full(&mut tle, &tm, GravConst::WGS72, OpsMode::IMPROVED);
+        assert!((pos - pos72).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_sgp4_checked() -> SKResult<()> {
+        // Vallado SGP4 verification case 28872, a low-perigee object
+        // with very high drag that decays within about an hour of epoch
+        let line1 = "1 28872U 05037B   05333.02012661  .25992681  00000-0  24476-3 0  1534";
+        let line2 = "2 28872  96.4736 157.9986 0303955 244.0492 110.6523 16.46015938 10708";
+        let mut tle = TLE::load_2line(line1, line2)?;
+        let tm: Vec<Instant> = (0..120)
+            .map(|m| tle.epoch + crate::Duration::from_minutes(m as f64))
+            .collect();
+
+        // Decay is reported at the first time it occurs
+        let err = sgp4_checked(&mut tle, &tm, GravConst::WGS72, OpsMode::IMPROVED).unwrap_err();
+        match err.downcast_ref::<SKErr>() {
+            Some(SKErr::Sgp4Decayed { time }) => {
+                let minutes = (*time - tle.epoch).as_minutes();
+                assert!(minutes > 30.0 && minutes < 90.0);
+                let (_, _, errs) = sgp4(&mut tle, &[*time - crate::Duration::from_minutes(1.0)]);
+                assert_eq!(errs[0], SGP4Error::SGP4Success);
+            }
+            _ => return skerror!("Expected decay error, got {}", err),
+        }
+
+        // Before decay, positions & velocities are returned
+        let (pos, _vel) = sgp4_checked(&mut tle, &tm[0..10], GravConst::WGS72, OpsMode::IMPROVED)?;
+        assert_eq!(pos.ncols(), 10);
+
+        // Invalid elements
+        let mut bad = tle.clone();
+        bad.satrec = None;
+        bad.eccen = 1.5;
+        let err = sgp4_checked(&mut bad, &tm, GravConst::WGS72, OpsMode::IMPROVED).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SKErr>(),
+            Some(SKErr::Sgp4BadElements {
+                error: SGP4Error::SGP4ErrorEccen,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tle_epoch_state() -> SKResult<()> {
+        let line1 = "1 45727U 20037E   24323.73967089  .00003818  00000+0  31595-3 0  9995";
+        let line2 = "2 45727  97.7798 139.6782 0011624 329.2427  30.8113 14.99451155239085";
+        let tle = TLE::load_2line(line1, line2)?;
+        let state = tle_epoch_state(&tle)?;
+        assert_eq!(state.time, tle.epoch);
+
+        // Mean motion in the TLE is Kozai; SGP4 initialization converts
+        // it to Brouwer, from which the mean semi-major axis follows
+        let mut tle_init = tle.clone();
+        sgp4(&mut tle_init, &[tle.epoch]);
+        let no = tle_init.satrec.as_ref().unwrap().no_unkozai / 60.0;
+        let a_mean = (crate::consts::MU_EARTH / (no * no)).cbrt();
+
+        // Osculating elements differ from mean elements by
+        // short-period J2 terms, and TEME differs from GCRF by precession
+        let kep = crate::kepler::Kepler::from_pv(state.pos_gcrf(), state.vel_gcrf())?;
+        assert!((kep.a - a_mean).abs() < 20.0e3);
+        assert!((kep.eccen - tle.eccen).abs() < 2.0e-3);
+        assert!((kep.incl.to_degrees() - tle.inclination).abs() < 0.3);
+
+        // Invalid elements fail SGP4 initialization
+        let mut bad = tle.clone();
+        bad.eccen = 1.5;
+        assert!(tle_epoch_state(&bad).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tle_seed_from_state() -> SKResult<()> {
+        let line1 = "1 45727U 20037E   24323.73967089  .00003818  00000+0  31595-3 0  9995";
+        let line2 = "2 45727  97.7798 139.6782 0011624 329.2427  30.8113 14.99451155239085";
+        let tle = TLE::load_2line(line1, line2)?;
+        let state = tle_epoch_state(&tle)?;
+
+        // Seed reproduces the state, and recovers the original mean elements
+        let seed = tle_seed_from_state(&state, tle.bstar)?;
+        let seed_state = tle_epoch_state(&seed)?;
+        assert!((seed_state.pos_gcrf() - state.pos_gcrf()).norm() < 0.01);
+        assert!((seed.mean_motion - tle.mean_motion).abs() < 1.0e-6);
+        assert!((seed.eccen - tle.eccen).abs() < 1.0e-6);
+        assert!((seed.inclination - tle.inclination).abs() < 1.0e-4);
+        assert!((seed.raan - tle.raan).abs() < 1.0e-4);
+
+        // Naive guess, taking osculating elements as mean elements,
+        // is kilometers off
+        let q = qteme2gcrf(&state.time).conjugate();
+        let naive = tle_from_osculating(
+            &(q * state.pos_gcrf()),
+            &(q * state.vel_gcrf()),
+            &state.time,
+            tle.bstar,
+        )?;
+        let naive_state = tle_epoch_state(&naive)?;
+        assert!((naive_state.pos_gcrf() - state.pos_gcrf()).norm() > 1.0e3);
+        Ok(())
+    }
+
     #[test]
     fn vallado_testvecs() -> SKResult<()> {
         let testdir = test::get_testvec_dir().unwrap().join("sgp4");
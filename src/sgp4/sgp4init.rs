@@ -243,6 +243,7 @@ pub fn sgp4init(
 
     satrec.error = 0;
     satrec.operationmode = opsmode;
+    satrec.gravconst = gravconst;
     // new alpha5 or 9-digit number
     /*
     #ifdef _MSC_VER
@@ -79,6 +79,7 @@ fn load_space_weather_csv() -> SKResult<Vec<SpaceWeatherRecord>> {
         .join("SW-All.csv");
     download_if_not_exist(&path, Some("http://celestrak.org/SpaceData/"))?;
 
+    log::debug!(target: "satkit::data", "Loading space weather from {}", path.display());
     let file = File::open(&path)?;
     io::BufReader::new(file)
         .lines()
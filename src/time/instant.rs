@@ -1,4 +1,4 @@
-use super::TimeScale;
+use super::{Duration, TimeScale};
 use serde::{Deserialize, Serialize};
 
 /// A module for handling time and date conversions.  Time is stored natively as
@@ -168,6 +168,37 @@ impl Instant {
         (self.raw - Self::UNIX_EPOCH.raw - microleapseconds(self.raw)) as f64 * 1.0e-6
     }
 
+    /// Leap-second offset, TAI - UTC, at this instant
+    ///
+    /// # Returns
+    /// Integer number of seconds by which TAI is ahead of UTC
+    ///
+    /// # Notes
+    /// * During a positive leap second (23:59:60 UTC) the offset
+    ///   prior to the leap second is returned; the new offset applies
+    ///   from 00:00:00 UTC of the following day
+    /// * Returns 0 for instants before 1972-01-01, the first entry
+    ///   of the leap-second table
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use satkit::Instant;
+    /// let t = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+    /// assert_eq!(t.tai_minus_utc(), 37);
+    /// ```
+    pub fn tai_minus_utc(&self) -> i32 {
+        for (t, ls) in LEAP_SECOND_TABLE.iter() {
+            if self.raw >= *t + 1_000_000 {
+                return (*ls / 1_000_000) as i32;
+            } else if self.raw >= *t {
+                // Within the leap second itself
+                return (*ls / 1_000_000) as i32 - 1;
+            }
+        }
+        0
+    }
+
     /// J2000 epoch is 2000-01-01 12:00:00 TT
     /// TT (Terristrial Time) is 32.184 seconds ahead of TAI
     pub const J2000: Self = Self {
@@ -344,6 +375,109 @@ impl Instant {
         Self::from_mjd_with_scale(utc, TimeScale::UTC)
     }
 
+    /// Start (00:00:00 UTC) of the UTC day containing this instant
+    fn utc_day_start(&self) -> Self {
+        // Elapsed time since midnight, including any leap second
+        let (_, _, _, hour, minute, second) = self.as_datetime();
+        let usec = (hour as i64 * 3600 + minute as i64 * 60) * 1_000_000
+            + (second * 1.0e6).round() as i64;
+        Self {
+            raw: self.raw - usec,
+        }
+    }
+
+    /// Round to the nearest multiple of a step from an origin
+    ///
+    /// # Arguments
+    /// * `step` - The cadence to round to, e.g. 10 seconds
+    /// * `origin` - Origin of the cadence.  If `None`, the start
+    ///   (00:00:00 UTC) of the UTC day containing this instant
+    ///
+    /// # Returns
+    /// A new Instant object on the cadence.  Halfway cases round
+    /// later.  If `step` is not positive, this instant is returned
+    ///
+    /// # Notes
+    /// * Rounding is in elapsed time from the origin, so on a day with
+    ///   a leap second the tick 86400 seconds after midnight
+    ///   is 23:59:60 rather than the following midnight
+    /// * Instants before the origin round the same way as those after,
+    ///   e.g. 7 seconds before the origin rounds to 10 seconds before
+    ///
+    /// # Example
+    /// ```
+    /// use satkit::{Duration, Instant};
+    /// let t = Instant::from_datetime(2024, 1, 1, 12, 0, 7.0);
+    /// let r = t.round_to(&Duration::from_seconds(10.0), None);
+    /// assert_eq!(r, Instant::from_datetime(2024, 1, 1, 12, 0, 10.0));
+    /// ```
+    pub fn round_to(&self, step: &Duration, origin: Option<&Self>) -> Self {
+        if step.usec <= 0 {
+            return *self;
+        }
+        let origin = origin.copied().unwrap_or_else(|| self.utc_day_start());
+        let elapsed = self.raw - origin.raw + step.usec / 2;
+        Self {
+            raw: origin.raw + elapsed.div_euclid(step.usec) * step.usec,
+        }
+    }
+
+    /// Truncate (round down) to a multiple of a step from an origin
+    ///
+    /// # Arguments
+    /// * `step` - The cadence to truncate to, e.g. 10 seconds
+    /// * `origin` - Origin of the cadence.  If `None`, the start
+    ///   (00:00:00 UTC) of the UTC day containing this instant
+    ///
+    /// # Returns
+    /// A new Instant object on the cadence, at or before this instant.
+    /// If `step` is not positive, this instant is returned
+    ///
+    /// # Notes
+    /// * See [`Instant::round_to`] for the handling of leap seconds
+    ///   & instants before the origin
+    pub fn truncate_to(&self, step: &Duration, origin: Option<&Self>) -> Self {
+        if step.usec <= 0 {
+            return *self;
+        }
+        let origin = origin.copied().unwrap_or_else(|| self.utc_day_start());
+        let elapsed = self.raw - origin.raw;
+        Self {
+            raw: origin.raw + elapsed.div_euclid(step.usec) * step.usec,
+        }
+    }
+
+    /// Integer index of the bin of a cadence containing this instant,
+    /// e.g. as a stable key for time-indexed caches
+    ///
+    /// # Arguments
+    /// * `step` - Width of the bins, e.g. 60 seconds
+    /// * `origin` - Start of bin 0
+    ///
+    /// # Returns
+    /// The bin index, i.e. the number of whole steps from the origin to
+    /// [`Instant::truncate_to`] this instant.  Instants exactly on a bin
+    /// boundary are in the bin starting at that boundary, and instants
+    /// before the origin have negative indices.  If `step` is not
+    /// positive, bins are one microsecond (the time resolution) wide
+    ///
+    /// # Example
+    /// ```
+    /// use satkit::{Duration, Instant};
+    /// let origin = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+    /// let step = Duration::from_seconds(60.0);
+    /// let t = Instant::from_datetime(2024, 1, 1, 0, 2, 30.0);
+    /// assert_eq!(t.bin_key(&step, &origin), 2);
+    /// assert_eq!((origin - step).bin_key(&step, &origin), -1);
+    /// ```
+    pub fn bin_key(&self, step: &Duration, origin: &Self) -> i64 {
+        let elapsed = self.raw - origin.raw;
+        match step.usec > 0 {
+            true => elapsed.div_euclid(step.usec),
+            false => elapsed,
+        }
+    }
+
     /// As Modified Julian Date with given time scale
     /// Days since 1858-11-17 00:00:00 UTC
     ///
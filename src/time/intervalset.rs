@@ -0,0 +1,256 @@
+use super::{Duration, Instant};
+
+/// Set of disjoint time intervals, e.g. satellite access windows
+///
+/// Intervals are stored sorted by start time, with overlapping
+/// intervals merged, so the ordering is deterministic regardless of
+/// the order in which intervals are inserted.
+///
+/// # Notes
+///
+/// * Intervals are closed, `[start, stop]`.  Intervals that touch,
+///   i.e. where one ends exactly when the next begins, are merged
+/// * Zero-length intervals (`start == stop`) and reversed intervals
+///   (`stop < start`) are empty and are dropped on insertion
+#[derive(Clone, Debug, Default)]
+pub struct IntervalSet {
+    intervals: Vec<(Instant, Instant)>,
+}
+
+impl IntervalSet {
+    /// Create an empty interval set
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Create an interval set from a list of (start, stop) intervals
+    ///
+    /// # Arguments
+    ///
+    /// * `intervals` - Intervals, in any order, which may overlap
+    ///
+    /// # Returns
+    ///
+    /// * Interval set with the union of the input intervals
+    pub fn from_intervals(intervals: &[(Instant, Instant)]) -> Self {
+        let mut v: Vec<(Instant, Instant)> = intervals
+            .iter()
+            .filter(|(start, stop)| stop.raw > start.raw)
+            .copied()
+            .collect();
+        v.sort_by_key(|(start, stop)| (start.raw, stop.raw));
+
+        let mut merged: Vec<(Instant, Instant)> = Vec::with_capacity(v.len());
+        for (start, stop) in v {
+            match merged.last_mut() {
+                Some(last) if start.raw <= last.1.raw => {
+                    if stop.raw > last.1.raw {
+                        last.1 = stop;
+                    }
+                }
+                _ => merged.push((start, stop)),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    /// Add an interval to the set, merging with any it overlaps or touches
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start of interval
+    /// * `stop` - End of interval
+    pub fn insert(&mut self, start: &Instant, stop: &Instant) {
+        let mut v = std::mem::take(&mut self.intervals);
+        v.push((*start, *stop));
+        *self = Self::from_intervals(&v);
+    }
+
+    /// The disjoint intervals in the set, sorted by start time
+    pub fn intervals(&self) -> &[(Instant, Instant)] {
+        &self.intervals
+    }
+
+    /// Number of disjoint intervals in the set
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// True if the set contains no intervals
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Sum of the durations of all intervals in the set
+    pub fn total_duration(&self) -> Duration {
+        Duration::new(
+            self.intervals
+                .iter()
+                .map(|(start, stop)| stop.raw - start.raw)
+                .sum(),
+        )
+    }
+
+    /// True if the time lies within one of the intervals (inclusive)
+    pub fn contains(&self, time: &Instant) -> bool {
+        // First interval whose end is not before the time
+        let ix = self
+            .intervals
+            .partition_point(|(_, stop)| stop.raw < time.raw);
+        ix < self.intervals.len() && self.intervals[ix].0.raw <= time.raw
+    }
+
+    /// Union of two interval sets
+    ///
+    /// # Returns
+    ///
+    /// * Times that are in either set
+    pub fn union(&self, other: &Self) -> Self {
+        let v: Vec<(Instant, Instant)> = self
+            .intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .copied()
+            .collect();
+        Self::from_intervals(&v)
+    }
+
+    /// Intersection of two interval sets
+    ///
+    /// # Returns
+    ///
+    /// * Times that are in both sets.  Intervals that only touch
+    ///   at a single instant do not intersect
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a0, a1) = self.intervals[i];
+            let (b0, b1) = other.intervals[j];
+            let start = if a0.raw > b0.raw { a0 } else { b0 };
+            let stop = if a1.raw < b1.raw { a1 } else { b1 };
+            if stop.raw > start.raw {
+                result.push((start, stop));
+            }
+            // Advance whichever interval ends first
+            if a1.raw < b1.raw {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: result }
+    }
+
+    /// Difference of two interval sets
+    ///
+    /// # Returns
+    ///
+    /// * Times that are in this set but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for &(start, stop) in self.intervals.iter() {
+            let mut cur = start;
+            // Skip intervals in other that end before this one starts
+            while j < other.intervals.len() && other.intervals[j].1.raw <= cur.raw {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].0.raw < stop.raw {
+                let (b0, b1) = other.intervals[k];
+                if b0.raw > cur.raw {
+                    result.push((cur, b0));
+                }
+                if b1.raw > cur.raw {
+                    cur = b1;
+                }
+                if cur.raw >= stop.raw {
+                    break;
+                }
+                k += 1;
+            }
+            if stop.raw > cur.raw {
+                result.push((cur, stop));
+            }
+        }
+        Self { intervals: result }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(seconds: f64) -> Instant {
+        Instant::from_datetime(2024, 1, 1, 0, 0, 0.0) + Duration::from_seconds(seconds)
+    }
+
+    fn secs(set: &IntervalSet) -> Vec<(f64, f64)> {
+        set.intervals()
+            .iter()
+            .map(|(a, b)| ((*a - t(0.0)).as_seconds(), (*b - t(0.0)).as_seconds()))
+            .collect()
+    }
+
+    #[test]
+    fn test_union() {
+        // Three overlapping windows, out of order
+        let set = IntervalSet::from_intervals(&[
+            (t(50.0), t(120.0)),
+            (t(0.0), t(60.0)),
+            (t(100.0), t(200.0)),
+        ]);
+        assert_eq!(secs(&set), vec![(0.0, 200.0)]);
+        assert!((set.total_duration().as_seconds() - 200.0).abs() < 1.0e-6);
+
+        // Touching intervals merge; zero-length & reversed intervals are dropped
+        let mut set = IntervalSet::from_intervals(&[(t(0.0), t(10.0)), (t(10.0), t(20.0))]);
+        set.insert(&t(30.0), &t(30.0));
+        set.insert(&t(50.0), &t(40.0));
+        assert_eq!(secs(&set), vec![(0.0, 20.0)]);
+
+        let other = IntervalSet::from_intervals(&[(t(30.0), t(40.0))]);
+        let u = set.union(&other);
+        assert_eq!(secs(&u), vec![(0.0, 20.0), (30.0, 40.0)]);
+        assert!(u.contains(&t(20.0)));
+        assert!(u.contains(&t(35.0)));
+        assert!(!u.contains(&t(25.0)));
+        assert!(!u.contains(&t(45.0)));
+    }
+
+    #[test]
+    fn test_intersection() {
+        // Disjoint sets have empty intersection, including touching intervals
+        let a = IntervalSet::from_intervals(&[(t(0.0), t(10.0)), (t(20.0), t(30.0))]);
+        let b = IntervalSet::from_intervals(&[(t(10.0), t(20.0)), (t(40.0), t(50.0))]);
+        assert!(a.intersection(&b).is_empty());
+        assert!(b.intersection(&a).is_empty());
+
+        let c = IntervalSet::from_intervals(&[(t(5.0), t(25.0))]);
+        assert_eq!(secs(&a.intersection(&c)), vec![(5.0, 10.0), (20.0, 25.0)]);
+        assert_eq!(secs(&c.intersection(&a)), vec![(5.0, 10.0), (20.0, 25.0)]);
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = IntervalSet::from_intervals(&[(t(0.0), t(100.0)), (t(200.0), t(300.0))]);
+        let b = IntervalSet::from_intervals(&[
+            (t(10.0), t(20.0)),
+            (t(90.0), t(210.0)),
+            (t(300.0), t(400.0)),
+        ]);
+        assert_eq!(
+            secs(&a.difference(&b)),
+            vec![(0.0, 10.0), (20.0, 90.0), (210.0, 300.0)]
+        );
+        assert_eq!(
+            secs(&b.difference(&a)),
+            vec![(100.0, 200.0), (300.0, 400.0)]
+        );
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(secs(&a.difference(&IntervalSet::new())), secs(&a));
+    }
+}
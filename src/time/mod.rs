@@ -3,12 +3,14 @@ mod instant;
 mod instant_err;
 mod instant_ops;
 mod instantparse;
+mod intervalset;
 mod timescale;
 mod weekday;
 
 pub use duration::Duration;
 pub use instant::Instant;
 pub use instant_err::InstantError;
+pub use intervalset::IntervalSet;
 pub use timescale::TimeScale;
 pub use weekday::Weekday;
 
@@ -54,6 +54,46 @@ fn test_unixtime() {
     assert!(g.5 == 40.0);
 }
 
+#[test]
+fn test_tai_minus_utc() {
+    assert_eq!(
+        Instant::from_datetime(2024, 6, 1, 0, 0, 0.0).tai_minus_utc(),
+        37
+    );
+    assert_eq!(
+        Instant::from_datetime(2000, 1, 1, 0, 0, 0.0).tai_minus_utc(),
+        32
+    );
+    assert_eq!(
+        Instant::from_datetime(1972, 3, 1, 0, 0, 0.0).tai_minus_utc(),
+        10
+    );
+    assert_eq!(
+        Instant::from_datetime(1971, 6, 1, 0, 0, 0.0).tai_minus_utc(),
+        0
+    );
+
+    // Across the leap second at the end of 2016
+    let leap = Instant::from_datetime(2016, 12, 31, 23, 59, 60.0);
+    assert_eq!(leap, Instant::new(1483228836000000));
+    let before = leap - Duration::from_microseconds(1);
+    assert_eq!(before.tai_minus_utc(), 36);
+    assert_eq!(leap.tai_minus_utc(), 36);
+    let during = leap + Duration::from_seconds(0.5);
+    assert_eq!(during.tai_minus_utc(), 36);
+    let after = leap + Duration::from_seconds(1.0);
+    assert_eq!(after.as_datetime(), (2017, 1, 1, 0, 0, 0.0));
+    assert_eq!(after.tai_minus_utc(), 37);
+
+    // Consistent with the difference between TAI & UTC MJD away from
+    // leap seconds
+    let t = Instant::from_datetime(2010, 3, 4, 5, 6, 7.0);
+    let diff = (t.as_mjd_with_scale(super::TimeScale::TAI)
+        - t.as_mjd_with_scale(super::TimeScale::UTC))
+        * 86400.0;
+    assert!((diff - t.tai_minus_utc() as f64).abs() < 1.0e-6);
+}
+
 #[test]
 fn test_leapsecond() {
     // Beginning of leap second
@@ -210,3 +250,95 @@ fn test_strptime() {
     assert!(g.4 == 27);
     assert!(g.5 == 19.0);
 }
+
+#[test]
+fn test_bin_key() {
+    let origin = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+    let step = Duration::from_seconds(10.0);
+
+    // Same bin, including its start boundary
+    let t = origin + Duration::from_seconds(42.0);
+    let key = t.bin_key(&step, &origin);
+    assert_eq!(key, 4);
+    for s in [40.0, 40.000001, 49.999999] {
+        let t2 = origin + Duration::from_seconds(s);
+        assert_eq!(t2.bin_key(&step, &origin), key);
+    }
+    // Adjacent bins differ by one
+    let t2 = origin + Duration::from_seconds(50.0);
+    assert_eq!(t2.bin_key(&step, &origin), key + 1);
+    let t2 = origin + Duration::from_seconds(39.999999);
+    assert_eq!(t2.bin_key(&step, &origin), key - 1);
+
+    // Before the origin: floor, not truncation toward zero
+    assert_eq!(origin.bin_key(&step, &origin), 0);
+    let t = origin - Duration::from_seconds(0.5);
+    assert_eq!(t.bin_key(&step, &origin), -1);
+    let t = origin - step;
+    assert_eq!(t.bin_key(&step, &origin), -1);
+    let t = origin - Duration::from_seconds(10.5);
+    assert_eq!(t.bin_key(&step, &origin), -2);
+
+    // Consistent with truncation to the cadence
+    let t = origin - Duration::from_seconds(1234.5);
+    let trunc = t.truncate_to(&step, Some(&origin));
+    assert_eq!(
+        trunc,
+        origin + Duration::from_seconds(10.0 * t.bin_key(&step, &origin) as f64)
+    );
+
+    // Non-positive step: microsecond bins
+    let t = origin + Duration::from_seconds(1.5);
+    assert_eq!(t.bin_key(&Duration::from_seconds(0.0), &origin), 1_500_000);
+}
+
+#[test]
+fn test_round_to() {
+    let step = Duration::from_seconds(10.0);
+    let t = Instant::from_datetime(2024, 1, 1, 12, 0, 7.0);
+    assert_eq!(
+        t.round_to(&step, None),
+        Instant::from_datetime(2024, 1, 1, 12, 0, 10.0)
+    );
+    assert_eq!(
+        t.truncate_to(&step, None),
+        Instant::from_datetime(2024, 1, 1, 12, 0, 0.0)
+    );
+
+    // Before 1970 (negative raw time)
+    let t = Instant::from_datetime(1965, 6, 1, 3, 4, 56.0);
+    assert_eq!(
+        t.round_to(&step, None),
+        Instant::from_datetime(1965, 6, 1, 3, 5, 0.0)
+    );
+    assert_eq!(
+        t.truncate_to(&step, None),
+        Instant::from_datetime(1965, 6, 1, 3, 4, 50.0)
+    );
+
+    // Before the origin
+    let origin = Instant::from_datetime(2024, 1, 1, 0, 0, 0.0);
+    let t = origin - Duration::from_seconds(7.0);
+    assert_eq!(t.round_to(&step, Some(&origin)), origin - step);
+    assert_eq!(t.truncate_to(&step, Some(&origin)), origin - step);
+    let t = origin - Duration::from_seconds(3.0);
+    assert_eq!(t.round_to(&step, Some(&origin)), origin);
+
+    // Across a leap second: 23:59:60 is a tick of the cadence
+    let leap = Instant::new(1483228836000000);
+    let t = leap - Duration::from_seconds(0.3);
+    assert_eq!(t.round_to(&Duration::from_seconds(1.0), None), leap);
+    let t = leap + Duration::from_seconds(0.7);
+    let next_day = leap + Duration::from_seconds(1.0);
+    assert_eq!(
+        t.round_to(&Duration::from_seconds(1.0), None),
+        next_day
+    );
+    assert_eq!(t.truncate_to(&step, None), leap);
+    assert_eq!(next_day.truncate_to(&step, None), next_day);
+    let t = next_day + Duration::from_seconds(17.0);
+    assert_eq!(
+        t.truncate_to(&step, None),
+        next_day + Duration::from_seconds(10.0)
+    );
+}
@@ -10,7 +10,7 @@
 /// * `TDB` - Barycentric Dynamical Time
 /// * `Invalid` - Invalid
 ///    
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum TimeScale {
     /// Invalid
@@ -103,6 +103,190 @@ pub struct TLE {
     pub(crate) satrec: Option<SatRec>,
 }
 
+/// Reference atmospheric density used to define B*,
+/// in kg / m^2 / Earth radius
+const BSTAR_REF_DENSITY: f64 = 0.156_966_15;
+
+/// Convert SGP4 starred ballistic coefficient (B*) to the ballistic
+/// coefficient used by the numerical drag model
+///
+/// # Arguments
+///
+/// * `bstar` - Starred ballistic coefficient, in units of inverse Earth radii
+///
+/// # Returns
+///
+/// * Coefficient of drag times area over mass, Cd * A / m, in m^2 / kg
+///
+/// # Notes
+///
+/// * B* = rho0 * Cd * A / (2 * m), where rho0 is the reference density of SGP4
+/// * B* is a free parameter in TLE fits, and is sometimes negative.  The sign
+///   is passed through, giving a non-physical negative ballistic coefficient,
+///   which the numerical propagator treats as no drag
+pub fn bstar_to_ballistic(bstar: f64) -> f64 {
+    2.0 * bstar / BSTAR_REF_DENSITY
+}
+
+/// Convert ballistic coefficient used by the numerical drag model to
+/// SGP4 starred ballistic coefficient (B*)
+///
+/// # Arguments
+///
+/// * `bc` - Coefficient of drag times area over mass, Cd * A / m, in m^2 / kg
+///
+/// # Returns
+///
+/// * Starred ballistic coefficient, in units of inverse Earth radii
+///
+/// # Notes
+///
+/// * Inverse of [`bstar_to_ballistic`]; negative values are passed through
+pub fn ballistic_to_bstar(bc: f64) -> f64 {
+    0.5 * bc * BSTAR_REF_DENSITY
+}
+
+/// Select the TLE with epoch nearest a given time
+///
+/// When several element sets for an object span a period of time,
+/// the one with epoch nearest the time of interest generally gives
+/// the most accurate SGP4 prediction
+///
+/// # Arguments
+///
+/// * `tles` - Element sets, in any order
+/// * `time` - Time of interest
+///
+/// # Returns
+///
+/// * Element set with minimum absolute difference between epoch and
+///   `time`, the earlier of two equally distant, or `None` if `tles`
+///   is empty
+pub fn select_tle<'a>(tles: &'a [TLE], time: &Instant) -> Option<&'a TLE> {
+    tles.iter().min_by_key(|tle| {
+        let dt = (tle.epoch - *time).as_microseconds();
+        (dt.abs(), dt)
+    })
+}
+
+/// Look up a GP JSON field as a string, treating empty strings as missing
+fn gp_str<'a>(value: &'a json::JsonValue, key: &str) -> Option<&'a str> {
+    value[key].as_str().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Look up a numeric GP JSON field
+///
+/// CelesTrak gives numbers as JSON numbers, while Space-Track gives
+/// them as strings, so both are accepted
+fn gp_num<T: std::str::FromStr>(value: &json::JsonValue, key: &str) -> SKResult<T> {
+    let s = match &value[key] {
+        json::JsonValue::Number(n) => n.to_string(),
+        v => match v.as_str() {
+            Some(s) => s.trim().to_string(),
+            None => return skerror!("GP JSON missing field {}", key),
+        },
+    };
+    match s.parse() {
+        Ok(v) => Ok(v),
+        Err(_) => skerror!("Could not parse GP JSON field {}: {}", key, s),
+    }
+}
+
+/// Create a TLE from a General Perturbations (GP) JSON record, as
+/// returned by the Space-Track and CelesTrak APIs
+///
+/// If the record includes the raw TLE lines ("TLE_LINE1" & "TLE_LINE2",
+/// as in Space-Track queries) they are parsed directly.  Otherwise the
+/// TLE is reconstructed from the decomposed element fields
+/// ("EPOCH", "MEAN_MOTION", "ECCENTRICITY", ...)
+///
+/// # Arguments
+///
+/// * `value` - A single GP record, parsed as a JSON object.  Numeric
+///   fields may be JSON numbers or strings
+///
+/// # Returns
+///
+/// * TLE object, named by "OBJECT_NAME" if present
+///
+/// # Example
+///
+/// ```
+/// use satkit::tle::tle_from_spacetrack_json;
+///
+/// let record = json::parse(r#"{
+///     "OBJECT_NAME": "ISS (ZARYA)", "OBJECT_ID": "1998-067A",
+///     "EPOCH": "2024-01-01T12:00:00.000000", "MEAN_MOTION": 15.49815308,
+///     "ECCENTRICITY": 0.0006703, "INCLINATION": 51.6416,
+///     "RA_OF_ASC_NODE": 247.4627, "ARG_OF_PERICENTER": 130.536,
+///     "MEAN_ANOMALY": 325.0288, "EPHEMERIS_TYPE": 0,
+///     "CLASSIFICATION_TYPE": "U", "NORAD_CAT_ID": 25544,
+///     "ELEMENT_SET_NO": 999, "REV_AT_EPOCH": 43234, "BSTAR": 0.0003027,
+///     "MEAN_MOTION_DOT": 0.00016717, "MEAN_MOTION_DDOT": 0
+/// }"#).unwrap();
+/// let tle = tle_from_spacetrack_json(&record).unwrap();
+/// assert_eq!(tle.sat_num, 25544);
+/// ```
+pub fn tle_from_spacetrack_json(value: &json::JsonValue) -> SKResult<TLE> {
+    if !value.is_object() {
+        return skerror!("GP JSON record must be an object");
+    }
+    let name = match (gp_str(value, "OBJECT_NAME"), gp_str(value, "TLE_LINE0")) {
+        (Some(n), _) => n.to_string(),
+        (None, Some(l0)) => l0.strip_prefix("0 ").unwrap_or(l0).to_string(),
+        (None, None) => "none".to_string(),
+    };
+
+    if let (Some(line1), Some(line2)) = (gp_str(value, "TLE_LINE1"), gp_str(value, "TLE_LINE2")) {
+        let mut tle = TLE::load_2line(line1, line2)?;
+        tle.name = name;
+        return Ok(tle);
+    }
+
+    // International designator, e.g. "1998-067A", is stored
+    // in the TLE as "98067A"
+    let mut tle = TLE::new();
+    if let Some(id) = gp_str(value, "OBJECT_ID") {
+        if id.len() < 9 || !id.is_char_boundary(4) || &id[4..5] != "-" {
+            return skerror!("Could not parse GP JSON OBJECT_ID: {}", id);
+        }
+        tle.desig_year = match id[2..4].parse() {
+            Ok(y) => y,
+            Err(_) => return skerror!("Could not parse GP JSON OBJECT_ID: {}", id),
+        };
+        tle.desig_launch = match id[5..8].parse() {
+            Ok(l) => l,
+            Err(_) => return skerror!("Could not parse GP JSON OBJECT_ID: {}", id),
+        };
+        tle.desig_piece = id[8..].to_string();
+        tle.intl_desig = format!("{}{}", &id[2..4], &id[5..]);
+    }
+
+    let epoch = match gp_str(value, "EPOCH") {
+        Some(e) => Instant::from_rfc3339(e)?,
+        None => return skerror!("GP JSON missing field EPOCH"),
+    };
+
+    Ok(TLE {
+        name,
+        sat_num: gp_num(value, "NORAD_CAT_ID")?,
+        epoch,
+        mean_motion_dot: gp_num(value, "MEAN_MOTION_DOT")?,
+        mean_motion_dot_dot: gp_num(value, "MEAN_MOTION_DDOT")?,
+        bstar: gp_num(value, "BSTAR")?,
+        ephem_type: gp_num(value, "EPHEMERIS_TYPE").unwrap_or_default(),
+        element_num: gp_num(value, "ELEMENT_SET_NO").unwrap_or_default(),
+        inclination: gp_num(value, "INCLINATION")?,
+        raan: gp_num(value, "RA_OF_ASC_NODE")?,
+        eccen: gp_num(value, "ECCENTRICITY")?,
+        arg_of_perigee: gp_num(value, "ARG_OF_PERICENTER")?,
+        mean_anomaly: gp_num(value, "MEAN_ANOMALY")?,
+        mean_motion: gp_num(value, "MEAN_MOTION")?,
+        rev_num: gp_num(value, "REV_AT_EPOCH").unwrap_or_default(),
+        ..tle
+    })
+}
+
 impl TLE {
     /// Load a vector of strings representing Two-Line Element Set (TLE) lines into a vector of
     /// TLE structures.
@@ -251,6 +435,61 @@ impl TLE {
         }
     }
 
+    /// Decode the epoch field of a TLE
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Epoch field, columns 19-32 of line 1, as
+    ///   "YYDDD.DDDDDDDD": two-digit year, then day of year with
+    ///   fractional part.  Day 1.0 is 00:00:00 UTC on January 1
+    ///
+    /// # Returns
+    ///
+    /// * Epoch, or error if the field cannot be parsed or the day
+    ///   is not within the year
+    ///
+    /// # Notes
+    ///
+    /// * Two-digit years 57 through 99 are 1957 through 1999; 00 through
+    ///   56 are 2000 through 2056
+    /// * Day 366 is valid only in leap years
+    /// * Fractional days are rounded to the nearest microsecond, and are
+    ///   of 86,400 seconds, i.e. leap seconds are not counted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use satkit::{Instant, TLE};
+    /// let epoch = TLE::decode_epoch("24366.50000000").unwrap();
+    /// assert_eq!(epoch, Instant::from_datetime(2024, 12, 31, 12, 0, 0.0));
+    /// ```
+    pub fn decode_epoch(field: &str) -> SKResult<Instant> {
+        if field.len() < 3 || !field.is_char_boundary(2) {
+            return skerror!("Invalid epoch field: \"{}\"", field);
+        }
+        let year: i32 = match field[0..2].trim().parse::<u32>() {
+            Ok(y) if y >= 57 => 1900 + y as i32,
+            Ok(y) => 2000 + y as i32,
+            Err(_) => return skerror!("Could not parse year"),
+        };
+        let day_of_year: f64 = match field[2..].trim().parse() {
+            Ok(d) => d,
+            Err(_) => return skerror!("Could not parse day of year"),
+        };
+
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let ndays = if leap { 366.0 } else { 365.0 };
+        if !(1.0..ndays + 1.0).contains(&day_of_year) {
+            return skerror!("Day of year {} is not within {}", day_of_year, year);
+        }
+
+        // Whole days from January 1; the Gregorian date conversion
+        // carries days past the end of the month into later months
+        let day = day_of_year.floor();
+        let usec = ((day_of_year - day) * 86_400_000_000.0).round() as i64;
+        Ok(Instant::from_date(year, 1, day as i32) + crate::Duration::from_microseconds(usec))
+    }
+
     /// Load 2 lines as strings into a structure representing
     /// a Two-Line Element Set  (TLE)
     ///
@@ -283,30 +522,7 @@ impl TLE {
     /// ```
     ///
     pub fn load_2line(line1: &str, line2: &str) -> SKResult<Self> {
-        let mut year: u32 = {
-            let mut mstr: String = "1".to_owned();
-            mstr.push_str(&line1[18..20]);
-            let mut s: u32 = match mstr.parse() {
-                Ok(y) => y,
-                Err(_) => return crate::skerror!("Could not parse year"),
-            };
-            s -= 100;
-            s
-        };
-        if year > 57 {
-            year += 1900;
-        } else {
-            year += 2000;
-        }
-        let day_of_year: f64 = match line1[20..32].parse() {
-            Ok(y) => y,
-            Err(_) => return crate::skerror!("Could not parse day of year"),
-        };
-
-        // Note: day_of_year starts from 1, not zero,
-        // also, go from Jan 2 to avoid leap-second
-        // issues, hence the "-2" at end
-        let epoch = Instant::from_date(year as i32, 1, 2).add_utc_days(day_of_year - 2.0);
+        let epoch = Self::decode_epoch(&line1[18..32])?;
 
         Ok(Self {
             name: "none".to_string(),
@@ -621,4 +837,214 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_select_tle() -> SKResult<()> {
+        let line2 = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+        let tles = [
+            "1 25544U 98067A   24010.50000000  .00016717  00000-0  10270-3 0  9005",
+            "1 25544U 98067A   24012.00000000  .00016717  00000-0  10270-3 0  9008",
+            "1 25544U 98067A   24011.00000000  .00016717  00000-0  10270-3 0  9006",
+        ]
+        .iter()
+        .map(|line1| TLE::load_2line(line1, line2))
+        .collect::<SKResult<Vec<TLE>>>()?;
+
+        // Nearest epoch, regardless of order
+        let time = Instant::from_datetime(2024, 1, 11, 18, 0, 0.0);
+        assert_eq!(select_tle(&tles, &time).unwrap().epoch, tles[1].epoch);
+        let time = Instant::from_datetime(2024, 1, 11, 3, 0, 0.0);
+        assert_eq!(select_tle(&tles, &time).unwrap().epoch, tles[2].epoch);
+        let time = Instant::from_datetime(2023, 12, 1, 0, 0, 0.0);
+        assert_eq!(select_tle(&tles, &time).unwrap().epoch, tles[0].epoch);
+
+        // Halfway between two epochs: the earlier is selected
+        let time = Instant::from_datetime(2024, 1, 11, 12, 0, 0.0);
+        assert_eq!(select_tle(&tles, &time).unwrap().epoch, tles[2].epoch);
+        let time = Instant::from_datetime(2024, 1, 10, 18, 0, 0.0);
+        assert_eq!(select_tle(&tles, &time).unwrap().epoch, tles[0].epoch);
+
+        assert!(select_tle(&[], &time).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bstar_ballistic() {
+        for bstar in [1.0e-4, 3.5e-5, 0.0, -2.0e-5] {
+            let bc = bstar_to_ballistic(bstar);
+            assert!((ballistic_to_bstar(bc) - bstar).abs() < 1.0e-18);
+            assert_eq!(bc.signum(), bstar.signum());
+        }
+
+        // Commonly quoted conversion is Cd * A / m = 12.741621 * B*
+        let bc = bstar_to_ballistic(1.0e-4);
+        assert!((bc - 12.741621e-4).abs() < 1.0e-8);
+
+        // B* of a sun-synchronous LEO satellite (Vallado test case 28057)
+        // gives a Cd * A / m within the range of real satellites,
+        // roughly 1e-4 to 1e-1 m^2/kg
+        let line1 = "1 28057U 03049A   06177.78615833  .00000060  00000-0  35940-4 0  1836";
+        let line2 = "2 28057  98.4283 247.6961 0000884  88.1964 271.9322 14.35478080140550";
+        let tle = TLE::load_2line(line1, line2).unwrap();
+        let bc = bstar_to_ballistic(tle.bstar);
+        assert!(bc > 1.0e-4 && bc < 0.1);
+    }
+
+    #[test]
+    fn test_spacetrack_json() -> SKResult<()> {
+        let line1 = "1 25544U 98067A   24001.50000000  .00016717  00000-0  30270-3 0  9993";
+        let line2 = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.49815308432345";
+        let mut card = TLE::load_2line(line1, line2)?;
+
+        // CelesTrak style, with only the decomposed fields
+        let celestrak = json::parse(
+            r#"{
+                "OBJECT_NAME": "ISS (ZARYA)",
+                "OBJECT_ID": "1998-067A",
+                "EPOCH": "2024-01-01T12:00:00.000000",
+                "MEAN_MOTION": 15.49815308,
+                "ECCENTRICITY": 0.0006703,
+                "INCLINATION": 51.6416,
+                "RA_OF_ASC_NODE": 247.4627,
+                "ARG_OF_PERICENTER": 130.536,
+                "MEAN_ANOMALY": 325.0288,
+                "EPHEMERIS_TYPE": 0,
+                "CLASSIFICATION_TYPE": "U",
+                "NORAD_CAT_ID": 25544,
+                "ELEMENT_SET_NO": 999,
+                "REV_AT_EPOCH": 43234,
+                "BSTAR": 0.0003027,
+                "MEAN_MOTION_DOT": 0.00016717,
+                "MEAN_MOTION_DDOT": 0
+            }"#,
+        )?;
+        let mut tle = tle_from_spacetrack_json(&celestrak)?;
+        assert_eq!(tle.name, "ISS (ZARYA)");
+        assert_eq!(tle.sat_num, card.sat_num);
+        assert_eq!(tle.intl_desig, card.intl_desig);
+        assert_eq!(tle.desig_year, card.desig_year);
+        assert_eq!(tle.desig_launch, card.desig_launch);
+        assert_eq!(tle.desig_piece, card.desig_piece);
+        assert_eq!(tle.element_num, 999);
+        assert_eq!(tle.rev_num, card.rev_num);
+        assert!((tle.epoch - card.epoch).as_seconds().abs() < 1.0e-6);
+
+        let times: Vec<Instant> = (0..10)
+            .map(|ix| card.epoch + crate::Duration::from_hours(ix as f64 * 6.0))
+            .collect();
+        let (pcard, vcard, _) = crate::sgp4::sgp4(&mut card, &times);
+        let (p, v, _) = crate::sgp4::sgp4(&mut tle, &times);
+        assert!((p - &pcard).abs().max() < 1.0e-3);
+        assert!((v - &vcard).abs().max() < 1.0e-6);
+
+        // Space-Track style, with raw lines & string values
+        let spacetrack = json::parse(&format!(
+            r#"{{
+                "OBJECT_NAME": "ISS (ZARYA)",
+                "NORAD_CAT_ID": "25544",
+                "MEAN_MOTION": "15.49815308",
+                "TLE_LINE0": "0 ISS (ZARYA)",
+                "TLE_LINE1": "{}",
+                "TLE_LINE2": "{}"
+            }}"#,
+            line1, line2
+        ))?;
+        let mut tle = tle_from_spacetrack_json(&spacetrack)?;
+        assert_eq!(tle.name, "ISS (ZARYA)");
+        let (p, _, _) = crate::sgp4::sgp4(&mut tle, &times);
+        assert!((p - &pcard).abs().max() < 1.0e-6);
+
+        // String values without raw lines
+        let mut strings = celestrak.clone();
+        strings["BSTAR"] = "0.00030270".into();
+        strings["NORAD_CAT_ID"] = "25544".into();
+        strings.remove("OBJECT_NAME");
+        let tle = tle_from_spacetrack_json(&strings)?;
+        assert_eq!(tle.bstar, card.bstar);
+        assert_eq!(tle.sat_num, 25544);
+        assert_eq!(tle.name, "none");
+
+        // Missing elements are an error
+        let mut missing = celestrak.clone();
+        missing.remove("MEAN_MOTION");
+        assert!(tle_from_spacetrack_json(&missing).is_err());
+        assert!(tle_from_spacetrack_json(&json::JsonValue::Null).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_epoch() -> SKResult<()> {
+        let dt = |year, month, day, hour, min, sec| {
+            Instant::from_datetime(year, month, day, hour, min, sec)
+        };
+
+        // Year boundaries of a leap year (2024) and non-leap year (2023)
+        assert_eq!(
+            TLE::decode_epoch("24001.00000000")?,
+            dt(2024, 1, 1, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("24060.25000000")?,
+            dt(2024, 2, 29, 6, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("24061.00000000")?,
+            dt(2024, 3, 1, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("24366.50000000")?,
+            dt(2024, 12, 31, 12, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("23060.00000000")?,
+            dt(2023, 3, 1, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("23365.50000000")?,
+            dt(2023, 12, 31, 12, 0, 0.0)
+        );
+        assert!(TLE::decode_epoch("23366.00000000").is_err());
+        assert!(TLE::decode_epoch("24367.00000000").is_err());
+        assert!(TLE::decode_epoch("24000.50000000").is_err());
+
+        // Last representable instant of a year is just before the next
+        let end = TLE::decode_epoch("23365.99999999")?;
+        let next = TLE::decode_epoch("24001.00000000")?;
+        assert_eq!((next - end).as_microseconds(), 864);
+
+        // Fractional days are resolved to the microsecond
+        let t = TLE::decode_epoch("24123.12345678")?;
+        assert_eq!(
+            (t - dt(2024, 5, 2, 0, 0, 0.0)).as_microseconds(),
+            10_666_665_792
+        );
+
+        // Two-digit year pivot at 57
+        assert_eq!(
+            TLE::decode_epoch("57001.00000000")?,
+            dt(1957, 1, 1, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("99365.00000000")?,
+            dt(1999, 12, 31, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("00060.00000000")?,
+            dt(2000, 2, 29, 0, 0, 0.0)
+        );
+        assert_eq!(
+            TLE::decode_epoch("56001.00000000")?,
+            dt(2056, 1, 1, 0, 0, 0.0)
+        );
+
+        // Decoded when loading lines
+        let line1 = "1 25544U 98067A   24366.50000000  .00016717  00000-0  10270-3 0  9005";
+        let line2 = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.49815308  1003";
+        let tle = TLE::load_2line(line1, line2)?;
+        assert_eq!(tle.epoch, dt(2024, 12, 31, 12, 0, 0.0));
+
+        assert!(TLE::decode_epoch("2").is_err());
+        assert!(TLE::decode_epoch("2x001.0").is_err());
+        Ok(())
+    }
 }
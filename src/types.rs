@@ -6,6 +6,21 @@ pub type SKResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 pub enum SKErr {
     #[error("SatKit Error: {0}")]
     Error(String),
+    /// SGP4 found the satellite to have decayed (radius below
+    /// that of the Earth) at the given time
+    #[error("SGP4: satellite decayed at {time}")]
+    Sgp4Decayed { time: crate::Instant },
+    /// SGP4 elements are invalid at the given time, either as
+    /// loaded from the TLE or as perturbed by propagation
+    #[error("SGP4: invalid elements at {time}: {error}")]
+    Sgp4BadElements {
+        time: crate::Instant,
+        error: crate::sgp4::SGP4Error,
+    },
+    /// Numerical integration stopped after taking the given number
+    /// of steps, reaching the given time
+    #[error("Integrator failed after {steps} steps, at {time}")]
+    IntegratorFailed { steps: usize, time: crate::Instant },
 }
 
 /// Macro for creating a new SKErr::Error
@@ -14,6 +14,7 @@ pub fn download_if_not_exist(fname: &Path, seturl: Option<&str>) -> SKResult<()>
     // Try to set proxy, if any, from environment variables
     let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
 
+    log::info!(target: "satkit::data", "Downloading {} to {}", url, fname.display());
     let resp = agent.get(url.as_str()).call()?;
 
     let mut dest = std::fs::File::create(fname)?;
@@ -33,6 +34,7 @@ pub fn download_file(url: &str, downloaddir: &Path, overwrite_if_exists: bool) -
         // Try to set proxy, if any, from environment variables
         let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
 
+        log::info!(target: "satkit::data", "Downloading {} to {}", url, fullpath.display());
         let resp = agent.get(url).call()?;
 
         let mut dest = std::fs::File::create(fullpath)?;
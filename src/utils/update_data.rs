@@ -1,3 +1,4 @@
+use super::download_file;
 use super::download_file_async;
 use super::download_to_string;
 use crate::skerror;
@@ -136,6 +137,22 @@ pub fn update_datafiles(dir: Option<PathBuf>, overwrite_if_exists: bool) -> SKRe
         &overwrite_if_exists,
     )?;
 
+    // Ocean tide coefficients are distributed by the IERS Conventions Centre.
+    // They are only needed for optional ocean tide perturbations, so failure
+    // to download them is not an error
+    if let Err(e) = download_file(
+        format!(
+            "{}{}",
+            crate::orbitprop::OCEAN_TIDE_URL,
+            crate::orbitprop::OCEAN_TIDE_FILE
+        )
+        .as_str(),
+        &downloaddir,
+        overwrite_if_exists,
+    ) {
+        println!("Warning: could not download ocean tide coefficients: {}", e);
+    }
+
     println!("Now downloading files that are regularly updated:");
     println!("  Space Weather & Earth Orientation Parameters");
     // Get a list of files that are updated with new data, and download them